@@ -3,26 +3,38 @@
 //! Each function is registered with `tauri::Builder::invoke_handler` and
 //! callable from the frontend via `invoke(...)`.
 
-use dictum_core::{audio::device::DeviceInfo, ipc::events::EngineStatus};
+use std::collections::BTreeMap;
+
+use dictum_core::{
+    audio::{device::DeviceInfo, PermissionStatus},
+    engine::{build_vad, EngineConfig},
+    inference::onnx::resolved_models_dir,
+    ipc::events::{EngineStatus, ModelCapabilities},
+    vad::benchmark::VadBenchmarkReport,
+    DictumEngine,
+};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tauri::State;
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 use tracing::{info, warn};
 
+use crate::form_mode::{FormSession, FormState, FormTemplate};
 use crate::model_profiles::{
     model_profile_catalog, recommend_model_profile, ModelProfileMetadata,
     ModelProfileRecommendation,
 };
 use crate::settings::{
-    normalize_cloud_mode, normalize_language_hint, normalize_model_profile, normalize_ort_ep,
-    normalize_performance_profile, normalize_toggle_shortcut, save_settings,
-    sync_runtime_with_settings, LearnedCorrection, RuntimeEnvMode, RuntimeSettings,
+    normalize_cloud_mode, normalize_injection_mode, normalize_language_hint,
+    normalize_model_profile, normalize_number_formatting, normalize_number_locale,
+    normalize_ort_ep, normalize_performance_profile, normalize_toggle_shortcut,
+    normalize_utterance_separator, save_settings, sync_runtime_with_settings, AppSettings,
+    LearnedCorrection, RuntimeEnvMode, RuntimeSettings,
 };
 use crate::state::{AppState, PerfSnapshot};
 use crate::storage::{
-    DictionaryEntry, HistoryPage, HistoryStorageSummary, PrivacySettings, SnippetEntry,
-    StatsPayload,
+    DictationSession, DictionaryEntry, HistoryPage, HistoryStorageSummary, PrivacySettings,
+    SnippetEntry, StatsPayload, StorageError,
 };
 
 const DEFAULT_UPDATE_REPO_SLUG: &str = "sinergaoptima/dictum";
@@ -81,6 +93,44 @@ pub struct DiagnosticsBundle {
     pub devices: Vec<DeviceInfo>,
 }
 
+/// Everything that decides runtime behaviour, as actually resolved.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveConfig {
+    /// The engine's config, with field names as in `EngineConfig`.
+    pub engine: EngineConfig,
+    pub performance_profile: String,
+    pub model_profile: String,
+    pub model_dir: String,
+    /// Execution provider the loaded model's sessions registered; `None`
+    /// before the first warm-up.
+    pub execution_provider: Option<String>,
+    /// Every `DICTUM_*` variable set in the process; secrets are masked.
+    pub env_overrides: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelBenchmarkCase {
+    pub file: String,
+    pub reference: Option<String>,
+    pub hypothesis: String,
+    pub word_error_rate: Option<f32>,
+    pub latency_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelBenchmarkReport {
+    pub corpus_dir: String,
+    pub model_profile: String,
+    pub ort_ep: String,
+    pub files: usize,
+    pub mean_word_error_rate: Option<f32>,
+    pub median_latency_ms: f64,
+    pub cases: Vec<ModelBenchmarkCase>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
@@ -238,6 +288,48 @@ fn parse_sha256_from_sums(contents: &str, file_name: &str) -> Option<String> {
     None
 }
 
+fn default_benchmark_corpus_dir() -> std::path::PathBuf {
+    std::env::var_os("DICTUM_BENCHMARK_CORPUS")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::PathBuf::from("benchmarks").join("fixtures"))
+}
+
+/// Recursively collect `.wav` files; each may have a sibling `.txt` reference.
+fn collect_corpus_wavs(
+    dir: &std::path::Path,
+    out: &mut Vec<std::path::PathBuf>,
+) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("failed to read corpus '{}': {e}", dir.display()))?;
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            collect_corpus_wavs(&path, out)?;
+        } else if path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|s| s.eq_ignore_ascii_case("wav"))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn median_ms(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
 #[cfg(target_os = "windows")]
 fn verify_windows_authenticode(path: &std::path::Path) -> Result<String, String> {
     let escaped = path.to_string_lossy().replace('\'', "''");
@@ -269,22 +361,52 @@ fn verify_windows_authenticode(path: &std::path::Path) -> Result<String, String>
     }
 }
 
+/// Shown when the OS privacy settings block microphone capture.
+pub const MIC_PERMISSION_DENIED: &str =
+    "Microphone access is blocked. Allow Dictum in your system privacy settings (Privacy & security → Microphone), then start again.";
+
+/// Pre-flight before starting capture: turns a denied OS permission into a
+/// clear message instead of a generic device error. Reads the registry on
+/// Windows, so async callers run it through `spawn_blocking`.
+pub fn ensure_microphone_permission() -> Result<(), String> {
+    let status = dictum_core::audio::check_permission();
+    if status.allows_capture() {
+        Ok(())
+    } else {
+        warn!(?status, "microphone permission denied");
+        Err(MIC_PERMISSION_DENIED.into())
+    }
+}
+
 /// Start audio capture and the transcription pipeline.
+///
+/// `device_name` overrides the device for this start only; the pin is
+/// changed through `set_preferred_input_device`. With neither, the current
+/// OS default is used, re-queried on every start.
 #[tauri::command]
 pub async fn start_engine(
     state: State<'_, AppState>,
     device_name: Option<String>,
 ) -> Result<(), String> {
-    if let Some(name) = device_name {
-        *state.preferred_input_device.lock() = Some(name);
-    }
-    let preferred = state.preferred_input_device.lock().clone();
+    tauri::async_runtime::spawn_blocking(ensure_microphone_permission)
+        .await
+        .map_err(|e| format!("permission check task failed: {e}"))??;
+    let preferred = device_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| state.preferred_input_device.lock().clone());
     state
         .engine
         .start_with_device(preferred)
         .map_err(|e| e.to_string())
 }
 
+/// Discard the utterance in progress without stopping capture.
+#[tauri::command]
+pub async fn reset_session(state: State<'_, AppState>) -> Result<(), String> {
+    state.engine.reset_session().map_err(|e| e.to_string())
+}
+
 /// Stop audio capture and the pipeline.
 #[tauri::command]
 pub async fn stop_engine(state: State<'_, AppState>) -> Result<(), String> {
@@ -306,13 +428,24 @@ pub async fn get_status(state: State<'_, AppState>) -> Result<EngineStatus, Stri
     Ok(state.engine.status())
 }
 
+/// Return the OS microphone permission for this app. Reads the registry
+/// on Windows, so it runs on the blocking pool.
+#[tauri::command]
+pub async fn get_microphone_permission() -> Result<PermissionStatus, String> {
+    tauri::async_runtime::spawn_blocking(dictum_core::audio::check_permission)
+        .await
+        .map_err(|e| format!("permission check task failed: {e}"))
+}
+
 /// Return a list of available audio input devices.
 #[tauri::command]
 pub async fn list_audio_devices(_state: State<'_, AppState>) -> Result<Vec<DeviceInfo>, String> {
     Ok(dictum_core::audio::device::list_input_devices())
 }
 
-/// Persist the preferred input device name used for future starts.
+/// Persist the preferred input device used for future starts. Accepts a
+/// `DeviceInfo::id` or, for older settings, a bare device name. `None`
+/// unpins it, so starts follow the OS default device.
 #[tauri::command]
 pub async fn set_preferred_input_device(
     state: State<'_, AppState>,
@@ -330,7 +463,7 @@ pub async fn set_preferred_input_device(
     Ok(())
 }
 
-/// Return the currently preferred input device name, if one is set.
+/// Return the currently preferred input device id or name, if one is set.
 #[tauri::command]
 pub async fn get_preferred_input_device(
     state: State<'_, AppState>,
@@ -344,6 +477,14 @@ pub async fn get_runtime_settings(state: State<'_, AppState>) -> Result<RuntimeS
     Ok(state.settings.lock().runtime_settings())
 }
 
+/// Return what the loaded model supports; `None` until warm-up succeeded.
+#[tauri::command]
+pub async fn get_model_capabilities(
+    state: State<'_, AppState>,
+) -> Result<Option<ModelCapabilities>, String> {
+    Ok(state.engine.model_capabilities())
+}
+
 /// Return model profile metadata catalog for UX guidance.
 #[tauri::command]
 pub async fn get_model_profile_catalog() -> Result<Vec<ModelProfileMetadata>, String> {
@@ -737,6 +878,168 @@ pub async fn run_benchmark_auto_tune(
     })
 }
 
+/// Run a labeled WAV corpus through the loaded model and report WER and latency.
+///
+/// `corpus_dir` defaults to `DICTUM_BENCHMARK_CORPUS`, then the bundled
+/// `benchmarks/fixtures`. Each `.wav` is scored against a sibling `.txt`
+/// reference when present.
+#[tauri::command]
+pub async fn run_model_benchmark(
+    state: State<'_, AppState>,
+    corpus_dir: Option<String>,
+) -> Result<ModelBenchmarkReport, String> {
+    if state.engine.is_running() {
+        return Err("Stop dictation before running the model benchmark.".into());
+    }
+    let corpus_dir = corpus_dir
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(default_benchmark_corpus_dir);
+    let (model_profile, ort_ep) = {
+        let settings = state.settings.lock();
+        (settings.model_profile.clone(), settings.ort_ep.clone())
+    };
+    let model = state.engine.model();
+
+    tauri::async_runtime::spawn_blocking(move || -> Result<ModelBenchmarkReport, String> {
+        let mut wavs = Vec::new();
+        collect_corpus_wavs(&corpus_dir, &mut wavs)?;
+        wavs.sort();
+        if wavs.is_empty() {
+            return Err(format!(
+                "no .wav files found in corpus '{}'",
+                corpus_dir.display()
+            ));
+        }
+
+        let mut cases = Vec::with_capacity(wavs.len());
+        for wav in &wavs {
+            let reference = std::fs::read_to_string(wav.with_extension("txt"))
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+            let started = std::time::Instant::now();
+            let segments = {
+                let mut guard = model.0.lock();
+                dictum_core::transcribe_file(&mut *guard, wav)
+                    .map_err(|e| format!("{}: {e}", wav.display()))?
+            };
+            let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+            let hypothesis = segments
+                .iter()
+                .filter(|seg| seg.kind == dictum_core::ipc::events::SegmentKind::Final)
+                .map(|seg| seg.text.trim())
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let word_error_rate = reference
+                .as_deref()
+                .and_then(|r| dictum_core::eval::word_error_rate(r, &hypothesis));
+            cases.push(ModelBenchmarkCase {
+                file: wav
+                    .strip_prefix(&corpus_dir)
+                    .unwrap_or(wav)
+                    .display()
+                    .to_string(),
+                reference,
+                hypothesis,
+                word_error_rate,
+                latency_ms,
+            });
+        }
+
+        let scored = cases
+            .iter()
+            .filter_map(|c| c.word_error_rate)
+            .collect::<Vec<_>>();
+        let latencies = cases.iter().map(|c| c.latency_ms).collect::<Vec<_>>();
+        Ok(ModelBenchmarkReport {
+            corpus_dir: corpus_dir.display().to_string(),
+            model_profile,
+            ort_ep,
+            files: cases.len(),
+            mean_word_error_rate: (!scored.is_empty())
+                .then(|| scored.iter().sum::<f32>() / scored.len() as f32),
+            median_latency_ms: median_ms(&latencies),
+            cases,
+        })
+    })
+    .await
+    .map_err(|e| format!("model benchmark task failed: {e}"))?
+}
+
+/// Score the configured VAD against a labeled WAV file.
+///
+/// `labels_path` defaults to the sibling `<name>.labels.txt`, in Audacity
+/// label-track format (`start<TAB>end` seconds per speech region). Audio is
+/// resampled to the engine's target rate first, as in the live pipeline.
+#[tauri::command]
+pub async fn benchmark_vad(
+    state: State<'_, AppState>,
+    wav_path: String,
+    labels_path: Option<String>,
+) -> Result<VadBenchmarkReport, String> {
+    let wav_path = std::path::PathBuf::from(wav_path.trim());
+    let labels_path = labels_path
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| wav_path.with_extension("labels.txt"));
+    let config = state.engine.config();
+
+    tauri::async_runtime::spawn_blocking(move || -> Result<VadBenchmarkReport, String> {
+        let labels = std::fs::read_to_string(&labels_path)
+            .map_err(|e| format!("{}: {e}", labels_path.display()))?;
+        let regions = dictum_core::vad::benchmark::parse_labels(&labels)
+            .map_err(|e| format!("{}: {e}", labels_path.display()))?;
+        let audio = dictum_core::audio::wav::read_wav_mono(&wav_path).map_err(|e| e.to_string())?;
+        let mut converter = dictum_core::audio::resample::RateConverter::new(
+            audio.sample_rate,
+            config.target_sample_rate,
+            960,
+        )
+        .map_err(|e| e.to_string())?;
+        let audio = dictum_core::buffering::chunk::AudioChunk::new(
+            converter.process(&audio.samples),
+            config.target_sample_rate,
+        );
+        let mut vad = build_vad(&config);
+        Ok(dictum_core::vad::benchmark::benchmark_vad(
+            vad.as_mut(),
+            &audio,
+            &regions,
+        ))
+    })
+    .await
+    .map_err(|e| format!("VAD benchmark task failed: {e}"))?
+}
+
+/// Start routing finals into the fields of a JSON form template instead of
+/// typing them. Replaces any form in progress.
+#[tauri::command]
+pub async fn start_form_mode(
+    state: State<'_, AppState>,
+    template_json: String,
+) -> Result<FormState, String> {
+    let session = FormSession::new(FormTemplate::from_json(&template_json)?);
+    let form_state = session.state();
+    *state.form_mode.lock() = Some(session);
+    Ok(form_state)
+}
+
+/// End form mode and return the filled fields; typing resumes.
+#[tauri::command]
+pub async fn stop_form_mode(state: State<'_, AppState>) -> Result<Option<FormState>, String> {
+    Ok(state.form_mode.lock().take().map(|form| form.state()))
+}
+
+/// Current form fields, or `None` outside form mode.
+#[tauri::command]
+pub async fn get_form_state(state: State<'_, AppState>) -> Result<Option<FormState>, String> {
+    Ok(state.form_mode.lock().as_ref().map(FormSession::state))
+}
+
 /// Return learned correction rules (`heard` -> `corrected`).
 #[tauri::command]
 pub async fn get_learned_corrections(
@@ -850,6 +1153,16 @@ pub async fn set_runtime_settings(
     onboarding_completed: Option<bool>,
     history_enabled: Option<bool>,
     retention_days: Option<usize>,
+    injection_mode: Option<String>,
+    injection_min_interval_ms: Option<u64>,
+    redact_pii: Option<bool>,
+    maintenance_interval_minutes: Option<u64>,
+    number_formatting: Option<String>,
+    number_locale: Option<String>,
+    utterance_separator: Option<String>,
+    utterance_separator_custom: Option<String>,
+    injection_stream_chunk_chars: Option<usize>,
+    injection_stream_interval_ms: Option<u64>,
 ) -> Result<RuntimeSettings, String> {
     let mut settings = state.settings.lock();
     let previous_shortcut = settings.toggle_shortcut.clone();
@@ -915,7 +1228,6 @@ pub async fn set_runtime_settings(
         } else {
             "local_only".into()
         };
-        settings.cloud_opt_in = settings.cloud_mode != "local_only";
     }
     if let Some(v) = reliability_mode {
         settings.reliability_mode = v;
@@ -929,6 +1241,36 @@ pub async fn set_runtime_settings(
     if let Some(v) = retention_days {
         settings.retention_days = v.clamp(1, 3650);
     }
+    if let Some(v) = injection_mode {
+        settings.injection_mode = normalize_injection_mode(&v);
+    }
+    if let Some(v) = injection_min_interval_ms {
+        settings.injection_min_interval_ms = v.clamp(0, 2_000);
+    }
+    if let Some(v) = redact_pii {
+        settings.redact_pii = v;
+    }
+    if let Some(v) = maintenance_interval_minutes {
+        settings.maintenance_interval_minutes = v.clamp(0, 1_440);
+    }
+    if let Some(v) = number_formatting {
+        settings.number_formatting = normalize_number_formatting(&v);
+    }
+    if let Some(v) = number_locale {
+        settings.number_locale = normalize_number_locale(&v);
+    }
+    if let Some(v) = utterance_separator {
+        settings.utterance_separator = normalize_utterance_separator(&v);
+    }
+    if let Some(v) = utterance_separator_custom {
+        settings.utterance_separator_custom = v;
+    }
+    if let Some(v) = injection_stream_chunk_chars {
+        settings.injection_stream_chunk_chars = v.clamp(0, 400);
+    }
+    if let Some(v) = injection_stream_interval_ms {
+        settings.injection_stream_interval_ms = v.clamp(0, 200);
+    }
     settings.normalize();
 
     let global_shortcut = app.global_shortcut();
@@ -949,6 +1291,12 @@ pub async fn set_runtime_settings(
     }
 
     sync_runtime_with_settings(state.engine.as_ref(), &settings, RuntimeEnvMode::Overwrite);
+    state
+        .transformer
+        .set_redaction_policy(settings.redaction_policy());
+    state
+        .transformer
+        .set_number_format(settings.number_format());
     save_settings(&state.settings_path, &settings).map_err(|e| e.to_string())?;
     state.store.prune_history(settings.retention_days)?;
     Ok(settings.runtime_settings())
@@ -986,6 +1334,37 @@ pub async fn get_diagnostics_bundle(
     })
 }
 
+/// Return the resolved engine config, model location, EP and env overrides.
+#[tauri::command]
+pub async fn get_effective_config(state: State<'_, AppState>) -> Result<EffectiveConfig, String> {
+    let settings = state.settings.lock().clone();
+    Ok(effective_config(&state.engine, &settings))
+}
+
+fn effective_config(engine: &DictumEngine, settings: &AppSettings) -> EffectiveConfig {
+    let env_overrides = std::env::vars()
+        .filter(|(key, _)| key.starts_with("DICTUM_"))
+        .map(|(key, value)| {
+            let value = if key.ends_with("_API_KEY") && !value.is_empty() {
+                "<redacted>".to_string()
+            } else {
+                value
+            };
+            (key, value)
+        })
+        .collect();
+    EffectiveConfig {
+        engine: engine.config(),
+        performance_profile: settings.performance_profile.clone(),
+        model_profile: settings.model_profile.clone(),
+        model_dir: resolved_models_dir().display().to_string(),
+        execution_provider: engine
+            .model_capabilities()
+            .and_then(|capabilities| capabilities.execution_provider),
+        env_overrides,
+    }
+}
+
 #[tauri::command]
 pub async fn get_privacy_settings(state: State<'_, AppState>) -> Result<PrivacySettings, String> {
     let settings = state.settings.lock();
@@ -1020,7 +1399,6 @@ pub async fn set_privacy_settings(
         } else {
             "local_only".into()
         };
-        settings.cloud_opt_in = settings.cloud_mode != "local_only";
     }
     settings.normalize();
     save_settings(&state.settings_path, &settings).map_err(|e| e.to_string())?;
@@ -1039,10 +1417,21 @@ pub async fn get_history(
     page: Option<usize>,
     page_size: Option<usize>,
     query: Option<String>,
-) -> Result<HistoryPage, String> {
-    state
-        .store
-        .get_history(page.unwrap_or(1), page_size.unwrap_or(50), query)
+    session_id: Option<String>,
+) -> Result<HistoryPage, StorageError> {
+    state.store.get_history(
+        page.unwrap_or(1),
+        page_size.unwrap_or(50),
+        query,
+        session_id,
+    )
+}
+
+#[tauri::command]
+pub async fn get_sessions(
+    state: State<'_, AppState>,
+) -> Result<Vec<DictationSession>, StorageError> {
+    state.store.get_sessions()
 }
 
 #[tauri::command]
@@ -1050,7 +1439,7 @@ pub async fn delete_history(
     state: State<'_, AppState>,
     ids: Option<Vec<String>>,
     older_than_days: Option<usize>,
-) -> Result<usize, String> {
+) -> Result<usize, StorageError> {
     state.store.delete_history(ids, older_than_days)
 }
 
@@ -1058,12 +1447,14 @@ pub async fn delete_history(
 pub async fn get_stats(
     state: State<'_, AppState>,
     range_days: Option<usize>,
-) -> Result<StatsPayload, String> {
+) -> Result<StatsPayload, StorageError> {
     state.store.get_stats(range_days.unwrap_or(30))
 }
 
 #[tauri::command]
-pub async fn get_dictionary(state: State<'_, AppState>) -> Result<Vec<DictionaryEntry>, String> {
+pub async fn get_dictionary(
+    state: State<'_, AppState>,
+) -> Result<Vec<DictionaryEntry>, StorageError> {
     state.store.list_dictionary()
 }
 
@@ -1071,21 +1462,21 @@ pub async fn get_dictionary(state: State<'_, AppState>) -> Result<Vec<Dictionary
 pub async fn upsert_dictionary(
     state: State<'_, AppState>,
     entry: DictionaryEntry,
-) -> Result<DictionaryEntry, String> {
+) -> Result<DictionaryEntry, StorageError> {
     let updated = state.store.upsert_dictionary(entry)?;
     state.transformer.refresh()?;
     Ok(updated)
 }
 
 #[tauri::command]
-pub async fn delete_dictionary(state: State<'_, AppState>, id: String) -> Result<(), String> {
+pub async fn delete_dictionary(state: State<'_, AppState>, id: String) -> Result<(), StorageError> {
     state.store.delete_dictionary(&id)?;
     state.transformer.refresh()?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_snippets(state: State<'_, AppState>) -> Result<Vec<SnippetEntry>, String> {
+pub async fn get_snippets(state: State<'_, AppState>) -> Result<Vec<SnippetEntry>, StorageError> {
     state.store.list_snippets()
 }
 
@@ -1093,14 +1484,14 @@ pub async fn get_snippets(state: State<'_, AppState>) -> Result<Vec<SnippetEntry
 pub async fn upsert_snippet(
     state: State<'_, AppState>,
     entry: SnippetEntry,
-) -> Result<SnippetEntry, String> {
+) -> Result<SnippetEntry, StorageError> {
     let updated = state.store.upsert_snippet(entry)?;
     state.transformer.refresh()?;
     Ok(updated)
 }
 
 #[tauri::command]
-pub async fn delete_snippet(state: State<'_, AppState>, id: String) -> Result<(), String> {
+pub async fn delete_snippet(state: State<'_, AppState>, id: String) -> Result<(), StorageError> {
     state.store.delete_snippet(&id)?;
     state.transformer.refresh()?;
     Ok(())
@@ -1109,9 +1500,11 @@ pub async fn delete_snippet(state: State<'_, AppState>, id: String) -> Result<()
 #[cfg(test)]
 mod tests {
     use super::{
-        normalize_repo_slug, parse_sha256_from_sums, select_checksums_asset,
-        select_installer_asset, version_tuple, GitHubAsset,
+        effective_config, median_ms, normalize_repo_slug, parse_sha256_from_sums,
+        select_checksums_asset, select_installer_asset, version_tuple, GitHubAsset,
     };
+    use crate::settings::{engine_config_for_settings, AppSettings};
+    use dictum_core::{inference::stub::StubModel, DictumEngine, ModelHandle};
 
     fn asset(name: &str) -> GitHubAsset {
         GitHubAsset {
@@ -1169,4 +1562,34 @@ mod tests {
         let parsed_reverse = parse_sha256_from_sums(&reverse, "Dictum_0.1.7_x64-setup.exe");
         assert_eq!(parsed_reverse.as_deref(), Some(hash));
     }
+
+    #[test]
+    fn median_ms_handles_odd_and_even_lengths() {
+        assert_eq!(median_ms(&[]), 0.0);
+        assert_eq!(median_ms(&[30.0, 10.0, 20.0]), 20.0);
+        assert_eq!(median_ms(&[40.0, 10.0, 30.0, 20.0]), 25.0);
+    }
+
+    #[test]
+    fn effective_config_reports_the_engine_it_was_built_with() {
+        let settings = AppSettings {
+            performance_profile: "latency_short_utterance".into(),
+            ..AppSettings::default()
+        };
+        let built = engine_config_for_settings(&settings);
+        let engine = DictumEngine::new(built.clone(), ModelHandle::new(StubModel::new()));
+
+        let effective = effective_config(&engine, &settings);
+        assert_eq!(
+            effective.engine.target_sample_rate,
+            built.target_sample_rate
+        );
+        assert_eq!(effective.engine.vad_threshold, built.vad_threshold);
+        assert_eq!(effective.performance_profile, "latency_short_utterance");
+        // Read from the warmed-up model, not the configured preference.
+        assert_eq!(effective.execution_provider, None);
+
+        let json = serde_json::to_value(&effective).unwrap();
+        assert_eq!(json["engine"]["target_sample_rate"], 16_000);
+    }
 }
@@ -0,0 +1,246 @@
+//! Dictation into a fixed set of named fields instead of the focused app.
+//!
+//! A [`FormTemplate`] lists the fields (e.g. name, address, notes). While a
+//! [`FormSession`] is active, each final is routed to a field rather than
+//! typed: an utterance that starts with a field's name or alias ("notes call
+//! back tomorrow") sets that field, anything else fills the current field and
+//! moves on to the next one, so a form can also be dictated in order. The
+//! result is the structured [`FormState`], emitted on `dictum://form`.
+
+use serde::{Deserialize, Serialize};
+
+/// Field list for form mode, parsed from JSON:
+/// `{"fields":[{"name":"name"},{"name":"notes","aliases":["note"]}]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormTemplate {
+    pub fields: Vec<FormFieldSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormFieldSpec {
+    /// Field key in the result, also accepted as a spoken field name.
+    pub name: String,
+    /// Other spoken names that select this field.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl FormTemplate {
+    /// Parse and validate a JSON template.
+    ///
+    /// # Errors
+    /// A message for invalid JSON, an empty field list, or a blank or
+    /// duplicate field name.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let template: Self =
+            serde_json::from_str(json).map_err(|e| format!("Invalid form template: {e}"))?;
+        if template.fields.is_empty() {
+            return Err("Form template needs at least one field.".into());
+        }
+        for (i, field) in template.fields.iter().enumerate() {
+            let name = field.name.trim();
+            if name.is_empty() {
+                return Err(format!("Form field {} has no name.", i + 1));
+            }
+            if template.fields[..i]
+                .iter()
+                .any(|f| f.name.trim().eq_ignore_ascii_case(name))
+            {
+                return Err(format!("Duplicate form field '{name}'."));
+            }
+        }
+        Ok(template)
+    }
+}
+
+/// Snapshot of a form being dictated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormState {
+    pub fields: Vec<FormFieldValue>,
+    /// Name of the field the next unnamed utterance goes to.
+    pub current: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormFieldValue {
+    pub name: String,
+    pub value: String,
+}
+
+/// Routes finals into the fields of a [`FormTemplate`].
+#[derive(Debug, Clone)]
+pub struct FormSession {
+    template: FormTemplate,
+    values: Vec<String>,
+    current: usize,
+}
+
+impl FormSession {
+    pub fn new(template: FormTemplate) -> Self {
+        let values = vec![String::new(); template.fields.len()];
+        Self {
+            template,
+            values,
+            current: 0,
+        }
+    }
+
+    /// Route one final.
+    ///
+    /// A leading field name replaces that field's value with the rest of
+    /// the utterance; said alone, it only selects the field. Otherwise the
+    /// text is appended to the current field. Either way a filled field
+    /// hands over to the next one, except for the last.
+    pub fn route(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        match self.match_field(text) {
+            Some((index, "")) => self.current = index,
+            Some((index, rest)) => {
+                self.values[index] = rest.to_string();
+                self.advance_from(index);
+            }
+            None => {
+                let index = self.current;
+                let value = &mut self.values[index];
+                if !value.is_empty() {
+                    value.push(' ');
+                }
+                value.push_str(text);
+                self.advance_from(index);
+            }
+        }
+    }
+
+    pub fn state(&self) -> FormState {
+        FormState {
+            fields: self
+                .template
+                .fields
+                .iter()
+                .zip(&self.values)
+                .map(|(field, value)| FormFieldValue {
+                    name: field.name.trim().to_string(),
+                    value: value.clone(),
+                })
+                .collect(),
+            current: self.template.fields[self.current].name.trim().to_string(),
+        }
+    }
+
+    fn advance_from(&mut self, index: usize) {
+        self.current = (index + 1).min(self.values.len() - 1);
+    }
+
+    /// The field named at the start of `text` and the text after the name.
+    /// The longest matching name wins, so "home address" beats "home".
+    fn match_field<'a>(&self, text: &'a str) -> Option<(usize, &'a str)> {
+        self.template
+            .fields
+            .iter()
+            .enumerate()
+            .flat_map(|(i, field)| {
+                std::iter::once(&field.name)
+                    .chain(&field.aliases)
+                    .map(move |name| (i, name.trim()))
+            })
+            .filter(|(_, name)| !name.is_empty())
+            .filter_map(|(i, name)| strip_spoken_name(text, name).map(|rest| (i, name.len(), rest)))
+            .max_by_key(|(_, len, _)| *len)
+            .map(|(i, _, rest)| (i, rest))
+    }
+}
+
+/// `text` after a leading `name` (case-insensitive, whole words), with the
+/// separators people say or the model writes after it ("name: ...",
+/// "name, ...", "name is ...") removed.
+fn strip_spoken_name<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let head = text.get(..name.len())?;
+    if !head.eq_ignore_ascii_case(name) {
+        return None;
+    }
+    let rest = &text[name.len()..];
+    if rest.chars().next().is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    let rest = rest.trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '-'));
+    let rest = match rest.get(..3) {
+        Some(is) if is.eq_ignore_ascii_case("is ") => &rest[3..],
+        _ => rest,
+    };
+    let rest = rest.trim();
+    // Only punctuation left ("Notes.") means the name was said alone.
+    Some(if rest.chars().all(|c| c.is_ascii_punctuation()) {
+        ""
+    } else {
+        rest
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact_form() -> FormSession {
+        let template = FormTemplate::from_json(
+            r#"{"fields":[{"name":"name"},{"name":"address"},{"name":"notes","aliases":["note"]}]}"#,
+        )
+        .expect("valid template");
+        FormSession::new(template)
+    }
+
+    fn value<'a>(state: &'a FormState, name: &str) -> &'a str {
+        &state
+            .fields
+            .iter()
+            .find(|f| f.name == name)
+            .expect("field exists")
+            .value
+    }
+
+    #[test]
+    fn spoken_field_names_route_to_their_fields() {
+        let mut form = contact_form();
+        form.route("Notes, call back tomorrow.");
+        form.route("name John Smith");
+
+        let state = form.state();
+        assert_eq!(value(&state, "name"), "John Smith");
+        assert_eq!(value(&state, "notes"), "call back tomorrow.");
+        assert_eq!(value(&state, "address"), "");
+        assert_eq!(state.current, "address");
+    }
+
+    #[test]
+    fn unnamed_utterances_fill_fields_in_order() {
+        let mut form = contact_form();
+        form.route("Jane Doe");
+        form.route("12 High Street");
+        form.route("Prefers email.");
+        form.route("Not urgent.");
+
+        let state = form.state();
+        assert_eq!(value(&state, "name"), "Jane Doe");
+        assert_eq!(value(&state, "address"), "12 High Street");
+        // The last field keeps collecting.
+        assert_eq!(value(&state, "notes"), "Prefers email. Not urgent.");
+
+        // A bare field name only moves the cursor; a name must be a whole word.
+        form.route("Name.");
+        form.route("Named later");
+        assert_eq!(value(&form.state(), "name"), "Jane Doe Named later");
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        assert!(FormTemplate::from_json(r#"{"fields":[]}"#).is_err());
+        assert!(FormTemplate::from_json(r#"{"fields":[{"name":" "}]}"#).is_err());
+        assert!(FormTemplate::from_json(r#"{"fields":[{"name":"a"},{"name":"A"}]}"#).is_err());
+    }
+}
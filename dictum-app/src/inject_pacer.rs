@@ -0,0 +1,221 @@
+//! Cadence control for typing finals into the target app.
+//!
+//! Slow editors drop characters when injections arrive back to back (e.g. a
+//! run of forced-flush continuation finals). The pacer keeps at least
+//! `min_interval` between injections; finals that arrive in the meantime are
+//! queued and typed together, in order, as one injection.
+//!
+//! A long injection can also be typed as a stream of word-aligned pieces
+//! ([`stream_chunks`]) so text appears progressively instead of all at once
+//! after a pause.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct InjectionPacer {
+    min_interval: Duration,
+    last_injection: Option<Instant>,
+    queue: VecDeque<String>,
+}
+
+impl InjectionPacer {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_injection: None,
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn set_min_interval(&mut self, min_interval: Duration) {
+        self.min_interval = min_interval;
+    }
+
+    /// Queue text for injection.
+    pub fn push(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if !text.is_empty() {
+            self.queue.push_back(text);
+        }
+    }
+
+    /// How long to wait before queued text may be injected. `None` when
+    /// nothing is queued, zero when it can go now.
+    pub fn wait_time(&self, now: Instant) -> Option<Duration> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        let wait = self
+            .last_injection
+            .map(|at| (at + self.min_interval).saturating_duration_since(now))
+            .unwrap_or_default();
+        Some(wait)
+    }
+
+    /// Take everything queued, joined in arrival order, if the interval has
+    /// elapsed. The caller is expected to inject the result right away.
+    pub fn take_ready(&mut self, now: Instant) -> Option<String> {
+        if self.wait_time(now)? > Duration::ZERO {
+            return None;
+        }
+        self.last_injection = Some(now);
+        Some(self.queue.drain(..).collect())
+    }
+
+    /// Restart the interval from `now`, for an injection that finished
+    /// later than it was taken (a streamed batch).
+    pub fn mark_injected(&mut self, now: Instant) {
+        self.last_injection = Some(now);
+    }
+}
+
+/// Split `text` into pieces of at most `max_chars` characters for streamed
+/// typing, breaking only where a word starts, so each piece ends with its
+/// trailing whitespace. A word longer than `max_chars` stays whole. The
+/// pieces concatenate back to `text`; `max_chars == 0` means one piece.
+pub fn stream_chunks(text: &str, max_chars: usize) -> Vec<&str> {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return vec![text];
+    }
+    let mut pieces = Vec::new();
+    let mut piece_start = 0;
+    let mut piece_chars = 0;
+    let mut word_start = 0;
+    let mut word_chars = 0;
+    let mut prev_was_space = false;
+    for (i, c) in text.char_indices() {
+        if prev_was_space && !c.is_whitespace() {
+            // `word_start..i` is a complete word plus its trailing space.
+            if piece_chars > 0 && piece_chars + word_chars > max_chars {
+                pieces.push(&text[piece_start..word_start]);
+                piece_start = word_start;
+                piece_chars = 0;
+            }
+            piece_chars += word_chars;
+            word_start = i;
+            word_chars = 0;
+        }
+        word_chars += 1;
+        prev_was_space = c.is_whitespace();
+    }
+    if piece_chars > 0 && piece_chars + word_chars > max_chars {
+        pieces.push(&text[piece_start..word_start]);
+        piece_start = word_start;
+    }
+    pieces.push(&text[piece_start..]);
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stream_chunks, InjectionPacer};
+    use std::time::{Duration, Instant};
+
+    /// Feed `(arrival_ms, text)` pairs through the pacer the way the
+    /// injection task does and return `(injected_at_ms, text)` pairs.
+    fn simulate(interval_ms: u64, arrivals: &[(u64, &str)]) -> Vec<(u64, String)> {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut pacer = InjectionPacer::new(Duration::from_millis(interval_ms));
+        let mut injected = Vec::new();
+        let mut pending = arrivals.iter().peekable();
+        let mut now_ms = 0;
+        loop {
+            while let Some(&&(arrival, text)) = pending.peek() {
+                if arrival > now_ms {
+                    break;
+                }
+                pacer.push(text);
+                pending.next();
+            }
+            if let Some(text) = pacer.take_ready(at(now_ms)) {
+                injected.push((now_ms, text));
+            }
+            let next_ready = pacer
+                .wait_time(at(now_ms))
+                .map(|wait| now_ms + wait.as_millis() as u64);
+            let next_arrival = pending.peek().map(|&&(arrival, _)| arrival);
+            now_ms = match (next_ready, next_arrival) {
+                (Some(a), Some(b)) => a.min(b),
+                (Some(a), None) | (None, Some(a)) => a,
+                (None, None) => return injected,
+            };
+        }
+    }
+
+    #[test]
+    fn bursts_are_spaced_by_min_interval() {
+        let injected = simulate(
+            50,
+            &[(0, "a "), (10, "b "), (20, "c "), (30, "d "), (200, "e ")],
+        );
+        let times: Vec<u64> = injected.iter().map(|(at, _)| *at).collect();
+        assert_eq!(times, vec![0, 50, 200]);
+        assert!(times.windows(2).all(|w| w[1] - w[0] >= 50));
+    }
+
+    #[test]
+    fn queued_finals_coalesce_in_arrival_order() {
+        let injected = simulate(50, &[(0, "one "), (5, "two "), (6, "three "), (7, "four ")]);
+        assert_eq!(
+            injected,
+            vec![(0, "one ".to_string()), (50, "two three four ".to_string())]
+        );
+    }
+
+    #[test]
+    fn zero_interval_injects_immediately() {
+        let mut pacer = InjectionPacer::new(Duration::ZERO);
+        let now = Instant::now();
+        assert_eq!(pacer.wait_time(now), None);
+        pacer.push("hi ");
+        assert_eq!(pacer.take_ready(now).as_deref(), Some("hi "));
+        pacer.push("again ");
+        assert_eq!(pacer.take_ready(now).as_deref(), Some("again "));
+        assert_eq!(pacer.take_ready(now), None);
+    }
+
+    #[test]
+    fn long_text_streams_in_word_aligned_pieces() {
+        let text = "The quarterly report is attached, please review the revenue \
+                    figures before Thursday's meeting with the board. ";
+        let pieces = stream_chunks(text, 24);
+        assert!(pieces.len() > 3);
+        assert_eq!(pieces.concat(), text);
+        for piece in &pieces {
+            assert!(piece.chars().count() <= 24, "{piece:?}");
+            assert!(piece.ends_with(' '), "{piece:?} splits a word");
+        }
+        assert_eq!(pieces[0], "The quarterly report is ");
+    }
+
+    #[test]
+    fn stream_chunks_keeps_short_text_and_long_words_whole() {
+        assert_eq!(stream_chunks("short final ", 24), vec!["short final "]);
+        assert_eq!(stream_chunks("a b c d e f", 0), vec!["a b c d e f"]);
+        assert_eq!(
+            stream_chunks("see antidisestablishmentarianism now ", 10),
+            vec!["see ", "antidisestablishmentarianism ", "now "]
+        );
+        assert_eq!(
+            stream_chunks("über straße café", 7),
+            vec!["über ", "straße ", "café"]
+        );
+    }
+
+    #[test]
+    fn mark_injected_restarts_the_interval() {
+        let start = Instant::now();
+        let mut pacer = InjectionPacer::new(Duration::from_millis(50));
+        pacer.push("long final ");
+        assert!(pacer.take_ready(start).is_some());
+        // The streamed batch finished typing 120 ms later.
+        pacer.mark_injected(start + Duration::from_millis(120));
+        pacer.push("next ");
+        assert_eq!(
+            pacer.wait_time(start + Duration::from_millis(130)),
+            Some(Duration::from_millis(40))
+        );
+    }
+}
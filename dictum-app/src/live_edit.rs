@@ -0,0 +1,162 @@
+//! Span bookkeeping for the "continuous document" injection mode.
+//!
+//! In this mode the in-flight utterance is typed into the target field as
+//! partials arrive and revised in place: each update backspaces over the part
+//! of the previously injected text that changed and types the new tail. A
+//! final commits the span so the next utterance starts after it.
+//!
+//! This module only does the arithmetic. `text_injector::apply_edit` turns an
+//! [`EditOp`] into key presses.
+
+/// One in-place edit: delete `backspaces` characters left of the caret, then
+/// type `insert`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditOp {
+    pub backspaces: usize,
+    pub insert: String,
+}
+
+impl EditOp {
+    pub fn is_noop(&self) -> bool {
+        self.backspaces == 0 && self.insert.is_empty()
+    }
+}
+
+/// Tracks the uncommitted span injected for the current utterance.
+///
+/// Counts are in `char`s, which is what a Backspace press removes in the
+/// editors we target.
+#[derive(Debug, Clone, Default)]
+pub struct LiveDocument {
+    /// Text currently injected but not yet committed.
+    pending: String,
+    focus: Option<isize>,
+}
+
+impl LiveDocument {
+    /// Replace the pending span with the latest partial hypothesis.
+    pub fn update_partial(&mut self, text: &str) -> EditOp {
+        self.replace_pending(text.to_string())
+    }
+
+    /// Replace the pending span with the final text and commit it.
+    pub fn commit(&mut self, final_text: &str) -> EditOp {
+        let op = self.replace_pending(final_text.to_string());
+        self.pending.clear();
+        op
+    }
+
+    /// Erase the pending span (e.g. the final was suppressed).
+    pub fn abandon(&mut self) -> EditOp {
+        self.replace_pending(String::new())
+    }
+
+    /// Forget the pending span without editing the field. Used when focus
+    /// moved, since the caret is no longer where our text ends.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Reset if the foreground target changed since the last edit. Returns
+    /// `true` when a reset happened.
+    pub fn track_focus(&mut self, focus: Option<isize>) -> bool {
+        let changed = self.focus.is_some() && focus != self.focus;
+        if changed {
+            self.reset();
+        }
+        self.focus = focus;
+        changed
+    }
+
+    fn replace_pending(&mut self, next: String) -> EditOp {
+        let op = diff_spans(&self.pending, &next);
+        self.pending = next;
+        op
+    }
+}
+
+/// Minimal edit that turns `current` into `next` when the caret sits at the
+/// end of `current`.
+fn diff_spans(current: &str, next: &str) -> EditOp {
+    let shared_bytes = current
+        .char_indices()
+        .zip(next.chars())
+        .take_while(|((_, a), b)| a == b)
+        .last()
+        .map(|((idx, ch), _)| idx + ch.len_utf8())
+        .unwrap_or(0);
+    EditOp {
+        backspaces: current[shared_bytes..].chars().count(),
+        insert: next[shared_bytes..].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EditOp, LiveDocument};
+
+    fn op(backspaces: usize, insert: &str) -> EditOp {
+        EditOp {
+            backspaces,
+            insert: insert.to_string(),
+        }
+    }
+
+    #[test]
+    fn growing_partials_only_append() {
+        let mut doc = LiveDocument::default();
+        assert_eq!(doc.update_partial("hello"), op(0, "hello"));
+        assert_eq!(doc.update_partial("hello world"), op(0, " world"));
+        assert_eq!(doc.update_partial("hello world"), op(0, ""));
+        assert!(doc.update_partial("hello world").is_noop());
+    }
+
+    #[test]
+    fn revised_partial_backspaces_to_divergence_point() {
+        let mut doc = LiveDocument::default();
+        doc.update_partial("their going");
+        assert_eq!(doc.update_partial("they're going"), op(8, "y're going"));
+        assert_eq!(doc.pending, "they're going");
+    }
+
+    #[test]
+    fn commit_replaces_pending_and_starts_fresh_span() {
+        let mut doc = LiveDocument::default();
+        doc.update_partial("testing one two");
+        assert_eq!(
+            doc.commit("Testing, one, two. "),
+            op(15, "Testing, one, two. ")
+        );
+        assert_eq!(doc.pending, "");
+        // Next utterance never touches committed text.
+        assert_eq!(doc.update_partial("next"), op(0, "next"));
+    }
+
+    #[test]
+    fn abandon_erases_only_pending_span() {
+        let mut doc = LiveDocument::default();
+        doc.commit("kept ");
+        doc.update_partial("draft");
+        assert_eq!(doc.abandon(), op(5, ""));
+        assert!(doc.abandon().is_noop());
+    }
+
+    #[test]
+    fn counts_chars_not_bytes() {
+        let mut doc = LiveDocument::default();
+        doc.update_partial("café ol");
+        assert_eq!(doc.update_partial("café olé"), op(0, "é"));
+        assert_eq!(doc.update_partial("naïve"), op(8, "naïve"));
+    }
+
+    #[test]
+    fn focus_change_drops_pending_without_edits() {
+        let mut doc = LiveDocument::default();
+        assert!(!doc.track_focus(Some(1)));
+        doc.update_partial("half typed");
+        assert!(!doc.track_focus(Some(1)));
+        assert!(doc.track_focus(Some(2)));
+        assert_eq!(doc.pending, "");
+        assert_eq!(doc.update_partial("fresh"), op(0, "fresh"));
+    }
+}
@@ -12,7 +12,12 @@
 )]
 
 mod commands;
+mod form_mode;
+mod inject_pacer;
+mod live_edit;
+mod maintenance;
 mod model_profiles;
+mod session;
 mod settings;
 mod state;
 mod storage;
@@ -25,12 +30,15 @@ use std::sync::{
 };
 use std::time::{Duration, Instant};
 
+#[cfg(target_os = "windows")]
+use dictum_core::engine::PipelinePriority;
 use dictum_core::{
     inference::{stub::StubModel, ModelHandle},
-    ipc::events::SegmentKind,
-    DictumEngine,
+    ipc::events::{BoundaryKind, SegmentKind, UtteranceRevisions},
+    DictumEngine, DictumError,
 };
 use parking_lot::Mutex;
+use session::SessionTracker;
 use settings::{
     apply_runtime_env_from_settings, default_settings_path, engine_config_for_settings,
     load_settings, RuntimeEnvMode,
@@ -44,7 +52,7 @@ use tauri::{
 };
 use tauri_plugin_global_shortcut::ShortcutState;
 use tracing::info;
-use transform::TextTransform;
+use transform::{rescue_partial, TextTransform, UtteranceJoiner};
 
 const DEFAULT_GLOBAL_TOGGLE_SHORTCUT: &str = "Ctrl+Shift+Space";
 const TRAY_SHOW_HIDE_ID: &str = "tray_show_hide";
@@ -87,6 +95,23 @@ fn enforce_single_instance() -> Option<isize> {
     Some(0)
 }
 
+/// `SetThreadPriority` for the pipeline thread; the core crate has no
+/// Windows implementation of its own.
+#[cfg(target_os = "windows")]
+fn set_pipeline_thread_priority(priority: PipelinePriority) -> std::io::Result<bool> {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_ABOVE_NORMAL,
+    };
+
+    if priority == PipelinePriority::Normal {
+        return Ok(false);
+    }
+    if unsafe { SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_ABOVE_NORMAL) } == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(true)
+}
+
 fn toggle_engine_from_shortcut<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
     let state = app.state::<AppState>();
     let toggle_inflight = Arc::clone(&state.shortcut_toggle_inflight);
@@ -106,11 +131,24 @@ fn toggle_engine_from_shortcut<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
     let engine = Arc::clone(&state.engine);
     let preferred_device = state.preferred_input_device.lock().clone();
     let toggle_inflight_for_task = Arc::clone(&toggle_inflight);
+    let app_for_task = app.clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let should_start = !matches!(
-            engine.status(),
-            dictum_core::ipc::events::EngineStatus::Listening
-        );
+        let should_start = !engine.is_running();
+
+        if should_start {
+            if let Err(detail) = commands::ensure_microphone_permission() {
+                // No command caller to return the error to; show it in the UI.
+                let event = dictum_core::EngineStatusEvent {
+                    status: dictum_core::EngineStatus::Error,
+                    detail: Some(detail),
+                };
+                if let Err(e) = app_for_task.emit("dictum://status", &event) {
+                    tracing::warn!("emit status: {e}");
+                }
+                toggle_inflight_for_task.store(false, Ordering::SeqCst);
+                return;
+            }
+        }
 
         let result = if should_start {
             engine.start_with_device(preferred_device)
@@ -168,6 +206,36 @@ fn toggle_main_window<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
     }
 }
 
+/// How long exit waits for the pipeline's terminal flush, and then again for
+/// the forwarding tasks to pass on what it produced.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Budget for loading the speech model at startup. A model on stalled
+/// storage or a wedged GPU driver falls back to the stub instead of
+/// freezing launch.
+const MODEL_WARM_UP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Next event for a forwarding task. Once `shutdown` is set, returns what is
+/// still queued and then `None`, so the task drains instead of being cut off.
+async fn forward_next<T: Clone>(
+    rx: &mut tokio::sync::broadcast::Receiver<T>,
+    shutdown: &mut tokio::sync::watch::Receiver<bool>,
+) -> Option<Result<T, tokio::sync::broadcast::error::RecvError>> {
+    use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+    if !*shutdown.borrow() {
+        tokio::select! {
+            received = rx.recv() => return Some(received),
+            _ = shutdown.changed() => {}
+        }
+    }
+    match rx.try_recv() {
+        Ok(event) => Some(Ok(event)),
+        Err(TryRecvError::Lagged(n)) => Some(Err(RecvError::Lagged(n))),
+        Err(TryRecvError::Empty | TryRecvError::Closed) => None,
+    }
+}
+
 fn setup_system_tray<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
     let show_hide_item = MenuItem::with_id(
         app,
@@ -234,6 +302,47 @@ fn is_duplicate_transcript(
     }
 }
 
+/// Tell the user when a final's audio matches an earlier utterance, e.g. a
+/// second instance or a replayed recording. Text dedupe misses these when
+/// the transcripts differ by a word.
+fn warn_on_duplicate_audio(
+    engine: &DictumEngine,
+    store: &LocalStore,
+    fingerprint: dictum_core::AudioFingerprint,
+) {
+    let duplicate = match store.find_audio_duplicate(fingerprint) {
+        Ok(Some(item)) => item,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("audio fingerprint lookup failed: {e}");
+            return;
+        }
+    };
+    tracing::warn!(
+        %fingerprint,
+        duplicate_of = %duplicate.id,
+        "final audio closely matches an earlier utterance"
+    );
+    engine.report_status_detail(format!(
+        "This audio closely matches an earlier dictation from {}.",
+        duplicate.created_at
+    ));
+}
+
+/// Erase the live partial span, unless focus moved away from it: the caret
+/// is then somewhere else and backspaces would eat that field's text.
+fn abandon_live_span(document: &mut live_edit::LiveDocument) {
+    if document.track_focus(text_injector::foreground_target_id()) {
+        tracing::debug!("focus changed; left live partial span in place");
+    }
+    let edit = document.abandon();
+    if !edit.is_noop() {
+        if let Err(e) = text_injector::apply_edit(&edit) {
+            tracing::warn!("failed to erase live partial span: {e}");
+        }
+    }
+}
+
 fn apply_learned_corrections(
     text: &str,
     corrections: &[settings::LearnedCorrection],
@@ -371,19 +480,29 @@ fn main() {
         performance_profile = %app_settings.performance_profile,
         vad_threshold = config.vad_threshold,
         min_speech_samples = config.min_speech_samples,
-        max_speech_samples = config.max_speech_samples,
+        max_speech_samples = config.effective_max_speech_samples(),
         vad_hangover_frames = config.vad_hangover_frames,
         enable_partial_inference = config.enable_partial_inference,
         silero_vad_threshold = config.silero_vad_threshold,
         "engine performance profile applied"
     );
-    let engine = Arc::new(DictumEngine::new(config, model));
-
     // Warm up the model before Tauri starts.
     // StubModel is a no-op; OnnxModel loads sessions on this call.
-    if let Err(e) = engine.warm_up() {
-        tracing::error!("model warm-up failed: {e}");
+    let mut engine = DictumEngine::new(config.clone(), model);
+    match engine.warm_up_with_timeout(MODEL_WARM_UP_TIMEOUT, None) {
+        Ok(_) => {}
+        Err(e @ DictumError::WarmUpTimeout(_)) => {
+            tracing::error!("{e} — using StubModel");
+            engine = DictumEngine::new(config, ModelHandle::new(StubModel::new()));
+            if let Err(e) = engine.warm_up() {
+                tracing::error!("stub warm-up failed: {e}");
+            }
+        }
+        Err(e) => tracing::error!("model warm-up failed: {e}"),
     }
+    let engine = Arc::new(engine);
+    #[cfg(target_os = "windows")]
+    engine.set_thread_priority_fn(set_pipeline_thread_priority);
 
     let store = Arc::new(
         LocalStore::new(LocalStore::default_db_path())
@@ -392,14 +511,29 @@ fn main() {
     if let Err(e) = store.prune_history(app_settings.retention_days) {
         tracing::warn!("history prune failed at startup: {e}");
     }
+    match store.maintenance() {
+        Ok(report) => tracing::debug!(
+            vacuumed = report.vacuumed,
+            page_count = report.page_count,
+            freelist_pages = report.freelist_pages,
+            "local store maintenance complete"
+        ),
+        Err(e) => tracing::warn!("local store maintenance failed at startup: {e}"),
+    }
     let transformer = Arc::new(TextTransform::new(Arc::clone(&store)));
     if let Err(e) = transformer.refresh() {
         tracing::warn!("failed to preload dictionary/snippets cache: {e}");
     }
+    transformer.set_redaction_policy(app_settings.redaction_policy());
+    transformer.set_number_format(app_settings.number_format());
     let settings_state = Arc::new(Mutex::new(app_settings.clone()));
 
     // ── Tauri app ─────────────────────────────────────────────────────────
     let engine_for_setup = Arc::clone(&engine);
+    let engine_for_exit = Arc::clone(&engine);
+    let (forward_shutdown_tx, forward_shutdown_rx) = tokio::sync::watch::channel(false);
+    let forwarders = Arc::new(Mutex::new(Vec::new()));
+    let forwarders_for_setup = Arc::clone(&forwarders);
     let toggle_debounce = Arc::new(Mutex::new(None::<Instant>));
     let toggle_debounce_for_handler = Arc::clone(&toggle_debounce);
     let global_shortcut_plugin = tauri_plugin_global_shortcut::Builder::new()
@@ -444,6 +578,10 @@ fn main() {
     let duplicate_final_suppressed_for_setup = Arc::clone(&duplicate_final_suppressed);
     let partial_rescues_used_for_setup = Arc::clone(&partial_rescues_used);
     let store_for_setup = Arc::clone(&store);
+    let store_for_maintenance = Arc::clone(&store);
+    let settings_for_maintenance = Arc::clone(&settings_state);
+    let sessions = Arc::new(SessionTracker::new(Arc::clone(&store)));
+    let sessions_for_setup = Arc::clone(&sessions);
     let transformer_for_setup = Arc::clone(&transformer);
     let settings_for_setup = Arc::clone(&settings_state);
     let learned_corrections_for_setup = Arc::new(parking_lot::RwLock::new(
@@ -452,6 +590,8 @@ fn main() {
     let learned_corrections_for_loop = Arc::clone(&learned_corrections_for_setup);
     let perf_metrics = Arc::new(Mutex::new(PerfMetrics::default()));
     let perf_metrics_for_setup = Arc::clone(&perf_metrics);
+    let form_mode = Arc::new(Mutex::new(None::<form_mode::FormSession>));
+    let form_mode_for_setup = Arc::clone(&form_mode);
 
     tauri::Builder::default()
         .plugin(global_shortcut_plugin)
@@ -463,7 +603,73 @@ fn main() {
             // Use tauri::async_runtime::spawn to share Tauri's Tokio runtime.
 
             let shared_speech_end = Arc::new(Mutex::new(None::<Instant>));
-            let shared_speech_end_for_activity = Arc::clone(&shared_speech_end);
+            let shared_speech_end_for_boundaries = Arc::clone(&shared_speech_end);
+
+            // Final-only injection runs on its own task so the pacer can hold
+            // finals back without stalling transcript forwarding.
+            let (inject_tx, mut inject_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+            let inject_calls_clone = Arc::clone(&inject_calls_for_setup);
+            let inject_success_clone = Arc::clone(&inject_success_for_setup);
+            let settings_for_injector = Arc::clone(&settings_for_setup);
+            let perf_metrics_for_injector = Arc::clone(&perf_metrics_for_setup);
+            // Ends once the transcript task has drained and dropped `inject_tx`.
+            let injector = tauri::async_runtime::spawn(async move {
+                let mut pacer = inject_pacer::InjectionPacer::new(Duration::ZERO);
+                while let Some(text) = inject_rx.recv().await {
+                    pacer.push(text);
+                    while let Some(wait) = pacer.wait_time(Instant::now()) {
+                        if !wait.is_zero() {
+                            tokio::time::sleep(wait).await;
+                        }
+                        while let Ok(more) = inject_rx.try_recv() {
+                            pacer.push(more);
+                        }
+                        pacer.set_min_interval(Duration::from_millis(
+                            settings_for_injector.lock().injection_min_interval_ms,
+                        ));
+                        let Some(batch) = pacer.take_ready(Instant::now()) else {
+                            continue;
+                        };
+                        let (chunk_chars, chunk_interval) = {
+                            let settings = settings_for_injector.lock();
+                            (
+                                settings.injection_stream_chunk_chars,
+                                Duration::from_millis(settings.injection_stream_interval_ms),
+                            )
+                        };
+                        inject_calls_clone.fetch_add(1, Ordering::Relaxed);
+                        let inject_started = Instant::now();
+                        // Long finals are typed piece by piece so the start
+                        // shows up while the rest is still being sent. The
+                        // batch counts as one injection either way.
+                        let mut result = Ok(());
+                        for (i, piece) in inject_pacer::stream_chunks(&batch, chunk_chars)
+                            .into_iter()
+                            .enumerate()
+                        {
+                            if i > 0 && !chunk_interval.is_zero() {
+                                tokio::time::sleep(chunk_interval).await;
+                            }
+                            result = text_injector::inject_text(piece);
+                            if result.is_err() {
+                                break;
+                            }
+                        }
+                        if let Err(e) = result {
+                            tracing::warn!("text injection failed: {e}");
+                        } else {
+                            inject_success_clone.fetch_add(1, Ordering::Relaxed);
+                        }
+                        // The min interval counts from the last typed piece.
+                        pacer.mark_injected(Instant::now());
+                        let inject_elapsed_ms = inject_started.elapsed().as_secs_f64() * 1000.0;
+                        perf_metrics_for_injector
+                            .lock()
+                            .record_inject(inject_elapsed_ms);
+                    }
+                }
+            });
+            forwarders_for_setup.lock().push(injector);
 
             let mut transcript_rx = engine_for_setup.subscribe_transcripts();
             let handle1 = app_handle.clone();
@@ -475,17 +681,29 @@ fn main() {
                 Arc::clone(&duplicate_final_suppressed_for_setup);
             let partial_rescues_used_clone = Arc::clone(&partial_rescues_used_for_setup);
             let store_clone = Arc::clone(&store_for_setup);
+            let sessions_clone = Arc::clone(&sessions_for_setup);
             let transformer_clone = Arc::clone(&transformer_for_setup);
             let settings_clone = Arc::clone(&settings_for_setup);
             let learned_corrections_clone = Arc::clone(&learned_corrections_for_loop);
             let perf_metrics_clone = Arc::clone(&perf_metrics_for_setup);
+            let form_mode_clone = Arc::clone(&form_mode_for_setup);
+            let engine_clone = Arc::clone(&engine_for_setup);
             let mut last_injected_text: Option<(String, Instant)> = None;
             let mut last_partial_text: Option<(String, Instant)> = None;
-            tauri::async_runtime::spawn(async move {
+            let mut live_document = live_edit::LiveDocument::default();
+            let mut utterance_revisions = UtteranceRevisions::default();
+            let mut utterance_joiner = UtteranceJoiner::default();
+            let mut joiner_session: Option<String> = None;
+            let mut shutdown_rx = forward_shutdown_rx.clone();
+            let transcripts = tauri::async_runtime::spawn(async move {
                 let mut last_perf_log = Instant::now();
-                loop {
-                    match transcript_rx.recv().await {
+                while let Some(received) = forward_next(&mut transcript_rx, &mut shutdown_rx).await {
+                    match received {
                         Ok(mut event) => {
+                            if !utterance_revisions.accept(&event) {
+                                tracing::debug!(seq = event.seq, "dropped stale transcript update");
+                                continue;
+                            }
                             let partial_text = event
                                 .segments
                                 .iter()
@@ -494,6 +712,42 @@ fn main() {
                                 .filter(|text| !text.is_empty())
                                 .collect::<Vec<_>>()
                                 .join(" ");
+                            // Partials reach the UI and, in continuous mode, the
+                            // document: mask them like finals. The raw text is
+                            // kept for a rescue, which is transformed then.
+                            let mut shown_partial_parts = Vec::new();
+                            for segment in event
+                                .segments
+                                .iter_mut()
+                                .filter(|segment| segment.kind == SegmentKind::Partial)
+                            {
+                                segment.text = transformer_clone.apply(&segment.text).text;
+                                if !segment.text.is_empty() {
+                                    shown_partial_parts.push(segment.text.clone());
+                                }
+                            }
+                            let shown_partial_text = shown_partial_parts.join(" ");
+                            let form_active = form_mode_clone.lock().is_some();
+                            let continuous_document = !form_active
+                                && settings_clone.lock().injection_mode == "continuous_document";
+                            if continuous_document {
+                                if live_document
+                                    .track_focus(text_injector::foreground_target_id())
+                                {
+                                    tracing::debug!("focus changed; dropped live partial span");
+                                }
+                                if !shown_partial_text.is_empty()
+                                    && !is_redacted_transcript(&shown_partial_text)
+                                {
+                                    let edit = live_document.update_partial(&shown_partial_text);
+                                    if !edit.is_noop() {
+                                        if let Err(e) = text_injector::apply_edit(&edit) {
+                                            tracing::warn!("live partial edit failed: {e}");
+                                            live_document.reset();
+                                        }
+                                    }
+                                }
+                            }
                             if !partial_text.is_empty() {
                                 last_partial_text = Some((partial_text, Instant::now()));
                             }
@@ -513,6 +767,12 @@ fn main() {
                                     &corrections_snapshot,
                                 );
                                 let transformed = transformer_clone.apply(corrected_text.trim());
+                                if transformed.redactions > 0 {
+                                    tracing::debug!(
+                                        count = transformed.redactions,
+                                        "masked PII in final transcript"
+                                    );
+                                }
                                 if !transformed.text.is_empty() {
                                     segment.text = transformed.text.clone();
                                     final_text_parts.push(transformed.text);
@@ -541,12 +801,17 @@ fn main() {
                                         "skipping injection for redacted transcript output"
                                     );
                                 } else if final_text.eq_ignore_ascii_case("[speech captured]") {
-                                    if let Some((partial, at)) = &last_partial_text {
-                                        if at.elapsed() <= Duration::from_secs(10)
-                                            && !partial.trim().is_empty()
-                                            && !is_redacted_transcript(partial)
-                                        {
-                                            final_text = partial.trim().to_string();
+                                    if let Some(partial) = &last_partial_text {
+                                        let rescued = rescue_partial(
+                                            &transformer_clone,
+                                            partial,
+                                            Duration::from_secs(10),
+                                        )
+                                        .filter(|rescued| !is_redacted_transcript(&rescued.text));
+                                        if let Some(rescued) = rescued {
+                                            final_text = rescued.text;
+                                            dictionary_applied |= rescued.dictionary_applied;
+                                            snippet_applied |= rescued.snippet_applied;
                                             used_partial_rescue = true;
                                             partial_rescues_used_clone
                                                 .fetch_add(1, Ordering::Relaxed);
@@ -587,27 +852,73 @@ fn main() {
                                         tracing::warn!(
                                             "skipping duplicate final transcript within dedupe window"
                                         );
+                                        if continuous_document {
+                                            abandon_live_span(&mut live_document);
+                                        }
                                         continue;
                                     }
-                                    let to_type = format!("{final_text} ");
-                                    inject_calls_clone.fetch_add(1, Ordering::Relaxed);
-                                    let inject_started = Instant::now();
-                                    if let Err(e) = text_injector::inject_text(&to_type) {
-                                        tracing::warn!("text injection failed: {e}");
-                                    } else {
-                                        inject_success_clone.fetch_add(1, Ordering::Relaxed);
+                                    let form_state = form_mode_clone.lock().as_mut().map(|form| {
+                                        form.route(&final_text);
+                                        form.state()
+                                    });
+                                    if let Some(form_state) = form_state {
+                                        // Form mode: fill a field instead of typing.
+                                        if let Err(e) = handle1.emit("dictum://form", &form_state) {
+                                            tracing::warn!("emit form: {e}");
+                                        }
                                         last_injected_text = Some((final_text.clone(), now));
+                                    } else {
+                                        let session_id = sessions_clone.current_id();
+                                        if session_id != joiner_session {
+                                            utterance_joiner.reset();
+                                            joiner_session = session_id;
+                                        }
+                                        utterance_joiner
+                                            .set_separator(settings_clone.lock().utterance_separator());
+                                        let to_type = utterance_joiner.join(&final_text);
+                                        if continuous_document {
+                                            // Commits must land in order with the live
+                                            // partial edits, so they are never paced.
+                                            inject_calls_clone.fetch_add(1, Ordering::Relaxed);
+                                            let inject_started = Instant::now();
+                                            // With focus moved, the final is typed
+                                            // fresh instead of replacing the span.
+                                            if live_document
+                                                .track_focus(text_injector::foreground_target_id())
+                                            {
+                                                tracing::debug!(
+                                                    "focus changed; typing final without live span"
+                                                );
+                                            }
+                                            if let Err(e) = text_injector::apply_edit(
+                                                &live_document.commit(&to_type),
+                                            ) {
+                                                tracing::warn!("text injection failed: {e}");
+                                            } else {
+                                                inject_success_clone.fetch_add(1, Ordering::Relaxed);
+                                                last_injected_text = Some((final_text.clone(), now));
+                                            }
+                                            let inject_elapsed_ms =
+                                                inject_started.elapsed().as_secs_f64() * 1000.0;
+                                            perf_metrics_clone.lock().record_inject(inject_elapsed_ms);
+                                        } else if inject_tx.send(to_type).is_ok() {
+                                            last_injected_text = Some((final_text.clone(), now));
+                                        }
                                     }
-                                    let inject_elapsed_ms =
-                                        inject_started.elapsed().as_secs_f64() * 1000.0;
-                                    perf_metrics_clone.lock().record_inject(inject_elapsed_ms);
                                     let latency_ms = shared_speech_end.lock().take().map(|t| t.elapsed().as_millis() as i64).unwrap_or(0);
-                                    let settings_guard = settings_clone.lock();
-                                    if settings_guard.history_enabled {
+                                    let (history_enabled, uses_cloud) = {
+                                        let settings = settings_clone.lock();
+                                        (settings.history_enabled, settings.uses_cloud())
+                                    };
+                                    if history_enabled {
                                         let persist_started = Instant::now();
+                                        let fingerprint = event.meta.audio_fingerprint;
+                                        if let Some(fingerprint) = fingerprint {
+                                            warn_on_duplicate_audio(&engine_clone, &store_clone, fingerprint);
+                                        }
                                         if let Err(e) = store_clone.insert_history(HistoryRecordInput {
                                             text: final_text.clone(),
-                                            source: if settings_guard.cloud_opt_in {
+                                            source: if uses_cloud {
                                                 "hybrid".into()
                                             } else {
                                                 "local".into()
@@ -615,6 +926,8 @@ fn main() {
                                             latency_ms,
                                             dictionary_applied,
                                             snippet_applied,
+                                            session_id: sessions_clone.current_id(),
+                                            fingerprint,
                                         }) {
                                             tracing::warn!("failed to persist history: {e}");
                                         }
@@ -624,6 +937,8 @@ fn main() {
                                             .lock()
                                             .record_persist(persist_elapsed_ms);
                                     }
+                                } else if continuous_document {
+                                    abandon_live_span(&mut live_document);
                                 }
                                 let finalize_elapsed_ms =
                                     finalize_started.elapsed().as_secs_f64() * 1000.0;
@@ -659,13 +974,17 @@ fn main() {
                     }
                 }
             });
+            forwarders_for_setup.lock().push(transcripts);
 
             let mut status_rx = engine_for_setup.subscribe_status();
             let handle2 = app_handle.clone();
-            tauri::async_runtime::spawn(async move {
-                loop {
-                    match status_rx.recv().await {
+            let sessions_for_status = Arc::clone(&sessions_for_setup);
+            let mut shutdown_rx = forward_shutdown_rx.clone();
+            let statuses = tauri::async_runtime::spawn(async move {
+                while let Some(received) = forward_next(&mut status_rx, &mut shutdown_rx).await {
+                    match received {
                         Ok(event) => {
+                            sessions_for_status.on_status(event.status);
                             if let Err(e) = handle2.emit("dictum://status", &event) {
                                 tracing::warn!("emit status: {e}");
                             }
@@ -677,18 +996,15 @@ fn main() {
                     }
                 }
             });
+            forwarders_for_setup.lock().push(statuses);
 
             let mut activity_rx = engine_for_setup.subscribe_activity();
             let handle3 = app_handle.clone();
-            tauri::async_runtime::spawn(async move {
-                let mut was_speech = false;
-                loop {
-                    match activity_rx.recv().await {
+            let mut shutdown_rx = forward_shutdown_rx.clone();
+            let activity = tauri::async_runtime::spawn(async move {
+                while let Some(received) = forward_next(&mut activity_rx, &mut shutdown_rx).await {
+                    match received {
                         Ok(event) => {
-                            if was_speech && !event.is_speech {
-                                *shared_speech_end_for_activity.lock() = Some(Instant::now());
-                            }
-                            was_speech = event.is_speech;
                             if let Err(e) = handle3.emit("dictum://activity", &event) {
                                 tracing::warn!("emit activity: {e}");
                             }
@@ -700,6 +1016,57 @@ fn main() {
                     }
                 }
             });
+            forwarders_for_setup.lock().push(activity);
+
+            let mut boundary_rx = engine_for_setup.subscribe_utterance_boundaries();
+            let handle4 = app_handle.clone();
+            let mut shutdown_rx = forward_shutdown_rx.clone();
+            let boundaries = tauri::async_runtime::spawn(async move {
+                while let Some(received) = forward_next(&mut boundary_rx, &mut shutdown_rx).await {
+                    match received {
+                        Ok(event) => {
+                            if event.kind == BoundaryKind::End {
+                                *shared_speech_end_for_boundaries.lock() = Some(Instant::now());
+                            }
+                            if let Err(e) = handle4.emit("dictum://utterance", &event) {
+                                tracing::warn!("emit utterance boundary: {e}");
+                            }
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                            tracing::warn!("utterance boundary receiver lagged by {n} events");
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            forwarders_for_setup.lock().push(boundaries);
+
+            // Retention is re-applied periodically so always-on instances
+            // do not keep history past its horizon until the next restart.
+            tauri::async_runtime::spawn(async move {
+                let interval_for = |settings: &Mutex<settings::AppSettings>| {
+                    maintenance::interval_from_minutes(
+                        settings.lock().maintenance_interval_minutes,
+                    )
+                };
+                let mut schedule = maintenance::MaintenanceSchedule::new(
+                    interval_for(&settings_for_maintenance),
+                    Instant::now(),
+                );
+                loop {
+                    tokio::time::sleep(schedule.wait_time(Instant::now())).await;
+                    schedule.set_interval(interval_for(&settings_for_maintenance));
+                    if !schedule.take_due(Instant::now()) {
+                        continue;
+                    }
+                    let retention_days = settings_for_maintenance.lock().retention_days;
+                    let store = Arc::clone(&store_for_maintenance);
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        maintenance::run_maintenance(&store, retention_days)
+                    })
+                    .await;
+                }
+            });
 
             ensure_pill_window(&app_handle)?;
 
@@ -723,30 +1090,41 @@ fn main() {
             store,
             transformer,
             perf_metrics,
+            form_mode,
         })
         .invoke_handler(tauri::generate_handler![
             commands::start_engine,
             commands::stop_engine,
+            commands::reset_session,
             commands::get_status,
+            commands::get_microphone_permission,
             commands::list_audio_devices,
             commands::set_preferred_input_device,
             commands::get_preferred_input_device,
             commands::get_runtime_settings,
+            commands::get_model_capabilities,
             commands::get_model_profile_catalog,
             commands::get_model_profile_recommendation,
             commands::check_for_app_update,
             commands::download_and_install_app_update,
             commands::run_auto_tune,
             commands::run_benchmark_auto_tune,
+            commands::run_model_benchmark,
+            commands::benchmark_vad,
+            commands::start_form_mode,
+            commands::stop_form_mode,
+            commands::get_form_state,
             commands::set_runtime_settings,
             commands::get_learned_corrections,
             commands::learn_correction,
             commands::delete_learned_correction,
             commands::get_perf_snapshot,
             commands::get_diagnostics_bundle,
+            commands::get_effective_config,
             commands::get_privacy_settings,
             commands::set_privacy_settings,
             commands::get_history,
+            commands::get_sessions,
             commands::delete_history,
             commands::get_stats,
             commands::get_dictionary,
@@ -756,6 +1134,27 @@ fn main() {
             commands::upsert_snippet,
             commands::delete_snippet,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running Tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building Tauri application")
+        .run(move |_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Let a final still in flight reach the UI and target app.
+                if !engine_for_exit.shutdown(SHUTDOWN_DRAIN_TIMEOUT) {
+                    tracing::warn!("pipeline did not finish before exit; its last final may be lost");
+                }
+                let _ = forward_shutdown_tx.send(true);
+                let tasks = std::mem::take(&mut *forwarders.lock());
+                let drained = tauri::async_runtime::block_on(tokio::time::timeout(
+                    SHUTDOWN_DRAIN_TIMEOUT,
+                    async move {
+                        for task in tasks {
+                            let _ = task.await;
+                        }
+                    },
+                ));
+                if drained.is_err() {
+                    tracing::warn!("event forwarding did not drain before exit");
+                }
+            }
+        });
 }
@@ -0,0 +1,111 @@
+//! Periodic history retention and database upkeep.
+//!
+//! Startup and settings changes already prune, but an instance left running
+//! for days would otherwise keep rows past their retention horizon. The
+//! schedule decides when the next pass is due; the background task in
+//! `main` sleeps until then and runs `prune_history` plus
+//! `LocalStore::maintenance` (WAL checkpoint, opportunistic `VACUUM`).
+
+use std::time::{Duration, Instant};
+
+use crate::storage::LocalStore;
+
+/// How long to wait before re-reading a disabled interval.
+const DISABLED_POLL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub struct MaintenanceSchedule {
+    /// `None` disables periodic runs.
+    interval: Option<Duration>,
+    last_run: Instant,
+}
+
+impl MaintenanceSchedule {
+    /// Start counting from `now`; the startup pass counts as the first run.
+    pub fn new(interval: Option<Duration>, now: Instant) -> Self {
+        Self {
+            interval,
+            last_run: now,
+        }
+    }
+
+    pub fn set_interval(&mut self, interval: Option<Duration>) {
+        self.interval = interval;
+    }
+
+    /// How long to sleep before polling again; zero when a run is due.
+    pub fn wait_time(&self, now: Instant) -> Duration {
+        match self.interval {
+            Some(interval) => (self.last_run + interval).saturating_duration_since(now),
+            None => DISABLED_POLL,
+        }
+    }
+
+    /// Whether a run is due at `now`. Marks it as started when it is.
+    pub fn take_due(&mut self, now: Instant) -> bool {
+        if self.interval.is_none() || !self.wait_time(now).is_zero() {
+            return false;
+        }
+        self.last_run = now;
+        true
+    }
+}
+
+/// `AppSettings::maintenance_interval_minutes` as a schedule interval.
+pub fn interval_from_minutes(minutes: u64) -> Option<Duration> {
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+}
+
+/// One maintenance pass; failures are logged, never fatal.
+pub fn run_maintenance(store: &LocalStore, retention_days: usize) {
+    match store.prune_history(retention_days) {
+        Ok(0) => {}
+        Ok(pruned) => tracing::info!(pruned, "pruned history past retention"),
+        Err(e) => tracing::warn!("scheduled history prune failed: {e}"),
+    }
+    match store.maintenance() {
+        Ok(report) => tracing::debug!(
+            vacuumed = report.vacuumed,
+            page_count = report.page_count,
+            freelist_pages = report.freelist_pages,
+            "scheduled local store maintenance complete"
+        ),
+        Err(e) => tracing::warn!("scheduled local store maintenance failed: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{interval_from_minutes, MaintenanceSchedule};
+
+    #[test]
+    fn maintenance_fires_once_per_interval() {
+        let start = Instant::now();
+        let mut schedule = MaintenanceSchedule::new(Some(Duration::from_secs(10)), start);
+
+        // Drive a fake clock forward one second at a time, as the task would
+        // after each sleep.
+        let mut fired = Vec::new();
+        for second in 1..=35 {
+            let now = start + Duration::from_secs(second);
+            if schedule.take_due(now) {
+                fired.push(second);
+            }
+        }
+        assert_eq!(fired, vec![10, 20, 30]);
+        assert_eq!(
+            schedule.wait_time(start + Duration::from_secs(35)),
+            Duration::from_secs(5)
+        );
+
+        // Disabling stops runs; re-enabling counts from the last run.
+        schedule.set_interval(None);
+        assert!(!schedule.take_due(start + Duration::from_secs(100)));
+        schedule.set_interval(interval_from_minutes(1));
+        assert!(!schedule.take_due(start + Duration::from_secs(89)));
+        assert!(schedule.take_due(start + Duration::from_secs(90)));
+        assert_eq!(interval_from_minutes(0), None);
+    }
+}
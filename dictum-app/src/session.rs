@@ -0,0 +1,130 @@
+//! Groups history into dictation sessions, one per start→stop run.
+//!
+//! Driven by engine status events: the first `Listening` opens a session,
+//! `Stopped`/`Error` closes it. Later `Listening` events that only carry a
+//! health notice do not start a new one. The closed session stays current
+//! until the next start, so a final that is processed after the stop status
+//! (the two arrive on separate channels) still lands in its own session.
+
+use std::sync::Arc;
+
+use dictum_core::EngineStatus;
+use parking_lot::Mutex;
+
+use crate::storage::LocalStore;
+
+#[derive(Debug, Default)]
+struct SessionState {
+    current: Option<String>,
+    open: bool,
+}
+
+pub struct SessionTracker {
+    store: Arc<LocalStore>,
+    state: Mutex<SessionState>,
+}
+
+impl SessionTracker {
+    pub fn new(store: Arc<LocalStore>) -> Self {
+        Self {
+            store,
+            state: Mutex::new(SessionState::default()),
+        }
+    }
+
+    pub fn on_status(&self, status: EngineStatus) {
+        let mut state = self.state.lock();
+        match status {
+            EngineStatus::Listening if !state.open => match self.store.start_session(None) {
+                Ok(session) => {
+                    tracing::debug!(session_id = %session.id, "dictation session started");
+                    state.current = Some(session.id);
+                    state.open = true;
+                }
+                Err(e) => tracing::warn!("failed to start dictation session: {e}"),
+            },
+            EngineStatus::Stopped | EngineStatus::Error if state.open => {
+                state.open = false;
+                if let Some(id) = state.current.as_deref() {
+                    if let Err(e) = self.store.end_session(id) {
+                        tracing::warn!("failed to close dictation session: {e}");
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Session new history rows belong to.
+    pub fn current_id(&self) -> Option<String> {
+        self.state.lock().current.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dictum_core::EngineStatus;
+
+    use super::SessionTracker;
+    use crate::storage::{HistoryRecordInput, LocalStore};
+
+    fn record(store: &LocalStore, tracker: &SessionTracker, text: &str) {
+        store
+            .insert_history(HistoryRecordInput {
+                text: text.into(),
+                source: "local".into(),
+                latency_ms: 0,
+                dictionary_applied: false,
+                snippet_applied: false,
+                session_id: tracker.current_id(),
+                fingerprint: None,
+            })
+            .expect("insert history");
+    }
+
+    #[test]
+    fn utterances_in_one_start_stop_cycle_share_a_session() {
+        let db_path = std::env::temp_dir().join(format!(
+            "dictum-session-test-{}-{}.db",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+        let store = Arc::new(LocalStore::new(db_path.clone()).expect("create store"));
+        let tracker = SessionTracker::new(Arc::clone(&store));
+
+        tracker.on_status(EngineStatus::Listening);
+        record(&store, &tracker, "first note");
+        // Health notices re-send `Listening`; still the same session.
+        tracker.on_status(EngineStatus::Listening);
+        record(&store, &tracker, "second note");
+        tracker.on_status(EngineStatus::Stopped);
+        // The last final can be processed after the stop status.
+        record(&store, &tracker, "trailing note");
+
+        tracker.on_status(EngineStatus::Listening);
+        record(&store, &tracker, "next run");
+        tracker.on_status(EngineStatus::Stopped);
+
+        let sessions = store.get_sessions().expect("list sessions");
+        assert_eq!(sessions.len(), 2);
+        let (second, first) = (&sessions[0], &sessions[1]);
+        assert_eq!(first.utterances, 3);
+        assert_eq!(second.utterances, 1);
+        assert!(first.ended_at.is_some() && second.ended_at.is_some());
+
+        let page = store
+            .get_history(1, 50, None, Some(first.id.clone()))
+            .expect("session history");
+        assert_eq!(page.total, 3);
+        assert!(page
+            .items
+            .iter()
+            .all(|item| item.session_id.as_deref() == Some(first.id.as_str())));
+        let all = store.get_history(1, 50, None, None).expect("all history");
+        assert_eq!(all.total, 4);
+
+        let _ = std::fs::remove_file(db_path);
+    }
+}
@@ -3,8 +3,29 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use dictum_core::{engine::EngineConfig, DictumEngine};
+use dictum_core::{
+    engine::{
+        ActivityBins, ActivityLevel, DebugAudioTap, EngineConfig, PausePunctuation,
+        PipelinePriority,
+    },
+    numerals::{NumberFormat, NumberSeparators},
+    redact::RedactionPolicy,
+    DictumEngine,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::transform::UtteranceSeparator;
+
+/// Layout version of the settings file this build writes. Bump it and add
+/// a step to `MIGRATIONS` whenever a field is renamed, removed or changes
+/// meaning.
+pub const SETTINGS_SCHEMA_VERSION: u32 = 2;
+
+/// `MIGRATIONS[n - 1]` upgrades a version `n` settings document to `n + 1`.
+/// Steps work on the raw JSON, before it is deserialized.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v1_to_v2];
+const _: () = assert!(MIGRATIONS.len() == SETTINGS_SCHEMA_VERSION as usize - 1);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +39,9 @@ pub struct LearnedCorrection {
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
 pub struct AppSettings {
+    /// Layout version the file was written with; files from before it was
+    /// recorded are version 1. See [`migrate_settings`].
+    pub schema_version: u32,
     pub preferred_input_device: Option<String>,
     pub model_profile: String,
     pub performance_profile: String,
@@ -42,11 +66,30 @@ pub struct AppSettings {
     pub history_enabled: bool,
     pub retention_days: usize,
     pub learned_corrections: Vec<LearnedCorrection>,
+    pub injection_mode: String,
+    pub injection_min_interval_ms: u64,
+    pub redact_pii: bool,
+    /// Minutes between background retention prunes; 0 disables them.
+    pub maintenance_interval_minutes: u64,
+    /// Spelled-out number formatting: `off`, `conservative` or `all`.
+    pub number_formatting: String,
+    /// Locale whose digit separators formatted numbers use, e.g. `de-DE`.
+    pub number_locale: String,
+    /// Text between injected finals: `space`, `newline`, `double_newline`
+    /// or `custom` (uses `utterance_separator_custom`).
+    pub utterance_separator: String,
+    pub utterance_separator_custom: String,
+    /// Type finals longer than this many characters in word-aligned pieces;
+    /// 0 types every final in one go.
+    pub injection_stream_chunk_chars: usize,
+    /// Pause between streamed pieces of one final, in ms.
+    pub injection_stream_interval_ms: u64,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
             preferred_input_device: None,
             model_profile: "distil-large-v3".into(),
             performance_profile: "whisper_balanced_english".into(),
@@ -71,6 +114,16 @@ impl Default for AppSettings {
             history_enabled: true,
             retention_days: 90,
             learned_corrections: Vec::new(),
+            injection_mode: "final_only".into(),
+            injection_min_interval_ms: 0,
+            redact_pii: false,
+            maintenance_interval_minutes: 60,
+            number_formatting: "off".into(),
+            number_locale: "en-US".into(),
+            utterance_separator: "space".into(),
+            utterance_separator_custom: String::new(),
+            injection_stream_chunk_chars: 0,
+            injection_stream_interval_ms: 12,
         }
     }
 }
@@ -101,6 +154,16 @@ pub struct RuntimeSettings {
     pub history_enabled: bool,
     pub retention_days: usize,
     pub correction_count: usize,
+    pub injection_mode: String,
+    pub injection_min_interval_ms: u64,
+    pub redact_pii: bool,
+    pub maintenance_interval_minutes: u64,
+    pub number_formatting: String,
+    pub number_locale: String,
+    pub utterance_separator: String,
+    pub utterance_separator_custom: String,
+    pub injection_stream_chunk_chars: usize,
+    pub injection_stream_interval_ms: u64,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -129,19 +192,9 @@ impl AppSettings {
             .as_ref()
             .map(|k| k.trim().to_string())
             .filter(|k| !k.is_empty());
-        let inferred_cloud_mode = if self.cloud_mode.trim().is_empty()
-            || (self.cloud_mode.trim().eq_ignore_ascii_case("local_only") && self.cloud_opt_in)
-        {
-            if self.cloud_opt_in {
-                "hybrid"
-            } else {
-                "local_only"
-            }
-        } else {
-            self.cloud_mode.as_str()
-        };
-        self.cloud_mode = normalize_cloud_mode(inferred_cloud_mode);
-        self.cloud_opt_in = self.cloud_mode != "local_only";
+        self.cloud_mode = normalize_cloud_mode(&self.cloud_mode);
+        // Read-only mirror of `cloud_mode` for callers of the v1 field.
+        self.cloud_opt_in = self.uses_cloud();
         if self.performance_profile == "whisper_balanced_english" {
             self.language_hint = "english".into();
         }
@@ -152,6 +205,21 @@ impl AppSettings {
             .as_ref()
             .map(|d| d.trim().to_string())
             .filter(|d| !d.is_empty());
+        self.injection_mode = normalize_injection_mode(&self.injection_mode);
+        self.injection_min_interval_ms = self.injection_min_interval_ms.clamp(0, 2_000);
+        self.maintenance_interval_minutes = self.maintenance_interval_minutes.clamp(0, 1_440);
+        self.number_formatting = normalize_number_formatting(&self.number_formatting);
+        self.number_locale = normalize_number_locale(&self.number_locale);
+        self.utterance_separator = normalize_utterance_separator(&self.utterance_separator);
+        self.utterance_separator_custom =
+            self.utterance_separator_custom.chars().take(64).collect();
+        self.injection_stream_chunk_chars = self.injection_stream_chunk_chars.clamp(0, 400);
+        self.injection_stream_interval_ms = self.injection_stream_interval_ms.clamp(0, 200);
+    }
+
+    /// Whether `cloud_mode` lets transcripts reach the cloud.
+    pub fn uses_cloud(&self) -> bool {
+        self.cloud_mode != "local_only"
     }
 
     pub fn runtime_settings(&self) -> RuntimeSettings {
@@ -179,52 +247,131 @@ impl AppSettings {
             history_enabled: self.history_enabled,
             retention_days: self.retention_days,
             correction_count: self.learned_corrections.len(),
+            injection_mode: self.injection_mode.clone(),
+            injection_min_interval_ms: self.injection_min_interval_ms,
+            redact_pii: self.redact_pii,
+            maintenance_interval_minutes: self.maintenance_interval_minutes,
+            number_formatting: self.number_formatting.clone(),
+            number_locale: self.number_locale.clone(),
+            utterance_separator: self.utterance_separator.clone(),
+            utterance_separator_custom: self.utterance_separator_custom.clone(),
+            injection_stream_chunk_chars: self.injection_stream_chunk_chars,
+            injection_stream_interval_ms: self.injection_stream_interval_ms,
         }
     }
-}
 
-pub fn apply_engine_profile(config: &mut EngineConfig, profile: &str) {
-    match profile {
-        "whisper_balanced_english" => {
-            // Higher whisper sensitivity while keeping enough hangover for quiet tails.
-            config.vad_threshold = 0.00125;
-            config.min_speech_samples = 460;
-            config.max_speech_samples = 96_000;
-            config.vad_hangover_frames = 7;
-            config.enable_partial_inference = true;
-            config.silero_vad_threshold = 0.045;
-        }
-        "latency_short_utterance" => {
-            config.vad_threshold = 0.00195;
-            config.min_speech_samples = 420;
-            config.max_speech_samples = 72_000;
-            config.vad_hangover_frames = 3;
-            config.enable_partial_inference = true;
-            config.silero_vad_threshold = 0.065;
-        }
-        "balanced_general" => {
-            config.vad_threshold = 0.0017;
-            config.min_speech_samples = 600;
-            config.max_speech_samples = 88_000;
-            config.vad_hangover_frames = 4;
-            config.enable_partial_inference = true;
-            config.silero_vad_threshold = 0.058;
+    /// PII masking applied to finals before injection and history.
+    pub fn redaction_policy(&self) -> RedactionPolicy {
+        if self.redact_pii {
+            RedactionPolicy::all()
+        } else {
+            RedactionPolicy::default()
         }
-        _ => {
-            // stability_long_form (default)
-            config.vad_threshold = 0.00145;
-            config.min_speech_samples = 520;
-            config.max_speech_samples = 104_000;
-            config.vad_hangover_frames = 6;
-            config.enable_partial_inference = true;
-            config.silero_vad_threshold = 0.052;
+    }
+
+    /// Separator typed between consecutive finals. An empty custom
+    /// separator falls back to a space.
+    pub fn utterance_separator(&self) -> UtteranceSeparator {
+        match self.utterance_separator.as_str() {
+            "newline" => UtteranceSeparator::Newline,
+            "double_newline" => UtteranceSeparator::DoubleNewline,
+            "custom" if !self.utterance_separator_custom.is_empty() => {
+                UtteranceSeparator::Custom(self.utterance_separator_custom.clone())
+            }
+            _ => UtteranceSeparator::Space,
         }
     }
+
+    /// Spelled-out number formatting applied to finals, if enabled.
+    pub fn number_format(&self) -> Option<NumberFormat> {
+        let conservative = match self.number_formatting.as_str() {
+            "conservative" => true,
+            "all" => false,
+            _ => return None,
+        };
+        Some(NumberFormat {
+            conservative,
+            separators: NumberSeparators::for_locale(&self.number_locale),
+        })
+    }
+}
+
+/// Engine tuning per performance profile, as serialized `EngineConfig`
+/// overlays on the defaults. The first entry is the fallback.
+const ENGINE_PRESETS: &[(&str, &str)] = &[
+    // Default: held tails for long-form dictation.
+    (
+        "stability_long_form",
+        r#"{"vad_threshold": 0.00145, "min_speech_samples": 520, "max_speech_samples": 104000,
+            "vad_hangover_frames": 6, "enable_partial_inference": true,
+            "silero_vad_threshold": 0.052}"#,
+    ),
+    // Higher whisper sensitivity while keeping enough hangover for quiet tails.
+    (
+        "whisper_balanced_english",
+        r#"{"vad_threshold": 0.00125, "min_speech_samples": 460, "max_speech_samples": 96000,
+            "vad_hangover_frames": 7, "enable_partial_inference": true,
+            "silero_vad_threshold": 0.045}"#,
+    ),
+    (
+        "latency_short_utterance",
+        r#"{"vad_threshold": 0.00195, "min_speech_samples": 420, "max_speech_samples": 72000,
+            "vad_hangover_frames": 3, "enable_partial_inference": true,
+            "silero_vad_threshold": 0.065}"#,
+    ),
+    (
+        "balanced_general",
+        r#"{"vad_threshold": 0.0017, "min_speech_samples": 600, "max_speech_samples": 88000,
+            "vad_hangover_frames": 4, "enable_partial_inference": true,
+            "silero_vad_threshold": 0.058}"#,
+    ),
+];
+
+/// The engine preset for `profile`, falling back to `stability_long_form`.
+pub fn engine_preset(profile: &str) -> EngineConfig {
+    let (_, json) = ENGINE_PRESETS
+        .iter()
+        .find(|(name, _)| *name == profile)
+        .unwrap_or(&ENGINE_PRESETS[0]);
+    serde_json::from_str(json).expect("built-in engine presets are valid")
 }
 
 pub fn engine_config_for_settings(settings: &AppSettings) -> EngineConfig {
-    let mut config = EngineConfig::default();
-    apply_engine_profile(&mut config, &settings.performance_profile);
+    let mut config = engine_preset(&settings.performance_profile);
+    // The meter UIs draw is computed in core from the same sliders.
+    config.activity_level = Some(ActivityLevel {
+        sensitivity: settings.activity_sensitivity,
+        noise_gate: settings.activity_noise_gate,
+        clip_threshold: settings.activity_clip_threshold,
+        ..ActivityLevel::default()
+    });
+    // Developer-only: dump utterance audio before/after resampling.
+    config.debug_audio = std::env::var_os("DICTUM_DEBUG_AUDIO_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(DebugAudioTap::new);
+    // Opt-in: run the pipeline above normal priority on busy machines.
+    if std::env::var("DICTUM_PIPELINE_PRIORITY")
+        .is_ok_and(|v| v.trim().eq_ignore_ascii_case("above_normal"))
+    {
+        config.pipeline_priority = PipelinePriority::AboveNormal;
+    }
+    // Opt-in: fingerprint finals to warn about the same audio dictated twice.
+    config.audio_fingerprint = std::env::var("DICTUM_AUDIO_FINGERPRINT")
+        .is_ok_and(|v| v.trim() == "1" || v.trim().eq_ignore_ascii_case("true"));
+    // Opt-in: commas and periods from the pauses between finals.
+    if std::env::var("DICTUM_PAUSE_PUNCTUATION")
+        .is_ok_and(|v| v.trim() == "1" || v.trim().eq_ignore_ascii_case("true"))
+    {
+        config.pause_punctuation = Some(PausePunctuation::default());
+    }
+    // Opt-in: per-chunk waveform or spectrum bars for richer visualizers.
+    config.activity_bins = std::env::var("DICTUM_ACTIVITY_BINS").ok().and_then(|v| {
+        match v.trim().to_ascii_lowercase().as_str() {
+            "waveform" => Some(ActivityBins::Waveform),
+            "spectrum" => Some(ActivityBins::Spectrum),
+            _ => None,
+        }
+    });
     config
 }
 
@@ -291,6 +438,39 @@ pub fn normalize_cloud_mode(raw: &str) -> String {
     }
 }
 
+pub fn normalize_injection_mode(raw: &str) -> String {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "continuous" | "document" | "continuous_document" => "continuous_document".into(),
+        _ => "final_only".into(),
+    }
+}
+
+pub fn normalize_number_formatting(raw: &str) -> String {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "conservative" => "conservative".into(),
+        "all" | "always" => "all".into(),
+        _ => "off".into(),
+    }
+}
+
+pub fn normalize_utterance_separator(raw: &str) -> String {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "newline" | "line" => "newline".into(),
+        "double_newline" | "paragraph" => "double_newline".into(),
+        "custom" => "custom".into(),
+        _ => "space".into(),
+    }
+}
+
+pub fn normalize_number_locale(raw: &str) -> String {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        "en-US".into()
+    } else {
+        trimmed.to_string()
+    }
+}
+
 fn normalize_phrase_bias_terms(raw: &[String]) -> Vec<String> {
     let mut out = Vec::new();
     for term in raw {
@@ -435,6 +615,11 @@ pub fn sync_runtime_with_settings(
 ) {
     engine.update_config(engine_config_for_settings(settings));
     apply_runtime_env_from_settings(settings, env_mode);
+    // The model reads the env hint only at construction; push changes live.
+    let language = Some(settings.language_hint.as_str()).filter(|hint| *hint != "auto");
+    if let Err(e) = engine.set_language_hint(language) {
+        tracing::warn!("failed to apply language hint: {e}");
+    }
 }
 
 pub fn default_settings_path() -> PathBuf {
@@ -463,11 +648,66 @@ pub fn default_settings_path() -> PathBuf {
     }
 }
 
+/// Upgrade a settings document in place to [`SETTINGS_SCHEMA_VERSION`] by
+/// running every migration after its recorded version, and return that
+/// version. A document from a newer build is left alone; fields this build
+/// does not know are dropped when it is deserialized.
+pub fn migrate_settings(document: &mut Value) -> u32 {
+    let Some(settings) = document.as_object_mut() else {
+        return SETTINGS_SCHEMA_VERSION;
+    };
+    let from = settings
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| u32::try_from(v).unwrap_or(u32::MAX).max(1));
+    for version in from..SETTINGS_SCHEMA_VERSION {
+        MIGRATIONS[version as usize - 1](settings);
+    }
+    if from < SETTINGS_SCHEMA_VERSION {
+        settings.insert("schemaVersion".into(), SETTINGS_SCHEMA_VERSION.into());
+    }
+    from
+}
+
+/// v1 kept cloud use in `cloudOptIn`, which won over a `cloudMode` that was
+/// missing, empty or `local_only`. v2 reads `cloudMode` alone, so the
+/// opt-in is folded into it here and dropped.
+fn migrate_v1_to_v2(settings: &mut Map<String, Value>) {
+    let opted_in = settings
+        .remove("cloudOptIn")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let mode = settings
+        .get("cloudMode")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .unwrap_or_default();
+    if mode.is_empty() || (opted_in && mode.eq_ignore_ascii_case("local_only")) {
+        let mode = if opted_in { "hybrid" } else { "local_only" };
+        settings.insert("cloudMode".into(), mode.into());
+    }
+}
+
 pub fn load_settings(path: &Path) -> AppSettings {
     let mut settings = fs::read_to_string(path)
         .ok()
-        .and_then(|raw| serde_json::from_str::<AppSettings>(&raw).ok())
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .and_then(|mut document| {
+            let from = migrate_settings(&mut document);
+            if from < SETTINGS_SCHEMA_VERSION {
+                tracing::info!(from, to = SETTINGS_SCHEMA_VERSION, "migrated settings");
+            } else if from > SETTINGS_SCHEMA_VERSION {
+                tracing::warn!(
+                    version = from,
+                    supported = SETTINGS_SCHEMA_VERSION,
+                    "settings were written by a newer version; unknown fields are ignored"
+                );
+            }
+            serde_json::from_value::<AppSettings>(document).ok()
+        })
         .unwrap_or_default();
+    // Whatever the file said, what gets saved back has this build's layout.
+    settings.schema_version = SETTINGS_SCHEMA_VERSION;
     settings.normalize();
     settings
 }
@@ -479,3 +719,111 @@ pub fn save_settings(path: &Path, settings: &AppSettings) -> std::io::Result<()>
     let json = serde_json::to_string_pretty(settings).map_err(std::io::Error::other)?;
     fs::write(path, json)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_engine_preset_deserializes() {
+        for (name, _) in ENGINE_PRESETS {
+            let config = engine_preset(name);
+            assert!(config.validate().is_ok(), "{name}");
+            assert_ne!(config, EngineConfig::default(), "{name}");
+        }
+        assert_eq!(
+            engine_preset("unknown"),
+            engine_preset("stability_long_form")
+        );
+        assert_eq!(
+            engine_preset("latency_short_utterance").vad_hangover_frames,
+            3
+        );
+    }
+
+    #[test]
+    fn v1_settings_migrate_to_the_current_schema() {
+        let path =
+            std::env::temp_dir().join(format!("dictum-settings-v1-{}.json", std::process::id()));
+        // A v1 file: no schemaVersion, cloud use recorded only by opt-in.
+        let v1 = r#"{
+            "modelProfile": "turbo",
+            "cloudOptIn": true,
+            "cloudMode": "local_only",
+            "retentionDays": 30,
+            "phraseBiasTerms": ["Dictum"],
+            "learnedCorrections": [{"heard": "dick tum", "corrected": "Dictum", "hits": 3}]
+        }"#;
+        fs::write(&path, v1).unwrap();
+        let settings = load_settings(&path);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(settings.schema_version, SETTINGS_SCHEMA_VERSION);
+        assert_eq!(settings.cloud_mode, "hybrid");
+        assert!(settings.cloud_opt_in);
+        assert_eq!(settings.model_profile, "large-v3-turbo");
+        assert_eq!(settings.retention_days, 30);
+        assert_eq!(settings.phrase_bias_terms, vec!["Dictum".to_string()]);
+        assert_eq!(settings.learned_corrections[0].corrected, "Dictum");
+        assert_eq!(settings.learned_corrections[0].hits, 3);
+
+        let mut opted_out: Value = serde_json::json!({ "cloudOptIn": false });
+        assert_eq!(migrate_settings(&mut opted_out), 1);
+        assert_eq!(opted_out["cloudMode"], "local_only");
+        assert_eq!(opted_out["schemaVersion"], SETTINGS_SCHEMA_VERSION);
+
+        // Current documents pass through untouched.
+        let mut current = serde_json::to_value(AppSettings::default()).unwrap();
+        let before = current.clone();
+        assert_eq!(migrate_settings(&mut current), SETTINGS_SCHEMA_VERSION);
+        assert_eq!(current, before);
+    }
+
+    #[test]
+    fn only_v1_files_let_the_opt_in_override_the_cloud_mode() {
+        let load = |document: &str, name: &str| {
+            let path = std::env::temp_dir().join(format!(
+                "dictum-settings-{name}-{}.json",
+                std::process::id()
+            ));
+            fs::write(&path, document).unwrap();
+            let settings = load_settings(&path);
+            let _ = fs::remove_file(&path);
+            settings
+        };
+        let v1 = load(
+            r#"{ "cloudOptIn": true, "cloudMode": "local_only" }"#,
+            "conflict-v1",
+        );
+        assert_eq!(v1.cloud_mode, "hybrid");
+        assert!(v1.cloud_opt_in);
+
+        // In v2 the stale opt-in is ignored: cloudMode alone decides.
+        let v2 = load(
+            r#"{ "schemaVersion": 2, "cloudOptIn": true, "cloudMode": "local_only" }"#,
+            "conflict-v2",
+        );
+        assert_eq!(v2.cloud_mode, "local_only");
+        assert!(!v2.cloud_opt_in);
+        let v2 = load(
+            r#"{ "schemaVersion": 2, "cloudOptIn": true }"#,
+            "missing-v2",
+        );
+        assert_eq!(v2.cloud_mode, "local_only");
+    }
+
+    #[test]
+    fn engine_meter_follows_the_activity_sliders() {
+        let settings = AppSettings {
+            activity_sensitivity: 6.0,
+            activity_noise_gate: 0.01,
+            ..AppSettings::default()
+        };
+        let config = engine_config_for_settings(&settings);
+        assert!(config.validate().is_ok());
+        let meter = config.activity_level.expect("meter configured");
+        // (0.06 - 0.01) * 6 = 0.3
+        assert!((meter.level(0.06, 0.0) - 0.3).abs() < 1e-6);
+        assert_eq!(meter.level(0.32, 0.0), 1.0);
+    }
+}
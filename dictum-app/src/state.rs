@@ -13,6 +13,7 @@ use std::sync::{
     Arc,
 };
 
+use crate::form_mode::FormSession;
 use crate::settings::{AppSettings, LearnedCorrection};
 use crate::storage::LocalStore;
 use crate::transform::TextTransform;
@@ -54,6 +55,9 @@ pub struct AppState {
     pub transformer: Arc<TextTransform>,
     /// Rolling stage latency metrics.
     pub perf_metrics: Arc<Mutex<PerfMetrics>>,
+    /// Active form-mode session; while set, finals fill its fields instead
+    /// of being typed.
+    pub form_mode: Arc<Mutex<Option<FormSession>>>,
 }
 
 impl AppState {
@@ -69,6 +73,9 @@ impl AppState {
             shortcut_toggle_executed: self.shortcut_toggle_executed.load(Ordering::Relaxed),
             shortcut_toggle_dropped: self.shortcut_toggle_dropped.load(Ordering::Relaxed),
             pipeline_frames_in: pipeline.frames_in,
+            pipeline_frames_dropped: pipeline.frames_dropped,
+            pipeline_ring_fill_pct: pipeline.ring_fill_pct,
+            pipeline_vad_noise_floor: pipeline.vad_noise_floor,
             pipeline_frames_resampled: pipeline.frames_resampled,
             pipeline_vad_windows: pipeline.vad_windows,
             pipeline_vad_speech: pipeline.vad_speech,
@@ -76,6 +83,7 @@ impl AppState {
             pipeline_inference_errors: pipeline.inference_errors,
             pipeline_segments_emitted: pipeline.segments_emitted,
             pipeline_fallback_emitted: pipeline.fallback_emitted,
+            pipeline_repeated_finals: pipeline.repeated_finals,
             pipeline_drain_ms: perf_stage_snapshot_from_pipeline(pipeline.drain_ms),
             pipeline_resample_ms: perf_stage_snapshot_from_pipeline(pipeline.resample_ms),
             pipeline_vad_ms: perf_stage_snapshot_from_pipeline(pipeline.vad_ms),
@@ -111,6 +119,9 @@ pub struct AppDiagnostics {
     pub shortcut_toggle_executed: usize,
     pub shortcut_toggle_dropped: usize,
     pub pipeline_frames_in: usize,
+    pub pipeline_frames_dropped: usize,
+    pub pipeline_ring_fill_pct: f32,
+    pub pipeline_vad_noise_floor: Option<f32>,
     pub pipeline_frames_resampled: usize,
     pub pipeline_vad_windows: usize,
     pub pipeline_vad_speech: usize,
@@ -118,6 +129,7 @@ pub struct AppDiagnostics {
     pub pipeline_inference_errors: usize,
     pub pipeline_segments_emitted: usize,
     pub pipeline_fallback_emitted: usize,
+    pub pipeline_repeated_finals: usize,
     pub pipeline_drain_ms: PerfStageSnapshot,
     pub pipeline_resample_ms: PerfStageSnapshot,
     pub pipeline_vad_ms: PerfStageSnapshot,
@@ -286,6 +298,9 @@ impl Serialize for AppDiagnostics {
             shortcut_toggle_executed: usize,
             shortcut_toggle_dropped: usize,
             pipeline_frames_in: usize,
+            pipeline_frames_dropped: usize,
+            pipeline_ring_fill_pct: f32,
+            pipeline_vad_noise_floor: Option<f32>,
             pipeline_frames_resampled: usize,
             pipeline_vad_windows: usize,
             pipeline_vad_speech: usize,
@@ -293,6 +308,7 @@ impl Serialize for AppDiagnostics {
             pipeline_inference_errors: usize,
             pipeline_segments_emitted: usize,
             pipeline_fallback_emitted: usize,
+            pipeline_repeated_finals: usize,
             pipeline_drain_ms: PerfStageSnapshot,
             pipeline_resample_ms: PerfStageSnapshot,
             pipeline_vad_ms: PerfStageSnapshot,
@@ -309,6 +325,9 @@ impl Serialize for AppDiagnostics {
             shortcut_toggle_executed: self.shortcut_toggle_executed,
             shortcut_toggle_dropped: self.shortcut_toggle_dropped,
             pipeline_frames_in: self.pipeline_frames_in,
+            pipeline_frames_dropped: self.pipeline_frames_dropped,
+            pipeline_ring_fill_pct: self.pipeline_ring_fill_pct,
+            pipeline_vad_noise_floor: self.pipeline_vad_noise_floor,
             pipeline_frames_resampled: self.pipeline_frames_resampled,
             pipeline_vad_windows: self.pipeline_vad_windows,
             pipeline_vad_speech: self.pipeline_vad_speech,
@@ -316,6 +335,7 @@ impl Serialize for AppDiagnostics {
             pipeline_inference_errors: self.pipeline_inference_errors,
             pipeline_segments_emitted: self.pipeline_segments_emitted,
             pipeline_fallback_emitted: self.pipeline_fallback_emitted,
+            pipeline_repeated_finals: self.pipeline_repeated_finals,
             pipeline_drain_ms: self.pipeline_drain_ms,
             pipeline_resample_ms: self.pipeline_resample_ms,
             pipeline_vad_ms: self.pipeline_vad_ms,
@@ -5,13 +5,118 @@ use aes_gcm::{
     Aes256Gcm, Nonce,
 };
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
-use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use dictum_core::AudioFingerprint;
 use rand::RngCore;
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 const HISTORY_PAGE_SCAN_BATCH: usize = 200;
+/// Most recent fingerprinted utterances compared against a new one.
+const FINGERPRINT_SCAN_LIMIT: usize = 500;
+/// How long a call waits on another connection's lock before failing with
+/// `StorageError::Locked`.
+const BUSY_TIMEOUT_MS: u64 = 5_000;
+const MAINTENANCE_BUSY_TIMEOUT_MS: u64 = 2_000;
+/// Only vacuum once at least this many pages are free...
+const VACUUM_MIN_FREE_PAGES: usize = 64;
+/// ...and they make up at least 1/N of the file.
+const VACUUM_FREE_RATIO_DENOM: usize = 4;
+
+/// Why a `LocalStore` call failed.
+///
+/// Commands return it as `{ code, message, retryable }` so the UI can tell a
+/// transient lock (retry) from a decrypt failure or a full disk (report).
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    /// Another connection holds the database; retrying shortly may succeed.
+    #[error("database is locked by another connection: {0}")]
+    Locked(String),
+    #[error("disk is full: {0}")]
+    DiskFull(String),
+    #[error("database file is corrupt or not a database: {0}")]
+    Corrupt(String),
+    #[error("failed to encrypt history text: {0}")]
+    Encrypt(String),
+    /// Stored text that does not decrypt under this machine's key.
+    #[error("failed to decrypt history text: {0}")]
+    Decrypt(String),
+    #[error("storage I/O error: {0}")]
+    Io(std::io::Error),
+    #[error("failed to encode stored data: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("database error: {0}")]
+    Database(rusqlite::Error),
+}
+
+impl StorageError {
+    /// Stable machine-readable code, mirrored in `shared/ipc_types.ts`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Locked(_) => "locked",
+            Self::DiskFull(_) => "disk_full",
+            Self::Corrupt(_) => "corrupt",
+            Self::Encrypt(_) => "encrypt_failed",
+            Self::Decrypt(_) => "decrypt_failed",
+            Self::Io(_) => "io",
+            Self::Serialization(_) => "serialization",
+            Self::Database(_) => "database",
+        }
+    }
+
+    /// Whether the same call may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Locked(_))
+    }
+}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(e: rusqlite::Error) -> Self {
+        use rusqlite::ErrorCode;
+
+        match &e {
+            rusqlite::Error::SqliteFailure(err, _) => match err.code {
+                ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => Self::Locked(e.to_string()),
+                ErrorCode::DiskFull => Self::DiskFull(e.to_string()),
+                ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => {
+                    Self::Corrupt(e.to_string())
+                }
+                _ => Self::Database(e),
+            },
+            _ => Self::Database(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::StorageFull {
+            Self::DiskFull(e.to_string())
+        } else {
+            Self::Io(e)
+        }
+    }
+}
+
+/// Commands that mix storage with other failures still report plain text.
+impl From<StorageError> for String {
+    fn from(e: StorageError) -> Self {
+        e.to_string()
+    }
+}
+
+impl Serialize for StorageError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut out = serializer.serialize_struct("StorageError", 3)?;
+        out.serialize_field("code", self.code())?;
+        out.serialize_field("message", &self.to_string())?;
+        out.serialize_field("retryable", &self.is_retryable())?;
+        out.end()
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -25,6 +130,10 @@ pub struct HistoryItem {
     pub char_count: usize,
     pub dictionary_applied: bool,
     pub snippet_applied: bool,
+    pub session_id: Option<String>,
+    /// Hex `AudioFingerprint` of the utterance, when fingerprinting is on.
+    #[serde(default)]
+    pub fingerprint: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -36,6 +145,19 @@ pub struct HistoryPage {
     pub page_size: usize,
 }
 
+/// One start→stop run of dictation.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationSession {
+    pub id: String,
+    pub name: String,
+    pub started_at: String,
+    /// `None` while the session is still open.
+    pub ended_at: Option<String>,
+    pub utterances: usize,
+    pub words: usize,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StatsBucket {
@@ -106,12 +228,22 @@ pub struct HistoryRecordInput {
     pub latency_ms: i64,
     pub dictionary_applied: bool,
     pub snippet_applied: bool,
+    pub session_id: Option<String>,
+    pub fingerprint: Option<AudioFingerprint>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    pub freelist_pages: usize,
+    pub page_count: usize,
+    pub vacuumed: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct LocalStore {
     db_path: PathBuf,
     cipher: TextCipher,
+    busy_timeout: std::time::Duration,
 }
 
 #[derive(Debug, Clone)]
@@ -135,36 +267,45 @@ impl TextCipher {
         Self { key }
     }
 
-    fn encrypt(&self, plain: &str) -> Result<String, String> {
+    fn encrypt(&self, plain: &str) -> Result<String, StorageError> {
         if plain.is_empty() {
             return Ok(String::new());
         }
-        let cipher = Aes256Gcm::new_from_slice(&self.key).map_err(|e| e.to_string())?;
+        let cipher = Aes256Gcm::new_from_slice(&self.key)
+            .map_err(|e| StorageError::Encrypt(e.to_string()))?;
         let mut nonce_bytes = [0u8; 12];
         rand::rngs::OsRng.fill_bytes(&mut nonce_bytes);
         let nonce = Nonce::from_slice(&nonce_bytes);
         let encrypted = cipher
             .encrypt(nonce, plain.as_bytes())
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| StorageError::Encrypt(e.to_string()))?;
         let mut out = Vec::with_capacity(12 + encrypted.len());
         out.extend_from_slice(&nonce_bytes);
         out.extend_from_slice(&encrypted);
         Ok(BASE64.encode(out))
     }
 
-    fn decrypt(&self, encoded: &str) -> Option<String> {
+    fn decrypt(&self, encoded: &str) -> Result<String, StorageError> {
         if encoded.is_empty() {
-            return Some(String::new());
+            return Ok(String::new());
         }
-        let bytes = BASE64.decode(encoded).ok()?;
+        let bytes = BASE64
+            .decode(encoded)
+            .map_err(|e| StorageError::Decrypt(format!("not base64: {e}")))?;
         if bytes.len() <= 12 {
-            return None;
+            return Err(StorageError::Decrypt(
+                "ciphertext shorter than its nonce".into(),
+            ));
         }
         let (nonce_bytes, cipher_bytes) = bytes.split_at(12);
         let nonce = Nonce::from_slice(nonce_bytes);
-        let cipher = Aes256Gcm::new_from_slice(&self.key).ok()?;
-        let plain = cipher.decrypt(nonce, cipher_bytes).ok()?;
-        String::from_utf8(plain).ok()
+        let cipher = Aes256Gcm::new_from_slice(&self.key)
+            .map_err(|e| StorageError::Decrypt(e.to_string()))?;
+        // A key derived for another user, machine or path fails here.
+        let plain = cipher
+            .decrypt(nonce, cipher_bytes)
+            .map_err(|_| StorageError::Decrypt("authentication failed".into()))?;
+        String::from_utf8(plain).map_err(|_| StorageError::Decrypt("plaintext is not UTF-8".into()))
     }
 }
 
@@ -195,23 +336,26 @@ impl LocalStore {
         }
     }
 
-    pub fn new(db_path: PathBuf) -> Result<Self, String> {
+    pub fn new(db_path: PathBuf) -> Result<Self, StorageError> {
         if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            std::fs::create_dir_all(parent)?;
         }
         let store = Self {
             cipher: TextCipher::new(&db_path),
             db_path,
+            busy_timeout: std::time::Duration::from_millis(BUSY_TIMEOUT_MS),
         };
         store.init_schema()?;
         Ok(store)
     }
 
-    fn open(&self) -> Result<Connection, String> {
-        Connection::open(&self.db_path).map_err(|e| e.to_string())
+    fn open(&self) -> Result<Connection, StorageError> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.busy_timeout(self.busy_timeout)?;
+        Ok(conn)
     }
 
-    fn init_schema(&self) -> Result<(), String> {
+    fn init_schema(&self) -> Result<(), StorageError> {
         let conn = self.open()?;
         conn.execute_batch(
             r#"
@@ -228,6 +372,13 @@ impl LocalStore {
               snippet_applied INTEGER NOT NULL DEFAULT 0
             );
 
+            CREATE TABLE IF NOT EXISTS sessions (
+              id TEXT PRIMARY KEY,
+              name TEXT NOT NULL,
+              started_at INTEGER NOT NULL,
+              ended_at INTEGER
+            );
+
             CREATE TABLE IF NOT EXISTS dictionary_entries (
               id TEXT PRIMARY KEY,
               term TEXT NOT NULL,
@@ -251,28 +402,162 @@ impl LocalStore {
             CREATE INDEX IF NOT EXISTS idx_history_created_at ON dictation_history(created_at DESC);
             CREATE INDEX IF NOT EXISTS idx_dictionary_term ON dictionary_entries(term);
             CREATE INDEX IF NOT EXISTS idx_snippets_trigger ON snippets(trigger);
+            CREATE INDEX IF NOT EXISTS idx_sessions_started_at ON sessions(started_at DESC);
             "#,
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
+
+        // Databases created before sessions existed lack the link column.
+        let has_session_id = conn
+            .prepare(
+                "SELECT 1 FROM pragma_table_info('dictation_history') WHERE name = 'session_id'",
+            )
+            .and_then(|mut stmt| stmt.exists([]))?;
+        if !has_session_id {
+            conn.execute_batch("ALTER TABLE dictation_history ADD COLUMN session_id TEXT;")?;
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_history_session ON dictation_history(session_id, created_at DESC);",
+        )?;
+        let has_fingerprint = conn
+            .prepare(
+                "SELECT 1 FROM pragma_table_info('dictation_history') WHERE name = 'fingerprint'",
+            )
+            .and_then(|mut stmt| stmt.exists([]))?;
+        if !has_fingerprint {
+            conn.execute_batch("ALTER TABLE dictation_history ADD COLUMN fingerprint TEXT;")?;
+        }
         Ok(())
     }
 
-    pub fn prune_history(&self, retention_days: usize) -> Result<usize, String> {
+    /// Open a new session. `name` defaults to the local start time.
+    pub fn start_session(&self, name: Option<String>) -> Result<DictationSession, StorageError> {
+        let now = Utc::now();
+        let name = name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| {
+                format!(
+                    "Dictation {}",
+                    now.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                )
+            });
+        let id = new_id("sess");
+        let conn = self.open()?;
+        conn.execute(
+            "INSERT INTO sessions (id, name, started_at) VALUES (?1, ?2, ?3)",
+            params![id, name, now.timestamp()],
+        )?;
+        Ok(DictationSession {
+            id,
+            name,
+            started_at: ts_to_rfc3339(now.timestamp()),
+            ended_at: None,
+            utterances: 0,
+            words: 0,
+        })
+    }
+
+    /// Close a session. Closing an already closed session keeps its end time.
+    pub fn end_session(&self, id: &str) -> Result<(), StorageError> {
+        let conn = self.open()?;
+        conn.execute(
+            "UPDATE sessions SET ended_at = ?2 WHERE id = ?1 AND ended_at IS NULL",
+            params![id, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Sessions that still have history, newest first.
+    pub fn get_sessions(&self) -> Result<Vec<DictationSession>, StorageError> {
+        let conn = self.open()?;
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.name, s.started_at, s.ended_at,
+                        COUNT(h.id), COALESCE(SUM(h.word_count), 0)
+                 FROM sessions s
+                 JOIN dictation_history h ON h.session_id = s.id
+                 GROUP BY s.id
+                 ORDER BY s.started_at DESC, s.rowid DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(DictationSession {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                started_at: ts_to_rfc3339(row.get(2)?),
+                ended_at: row.get::<_, Option<i64>>(3)?.map(ts_to_rfc3339),
+                utterances: row.get::<_, i64>(4)? as usize,
+                words: row.get::<_, i64>(5)? as usize,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(StorageError::from)
+    }
+
+    pub fn prune_history(&self, retention_days: usize) -> Result<usize, StorageError> {
         if retention_days == 0 {
             return Ok(0);
         }
         let cutoff = Utc::now() - Duration::days(retention_days as i64);
         let conn = self.open()?;
-        let changed = conn
-            .execute(
-                "DELETE FROM dictation_history WHERE created_at < ?1",
-                params![cutoff.timestamp()],
-            )
-            .map_err(|e| e.to_string())?;
+        let changed = conn.execute(
+            "DELETE FROM dictation_history WHERE created_at < ?1",
+            params![cutoff.timestamp()],
+        )?;
+        conn.execute(
+            "DELETE FROM sessions
+             WHERE ended_at < ?1
+               AND NOT EXISTS (SELECT 1 FROM dictation_history h WHERE h.session_id = sessions.id)",
+            params![cutoff.timestamp()],
+        )?;
         Ok(changed)
     }
 
-    pub fn insert_history(&self, input: HistoryRecordInput) -> Result<(), String> {
+    /// Checkpoint the WAL back into the main database file and truncate it,
+    /// then `VACUUM` when enough pages sit on the freelist (typically after a
+    /// large prune). `VACUUM` needs exclusive access, so it waits up to
+    /// `MAINTENANCE_BUSY_TIMEOUT_MS` for other connections and gives up
+    /// quietly if the database stays busy.
+    pub fn maintenance(&self) -> Result<MaintenanceReport, StorageError> {
+        let conn = self.open()?;
+        conn.busy_timeout(std::time::Duration::from_millis(
+            MAINTENANCE_BUSY_TIMEOUT_MS,
+        ))?;
+        checkpoint_truncate(&conn)?;
+
+        let page_count = pragma_usize(&conn, "page_count")?;
+        let freelist_pages = pragma_usize(&conn, "freelist_count")?;
+        let mut report = MaintenanceReport {
+            freelist_pages,
+            page_count,
+            vacuumed: false,
+        };
+        let should_vacuum = freelist_pages >= VACUUM_MIN_FREE_PAGES
+            && freelist_pages * VACUUM_FREE_RATIO_DENOM >= page_count;
+        if !should_vacuum {
+            return Ok(report);
+        }
+
+        match conn.execute_batch("VACUUM;") {
+            Ok(()) => {
+                report.vacuumed = true;
+                report.page_count = pragma_usize(&conn, "page_count")?;
+                report.freelist_pages = pragma_usize(&conn, "freelist_count")?;
+                // VACUUM writes through the WAL; fold it back so the -wal file shrinks too.
+                checkpoint_truncate(&conn)?;
+            }
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if matches!(
+                    err.code,
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+                ) =>
+            {
+                tracing::debug!("skipping VACUUM, database busy: {err}");
+            }
+            Err(e) => return Err(e.into()),
+        }
+        Ok(report)
+    }
+
+    pub fn insert_history(&self, input: HistoryRecordInput) -> Result<(), StorageError> {
         let now = Utc::now().timestamp();
         let id = new_id("hist");
         let text_enc = self.cipher.encrypt(&input.text)?;
@@ -282,8 +567,8 @@ impl LocalStore {
         conn.execute(
             r#"
             INSERT INTO dictation_history
-            (id, created_at, text_enc, source, latency_ms, word_count, char_count, dictionary_applied, snippet_applied)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            (id, created_at, text_enc, source, latency_ms, word_count, char_count, dictionary_applied, snippet_applied, session_id, fingerprint)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
             "#,
             params![
                 id,
@@ -294,19 +579,49 @@ impl LocalStore {
                 word_count as i64,
                 char_count as i64,
                 if input.dictionary_applied { 1_i64 } else { 0_i64 },
-                if input.snippet_applied { 1_i64 } else { 0_i64 }
+                if input.snippet_applied { 1_i64 } else { 0_i64 },
+                input.session_id,
+                input.fingerprint.map(|fp| fp.to_string())
             ],
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
         Ok(())
     }
 
+    /// The most recent history item whose audio fingerprint is a near
+    /// duplicate of `fingerprint`, among the last `FINGERPRINT_SCAN_LIMIT`
+    /// fingerprinted items. Call before inserting the new item.
+    pub fn find_audio_duplicate(
+        &self,
+        fingerprint: AudioFingerprint,
+    ) -> Result<Option<HistoryItem>, StorageError> {
+        let conn = self.open()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, created_at, text_enc, source, latency_ms, word_count, char_count, dictionary_applied, snippet_applied, session_id, fingerprint
+             FROM dictation_history
+             WHERE fingerprint IS NOT NULL
+             ORDER BY created_at DESC, rowid DESC
+             LIMIT ?1",
+        )?;
+        let mut rows = stmt.query(params![FINGERPRINT_SCAN_LIMIT as i64])?;
+        while let Some(row) = rows.next()? {
+            let stored = row.get::<_, String>(10)?.parse::<AudioFingerprint>().ok();
+            if !stored.is_some_and(|stored| stored.is_near_duplicate(&fingerprint)) {
+                continue;
+            }
+            if let Some(item) = self.read_history_row(row)? {
+                return Ok(Some(item));
+            }
+        }
+        Ok(None)
+    }
+
     pub fn get_history(
         &self,
         page: usize,
         page_size: usize,
         query: Option<String>,
-    ) -> Result<HistoryPage, String> {
+        session_id: Option<String>,
+    ) -> Result<HistoryPage, StorageError> {
         let page = page.max(1);
         let page_size = page_size.clamp(1, 200);
         let start = (page - 1).saturating_mul(page_size);
@@ -317,24 +632,22 @@ impl LocalStore {
             .filter(|q| !q.is_empty());
 
         if query.is_none() {
-            let total = conn
-                .query_row("SELECT COUNT(*) FROM dictation_history", [], |row| {
-                    row.get::<_, i64>(0)
-                })
-                .map_err(|e| e.to_string())? as usize;
+            let total = conn.query_row(
+                "SELECT COUNT(*) FROM dictation_history WHERE ?1 IS NULL OR session_id = ?1",
+                params![session_id],
+                |row| row.get::<_, i64>(0),
+            )? as usize;
             let mut stmt = conn
                 .prepare(
-                    "SELECT id, created_at, text_enc, source, latency_ms, word_count, char_count, dictionary_applied, snippet_applied
+                    "SELECT id, created_at, text_enc, source, latency_ms, word_count, char_count, dictionary_applied, snippet_applied, session_id, fingerprint
                      FROM dictation_history
+                     WHERE ?3 IS NULL OR session_id = ?3
                      ORDER BY created_at DESC
                      LIMIT ?1 OFFSET ?2",
-                )
-                .map_err(|e| e.to_string())?;
-            let mut rows = stmt
-                .query(params![page_size as i64, start as i64])
-                .map_err(|e| e.to_string())?;
+                )?;
+            let mut rows = stmt.query(params![page_size as i64, start as i64, session_id])?;
             let mut items = Vec::new();
-            while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+            while let Some(row) = rows.next()? {
                 if let Some(item) = self.read_history_row(row)? {
                     items.push(item);
                 }
@@ -356,20 +669,22 @@ impl LocalStore {
 
         let mut stmt = conn
             .prepare(
-                "SELECT id, created_at, text_enc, source, latency_ms, word_count, char_count, dictionary_applied, snippet_applied
+                "SELECT id, created_at, text_enc, source, latency_ms, word_count, char_count, dictionary_applied, snippet_applied, session_id, fingerprint
                  FROM dictation_history
+                 WHERE ?3 IS NULL OR session_id = ?3
                  ORDER BY created_at DESC
                  LIMIT ?1 OFFSET ?2",
-            )
-            .map_err(|e| e.to_string())?;
+            )?;
 
         loop {
-            let mut rows = stmt
-                .query(params![HISTORY_PAGE_SCAN_BATCH as i64, offset as i64])
-                .map_err(|e| e.to_string())?;
+            let mut rows = stmt.query(params![
+                HISTORY_PAGE_SCAN_BATCH as i64,
+                offset as i64,
+                session_id
+            ])?;
             let mut scanned = 0usize;
 
-            while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+            while let Some(row) = rows.next()? {
                 scanned += 1;
                 let Some(item) = self.read_history_row(row)? else {
                     continue;
@@ -402,55 +717,50 @@ impl LocalStore {
         &self,
         ids: Option<Vec<String>>,
         older_than_days: Option<usize>,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, StorageError> {
         let conn = self.open()?;
         let mut deleted = 0usize;
 
         if let Some(ids) = ids {
             for id in ids {
-                deleted += conn
-                    .execute("DELETE FROM dictation_history WHERE id = ?1", params![id])
-                    .map_err(|e| e.to_string())?;
+                deleted +=
+                    conn.execute("DELETE FROM dictation_history WHERE id = ?1", params![id])?;
             }
         }
 
         if let Some(days) = older_than_days {
             let cutoff = Utc::now() - Duration::days(days as i64);
-            deleted += conn
-                .execute(
-                    "DELETE FROM dictation_history WHERE created_at < ?1",
-                    params![cutoff.timestamp()],
-                )
-                .map_err(|e| e.to_string())?;
+            deleted += conn.execute(
+                "DELETE FROM dictation_history WHERE created_at < ?1",
+                params![cutoff.timestamp()],
+            )?;
         }
 
         Ok(deleted)
     }
 
-    pub fn get_stats(&self, range_days: usize) -> Result<StatsPayload, String> {
+    pub fn get_stats(&self, range_days: usize) -> Result<StatsPayload, StorageError> {
         let range_days = range_days.clamp(1, 365);
         let cutoff = Utc::now() - Duration::days(range_days as i64);
         let conn = self.open()?;
-        let totals = conn
-            .query_row(
-                "SELECT
+        let totals = conn.query_row(
+            "SELECT
                     COUNT(*),
                     COALESCE(SUM(word_count), 0),
                     COALESCE(SUM(char_count), 0),
                     COALESCE(AVG(CASE WHEN latency_ms > 0 THEN latency_ms END), 0.0)
                  FROM dictation_history
                  WHERE created_at >= ?1",
-                params![cutoff.timestamp()],
-                |row| {
-                    Ok((
-                        row.get::<_, i64>(0)? as usize,
-                        row.get::<_, i64>(1)? as usize,
-                        row.get::<_, i64>(2)? as usize,
-                        row.get::<_, f64>(3)? as f32,
-                    ))
-                },
-            )
-            .map_err(|e| e.to_string())?;
+            params![cutoff.timestamp()],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, i64>(1)? as usize,
+                    row.get::<_, i64>(2)? as usize,
+                    row.get::<_, f64>(3)? as f32,
+                ))
+            },
+        )?;
 
         let mut stmt = conn
             .prepare(
@@ -464,20 +774,17 @@ impl LocalStore {
                  WHERE created_at >= ?1
                  GROUP BY day
                  ORDER BY day ASC",
-            )
-            .map_err(|e| e.to_string())?;
-        let mut rows = stmt
-            .query(params![cutoff.timestamp()])
-            .map_err(|e| e.to_string())?;
+            )?;
+        let mut rows = stmt.query(params![cutoff.timestamp()])?;
 
         let mut out_buckets = Vec::new();
-        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        while let Some(row) = rows.next()? {
             out_buckets.push(StatsBucket {
-                date: row.get(0).map_err(|e| e.to_string())?,
-                utterances: row.get::<_, i64>(1).map_err(|e| e.to_string())? as usize,
-                words: row.get::<_, i64>(2).map_err(|e| e.to_string())? as usize,
-                chars: row.get::<_, i64>(3).map_err(|e| e.to_string())? as usize,
-                avg_latency_ms: row.get::<_, f64>(4).map_err(|e| e.to_string())? as f32,
+                date: row.get(0)?,
+                utterances: row.get::<_, i64>(1)? as usize,
+                words: row.get::<_, i64>(2)? as usize,
+                chars: row.get::<_, i64>(3)? as usize,
+                avg_latency_ms: row.get::<_, f64>(4)? as f32,
             });
         }
 
@@ -491,21 +798,19 @@ impl LocalStore {
         })
     }
 
-    pub fn history_storage_summary(&self) -> Result<HistoryStorageSummary, String> {
+    pub fn history_storage_summary(&self) -> Result<HistoryStorageSummary, StorageError> {
         let conn = self.open()?;
-        let (total_records, oldest, newest) = conn
-            .query_row(
-                "SELECT COUNT(*), MIN(created_at), MAX(created_at) FROM dictation_history",
-                [],
-                |row| {
-                    Ok((
-                        row.get::<_, i64>(0)? as usize,
-                        row.get::<_, Option<i64>>(1)?,
-                        row.get::<_, Option<i64>>(2)?,
-                    ))
-                },
-            )
-            .map_err(|e| e.to_string())?;
+        let (total_records, oldest, newest) = conn.query_row(
+            "SELECT COUNT(*), MIN(created_at), MAX(created_at) FROM dictation_history",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                ))
+            },
+        )?;
 
         Ok(HistoryStorageSummary {
             db_path: self.db_path.display().to_string(),
@@ -515,27 +820,25 @@ impl LocalStore {
         })
     }
 
-    pub fn list_dictionary(&self) -> Result<Vec<DictionaryEntry>, String> {
+    pub fn list_dictionary(&self) -> Result<Vec<DictionaryEntry>, StorageError> {
         let conn = self.open()?;
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, term, aliases_json, language, enabled, created_at, updated_at
+        let mut stmt = conn.prepare(
+            "SELECT id, term, aliases_json, language, enabled, created_at, updated_at
                  FROM dictionary_entries ORDER BY updated_at DESC",
-            )
-            .map_err(|e| e.to_string())?;
-        let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+        )?;
+        let mut rows = stmt.query([])?;
         let mut out = Vec::new();
-        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
-            let aliases_json: String = row.get(2).map_err(|e| e.to_string())?;
+        while let Some(row) = rows.next()? {
+            let aliases_json: String = row.get(2)?;
             let aliases = serde_json::from_str::<Vec<String>>(&aliases_json).unwrap_or_default();
-            let created_at = ts_to_rfc3339(row.get::<_, i64>(5).map_err(|e| e.to_string())?);
-            let updated_at = ts_to_rfc3339(row.get::<_, i64>(6).map_err(|e| e.to_string())?);
+            let created_at = ts_to_rfc3339(row.get::<_, i64>(5)?);
+            let updated_at = ts_to_rfc3339(row.get::<_, i64>(6)?);
             out.push(DictionaryEntry {
-                id: row.get(0).map_err(|e| e.to_string())?,
-                term: row.get(1).map_err(|e| e.to_string())?,
+                id: row.get(0)?,
+                term: row.get(1)?,
                 aliases,
-                language: row.get(3).map_err(|e| e.to_string())?,
-                enabled: row.get::<_, i64>(4).map_err(|e| e.to_string())? != 0,
+                language: row.get(3)?,
+                enabled: row.get::<_, i64>(4)? != 0,
                 created_at,
                 updated_at,
             });
@@ -543,14 +846,17 @@ impl LocalStore {
         Ok(out)
     }
 
-    pub fn upsert_dictionary(&self, mut entry: DictionaryEntry) -> Result<DictionaryEntry, String> {
+    pub fn upsert_dictionary(
+        &self,
+        mut entry: DictionaryEntry,
+    ) -> Result<DictionaryEntry, StorageError> {
         let now = Utc::now().timestamp();
         if entry.id.trim().is_empty() {
             entry.id = new_id("dict");
             entry.created_at = ts_to_rfc3339(now);
         }
         entry.updated_at = ts_to_rfc3339(now);
-        let aliases_json = serde_json::to_string(&entry.aliases).map_err(|e| e.to_string())?;
+        let aliases_json = serde_json::to_string(&entry.aliases)?;
         let conn = self.open()?;
         conn.execute(
             r#"
@@ -572,43 +878,39 @@ impl LocalStore {
                 now,
                 now,
             ],
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
         Ok(entry)
     }
 
-    pub fn delete_dictionary(&self, id: &str) -> Result<(), String> {
+    pub fn delete_dictionary(&self, id: &str) -> Result<(), StorageError> {
         let conn = self.open()?;
-        conn.execute("DELETE FROM dictionary_entries WHERE id = ?1", params![id])
-            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM dictionary_entries WHERE id = ?1", params![id])?;
         Ok(())
     }
 
-    pub fn list_snippets(&self) -> Result<Vec<SnippetEntry>, String> {
+    pub fn list_snippets(&self) -> Result<Vec<SnippetEntry>, StorageError> {
         let conn = self.open()?;
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, trigger, expansion, mode, enabled, created_at, updated_at
+        let mut stmt = conn.prepare(
+            "SELECT id, trigger, expansion, mode, enabled, created_at, updated_at
                  FROM snippets ORDER BY updated_at DESC",
-            )
-            .map_err(|e| e.to_string())?;
-        let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+        )?;
+        let mut rows = stmt.query([])?;
         let mut out = Vec::new();
-        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        while let Some(row) = rows.next()? {
             out.push(SnippetEntry {
-                id: row.get(0).map_err(|e| e.to_string())?,
-                trigger: row.get(1).map_err(|e| e.to_string())?,
-                expansion: row.get(2).map_err(|e| e.to_string())?,
-                mode: row.get(3).map_err(|e| e.to_string())?,
-                enabled: row.get::<_, i64>(4).map_err(|e| e.to_string())? != 0,
-                created_at: ts_to_rfc3339(row.get::<_, i64>(5).map_err(|e| e.to_string())?),
-                updated_at: ts_to_rfc3339(row.get::<_, i64>(6).map_err(|e| e.to_string())?),
+                id: row.get(0)?,
+                trigger: row.get(1)?,
+                expansion: row.get(2)?,
+                mode: row.get(3)?,
+                enabled: row.get::<_, i64>(4)? != 0,
+                created_at: ts_to_rfc3339(row.get::<_, i64>(5)?),
+                updated_at: ts_to_rfc3339(row.get::<_, i64>(6)?),
             });
         }
         Ok(out)
     }
 
-    pub fn upsert_snippet(&self, mut entry: SnippetEntry) -> Result<SnippetEntry, String> {
+    pub fn upsert_snippet(&self, mut entry: SnippetEntry) -> Result<SnippetEntry, StorageError> {
         let now = Utc::now().timestamp();
         if entry.id.trim().is_empty() {
             entry.id = new_id("snip");
@@ -642,42 +944,67 @@ impl LocalStore {
                 now,
                 now,
             ],
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
         Ok(entry)
     }
 
-    pub fn delete_snippet(&self, id: &str) -> Result<(), String> {
+    pub fn delete_snippet(&self, id: &str) -> Result<(), StorageError> {
         let conn = self.open()?;
-        conn.execute("DELETE FROM snippets WHERE id = ?1", params![id])
-            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM snippets WHERE id = ?1", params![id])?;
         Ok(())
     }
 
-    fn read_history_row(&self, row: &rusqlite::Row<'_>) -> Result<Option<HistoryItem>, String> {
-        let enc: String = row.get(2).map_err(|e| e.to_string())?;
-        let Some(text) = self.cipher.decrypt(&enc) else {
-            return Ok(None);
+    fn read_history_row(
+        &self,
+        row: &rusqlite::Row<'_>,
+    ) -> Result<Option<HistoryItem>, StorageError> {
+        let enc: String = row.get(2)?;
+        // Rows written under another key are skipped, not fatal to the page.
+        let text = match self.cipher.decrypt(&enc) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::debug!("skipping history row: {e}");
+                return Ok(None);
+            }
         };
-        let created_at: i64 = row.get(1).map_err(|e| e.to_string())?;
+        let created_at: i64 = row.get(1)?;
         Ok(Some(HistoryItem {
-            id: row.get(0).map_err(|e| e.to_string())?,
+            id: row.get(0)?,
             created_at: Utc
                 .timestamp_opt(created_at, 0)
                 .single()
                 .unwrap_or_else(Utc::now)
                 .to_rfc3339(),
             text,
-            source: row.get(3).map_err(|e| e.to_string())?,
-            latency_ms: row.get(4).map_err(|e| e.to_string())?,
-            word_count: row.get::<_, i64>(5).map_err(|e| e.to_string())? as usize,
-            char_count: row.get::<_, i64>(6).map_err(|e| e.to_string())? as usize,
-            dictionary_applied: row.get::<_, i64>(7).map_err(|e| e.to_string())? != 0,
-            snippet_applied: row.get::<_, i64>(8).map_err(|e| e.to_string())? != 0,
+            source: row.get(3)?,
+            latency_ms: row.get(4)?,
+            word_count: row.get::<_, i64>(5)? as usize,
+            char_count: row.get::<_, i64>(6)? as usize,
+            dictionary_applied: row.get::<_, i64>(7)? != 0,
+            snippet_applied: row.get::<_, i64>(8)? != 0,
+            session_id: row.get(9)?,
+            fingerprint: row.get(10)?,
         }))
     }
 }
 
+fn checkpoint_truncate(conn: &Connection) -> Result<(), StorageError> {
+    // Returns (busy, wal_frames, checkpointed_frames); busy=1 means readers held it open.
+    let busy = conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+        row.get::<_, i64>(0)
+    })?;
+    if busy != 0 {
+        tracing::debug!("WAL checkpoint could not complete, readers still active");
+    }
+    Ok(())
+}
+
+fn pragma_usize(conn: &Connection, name: &str) -> Result<usize, StorageError> {
+    conn.query_row(&format!("PRAGMA {name}"), [], |row| row.get::<_, i64>(0))
+        .map(|v| v.max(0) as usize)
+        .map_err(StorageError::from)
+}
+
 fn ts_to_rfc3339(ts: i64) -> String {
     let dt: DateTime<Utc> = Utc.timestamp_opt(ts, 0).single().unwrap_or_else(Utc::now);
     dt.to_rfc3339()
@@ -693,11 +1020,29 @@ fn new_id(prefix: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{new_id, LocalStore};
+    use super::{
+        new_id, AudioFingerprint, HistoryRecordInput, LocalStore, StorageError, TextCipher,
+    };
     use chrono::Utc;
     use rusqlite::params;
+    use std::path::Path;
     use std::path::PathBuf;
 
+    fn db_footprint(db_path: &Path) -> u64 {
+        let wal = PathBuf::from(format!("{}-wal", db_path.display()));
+        [db_path, wal.as_path()]
+            .iter()
+            .filter_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum()
+    }
+
+    fn remove_db_files(db_path: &Path) {
+        let _ = std::fs::remove_file(db_path);
+        let _ = std::fs::remove_file(format!("{}-wal", db_path.display()));
+        let _ = std::fs::remove_file(format!("{}-shm", db_path.display()));
+    }
+
     fn temp_db_path(test_name: &str) -> PathBuf {
         let mut path = std::env::temp_dir();
         path.push(format!(
@@ -741,7 +1086,7 @@ mod tests {
         seed_history(&store, "second entry", 20, base - 1);
         seed_history(&store, "third entry", 30, base);
 
-        let page = store.get_history(1, 2, None).expect("get history");
+        let page = store.get_history(1, 2, None, None).expect("get history");
         assert_eq!(page.total, 3);
         assert_eq!(page.items.len(), 2);
         assert_eq!(page.items[0].text, "third entry");
@@ -760,7 +1105,7 @@ mod tests {
         seed_history(&store, "echo alpha", 30, base);
 
         let page = store
-            .get_history(1, 10, Some("alpha".into()))
+            .get_history(1, 10, Some("alpha".into()), None)
             .expect("query history");
         assert_eq!(page.total, 2);
         assert_eq!(page.items.len(), 2);
@@ -769,6 +1114,37 @@ mod tests {
         let _ = std::fs::remove_file(db_path);
     }
 
+    #[test]
+    fn near_duplicate_audio_is_found_by_fingerprint() {
+        let db_path = temp_db_path("fingerprint");
+        let store = LocalStore::new(db_path.clone()).expect("create store");
+        let stored = AudioFingerprint(0xF0F0_F0F0_0000_FFFF);
+        store
+            .insert_history(HistoryRecordInput {
+                fingerprint: Some(stored),
+                ..record("first take")
+            })
+            .expect("insert");
+        store
+            .insert_history(record("no fingerprint"))
+            .expect("insert");
+
+        // Three bits off: the same audio, transcribed differently.
+        let found = store
+            .find_audio_duplicate(AudioFingerprint(stored.0 ^ 0b1011))
+            .expect("scan")
+            .expect("duplicate found");
+        assert_eq!(found.text, "first take");
+        assert_eq!(found.fingerprint, Some(stored.to_string()));
+
+        assert!(store
+            .find_audio_duplicate(AudioFingerprint(!stored.0))
+            .expect("scan")
+            .is_none());
+
+        remove_db_files(&db_path);
+    }
+
     #[test]
     fn get_stats_aggregates_in_sql() {
         let db_path = temp_db_path("stats");
@@ -789,4 +1165,118 @@ mod tests {
 
         let _ = std::fs::remove_file(db_path);
     }
+
+    #[test]
+    fn maintenance_shrinks_database_after_bulk_delete() {
+        let db_path = temp_db_path("maintenance");
+        let store = LocalStore::new(db_path.clone()).expect("create store");
+        let base = Utc::now().timestamp();
+        let filler = "lorem ipsum dolor sit amet ".repeat(20);
+        for i in 0..2_000 {
+            seed_history(&store, &filler, 10, base - 10_000 - i);
+        }
+        let bloated = db_footprint(&db_path);
+
+        let deleted = store.delete_history(None, Some(0)).expect("bulk delete");
+        assert_eq!(deleted, 2_000);
+        let report = store.maintenance().expect("maintenance");
+        assert!(report.vacuumed);
+        assert_eq!(report.freelist_pages, 0);
+
+        let compacted = db_footprint(&db_path);
+        assert!(
+            compacted < bloated / 4,
+            "expected compaction, before={bloated} after={compacted}"
+        );
+        assert_eq!(
+            store
+                .history_storage_summary()
+                .expect("summary")
+                .total_records,
+            0
+        );
+
+        remove_db_files(&db_path);
+    }
+
+    fn record(text: &str) -> HistoryRecordInput {
+        HistoryRecordInput {
+            text: text.into(),
+            source: "local".into(),
+            latency_ms: 0,
+            dictionary_applied: false,
+            snippet_applied: false,
+            session_id: None,
+            fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn locked_database_reports_a_retryable_lock() {
+        let db_path = temp_db_path("locked");
+        let mut store = LocalStore::new(db_path.clone()).expect("create store");
+        store.busy_timeout = std::time::Duration::from_millis(50);
+        let holder = store.open().expect("open second connection");
+        holder
+            .execute_batch("BEGIN EXCLUSIVE;")
+            .expect("take write lock");
+
+        let err = store
+            .insert_history(record("blocked"))
+            .expect_err("insert while locked");
+        assert!(matches!(err, StorageError::Locked(_)), "{err:?}");
+        assert!(err.is_retryable());
+        let payload = serde_json::to_value(&err).expect("serialize");
+        assert_eq!(payload["code"], "locked");
+        assert_eq!(payload["retryable"], true);
+        assert!(payload["message"].as_str().unwrap().contains("locked"));
+
+        holder.execute_batch("COMMIT;").expect("release lock");
+        store
+            .insert_history(record("unblocked"))
+            .expect("insert after release");
+
+        drop(holder);
+        remove_db_files(&db_path);
+    }
+
+    #[test]
+    fn foreign_ciphertext_reports_decrypt_failure() {
+        let db_path = temp_db_path("decrypt");
+        let store = LocalStore::new(db_path.clone()).expect("create store");
+        let foreign = TextCipher::new(Path::new("/elsewhere/dictum.db"))
+            .encrypt("someone else's words")
+            .expect("encrypt");
+
+        let err = store.cipher.decrypt(&foreign).expect_err("wrong key");
+        assert!(matches!(err, StorageError::Decrypt(_)), "{err:?}");
+        assert_eq!(err.code(), "decrypt_failed");
+        assert!(!err.is_retryable());
+        assert!(matches!(
+            store.cipher.decrypt("not base64!"),
+            Err(StorageError::Decrypt(_))
+        ));
+        assert!(matches!(
+            store.cipher.decrypt("AAAA"),
+            Err(StorageError::Decrypt(_))
+        ));
+
+        // Undecryptable rows drop out of a history page instead of failing it.
+        let base = Utc::now().timestamp();
+        seed_history(&store, "mine", 10, base - 1);
+        seed_history(&store, "theirs", 10, base);
+        store
+            .open()
+            .expect("open store")
+            .execute(
+                "UPDATE dictation_history SET text_enc = ?1 WHERE created_at = ?2",
+                params![foreign, base],
+            )
+            .expect("swap ciphertext");
+        let page = store.get_history(1, 10, None, None).expect("get history");
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].text, "mine");
+
+        remove_db_files(&db_path);
+    }
 }
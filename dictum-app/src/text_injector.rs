@@ -60,6 +60,30 @@ pub fn inject_text(text: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Apply an in-place edit for continuous document mode: press Backspace
+/// `edit.backspaces` times, then type `edit.insert`.
+#[cfg(target_os = "windows")]
+pub fn apply_edit(edit: &crate::live_edit::EditOp) -> Result<(), String> {
+    if edit.backspaces > 0 {
+        wait_for_hotkey_modifiers_release();
+        let back = vk_back();
+        for _ in 0..edit.backspaces {
+            send_key_chord(&[], back)?;
+        }
+    }
+    inject_text(&edit.insert)
+}
+
+/// Opaque identifier of the foreground window, used to detect focus changes.
+#[cfg(target_os = "windows")]
+pub fn foreground_target_id() -> Option<isize> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    // SAFETY: GetForegroundWindow takes no arguments and returns a handle or null.
+    let hwnd = unsafe { GetForegroundWindow() };
+    (!hwnd.is_null()).then_some(hwnd as isize)
+}
+
 #[cfg(target_os = "windows")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum InjectMethod {
@@ -365,6 +389,11 @@ fn vk_shift() -> u16 {
     windows_sys::Win32::UI::Input::KeyboardAndMouse::VK_SHIFT
 }
 
+#[cfg(target_os = "windows")]
+fn vk_back() -> u16 {
+    windows_sys::Win32::UI::Input::KeyboardAndMouse::VK_BACK
+}
+
 #[cfg(target_os = "windows")]
 fn vk_v() -> u16 {
     windows_sys::Win32::UI::Input::KeyboardAndMouse::VK_V
@@ -509,3 +538,13 @@ fn send_unicode_chunk(chunk: &[u16]) -> Result<(), String> {
 pub fn inject_text(_text: &str) -> Result<(), String> {
     Ok(())
 }
+
+#[cfg(not(target_os = "windows"))]
+pub fn apply_edit(_edit: &crate::live_edit::EditOp) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn foreground_target_id() -> Option<isize> {
+    None
+}
@@ -1,20 +1,28 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use dictum_core::numerals::{format_numbers, NumberFormat};
+use dictum_core::redact::{redact, RedactionPolicy};
 use parking_lot::RwLock;
 
-use crate::storage::{DictionaryEntry, LocalStore, SnippetEntry};
+use crate::storage::{DictionaryEntry, LocalStore, SnippetEntry, StorageError};
 
 #[derive(Debug, Clone)]
 pub struct TransformResult {
     pub text: String,
     pub dictionary_applied: bool,
     pub snippet_applied: bool,
+    /// Number of PII spans masked by the redaction policy.
+    pub redactions: usize,
 }
 
 #[derive(Debug, Clone, Default)]
 struct TransformCache {
     dictionary: Vec<DictionaryEntry>,
     snippets: Vec<SnippetEntry>,
+    redaction: RedactionPolicy,
+    /// `None` leaves spelled-out numbers alone.
+    numbers: Option<NumberFormat>,
 }
 
 #[derive(Clone)]
@@ -31,7 +39,7 @@ impl TextTransform {
         }
     }
 
-    pub fn refresh(&self) -> Result<(), String> {
+    pub fn refresh(&self) -> Result<(), StorageError> {
         let dictionary = self.store.list_dictionary()?;
         let snippets = self.store.list_snippets()?;
         let mut guard = self.cache.write();
@@ -40,6 +48,14 @@ impl TextTransform {
         Ok(())
     }
 
+    pub fn set_redaction_policy(&self, policy: RedactionPolicy) {
+        self.cache.write().redaction = policy;
+    }
+
+    pub fn set_number_format(&self, format: Option<NumberFormat>) {
+        self.cache.write().numbers = format;
+    }
+
     pub fn apply(&self, text: &str) -> TransformResult {
         let guard = self.cache.read();
         let mut out = text.trim().to_string();
@@ -48,6 +64,7 @@ impl TextTransform {
                 text: out,
                 dictionary_applied: false,
                 snippet_applied: false,
+                redactions: 0,
             };
         }
 
@@ -90,14 +107,82 @@ impl TextTransform {
             out = strip_terminal_period(&out);
         }
 
+        if let Some(format) = guard.numbers.as_ref() {
+            out = format_numbers(&out, format);
+        }
+
+        // Last, so snippet expansions and formatted numbers are masked too.
+        let (out, spans) = redact(&out, &guard.redaction);
+
         TransformResult {
             text: out,
             dictionary_applied,
             snippet_applied,
+            redactions: spans.len(),
         }
     }
 }
 
+/// Stand-in for a placeholder final: `partial`, the latest partial and when
+/// it arrived, if it is non-empty and no older than `max_age`. Transformed
+/// like a final, so the redaction policy applies to rescued text too.
+pub fn rescue_partial(
+    transform: &TextTransform,
+    partial: &(String, Instant),
+    max_age: Duration,
+) -> Option<TransformResult> {
+    let (text, at) = partial;
+    if at.elapsed() > max_age || text.trim().is_empty() {
+        return None;
+    }
+    Some(transform.apply(text)).filter(|rescued| !rescued.text.is_empty())
+}
+
+/// Text typed between consecutive finals.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum UtteranceSeparator {
+    /// A space after every final, including the last.
+    #[default]
+    Space,
+    /// Each final after the first starts on a new line.
+    Newline,
+    /// Each final after the first starts a new paragraph.
+    DoubleNewline,
+    /// Arbitrary text before each final after the first.
+    Custom(String),
+}
+
+/// Formats finals for injection, placing the separator between them.
+#[derive(Debug, Default)]
+pub struct UtteranceJoiner {
+    separator: UtteranceSeparator,
+    started: bool,
+}
+
+impl UtteranceJoiner {
+    pub fn set_separator(&mut self, separator: UtteranceSeparator) {
+        self.separator = separator;
+    }
+
+    /// Forget earlier finals, so the next one gets no leading separator.
+    pub fn reset(&mut self) {
+        self.started = false;
+    }
+
+    /// The text to type for `text`, the next final.
+    pub fn join(&mut self, text: &str) -> String {
+        let first = !std::mem::replace(&mut self.started, true);
+        let lead = match &self.separator {
+            UtteranceSeparator::Space => return format!("{text} "),
+            _ if first => "",
+            UtteranceSeparator::Newline => "\n",
+            UtteranceSeparator::DoubleNewline => "\n\n",
+            UtteranceSeparator::Custom(custom) => custom,
+        };
+        format!("{lead}{text}")
+    }
+}
+
 fn replace_slash_trigger(text: &str, trigger: &str, replacement: &str) -> String {
     let with_slash = if trigger.starts_with('/') {
         trigger.to_string()
@@ -222,3 +307,80 @@ fn match_case(source: &str, replacement: &str) -> String {
         replacement.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{rescue_partial, TextTransform, UtteranceJoiner, UtteranceSeparator};
+    use crate::storage::LocalStore;
+    use dictum_core::redact::RedactionPolicy;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    fn typed(joiner: &mut UtteranceJoiner, finals: &[&str]) -> String {
+        finals.iter().map(|text| joiner.join(text)).collect()
+    }
+
+    #[test]
+    fn separator_goes_between_finals_but_not_before_the_first() {
+        let finals = ["Milk", "Eggs", "Bread"];
+        let cases = [
+            (UtteranceSeparator::Space, "Milk Eggs Bread "),
+            (UtteranceSeparator::Newline, "Milk\nEggs\nBread"),
+            (UtteranceSeparator::DoubleNewline, "Milk\n\nEggs\n\nBread"),
+            (
+                UtteranceSeparator::Custom("\n- ".into()),
+                "Milk\n- Eggs\n- Bread",
+            ),
+        ];
+        for (separator, expected) in cases {
+            let mut joiner = UtteranceJoiner::default();
+            joiner.set_separator(separator.clone());
+            assert_eq!(typed(&mut joiner, &finals), expected, "{separator:?}");
+        }
+    }
+
+    #[test]
+    fn reset_starts_a_fresh_run() {
+        let mut joiner = UtteranceJoiner::default();
+        joiner.set_separator(UtteranceSeparator::Newline);
+        assert_eq!(typed(&mut joiner, &["one", "two"]), "one\ntwo");
+        joiner.reset();
+        assert_eq!(joiner.join("three"), "three");
+        assert_eq!(joiner.join("four"), "\nfour");
+    }
+
+    #[test]
+    fn rescued_partials_are_redacted_like_finals() {
+        let db_path = std::env::temp_dir().join(format!(
+            "dictum-transform-test-rescue-{}-{}.db",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+        let transform = TextTransform::new(Arc::new(
+            LocalStore::new(db_path.clone()).expect("create store"),
+        ));
+        transform.set_redaction_policy(RedactionPolicy::all());
+        let max_age = Duration::from_secs(10);
+
+        let partial = (
+            "mail me at jane.doe@example.com".to_string(),
+            Instant::now(),
+        );
+        let rescued = rescue_partial(&transform, &partial, max_age).expect("fresh partial");
+        assert!(
+            !rescued.text.contains("jane.doe@example.com"),
+            "{}",
+            rescued.text
+        );
+        assert_eq!(rescued.redactions, 1);
+
+        let blank = ("  ".to_string(), Instant::now());
+        assert!(rescue_partial(&transform, &blank, max_age).is_none());
+        let stale = (partial.0, Instant::now() - Duration::from_secs(11));
+        assert!(rescue_partial(&transform, &stale, max_age).is_none());
+
+        let _ = std::fs::remove_file(&db_path);
+        let _ = std::fs::remove_file(format!("{}-wal", db_path.display()));
+        let _ = std::fs::remove_file(format!("{}-shm", db_path.display()));
+    }
+}
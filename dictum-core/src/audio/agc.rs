@@ -0,0 +1,164 @@
+//! Automatic gain control with attack/release smoothing.
+//!
+//! Unlike the per-chunk boost in the pipeline, which picks a fresh gain for
+//! every chunk from that chunk's RMS, the AGC keeps its gain across chunks and
+//! moves it toward `target_rms / rms` with separate time constants for turning
+//! down (attack) and turning up (release). Within a chunk the gain is ramped
+//! linearly from the previous value, so there are no steps at chunk edges.
+
+/// Chunks quieter than this are treated as silence: the gain is held instead
+/// of climbing toward `max_gain` on the noise floor.
+const AGC_SILENCE_RMS: f32 = 3e-5;
+
+use serde::{Deserialize, Serialize};
+
+/// Tunables for [`AutomaticGainControl`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgcConfig {
+    /// Output RMS the AGC steers toward. Default: 0.05.
+    pub target_rms: f32,
+    /// Time constant when the gain has to drop (input got louder), in ms.
+    /// Default: 20.
+    pub attack_ms: f32,
+    /// Time constant when the gain has to rise (input got quieter), in ms.
+    /// Default: 400.
+    pub release_ms: f32,
+    /// Upper bound on the applied gain. Default: 9.0.
+    pub max_gain: f32,
+}
+
+impl Default for AgcConfig {
+    fn default() -> Self {
+        Self {
+            target_rms: 0.05,
+            attack_ms: 20.0,
+            release_ms: 400.0,
+            max_gain: 9.0,
+        }
+    }
+}
+
+/// Stateful AGC. Feed it consecutive chunks of one stream.
+#[derive(Debug, Clone)]
+pub struct AutomaticGainControl {
+    config: AgcConfig,
+    sample_rate: u32,
+    gain: f32,
+}
+
+impl AutomaticGainControl {
+    pub fn new(config: AgcConfig, sample_rate: u32) -> Self {
+        Self {
+            config,
+            sample_rate: sample_rate.max(1),
+            gain: 1.0,
+        }
+    }
+
+    /// Gain reached at the end of the last processed chunk.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Return to unity gain.
+    pub fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+
+    /// Update the gain from this chunk's level and apply it in place.
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        let start_gain = self.gain;
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        if rms > AGC_SILENCE_RMS {
+            let desired = (self.config.target_rms / rms).clamp(0.0, self.config.max_gain.max(0.0));
+            let tau_ms = if desired < self.gain {
+                self.config.attack_ms
+            } else {
+                self.config.release_ms
+            };
+            let chunk_ms = samples.len() as f32 * 1000.0 / self.sample_rate as f32;
+            let alpha = if tau_ms <= 0.0 {
+                1.0
+            } else {
+                1.0 - (-chunk_ms / tau_ms).exp()
+            };
+            self.gain += alpha * (desired - self.gain);
+        }
+
+        let end_gain = self.gain;
+        let step = (end_gain - start_gain) / samples.len() as f32;
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let gain = start_gain + step * (i + 1) as f32;
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AgcConfig, AutomaticGainControl};
+
+    const RATE: u32 = 16_000;
+    const CHUNK: usize = 320; // 20 ms
+
+    fn chunk_at(rms: f32) -> Vec<f32> {
+        // Square wave: RMS equals amplitude.
+        (0..CHUNK)
+            .map(|i| if i % 2 == 0 { rms } else { -rms })
+            .collect()
+    }
+
+    #[test]
+    fn step_change_converges_over_several_chunks() {
+        let mut agc = AutomaticGainControl::new(AgcConfig::default(), RATE);
+
+        // Settle on a quiet input: gain rises toward 0.05 / 0.01 = 5.
+        for _ in 0..200 {
+            agc.process(&mut chunk_at(0.01));
+        }
+        assert!((agc.gain() - 5.0).abs() < 0.05, "gain {}", agc.gain());
+
+        // Input jumps 10x louder; desired gain is now 0.5.
+        let mut gains = Vec::new();
+        for _ in 0..8 {
+            agc.process(&mut chunk_at(0.1));
+            gains.push(agc.gain());
+        }
+        assert!(gains[0] < 5.0 && gains[0] > 1.0, "jumped: {gains:?}");
+        assert!(gains.windows(2).all(|w| w[1] < w[0]), "{gains:?}");
+        assert!((gains[7] - 0.5).abs() < 0.05, "{gains:?}");
+    }
+
+    #[test]
+    fn release_is_slower_than_attack() {
+        let config = AgcConfig::default();
+        let mut down = AutomaticGainControl::new(config, RATE);
+        down.process(&mut chunk_at(0.5)); // desired 0.1, below unity
+        let mut up = AutomaticGainControl::new(config, RATE);
+        up.process(&mut chunk_at(0.025)); // desired 2.0, above unity
+
+        let attack_progress = (1.0 - down.gain()) / (1.0 - 0.1);
+        let release_progress = (up.gain() - 1.0) / (2.0 - 1.0);
+        assert!(attack_progress > release_progress * 4.0);
+    }
+
+    #[test]
+    fn silence_holds_gain_and_ramp_has_no_step() {
+        let mut agc = AutomaticGainControl::new(AgcConfig::default(), RATE);
+        for _ in 0..50 {
+            agc.process(&mut chunk_at(0.02));
+        }
+        let held = agc.gain();
+        agc.process(&mut vec![0.0; CHUNK]);
+        assert_eq!(agc.gain(), held);
+
+        // First sample of the next chunk is scaled by (almost) the held gain.
+        let mut loud = chunk_at(0.2);
+        agc.process(&mut loud);
+        assert!((loud[0] / 0.2 - held).abs() < held * 0.05);
+    }
+}
@@ -1,10 +1,15 @@
 //! Audio device enumeration.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Metadata about an audio input device.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceInfo {
+    /// Stable identifier, see [`stable_device_id`]. Prefer this over `name`
+    /// when persisting a selection.
+    pub id: String,
     /// Human-readable device name reported by the OS.
     pub name: String,
     /// Whether this is the system default input device.
@@ -70,6 +75,98 @@ pub fn mic_preference_score(name: &str) -> i32 {
     score
 }
 
+/// Stable identifier for an input device.
+///
+/// cpal does not expose a persistent hardware ID, so this is the device name
+/// plus its position among devices sharing that name in enumeration order,
+/// e.g. `"USB Audio Device#1"` for the second of two identical mics.
+pub fn stable_device_id(name: &str, occurrence: usize) -> String {
+    format!("{name}#{occurrence}")
+}
+
+/// Stable ids for an enumeration of device names, in the same order.
+pub fn stable_device_ids<S: AsRef<str>>(names: &[S]) -> Vec<String> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    names
+        .iter()
+        .map(|name| {
+            let name = name.as_ref();
+            let occurrence = seen.entry(name).or_insert(0);
+            let id = stable_device_id(name, *occurrence);
+            *occurrence += 1;
+            id
+        })
+        .collect()
+}
+
+/// Index of the device matching a saved preference.
+///
+/// `preferred` is matched as a stable id first, then as a plain name (the
+/// first device with that name) so settings saved before ids existed keep
+/// working.
+pub fn find_preferred_device<S: AsRef<str>>(names: &[S], preferred: &str) -> Option<usize> {
+    stable_device_ids(names)
+        .iter()
+        .position(|id| id == preferred)
+        .or_else(|| names.iter().position(|name| name.as_ref() == preferred))
+}
+
+/// Pick the input device to open from an enumeration of device `names`.
+///
+/// Two modes, decided by `preferred`:
+/// - **Pinned** (`Some`): the device matching the saved id or name (see
+///   [`find_preferred_device`]). If it is missing, selection falls through
+///   to the default as if unpinned.
+/// - **Follow system default** (`None`): the device named `default_name`,
+///   which callers query from the OS at each start, so a headset plugged in
+///   while idle is picked up by the next start.
+///
+/// A loopback-like choice is swapped for the best-scoring microphone when
+/// one exists. With neither a match nor a default, the best microphone
+/// wins, then the first device. `None` only for an empty enumeration.
+pub fn select_input_device<S: AsRef<str>>(
+    names: &[S],
+    default_name: Option<&str>,
+    preferred: Option<&str>,
+) -> Option<usize> {
+    if names.is_empty() {
+        return None;
+    }
+    let name_at = |idx: usize| names[idx].as_ref();
+    let preferred_idx = preferred.and_then(|preferred| find_preferred_device(names, preferred));
+    if let (Some(preferred), None) = (preferred, preferred_idx) {
+        tracing::warn!("preferred input device '{preferred}' not found, falling back");
+    }
+    let default_idx = default_name.and_then(|name| names.iter().position(|n| n.as_ref() == name));
+    let best_mic_idx = (0..names.len())
+        .filter(|&idx| !is_loopback_like_name(name_at(idx)))
+        .max_by_key(|&idx| mic_preference_score(name_at(idx)));
+
+    let Some(idx) = preferred_idx.or(default_idx) else {
+        if best_mic_idx.is_some() {
+            tracing::warn!(
+                "no default input device, falling back to best available microphone input"
+            );
+        } else {
+            tracing::warn!(
+                "no default microphone input device, falling back to first available input"
+            );
+        }
+        return Some(best_mic_idx.unwrap_or(0));
+    };
+    match best_mic_idx {
+        Some(safe_idx) if safe_idx != idx && is_loopback_like_name(name_at(idx)) => {
+            tracing::warn!(
+                chosen = name_at(idx),
+                selected = name_at(safe_idx),
+                "input appears loopback-like; switching to recommended microphone input"
+            );
+            Some(safe_idx)
+        }
+        _ => Some(idx),
+    }
+}
+
 /// List all available audio input devices on the system.
 ///
 /// Returns an empty `Vec` if cpal is not available or no devices exist.
@@ -82,16 +179,23 @@ pub fn list_input_devices() -> Vec<DeviceInfo> {
 
     match host.input_devices() {
         Ok(devices) => {
-            let mut list = devices
+            let names = devices
                 .enumerate()
-                .map(|d| {
-                    let (idx, device) = d;
-                    let name = device
+                .map(|(idx, device)| {
+                    device
                         .name()
-                        .unwrap_or_else(|_| format!("Input Device {}", idx + 1));
+                        .unwrap_or_else(|_| format!("Input Device {}", idx + 1))
+                })
+                .collect::<Vec<_>>();
+            let ids = stable_device_ids(&names);
+            let mut list = names
+                .into_iter()
+                .zip(ids)
+                .map(|(name, id)| {
                     let is_default = default_name.as_deref() == Some(name.as_str());
                     let is_loopback_like = is_loopback_like_name(&name);
                     DeviceInfo {
+                        id,
                         name,
                         is_default,
                         is_loopback_like,
@@ -126,6 +230,7 @@ pub fn list_input_devices() -> Vec<DeviceInfo> {
                     .unwrap_or_else(|_| "Default Input Device".to_string());
                 let is_loopback_like = is_loopback_like_name(&name);
                 vec![DeviceInfo {
+                    id: stable_device_id(&name, 0),
                     name,
                     is_default: true,
                     is_loopback_like,
@@ -145,7 +250,10 @@ pub fn list_input_devices() -> Vec<DeviceInfo> {
 
 #[cfg(test)]
 mod tests {
-    use super::{is_loopback_like_name, mic_preference_score};
+    use super::{
+        find_preferred_device, is_loopback_like_name, mic_preference_score, select_input_device,
+        stable_device_ids,
+    };
 
     #[test]
     fn detects_common_loopback_names() {
@@ -162,4 +270,81 @@ mod tests {
         let loopback = mic_preference_score("Stereo Mix (Realtek Audio)");
         assert!(mic > loopback);
     }
+
+    #[test]
+    fn selects_between_same_named_devices_by_id() {
+        let names = [
+            "USB Audio Device",
+            "Stereo Mix (Realtek Audio)",
+            "USB Audio Device",
+        ];
+        let ids = stable_device_ids(&names);
+        assert_eq!(
+            ids,
+            [
+                "USB Audio Device#0",
+                "Stereo Mix (Realtek Audio)#0",
+                "USB Audio Device#1"
+            ]
+        );
+
+        assert_eq!(find_preferred_device(&names, "USB Audio Device#1"), Some(2));
+        assert_eq!(find_preferred_device(&names, "USB Audio Device#0"), Some(0));
+        // Legacy settings stored the bare name: first match wins.
+        assert_eq!(find_preferred_device(&names, "USB Audio Device"), Some(0));
+        assert_eq!(find_preferred_device(&names, "USB Audio Device#2"), None);
+    }
+
+    #[test]
+    fn unpinned_selection_follows_a_changed_default() {
+        let at_launch = ["Microphone Array (Realtek)"];
+        assert_eq!(
+            select_input_device(&at_launch, Some("Microphone Array (Realtek)"), None),
+            Some(0)
+        );
+
+        // Headset plugged in while idle; the OS makes it the default.
+        let after_plug = ["Microphone Array (Realtek)", "Headset Microphone (USB)"];
+        assert_eq!(
+            select_input_device(&after_plug, Some("Headset Microphone (USB)"), None),
+            Some(1)
+        );
+        // A pinned device ignores the new default...
+        assert_eq!(
+            select_input_device(
+                &after_plug,
+                Some("Headset Microphone (USB)"),
+                Some("Microphone Array (Realtek)#0")
+            ),
+            Some(0)
+        );
+        // ...until it disappears.
+        assert_eq!(
+            select_input_device(
+                &["Headset Microphone (USB)"],
+                Some("Headset Microphone (USB)"),
+                Some("Microphone Array (Realtek)#0")
+            ),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn loopback_choices_and_missing_defaults_fall_back_to_a_microphone() {
+        let names = ["Stereo Mix (Realtek Audio)", "Microphone (USB)"];
+        assert_eq!(
+            select_input_device(&names, Some("Stereo Mix (Realtek Audio)"), None),
+            Some(1)
+        );
+        assert_eq!(
+            select_input_device(&names, None, Some("Stereo Mix (Realtek Audio)")),
+            Some(1)
+        );
+        assert_eq!(select_input_device(&names, None, None), Some(1));
+        assert_eq!(
+            select_input_device(&["Stereo Mix (Realtek Audio)"], None, None),
+            Some(0)
+        );
+        assert_eq!(select_input_device::<&str>(&[], Some("Mic"), None), None);
+    }
 }
@@ -0,0 +1,200 @@
+//! WAV file capture source.
+//!
+//! [`FileCapture`] feeds a recording into the same ring buffer contract as
+//! [`super::AudioCapture`]: mono f32 frames at the source's native rate, with
+//! `sample_rate` reported for the pipeline's resampler. The pipeline cannot
+//! tell the two apart, so regression runs and batch jobs exercise exactly
+//! the VAD and inference path live dictation uses — headless, without a
+//! microphone.
+
+use std::path::Path;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info};
+
+use crate::{
+    audio::wav::read_wav_mono,
+    buffering::CountingProducer,
+    error::{DictumError, Result},
+};
+
+/// Audio pushed per step, like a device callback delivering 10 ms buffers.
+const BLOCK_MS: u32 = 10;
+
+/// How long the feeder waits for the pipeline to make room or drain.
+const WAIT_STEP: Duration = Duration::from_millis(2);
+
+/// How fast [`FileCapture`] feeds the ring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilePacing {
+    /// One block per block duration, as a microphone would. Blocks the
+    /// ring cannot take are dropped and counted, also as with a microphone.
+    RealTime,
+    /// As fast as the pipeline drains, waiting for room instead of
+    /// dropping anything.
+    #[default]
+    AsFastAsPossible,
+}
+
+/// Handle to a WAV file being fed into the ring buffer.
+///
+/// Once every sample has been pushed and the pipeline has drained the ring,
+/// the feeder clears the shared `running` flag, so the pipeline ends with
+/// its usual terminal flush as if `stop()` had been called.
+pub struct FileCapture {
+    /// Shared flag — set to `false` to stop feeding early.
+    running: Arc<AtomicBool>,
+    feeder: Option<JoinHandle<()>>,
+    /// The file's native sample rate (Hz).
+    pub sample_rate: u32,
+}
+
+impl FileCapture {
+    /// Read `path` (8/16/24/32-bit integer or float PCM, downmixed to mono)
+    /// and start feeding it into `producer` on a background thread.
+    ///
+    /// # Errors
+    /// Whatever [`read_wav_mono`] returns for the file; `AudioStream` if the
+    /// feeder thread cannot be spawned.
+    pub fn open(
+        path: impl AsRef<Path>,
+        producer: CountingProducer,
+        running: Arc<AtomicBool>,
+        pacing: FilePacing,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let chunk = read_wav_mono(path)?;
+        let sample_rate = chunk.sample_rate;
+        info!(
+            path = %path.display(),
+            sample_rate,
+            samples = chunk.samples.len(),
+            ?pacing,
+            "feeding audio from file"
+        );
+
+        let feeder_running = Arc::clone(&running);
+        let feeder = std::thread::Builder::new()
+            .name("dictum-file-capture".into())
+            .spawn(move || {
+                feed(
+                    &chunk.samples,
+                    sample_rate,
+                    producer,
+                    &feeder_running,
+                    pacing,
+                )
+            })
+            .map_err(|e| DictumError::AudioStream(e.to_string()))?;
+
+        Ok(Self {
+            running,
+            feeder: Some(feeder),
+            sample_rate,
+        })
+    }
+
+    /// Stop: the feeder gives up at its next block.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+impl Drop for FileCapture {
+    fn drop(&mut self) {
+        self.stop();
+        if let Some(feeder) = self.feeder.take() {
+            let _ = feeder.join();
+        }
+    }
+}
+
+fn feed(
+    samples: &[f32],
+    sample_rate: u32,
+    mut producer: CountingProducer,
+    running: &AtomicBool,
+    pacing: FilePacing,
+) {
+    let block = (sample_rate / (1000 / BLOCK_MS)).max(1) as usize;
+    let started = Instant::now();
+    for (index, mut pending) in samples.chunks(block).enumerate() {
+        if pacing == FilePacing::RealTime {
+            let due = started + Duration::from_millis(u64::from(BLOCK_MS) * index as u64);
+            std::thread::sleep(due.saturating_duration_since(Instant::now()));
+        }
+        loop {
+            if !running.load(Ordering::Relaxed) {
+                return;
+            }
+            if pacing == FilePacing::RealTime {
+                producer.push_slice(pending);
+                break;
+            }
+            let fits = pending.len().min(producer.vacant_len());
+            pending = &pending[producer.push_slice(&pending[..fits])..];
+            if pending.is_empty() {
+                break;
+            }
+            std::thread::sleep(WAIT_STEP);
+        }
+    }
+
+    // End of file: let the pipeline take the rest, then end the run.
+    while running.load(Ordering::Relaxed) && producer.occupied_len() > 0 {
+        std::thread::sleep(WAIT_STEP);
+    }
+    debug!("end of file reached — stopping capture");
+    running.store(false, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use super::{FileCapture, FilePacing};
+    use crate::audio::wav::write_wav_mono;
+    use crate::buffering::{create_audio_ring_with_capacity, Consumer, CountingProducer};
+
+    #[test]
+    fn file_is_fed_whole_and_then_ends_the_run() {
+        let path =
+            std::env::temp_dir().join(format!("dictum-file-capture-{}.wav", std::process::id()));
+        let samples: Vec<f32> = (0..4_000).map(|i| (i % 100) as f32 / 100.0).collect();
+        write_wav_mono(&path, &samples, 8_000).expect("write wav");
+
+        // A ring smaller than the file: fast pacing must wait, not drop.
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let (producer, mut consumer) = create_audio_ring_with_capacity(1_024);
+        let running = Arc::new(AtomicBool::new(true));
+        let capture = FileCapture::open(
+            &path,
+            CountingProducer::new(producer, Arc::clone(&dropped)),
+            Arc::clone(&running),
+            FilePacing::AsFastAsPossible,
+        )
+        .expect("open");
+        assert_eq!(capture.sample_rate, 8_000);
+
+        let mut received = Vec::new();
+        let mut buf = [0.0_f32; 256];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while running.load(Ordering::SeqCst) && Instant::now() < deadline {
+            let n = consumer.pop_slice(&mut buf);
+            received.extend_from_slice(&buf[..n]);
+        }
+        drop(capture);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(!running.load(Ordering::SeqCst), "feeder ends the run");
+        assert_eq!(dropped.load(Ordering::Relaxed), 0);
+        assert_eq!(received, samples);
+    }
+}
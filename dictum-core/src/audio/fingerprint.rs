@@ -0,0 +1,200 @@
+//! A cheap spectral hash of an utterance, for spotting near-duplicate audio.
+//!
+//! Text dedupe misses the same recording dictated twice (two running
+//! instances, a clip replayed into the mic) whenever the transcripts differ
+//! by a word. [`AudioFingerprint`] summarises the audio itself: the utterance
+//! is split into [`TIME_SLICES`] equal slices, each measured in
+//! [`BANDS`] mel-spaced bands, and every bit records whether the energy
+//! difference between neighbouring bands rose or fell from one slice to the
+//! next. Level changes and mild noise leave most bits alone, so similar audio
+//! lands within a few bits while unrelated audio differs in about half.
+//!
+//! The bands are probed with a few Goertzel filters each rather than a full
+//! FFT, which keeps the cost to a small fraction of inference.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Slices the utterance is divided into; bits compare consecutive slices.
+pub const TIME_SLICES: usize = 9;
+/// Mel-spaced bands per slice; bits compare neighbouring bands.
+pub const BANDS: usize = 9;
+/// Fingerprints at most this many bits apart are treated as the same audio.
+pub const NEAR_DUPLICATE_BITS: u32 = 12;
+
+const FRAME_MS: usize = 20;
+const LOW_HZ: f32 = 150.0;
+const HIGH_HZ: f32 = 4_000.0;
+/// Goertzel probes spread across each band.
+const PROBES_PER_BAND: usize = 3;
+
+/// 64-bit spectral hash of an utterance. Serialized as 16 hex digits, since
+/// JSON consumers cannot hold a full `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AudioFingerprint(pub u64);
+
+impl AudioFingerprint {
+    /// Fingerprint `samples` at `sample_rate`. `None` when the audio is
+    /// shorter than one frame per slice.
+    pub fn compute(samples: &[f32], sample_rate: u32) -> Option<Self> {
+        let frame_len = (sample_rate as usize * FRAME_MS / 1000).max(1);
+        let frames = samples.len() / frame_len;
+        if sample_rate == 0 || frames < TIME_SLICES {
+            return None;
+        }
+        let probes = band_probes(sample_rate);
+
+        let mut energies = [[0f32; BANDS]; TIME_SLICES];
+        for (slice, bands) in energies.iter_mut().enumerate() {
+            let first = slice * frames / TIME_SLICES;
+            let last = (slice + 1) * frames / TIME_SLICES;
+            for frame in first..last {
+                let frame = &samples[frame * frame_len..(frame + 1) * frame_len];
+                for (band, freqs) in bands.iter_mut().zip(&probes) {
+                    *band += freqs
+                        .iter()
+                        .map(|&f| goertzel_power(frame, f, sample_rate))
+                        .sum::<f32>();
+                }
+            }
+            let count = (last - first).max(1) as f32;
+            for band in bands.iter_mut() {
+                *band = (*band / count + 1e-10).ln();
+            }
+        }
+
+        let mut bits = 0u64;
+        for slice in 1..TIME_SLICES {
+            for band in 0..BANDS - 1 {
+                let now = energies[slice][band] - energies[slice][band + 1];
+                let before = energies[slice - 1][band] - energies[slice - 1][band + 1];
+                bits = (bits << 1) | u64::from(now > before);
+            }
+        }
+        Some(Self(bits))
+    }
+
+    /// Bits that differ between the two fingerprints, 0..=64.
+    pub fn distance(&self, other: &Self) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    /// Whether `other` is within [`NEAR_DUPLICATE_BITS`] of this one.
+    pub fn is_near_duplicate(&self, other: &Self) -> bool {
+        self.distance(other) <= NEAR_DUPLICATE_BITS
+    }
+}
+
+/// Probe frequencies for each band, evenly spaced on the mel scale between
+/// `LOW_HZ` and `HIGH_HZ` (capped below Nyquist).
+fn band_probes(sample_rate: u32) -> [[f32; PROBES_PER_BAND]; BANDS] {
+    let mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+    let high = HIGH_HZ.min(sample_rate as f32 * 0.45);
+    let (low_mel, high_mel) = (mel(LOW_HZ), mel(high));
+    let step = (high_mel - low_mel) / (BANDS * PROBES_PER_BAND) as f32;
+
+    let mut probes = [[0f32; PROBES_PER_BAND]; BANDS];
+    for (band, freqs) in probes.iter_mut().enumerate() {
+        for (i, f) in freqs.iter_mut().enumerate() {
+            let index = band * PROBES_PER_BAND + i;
+            *f = hz(low_mel + step * (index as f32 + 0.5));
+        }
+    }
+    probes
+}
+
+/// Power of `frame` at `freq` Hz.
+pub(crate) fn goertzel_power(frame: &[f32], freq: f32, sample_rate: u32) -> f32 {
+    let coeff = 2.0 * (2.0 * std::f32::consts::PI * freq / sample_rate as f32).cos();
+    let (mut s1, mut s2) = (0f32, 0f32);
+    for &x in frame {
+        let s0 = x + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    (s1 * s1 + s2 * s2 - coeff * s1 * s2).max(0.0) / frame.len() as f32
+}
+
+impl fmt::Display for AudioFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for AudioFingerprint {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u64::from_str_radix(s.trim(), 16).map(Self)
+    }
+}
+
+impl Serialize for AudioFingerprint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AudioFingerprint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        hex.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One second of gliding tones with a syllable-like envelope at 16 kHz.
+    /// `pitch` scales every frequency; `noise` adds deterministic hiss.
+    fn voice(pitch: f32, seed: u32, noise: f32) -> Vec<f32> {
+        let mut state = seed;
+        (0..16_000)
+            .map(|i| {
+                let t = i as f32 / 16_000.0;
+                let glide = pitch * (400.0 + 1_500.0 * (t * 3.0).sin().abs());
+                let formant = pitch * (1_800.0 - 900.0 * t);
+                let envelope = 0.2 + 0.8 * (t * 2.0 * std::f32::consts::PI * 4.0).sin().abs();
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let hiss = (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+                envelope
+                    * (0.3 * (2.0 * std::f32::consts::PI * glide * t).sin()
+                        + 0.15 * (2.0 * std::f32::consts::PI * formant * t).sin())
+                    + noise * hiss
+            })
+            .collect()
+    }
+
+    #[test]
+    fn similar_audio_is_close_and_different_audio_is_far() {
+        let original = AudioFingerprint::compute(&voice(1.0, 1, 0.0), 16_000).expect("fp");
+        // Same audio, quieter and with hiss: a second take through the mic.
+        let retake: Vec<f32> = voice(1.0, 2, 0.01).iter().map(|s| s * 0.6).collect();
+        let retake = AudioFingerprint::compute(&retake, 16_000).expect("fp");
+        // Different pitch contour.
+        let other = AudioFingerprint::compute(&voice(1.7, 3, 0.0), 16_000).expect("fp");
+
+        let near = original.distance(&retake);
+        let far = original.distance(&other);
+        assert!(original.is_near_duplicate(&retake), "near distance {near}");
+        assert!(!original.is_near_duplicate(&other), "far distance {far}");
+        assert!(far > near * 2, "near {near}, far {far}");
+    }
+
+    #[test]
+    fn short_audio_has_no_fingerprint_and_hex_round_trips() {
+        assert_eq!(AudioFingerprint::compute(&[0.1; 1_000], 16_000), None);
+
+        let fp = AudioFingerprint(0x00ab_cdef_0123_4567);
+        let json = serde_json::to_string(&fp).expect("serialize");
+        assert_eq!(json, "\"00abcdef01234567\"");
+        assert_eq!(
+            serde_json::from_str::<AudioFingerprint>(&json).expect("deserialize"),
+            fp
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Limits on how far quiet input is boosted.
+//!
+//! Level normalisation makes whispered speech usable, but the same boost
+//! applied to an idle, hissy microphone turns background noise into
+//! something VAD and the model take for speech. [`GainLimits`] caps the
+//! gain and refuses to boost chunks that look like noise: either nearly
+//! silent, or broadband hiss. Hiss is recognised by its lag-1
+//! autocorrelation, which is near zero for white noise and clearly positive
+//! for voiced speech (most of its energy sits well below Nyquist).
+
+use serde::{Deserialize, Serialize};
+
+/// Ceiling and noise guard for a level-normalising gain.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GainLimits {
+    /// Largest gain ever applied. Default: 9.0.
+    pub max_gain: f32,
+    /// Chunks at or below this RMS are never boosted. Default: 3e-5.
+    pub noise_floor_rms: f32,
+    /// Chunks whose lag-1 autocorrelation is below this are treated as
+    /// hiss and never boosted. `None` disables the check. Default: 0.2.
+    pub hiss_correlation: Option<f32>,
+}
+
+impl Default for GainLimits {
+    fn default() -> Self {
+        Self {
+            max_gain: 9.0,
+            noise_floor_rms: 3e-5,
+            hiss_correlation: Some(0.2),
+        }
+    }
+}
+
+impl GainLimits {
+    /// `desired` limited to `max_gain`, or unity when `samples` (with level
+    /// `rms`) look like noise. Never attenuates below `desired` when that is
+    /// under unity.
+    pub fn limit(&self, samples: &[f32], rms: f32, desired: f32) -> f32 {
+        if desired <= 1.0 {
+            return desired;
+        }
+        if rms <= self.noise_floor_rms {
+            return 1.0;
+        }
+        if self
+            .hiss_correlation
+            .is_some_and(|floor| lag1_autocorrelation(samples) < floor)
+        {
+            return 1.0;
+        }
+        desired.min(self.max_gain.max(1.0))
+    }
+}
+
+/// Normalised lag-1 autocorrelation in [-1, 1]; 0 for silence.
+pub fn lag1_autocorrelation(samples: &[f32]) -> f32 {
+    let energy = samples.iter().map(|s| s * s).sum::<f32>();
+    if energy <= f32::EPSILON {
+        return 0.0;
+    }
+    let lagged = samples.windows(2).map(|w| w[0] * w[1]).sum::<f32>();
+    lagged / energy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_tones_are_boosted_up_to_the_ceiling() {
+        let limits = GainLimits::default();
+        let tone: Vec<f32> = (0..320)
+            .map(|i| 0.002 * (i as f32 * 2.0 * std::f32::consts::PI * 200.0 / 16_000.0).sin())
+            .collect();
+        assert!(lag1_autocorrelation(&tone) > 0.9);
+        assert_eq!(limits.limit(&tone, 0.0014, 6.0), 6.0);
+        assert_eq!(limits.limit(&tone, 0.0014, 40.0), 9.0);
+        assert_eq!(limits.limit(&tone, 1e-5, 6.0), 1.0);
+
+        // Nyquist-rate buzz (autocorrelation -1) is below the hiss floor too.
+        let buzz: Vec<f32> = (0..320)
+            .map(|i| if i % 2 == 0 { 0.002 } else { -0.002 })
+            .collect();
+        assert_eq!(limits.limit(&buzz, 0.002, 6.0), 1.0);
+    }
+}
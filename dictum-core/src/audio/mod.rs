@@ -9,7 +9,8 @@
 //! - Perform I/O
 //!
 //! This module satisfies that contract by writing directly into an SPSC ring buffer
-//! producer whose `push_slice` is lock-free and allocation-free.
+//! producer whose `push_slice` is lock-free and allocation-free. Samples a full
+//! ring turns away are counted by [`CountingProducer`] for the diagnostics.
 //!
 //! # Threading note
 //!
@@ -17,8 +18,14 @@
 //! `AudioCapture` therefore must be created and dropped on the same thread.
 //! The pipeline accomplishes this by calling `open_default` inside `spawn_blocking`.
 
+pub mod agc;
 pub mod device;
+pub mod file;
+pub mod fingerprint;
+pub mod gain;
+pub mod permission;
 pub mod resample;
+pub mod wav;
 
 #[cfg(feature = "audio-cpal")]
 use cpal::{
@@ -27,7 +34,7 @@ use cpal::{
 };
 
 use crate::{
-    buffering::{AudioProducer, Producer},
+    buffering::CountingProducer,
     error::{DictumError, Result},
 };
 use std::sync::{
@@ -36,6 +43,35 @@ use std::sync::{
 };
 use tracing::{error, info, warn};
 
+pub use permission::{check_permission, PermissionStatus};
+
+/// Average interleaved frames of `channels` samples into mono, converting
+/// each sample with `to_f32`. `out` is overwritten; a trailing partial frame
+/// is dropped. Capture and the fallback WAV writer both downmix through
+/// here, so every recogniser hears the same signal. Reuses `out`'s
+/// capacity, so it is safe in the capture callback once warmed up.
+pub fn downmix_into<T: Copy>(
+    interleaved: &[T],
+    channels: usize,
+    to_f32: impl Fn(T) -> f32,
+    out: &mut Vec<f32>,
+) {
+    let channels = channels.max(1);
+    out.clear();
+    out.extend(
+        interleaved
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().map(|&s| to_f32(s)).sum::<f32>() / channels as f32),
+    );
+}
+
+/// [`downmix_into`] for interleaved `f32` audio, into a new buffer.
+pub fn downmix_to_mono(interleaved: &[f32], channels: u16) -> Vec<f32> {
+    let mut mono = Vec::with_capacity(interleaved.len() / usize::from(channels.max(1)));
+    downmix_into(interleaved, usize::from(channels), |s| s, &mut mono);
+    mono
+}
+
 /// Handle to an active audio capture stream.
 ///
 /// **Not `Send`** — `cpal::Stream` is bound to its creation thread on Windows/macOS.
@@ -51,13 +87,14 @@ pub struct AudioCapture {
 }
 
 impl AudioCapture {
-    /// Open an input device by preferred name, otherwise fall back to
-    /// default input device and then first available device.
+    /// Open the input device chosen by [`device::select_input_device`]: the
+    /// pinned `preferred_device` (stable id or name) if present, otherwise
+    /// the current OS default.
     #[cfg(feature = "audio-cpal")]
     pub fn open_with_preference(
-        mut producer: AudioProducer,
+        mut producer: CountingProducer,
         running: Arc<AtomicBool>,
-        preferred_device_name: Option<&str>,
+        preferred_device: Option<&str>,
     ) -> Result<Self> {
         use cpal::traits::HostTrait;
 
@@ -77,74 +114,13 @@ impl AudioCapture {
             return Err(DictumError::NoDefaultInputDevice);
         }
 
+        // Queried on every open, so an unpinned start follows the current
+        // OS default rather than the one seen at launch.
         let default_name = host.default_input_device().and_then(|d| d.name().ok());
-        let preferred_idx = preferred_device_name.and_then(|preferred| {
-            devices
-                .iter()
-                .position(|(name, _)| name.as_str() == preferred)
-        });
-        let default_idx = default_name
-            .as_deref()
-            .and_then(|name| devices.iter().position(|(n, _)| n.as_str() == name));
-        let best_non_loopback_idx = devices
-            .iter()
-            .enumerate()
-            .filter(|(_, (name, _))| !device::is_loopback_like_name(name))
-            .max_by_key(|(_, (name, _))| device::mic_preference_score(name))
-            .map(|(idx, _)| idx);
-
-        if preferred_device_name.is_some() && preferred_idx.is_none() {
-            warn!(
-                "preferred input device '{}' not found, falling back",
-                preferred_device_name.unwrap_or_default()
-            );
-        }
-
-        let selected_idx = if let Some(idx) = preferred_idx {
-            if device::is_loopback_like_name(&devices[idx].0) {
-                if let Some(safe_idx) = best_non_loopback_idx {
-                    if safe_idx != idx {
-                        warn!(
-                            preferred = devices[idx].0.as_str(),
-                            selected = devices[safe_idx].0.as_str(),
-                            "preferred device appears loopback-like; switching to recommended microphone input"
-                        );
-                        safe_idx
-                    } else {
-                        idx
-                    }
-                } else {
-                    idx
-                }
-            } else {
-                idx
-            }
-        } else if let Some(idx) = default_idx {
-            if device::is_loopback_like_name(&devices[idx].0) {
-                if let Some(safe_idx) = best_non_loopback_idx {
-                    if safe_idx != idx {
-                        warn!(
-                            default = devices[idx].0.as_str(),
-                            selected = devices[safe_idx].0.as_str(),
-                            "default input appears loopback-like; switching to recommended microphone input"
-                        );
-                        safe_idx
-                    } else {
-                        idx
-                    }
-                } else {
-                    idx
-                }
-            } else {
-                idx
-            }
-        } else if let Some(idx) = best_non_loopback_idx {
-            warn!("no default input device, falling back to best available microphone input");
-            idx
-        } else {
-            warn!("no default microphone input device, falling back to first available input");
-            0
-        };
+        let names: Vec<&str> = devices.iter().map(|(name, _)| name.as_str()).collect();
+        let selected_idx =
+            device::select_input_device(&names, default_name.as_deref(), preferred_device)
+                .unwrap_or(0);
 
         let (selected_name, device) = devices.swap_remove(selected_idx);
 
@@ -191,16 +167,7 @@ impl AudioCapture {
                             return;
                         }
 
-                        let frames = data.len() / ch;
-                        mix_buf_f32.resize(frames, 0.0);
-                        for f in 0..frames {
-                            let mut sum = 0f32;
-                            let base = f * ch;
-                            for c in 0..ch {
-                                sum += data[base + c];
-                            }
-                            mix_buf_f32[f] = sum / ch as f32;
-                        }
+                        downmix_into(data, ch, |s| s, &mut mix_buf_f32);
                         let written = producer.push_slice(&mix_buf_f32);
                         if written < mix_buf_f32.len() {
                             warn!(
@@ -223,22 +190,7 @@ impl AudioCapture {
                         if !running_i16.load(Ordering::Relaxed) {
                             return;
                         }
-                        let frames = data.len() / ch;
-                        mix_buf_i16.resize(frames, 0.0);
-                        if ch == 1 {
-                            for (idx, sample) in data.iter().take(frames).enumerate() {
-                                mix_buf_i16[idx] = *sample as f32 / 32768.0;
-                            }
-                        } else {
-                            for f in 0..frames {
-                                let mut sum = 0f32;
-                                let base = f * ch;
-                                for c in 0..ch {
-                                    sum += data[base + c] as f32 / 32768.0;
-                                }
-                                mix_buf_i16[f] = sum / ch as f32;
-                            }
-                        }
+                        downmix_into(data, ch, |s| s as f32 / 32768.0, &mut mix_buf_i16);
                         let written = producer.push_slice(&mix_buf_i16);
                         if written < mix_buf_i16.len() {
                             warn!(
@@ -261,22 +213,7 @@ impl AudioCapture {
                         if !running_u8.load(Ordering::Relaxed) {
                             return;
                         }
-                        let frames = data.len() / ch;
-                        mix_buf_u8.resize(frames, 0.0);
-                        if ch == 1 {
-                            for (idx, sample) in data.iter().take(frames).enumerate() {
-                                mix_buf_u8[idx] = (*sample as f32 - 128.0) / 128.0;
-                            }
-                        } else {
-                            for f in 0..frames {
-                                let mut sum = 0f32;
-                                let base = f * ch;
-                                for c in 0..ch {
-                                    sum += (data[base + c] as f32 - 128.0) / 128.0;
-                                }
-                                mix_buf_u8[f] = sum / ch as f32;
-                            }
-                        }
+                        downmix_into(data, ch, |s| (s as f32 - 128.0) / 128.0, &mut mix_buf_u8);
                         let written = producer.push_slice(&mix_buf_u8);
                         if written < mix_buf_u8.len() {
                             warn!(
@@ -318,7 +255,7 @@ impl AudioCapture {
     /// Returns `DictumError::NoDefaultInputDevice` when no microphone is available,
     /// or `DictumError::AudioStream` if cpal fails to build the stream.
     #[cfg(feature = "audio-cpal")]
-    pub fn open_default(producer: AudioProducer, running: Arc<AtomicBool>) -> Result<Self> {
+    pub fn open_default(producer: CountingProducer, running: Arc<AtomicBool>) -> Result<Self> {
         Self::open_with_preference(producer, running, None)
     }
 
@@ -332,16 +269,16 @@ impl AudioCapture {
 #[cfg(not(feature = "audio-cpal"))]
 impl AudioCapture {
     pub fn open_with_preference(
-        _producer: AudioProducer,
+        _producer: CountingProducer,
         _running: Arc<AtomicBool>,
-        _preferred_device_name: Option<&str>,
+        _preferred_device: Option<&str>,
     ) -> Result<Self> {
         Err(DictumError::AudioStream(
             "compiled without audio-cpal feature".into(),
         ))
     }
 
-    pub fn open_default(producer: AudioProducer, running: Arc<AtomicBool>) -> Result<Self> {
+    pub fn open_default(producer: CountingProducer, running: Arc<AtomicBool>) -> Result<Self> {
         Self::open_with_preference(producer, running, None)
     }
 
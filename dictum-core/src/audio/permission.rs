@@ -0,0 +1,149 @@
+//! Microphone permission pre-flight.
+//!
+//! A capture stream opened without OS permission fails (or records silence)
+//! with nothing that tells it apart from a broken device. Hosts call
+//! [`check_permission`] before starting so they can send the user to the
+//! right settings page instead.
+//!
+//! Windows keeps the privacy switches in the capability consent store in the
+//! registry, read here through the safe `windows-registry` API. Other
+//! platforms have no query reachable without FFI (this crate forbids
+//! `unsafe`) and report `Unknown`.
+//!
+//! The check makes blocking registry calls: async hosts should run it off
+//! the executor (`spawn_blocking`).
+
+use serde::{Deserialize, Serialize};
+
+/// Whether the OS lets this process capture from the microphone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+    /// The user has not made a choice yet; starting may prompt.
+    Undetermined,
+    /// The platform permission could not be queried.
+    Unknown,
+}
+
+impl PermissionStatus {
+    /// Whether a start attempt is worth making.
+    pub fn allows_capture(self) -> bool {
+        !matches!(self, Self::Denied)
+    }
+}
+
+/// Query the OS microphone permission for this process.
+pub fn check_permission() -> PermissionStatus {
+    #[cfg(windows)]
+    {
+        windows_consent::query()
+    }
+    #[cfg(not(windows))]
+    {
+        PermissionStatus::Unknown
+    }
+}
+
+/// Map the Windows consent-store `Value`s to a status: the device-wide
+/// switch, the per-user switch, and the per-user switch for desktop
+/// (non-packaged) apps. Any `Deny` wins; a missing value means the switch
+/// was never touched.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn status_from_consent(
+    system: Option<&str>,
+    user: Option<&str>,
+    desktop_apps: Option<&str>,
+) -> PermissionStatus {
+    let values = [system, user, desktop_apps];
+    if values
+        .iter()
+        .flatten()
+        .any(|v| v.eq_ignore_ascii_case("deny"))
+    {
+        return PermissionStatus::Denied;
+    }
+    if values
+        .iter()
+        .flatten()
+        .any(|v| !v.eq_ignore_ascii_case("allow"))
+    {
+        return PermissionStatus::Unknown;
+    }
+    // The device-wide switch defaults to on and is often absent; the user's
+    // own switch is the one that records a choice.
+    if user.is_some() {
+        PermissionStatus::Granted
+    } else {
+        PermissionStatus::Undetermined
+    }
+}
+
+#[cfg(windows)]
+mod windows_consent {
+    use super::{status_from_consent, PermissionStatus};
+    use windows_registry::{Key, CURRENT_USER, LOCAL_MACHINE};
+
+    const CONSENT_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone";
+    /// `HRESULT_FROM_WIN32(ERROR_FILE_NOT_FOUND)`: no such key or value.
+    const NOT_FOUND: i32 = 0x8007_0002_u32 as i32;
+
+    fn read(root: &Key, subkey: &str) -> Result<Option<String>, ()> {
+        let value = root
+            .open(format!(r"{CONSENT_KEY}{subkey}"))
+            .and_then(|key| key.get_string("Value"));
+        match value {
+            Ok(value) => Ok(Some(value)),
+            // A missing key or value is a normal "never set" answer.
+            Err(e) if e.code().0 == NOT_FOUND => Ok(None),
+            Err(_) => Err(()),
+        }
+    }
+
+    pub(super) fn query() -> PermissionStatus {
+        let (Ok(system), Ok(user), Ok(desktop)) = (
+            read(LOCAL_MACHINE, ""),
+            read(CURRENT_USER, ""),
+            read(CURRENT_USER, r"\NonPackaged"),
+        ) else {
+            return PermissionStatus::Unknown;
+        };
+        status_from_consent(system.as_deref(), user.as_deref(), desktop.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{status_from_consent, PermissionStatus};
+
+    #[test]
+    fn consent_store_values_map_to_status() {
+        use PermissionStatus::*;
+
+        assert_eq!(
+            status_from_consent(Some("Allow"), Some("Allow"), Some("Allow")),
+            Granted
+        );
+        assert_eq!(status_from_consent(None, Some("Allow"), None), Granted);
+        assert_eq!(
+            status_from_consent(Some("Deny"), Some("Allow"), None),
+            Denied
+        );
+        assert_eq!(
+            status_from_consent(Some("Allow"), Some("Allow"), Some("Deny")),
+            Denied
+        );
+        assert_eq!(status_from_consent(None, None, None), Undetermined);
+        assert_eq!(status_from_consent(Some("Allow"), None, None), Undetermined);
+        assert_eq!(status_from_consent(None, Some("Prompt"), None), Unknown);
+        assert!(!Denied.allows_capture());
+        assert!(Unknown.allows_capture());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn unsupported_platforms_report_unknown() {
+        assert_eq!(super::check_permission(), PermissionStatus::Unknown);
+    }
+}
@@ -137,6 +137,14 @@ impl RateConverter {
         result
     }
 
+    /// Input samples held back until the next full `chunk_size` block.
+    ///
+    /// Non-zero after an empty [`process`](Self::process) result means the
+    /// converter is buffering rather than dropping audio.
+    pub fn pending_input(&self) -> usize {
+        self.input_buf.len().saturating_sub(self.input_start)
+    }
+
     /// Returns `true` when capture rate == target rate (no resampling occurs).
     pub fn is_passthrough(&self) -> bool {
         self.resampler.is_none()
@@ -192,7 +200,9 @@ mod tests {
         // Two 500-sample pushes = 1000 total ≥ 960 chunk_size → should produce output
         let out1 = rc.process(&vec![0.0f32; 500]);
         assert!(out1.is_empty());
+        assert_eq!(rc.pending_input(), 500);
         let out2 = rc.process(&vec![0.0f32; 500]);
         assert!(!out2.is_empty(), "second push should trigger processing");
+        assert_eq!(rc.pending_input(), 40);
     }
 }
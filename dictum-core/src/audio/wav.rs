@@ -0,0 +1,554 @@
+//! WAV file loading for offline transcription and benchmarking, and
+//! writing for debug audio dumps and fallback recognisers.
+//!
+//! `transcribe_file` runs on whatever file the user points it at, so the RIFF
+//! parser here trusts nothing in the header: every chunk size is checked
+//! against the bytes actually present, header fields are capped to sane
+//! limits, and anything other than plain integer PCM or IEEE float is
+//! rejected with [`DictumError::InvalidWav`] rather than guessed at.
+
+use std::path::Path;
+
+use crate::{
+    buffering::chunk::AudioChunk,
+    error::{DictumError, Result},
+};
+
+/// Largest file `read_wav_mono` will load (about 1.5 h of 48 kHz stereo
+/// 32-bit float).
+pub const MAX_WAV_BYTES: u64 = 2 << 30;
+/// Most channels accepted in a `fmt ` chunk.
+pub const MAX_WAV_CHANNELS: u16 = 32;
+/// Highest sample rate accepted in a `fmt ` chunk.
+pub const MAX_WAV_SAMPLE_RATE: u32 = 384_000;
+
+const FORMAT_PCM: u16 = 0x0001;
+const FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// Bytes 2..16 of every `KSDATAFORMAT_SUBTYPE_*` GUID derived from a legacy
+/// format tag; the tag itself is in bytes 0..2.
+const SUBFORMAT_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// Read a WAV file and downmix it to a mono f32 chunk at the file's native rate.
+///
+/// Integer PCM is scaled to [-1, 1]; float PCM is passed through. Resampling to
+/// the model rate is left to the `SpeechModel` implementation.
+///
+/// # Errors
+/// `InvalidWav` for files over [`MAX_WAV_BYTES`] and for anything
+/// [`parse_wav_mono`] rejects.
+pub fn read_wav_mono(path: impl AsRef<Path>) -> Result<AudioChunk> {
+    let path = path.as_ref();
+    let io_err = |e: std::io::Error| DictumError::Other(anyhow::anyhow!("{}: {e}", path.display()));
+    let len = std::fs::metadata(path).map_err(io_err)?.len();
+    if len > MAX_WAV_BYTES {
+        return Err(DictumError::InvalidWav(format!(
+            "{}: {len} bytes exceeds the {MAX_WAV_BYTES}-byte limit",
+            path.display()
+        )));
+    }
+    let bytes = std::fs::read(path).map_err(io_err)?;
+    decode(&bytes).map_err(|e| DictumError::InvalidWav(format!("{}: {e}", path.display())))
+}
+
+/// Parse an in-memory WAV file; see [`read_wav_mono`].
+///
+/// Never panics on malformed input. A `data` chunk that claims more bytes
+/// than the file holds is read up to the last whole frame, as players do
+/// for recordings cut off mid-write; a truncated `fmt ` chunk is an error.
+///
+/// # Errors
+/// `InvalidWav` for non-RIFF input, a missing or malformed `fmt ` or `data`
+/// chunk, out-of-range header fields, and encodings other than 8/16/24/32-bit
+/// integer PCM or 32/64-bit float (including `WAVE_FORMAT_EXTENSIBLE` files
+/// with any other subformat).
+pub fn parse_wav_mono(bytes: &[u8]) -> Result<AudioChunk> {
+    decode(bytes).map_err(DictumError::InvalidWav)
+}
+
+/// Write mono `samples` as a 32-bit float WAV file at `sample_rate`.
+///
+/// # Errors
+/// `DictumError::Other` with the path when the file cannot be written.
+pub fn write_wav_mono(path: impl AsRef<Path>, samples: &[f32], sample_rate: u32) -> Result<()> {
+    let path = path.as_ref();
+    let bytes = encode_wav(samples, 1, sample_rate, WavSampleFormat::Float32);
+    std::fs::write(path, bytes)
+        .map_err(|e| DictumError::Other(anyhow::anyhow!("{}: {e}", path.display())))
+}
+
+/// Sample encoding of WAV audio written by [`encode_wav`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WavSampleFormat {
+    /// 16-bit integer PCM; accepted everywhere.
+    #[default]
+    Pcm16,
+    /// 32-bit IEEE float; keeps the detail of quiet, normalized audio.
+    Float32,
+}
+
+/// Mono WAV file bytes for `samples` in `format`. `samples` holds
+/// interleaved frames of `channels`; anything above mono is downmixed the way
+/// capture does it, so a fallback hears what the local model heard.
+pub fn encode_wav(
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+    format: WavSampleFormat,
+) -> Vec<u8> {
+    let mono;
+    let samples = if channels > 1 {
+        mono = super::downmix_to_mono(samples, channels);
+        &mono
+    } else {
+        samples
+    };
+    let (format_tag, bytes_per_sample, fmt_len) = match format {
+        WavSampleFormat::Pcm16 => (1u16, 2u32, 16u32),
+        // Non-PCM formats carry a `cbSize` field and need a `fact` chunk.
+        WavSampleFormat::Float32 => (3u16, 4u32, 18u32),
+    };
+    let data_len = samples.len() as u32 * bytes_per_sample;
+    let fact_len = if format == WavSampleFormat::Pcm16 {
+        0
+    } else {
+        12
+    };
+    let riff_len = 4 + (8 + fmt_len) + fact_len + 8 + data_len;
+
+    let mut out = Vec::with_capacity(8 + riff_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&riff_len.to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&fmt_len.to_le_bytes());
+    out.extend_from_slice(&format_tag.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // mono
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * bytes_per_sample).to_le_bytes()); // byte rate
+    out.extend_from_slice(&(bytes_per_sample as u16).to_le_bytes()); // block align
+    out.extend_from_slice(&(bytes_per_sample as u16 * 8).to_le_bytes()); // bits per sample
+    if fmt_len == 18 {
+        out.extend_from_slice(&0u16.to_le_bytes()); // cbSize
+    }
+    if fact_len > 0 {
+        out.extend_from_slice(b"fact");
+        out.extend_from_slice(&4u32.to_le_bytes());
+        out.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    }
+
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for &sample in samples {
+        let sample = sample.clamp(-1.0, 1.0);
+        match format {
+            WavSampleFormat::Pcm16 => {
+                let v = (sample * i16::MAX as f32).round() as i16;
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            WavSampleFormat::Float32 => out.extend_from_slice(&sample.to_le_bytes()),
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Int,
+    Float,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Format {
+    encoding: Encoding,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+impl Format {
+    fn bytes_per_sample(self) -> usize {
+        usize::from(self.bits_per_sample / 8)
+    }
+
+    fn block_align(self) -> usize {
+        self.bytes_per_sample() * usize::from(self.channels)
+    }
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Option<u16> {
+    bytes
+        .get(at..at + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn decode(bytes: &[u8]) -> std::result::Result<AudioChunk, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a RIFF/WAVE file".into());
+    }
+
+    // The RIFF size is ignored: streaming writers leave it 0 or 0xFFFFFFFF,
+    // and the chunk walk is bounded by the real length anyway.
+    let mut format = None;
+    let mut data = None;
+    let mut pos = 12;
+    while let Some(size) = u32_at(bytes, pos + 4) {
+        let id = &bytes[pos..pos + 4];
+        let body_start = pos + 8;
+        let available = bytes.len() - body_start;
+        let size = usize::try_from(size).unwrap_or(usize::MAX);
+        match id {
+            b"fmt " if format.is_none() => {
+                if size > available {
+                    return Err(format!(
+                        "fmt chunk claims {size} bytes, only {available} present"
+                    ));
+                }
+                format = Some(parse_format(&bytes[body_start..body_start + size])?);
+            }
+            b"data" if data.is_none() => {
+                data = Some(&bytes[body_start..body_start + size.min(available)]);
+            }
+            _ => {}
+        }
+        if size >= available {
+            break;
+        }
+        // Chunks are word-aligned; the pad byte is not counted in `size`.
+        pos = body_start + size + (size & 1);
+    }
+
+    let format = format.ok_or("missing fmt chunk")?;
+    let data = data.ok_or("missing data chunk")?;
+    let block_align = format.block_align();
+    let channels = usize::from(format.channels);
+    let width = format.bytes_per_sample();
+
+    let samples = data
+        .chunks_exact(block_align)
+        .map(|frame| {
+            let sum: f32 = frame
+                .chunks_exact(width)
+                .map(|sample| decode_sample(sample, format.encoding))
+                .sum();
+            sum / channels as f32
+        })
+        .collect();
+    Ok(AudioChunk::new(samples, format.sample_rate))
+}
+
+fn parse_format(body: &[u8]) -> std::result::Result<Format, String> {
+    let field =
+        |at| u16_at(body, at).ok_or_else(|| format!("fmt chunk too short ({} bytes)", body.len()));
+    let mut tag = field(0)?;
+    let channels = field(2)?;
+    let sample_rate = u32_at(body, 4).ok_or("fmt chunk too short")?;
+    let block_align = field(12)?;
+    let bits_per_sample = field(14)?;
+
+    if tag == FORMAT_EXTENSIBLE {
+        let cb_size = field(16)?;
+        let guid = body
+            .get(24..40)
+            .filter(|_| cb_size >= 22)
+            .ok_or("extensible fmt chunk is missing its subformat")?;
+        if guid[2..] != SUBFORMAT_GUID_TAIL {
+            return Err("unsupported extensible subformat".into());
+        }
+        tag = u16::from_le_bytes([guid[0], guid[1]]);
+    }
+
+    let encoding = match (tag, bits_per_sample) {
+        (FORMAT_PCM, 8 | 16 | 24 | 32) => Encoding::Int,
+        (FORMAT_IEEE_FLOAT, 32 | 64) => Encoding::Float,
+        (FORMAT_PCM | FORMAT_IEEE_FLOAT, bits) => {
+            return Err(format!("unsupported sample width of {bits} bits"))
+        }
+        (tag, _) => return Err(format!("unsupported encoding (format tag {tag:#06x})")),
+    };
+    if channels == 0 || channels > MAX_WAV_CHANNELS {
+        return Err(format!(
+            "{channels} channels is outside 1..={MAX_WAV_CHANNELS}"
+        ));
+    }
+    if sample_rate == 0 || sample_rate > MAX_WAV_SAMPLE_RATE {
+        return Err(format!(
+            "sample rate {sample_rate} Hz is outside 1..={MAX_WAV_SAMPLE_RATE}"
+        ));
+    }
+    let format = Format {
+        encoding,
+        channels,
+        sample_rate,
+        bits_per_sample,
+    };
+    // Frames are split on the computed alignment; a header that disagrees
+    // with itself is not worth guessing about.
+    if usize::from(block_align) != format.block_align() {
+        return Err(format!(
+            "block align {block_align} does not match {channels} x {bits_per_sample}-bit samples"
+        ));
+    }
+    Ok(format)
+}
+
+fn decode_sample(bytes: &[u8], encoding: Encoding) -> f32 {
+    let value = match (encoding, bytes) {
+        // 8-bit PCM is unsigned with a 128 midpoint.
+        (Encoding::Int, &[b]) => (f32::from(b) - 128.0) / 127.0,
+        (Encoding::Int, &[a, b]) => f32::from(i16::from_le_bytes([a, b])) / i16::MAX as f32,
+        (Encoding::Int, &[a, b, c]) => {
+            // Sign-extend by placing the 24 bits at the top of an i32.
+            let v = i32::from_le_bytes([0, a, b, c]) >> 8;
+            v as f32 / ((1 << 23) - 1) as f32
+        }
+        (Encoding::Int, &[a, b, c, d]) => i32::from_le_bytes([a, b, c, d]) as f32 / i32::MAX as f32,
+        (Encoding::Float, &[a, b, c, d]) => f32::from_le_bytes([a, b, c, d]),
+        (Encoding::Float, &[a, b, c, d, e, f, g, h]) => {
+            f64::from_le_bytes([a, b, c, d, e, f, g, h]) as f32
+        }
+        _ => 0.0,
+    };
+    // NaN or infinity in float data would poison every downstream RMS and
+    // mel frame.
+    if value.is_finite() {
+        value
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        encode_wav, parse_wav_mono, read_wav_mono, write_wav_mono, WavSampleFormat,
+        SUBFORMAT_GUID_TAIL,
+    };
+    use crate::error::DictumError;
+
+    /// A canonical 44-byte-header WAV around `data`.
+    fn wav(tag: u16, channels: u16, rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let align = channels * bits / 8;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&tag.to_le_bytes());
+        out.extend_from_slice(&channels.to_le_bytes());
+        out.extend_from_slice(&rate.to_le_bytes());
+        out.extend_from_slice(&(rate * u32::from(align)).to_le_bytes());
+        out.extend_from_slice(&align.to_le_bytes());
+        out.extend_from_slice(&bits.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn extensible(subformat_tag: u16, tail: [u8; 14]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF\0\0\0\0WAVEfmt ");
+        out.extend_from_slice(&40u32.to_le_bytes());
+        out.extend_from_slice(&0xFFFEu16.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&16_000u32.to_le_bytes());
+        out.extend_from_slice(&32_000u32.to_le_bytes());
+        out.extend_from_slice(&2u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(&22u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(&4u32.to_le_bytes());
+        out.extend_from_slice(&subformat_tag.to_le_bytes());
+        out.extend_from_slice(&tail);
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&2u32.to_le_bytes());
+        out.extend_from_slice(&i16::MAX.to_le_bytes());
+        out
+    }
+
+    fn rejection(bytes: &[u8]) -> String {
+        match parse_wav_mono(bytes) {
+            Err(DictumError::InvalidWav(msg)) => msg,
+            other => panic!("expected InvalidWav, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stereo_int16_is_downmixed_and_scaled() {
+        let path = std::env::temp_dir().join(format!(
+            "dictum-wav-test-{}-{}.wav",
+            std::process::id(),
+            line!()
+        ));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).expect("create wav");
+        for (l, r) in [(i16::MAX, 0), (0, i16::MIN + 1), (1000, 1000)] {
+            writer.write_sample(l).expect("write");
+            writer.write_sample(r).expect("write");
+        }
+        writer.finalize().expect("finalize");
+
+        let chunk = read_wav_mono(&path).expect("read wav");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(chunk.sample_rate, 8_000);
+        assert_eq!(chunk.samples.len(), 3);
+        assert!((chunk.samples[0] - 0.5).abs() < 1e-4, "{:?}", chunk.samples);
+        assert!((chunk.samples[1] + 0.5).abs() < 1e-4, "{:?}", chunk.samples);
+        assert!((chunk.samples[2] - 1000.0 / i16::MAX as f32).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sample_widths_and_float_are_decoded() {
+        let chunk = parse_wav_mono(&wav(1, 1, 16_000, 8, &[255, 128, 1])).expect("8-bit");
+        assert_eq!(chunk.samples, vec![1.0, 0.0, -1.0]);
+
+        let data = [0xFF, 0xFF, 0x7F, 0x01, 0x00, 0x80];
+        let chunk = parse_wav_mono(&wav(1, 1, 16_000, 24, &data)).expect("24-bit");
+        assert!((chunk.samples[0] - 1.0).abs() < 1e-6);
+        assert!((chunk.samples[1] + 1.0).abs() < 1e-6, "{:?}", chunk.samples);
+
+        let mut data = Vec::new();
+        for v in [0.25f32, f32::NAN, f32::INFINITY] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        let chunk = parse_wav_mono(&wav(3, 1, 16_000, 32, &data)).expect("float");
+        assert_eq!(chunk.samples, vec![0.25, 0.0, 0.0]);
+
+        let chunk = parse_wav_mono(&extensible(1, SUBFORMAT_GUID_TAIL)).expect("extensible pcm");
+        assert_eq!(chunk.samples, vec![1.0]);
+    }
+
+    #[test]
+    fn corrupt_fixtures_are_rejected() {
+        let good = wav(1, 1, 16_000, 16, &[0, 0, 0, 0]);
+        assert!(rejection(&good[..11]).contains("RIFF"));
+        assert!(rejection(b"RIFX\0\0\0\0WAVE").contains("RIFF"));
+
+        // Cut inside the fmt chunk.
+        assert!(rejection(&good[..30]).contains("fmt chunk claims 16 bytes"));
+        // No data chunk at all.
+        assert!(rejection(&good[..36]).contains("missing data"));
+        // data before fmt is fine, but fmt is still required.
+        let mut only_data = b"RIFF\0\0\0\0WAVE".to_vec();
+        only_data.extend_from_slice(&good[36..]);
+        assert!(rejection(&only_data).contains("missing fmt"));
+
+        assert!(rejection(&wav(1, 0, 16_000, 16, &[])).contains("channels"));
+        assert!(rejection(&wav(1, 200, 16_000, 16, &[])).contains("channels"));
+        assert!(rejection(&wav(1, 1, 0, 16, &[])).contains("sample rate"));
+        assert!(rejection(&wav(1, 1, 16_000, 12, &[])).contains("width"));
+        assert!(rejection(&wav(3, 1, 16_000, 16, &[])).contains("width"));
+        // μ-law and ADPCM.
+        assert!(rejection(&wav(7, 1, 8_000, 8, &[])).contains("0x0007"));
+        assert!(rejection(&wav(2, 1, 8_000, 4, &[])).contains("0x0002"));
+
+        let mut lying_align = good.clone();
+        lying_align[32] = 7;
+        assert!(rejection(&lying_align).contains("block align"));
+
+        let mut tail = SUBFORMAT_GUID_TAIL;
+        tail[13] ^= 0xFF;
+        assert!(rejection(&extensible(1, tail)).contains("subformat"));
+        assert!(rejection(&extensible(0x55, SUBFORMAT_GUID_TAIL)).contains("0x0055"));
+        let mut short_ext = extensible(1, SUBFORMAT_GUID_TAIL);
+        short_ext[36] = 0;
+        assert!(rejection(&short_ext).contains("subformat"));
+    }
+
+    #[test]
+    fn lying_data_size_reads_whole_frames_present() {
+        let mut bytes = wav(1, 2, 16_000, 16, &[0, 0x40, 0, 0x40, 0, 0x40]);
+        // Claim far more data than exists; the dangling half frame is dropped.
+        let at = bytes.len() - 10;
+        bytes[at..at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let chunk = parse_wav_mono(&bytes).expect("truncated data");
+        assert_eq!(chunk.samples.len(), 1);
+        assert!((chunk.samples[0] - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn wav_writer_headers_match_sample_format() {
+        let samples = [0.0, 0.25, -0.5, 1.5];
+        let u16_at = |b: &[u8], at: usize| u16::from_le_bytes([b[at], b[at + 1]]);
+        let u32_at = |b: &[u8], at: usize| u32::from_le_bytes(b[at..at + 4].try_into().unwrap());
+
+        let pcm = encode_wav(&samples, 1, 16_000, WavSampleFormat::Pcm16);
+        assert_eq!(&pcm[0..4], b"RIFF");
+        assert_eq!(u32_at(&pcm, 4) as usize, pcm.len() - 8);
+        assert_eq!(u16_at(&pcm, 20), 1); // PCM
+        assert_eq!(u32_at(&pcm, 28), 32_000); // byte rate
+        assert_eq!(u16_at(&pcm, 32), 2); // block align
+        assert_eq!(u16_at(&pcm, 34), 16);
+        assert_eq!(&pcm[36..40], b"data");
+        assert_eq!(u32_at(&pcm, 40), 8);
+        assert_eq!(i16::from_le_bytes([pcm[48], pcm[49]]), -16_384);
+
+        let float = encode_wav(&samples, 1, 16_000, WavSampleFormat::Float32);
+        assert_eq!(u32_at(&float, 4) as usize, float.len() - 8);
+        assert_eq!(u32_at(&float, 16), 18); // fmt chunk with cbSize
+        assert_eq!(u16_at(&float, 20), 3); // IEEE float
+        assert_eq!(u32_at(&float, 28), 64_000);
+        assert_eq!(u16_at(&float, 32), 4);
+        assert_eq!(u16_at(&float, 34), 32);
+        assert_eq!(&float[38..42], b"fact");
+        assert_eq!(u32_at(&float, 46), 4); // sample frames
+        assert_eq!(&float[50..54], b"data");
+        assert_eq!(u32_at(&float, 54), 16);
+        let sample_at =
+            |i: usize| f32::from_le_bytes(float[58 + i * 4..62 + i * 4].try_into().unwrap());
+        assert_eq!(sample_at(1), 0.25);
+        assert_eq!(sample_at(3), 1.0, "out-of-range samples are clamped");
+    }
+
+    #[test]
+    fn stereo_fallback_wav_matches_the_capture_downmix() {
+        // Left and right disagree, so averaging is visible.
+        let stereo: Vec<f32> = (0..320)
+            .flat_map(|i| {
+                let t = i as f32 / 16_000.0;
+                let left = 0.6 * (2.0 * std::f32::consts::PI * 440.0 * t).sin();
+                [left, -0.2 * left + 0.1]
+            })
+            .collect();
+
+        let mut captured = Vec::new();
+        crate::audio::downmix_into(&stereo, 2, |s| s, &mut captured);
+        let wav = encode_wav(&stereo, 2, 16_000, WavSampleFormat::Float32);
+        let fallback = parse_wav_mono(&wav).expect("parse fallback wav");
+
+        assert_eq!(fallback.sample_rate, 16_000);
+        assert_eq!(captured.len(), 320);
+        assert_eq!(fallback.samples, captured);
+    }
+
+    #[test]
+    fn written_float_wav_reads_back_unchanged() {
+        let path = std::env::temp_dir().join(format!(
+            "dictum-wav-test-{}-{}.wav",
+            std::process::id(),
+            line!()
+        ));
+        let samples = [0.0, 0.125, -0.75, 1.0];
+        write_wav_mono(&path, &samples, 22_050).expect("write wav");
+
+        let chunk = read_wav_mono(&path).expect("read wav");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(chunk.sample_rate, 22_050);
+        assert_eq!(chunk.samples, samples);
+    }
+}
@@ -14,7 +14,9 @@ fn main() {
 
 #[cfg(feature = "onnx")]
 fn run() -> Result<(), String> {
-    use dictum_core::{buffering::chunk::AudioChunk, OnnxModel, OnnxModelConfig, SpeechModel};
+    use dictum_core::{
+        audio::wav::read_wav_mono, eval::word_error_rate, OnnxModel, OnnxModelConfig, SpeechModel,
+    };
     use serde::Serialize;
     use std::collections::BTreeMap;
     use std::path::{Path, PathBuf};
@@ -38,6 +40,7 @@ fn run() -> Result<(), String> {
         is_empty: bool,
         used_placeholder: bool,
         similarity_to_expected: Option<f32>,
+        word_error_rate: Option<f32>,
     }
 
     #[derive(Debug, Clone, Serialize)]
@@ -66,6 +69,7 @@ fn run() -> Result<(), String> {
         placeholder_rate: f64,
         avg_confidence: Option<f32>,
         avg_similarity_to_expected: Option<f32>,
+        avg_word_error_rate: Option<f32>,
         categories: Vec<CategorySummary>,
         cases: Vec<CaseResult>,
     }
@@ -141,47 +145,6 @@ fn run() -> Result<(), String> {
         Ok(())
     }
 
-    fn read_wav_mono_f32(path: &Path) -> Result<(Vec<f32>, u32), String> {
-        let mut reader = hound::WavReader::open(path).map_err(|e| e.to_string())?;
-        let spec = reader.spec();
-        let channels = usize::from(spec.channels.max(1));
-
-        let interleaved: Vec<f32> = match spec.sample_format {
-            hound::SampleFormat::Float => reader
-                .samples::<f32>()
-                .map(|s| s.map_err(|e| e.to_string()))
-                .collect::<Result<Vec<_>, _>>()?,
-            hound::SampleFormat::Int => {
-                if spec.bits_per_sample <= 16 {
-                    reader
-                        .samples::<i16>()
-                        .map(|s| {
-                            s.map(|v| (v as f32) / (i16::MAX as f32))
-                                .map_err(|e| e.to_string())
-                        })
-                        .collect::<Result<Vec<_>, _>>()?
-                } else {
-                    let max = ((1_i64 << (spec.bits_per_sample - 1)) - 1) as f32;
-                    reader
-                        .samples::<i32>()
-                        .map(|s| s.map(|v| (v as f32) / max).map_err(|e| e.to_string()))
-                        .collect::<Result<Vec<_>, _>>()?
-                }
-            }
-        };
-
-        if channels == 1 {
-            return Ok((interleaved, spec.sample_rate));
-        }
-
-        let mut mono = Vec::with_capacity(interleaved.len() / channels);
-        for frame in interleaved.chunks(channels) {
-            let sum = frame.iter().copied().sum::<f32>();
-            mono.push(sum / channels as f32);
-        }
-        Ok((mono, spec.sample_rate))
-    }
-
     fn category_for(path: &Path) -> String {
         let joined = path
             .components()
@@ -324,8 +287,7 @@ fn run() -> Result<(), String> {
 
     let mut cases = Vec::new();
     for wav in &wav_files {
-        let (samples, sample_rate) = read_wav_mono_f32(wav)?;
-        let chunk = AudioChunk::new(samples, sample_rate);
+        let chunk = read_wav_mono(wav).map_err(|e| e.to_string())?;
         let expected = expected_text_for(wav);
         let category = category_for(wav);
         let file = wav
@@ -357,6 +319,9 @@ fn run() -> Result<(), String> {
             let similarity_to_expected = expected
                 .as_ref()
                 .and_then(|exp| overlap_similarity(exp, &text));
+            let word_error_rate = expected
+                .as_ref()
+                .and_then(|exp| word_error_rate(exp, &text));
             let used_placeholder = text.trim().eq_ignore_ascii_case("[speech captured]");
             cases.push(CaseResult {
                 file: file.clone(),
@@ -368,6 +333,7 @@ fn run() -> Result<(), String> {
                 is_empty: text.trim().is_empty(),
                 used_placeholder,
                 similarity_to_expected,
+                word_error_rate,
             });
             println!(
                 "{file} [{iteration}/{iters}] {latency:.1} ms",
@@ -398,6 +364,10 @@ fn run() -> Result<(), String> {
         .iter()
         .filter_map(|r| r.similarity_to_expected)
         .collect::<Vec<_>>();
+    let all_wer = cases
+        .iter()
+        .filter_map(|r| r.word_error_rate)
+        .collect::<Vec<_>>();
     let summary = Summary {
         fixtures_dir: args.fixtures_dir.display().to_string(),
         iterations: args.iterations,
@@ -430,6 +400,11 @@ fn run() -> Result<(), String> {
         } else {
             Some(all_sim.iter().sum::<f32>() / all_sim.len() as f32)
         },
+        avg_word_error_rate: if all_wer.is_empty() {
+            None
+        } else {
+            Some(all_wer.iter().sum::<f32>() / all_wer.len() as f32)
+        },
         categories,
         cases,
     };
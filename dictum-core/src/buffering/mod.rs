@@ -5,7 +5,13 @@
 
 pub mod chunk;
 
-use ringbuf::{traits::Split, HeapRb};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use ringbuf::{
+    traits::{Observer, Split},
+    HeapRb,
+};
 
 pub use ringbuf::traits::{Consumer, Producer};
 
@@ -15,14 +21,132 @@ pub type AudioProducer = ringbuf::HeapProd<f32>;
 /// Type alias for the consumer half — held by the pipeline thread.
 pub type AudioConsumer = ringbuf::HeapCons<f32>;
 
+/// Producer half that counts the samples a full ring turns away, so capture
+/// drops show up in the pipeline diagnostics rather than only in the log.
+pub struct CountingProducer {
+    producer: AudioProducer,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl CountingProducer {
+    /// Wrap `producer`, adding every dropped sample to `dropped`.
+    pub fn new(producer: AudioProducer, dropped: Arc<AtomicUsize>) -> Self {
+        Self { producer, dropped }
+    }
+
+    /// Push as many of `samples` as fit and return how many were written.
+    /// Lock- and allocation-free, like the wrapped `push_slice`.
+    pub fn push_slice(&mut self, samples: &[f32]) -> usize {
+        let written = self.producer.push_slice(samples);
+        if written < samples.len() {
+            self.dropped
+                .fetch_add(samples.len() - written, Ordering::Relaxed);
+        }
+        written
+    }
+
+    /// Samples pushed but not yet taken by the consumer.
+    pub fn occupied_len(&self) -> usize {
+        self.producer.occupied_len()
+    }
+
+    /// Samples that can be pushed right now without dropping any.
+    pub fn vacant_len(&self) -> usize {
+        self.producer.vacant_len()
+    }
+}
+
+/// How full `consumer`'s ring is, in percent of its capacity.
+pub fn ring_fill_pct(consumer: &AudioConsumer) -> f32 {
+    consumer.occupied_len() as f32 * 100.0 / consumer.capacity().get() as f32
+}
+
 /// Buffer capacity: 2^22 = 4 194 304 f32 samples ≈ 87.4 s at 48 kHz.
 /// This protects long dictation from callback drops while final inference runs.
 pub const RING_CAPACITY: usize = 1 << 22;
 
+/// Largest ring `create_audio_ring_with_capacity` accepts: 2^26 samples,
+/// 256 MB, ≈ 23 min at 48 kHz.
+pub const MAX_RING_CAPACITY: usize = 1 << 26;
+
 /// Create a matched producer/consumer pair backed by a heap-allocated ring buffer.
 ///
 /// # Panics
 /// Never panics — `HeapRb` construction cannot fail for reasonable capacities.
 pub fn create_audio_ring() -> (AudioProducer, AudioConsumer) {
-    HeapRb::<f32>::new(RING_CAPACITY).split()
+    create_audio_ring_with_capacity(RING_CAPACITY)
+}
+
+/// Capacity a ring asked for `requested` samples actually gets: the next
+/// power of two, at least 1, so index wrap-around stays a mask.
+pub fn effective_ring_capacity(requested: usize) -> usize {
+    requested.clamp(1, MAX_RING_CAPACITY).next_power_of_two()
+}
+
+/// Like `create_audio_ring`, holding `effective_ring_capacity(capacity)`
+/// samples. Smaller rings save memory at the cost of headroom while a
+/// final's inference keeps the pipeline from draining.
+pub fn create_audio_ring_with_capacity(capacity: usize) -> (AudioProducer, AudioConsumer) {
+    HeapRb::<f32>::new(effective_ring_capacity(capacity)).split()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::{
+        create_audio_ring_with_capacity, effective_ring_capacity, ring_fill_pct, Consumer,
+        CountingProducer, Producer, MAX_RING_CAPACITY, RING_CAPACITY,
+    };
+    use ringbuf::traits::Observer;
+
+    #[test]
+    fn small_ring_rounds_up_and_still_carries_samples() {
+        assert_eq!(effective_ring_capacity(0), 1);
+        assert_eq!(effective_ring_capacity(1_000), 1_024);
+        assert_eq!(effective_ring_capacity(RING_CAPACITY), RING_CAPACITY);
+        assert_eq!(effective_ring_capacity(usize::MAX), MAX_RING_CAPACITY);
+
+        let (mut producer, mut consumer) = create_audio_ring_with_capacity(100);
+        assert_eq!(producer.capacity().get(), 128);
+        let samples: Vec<f32> = (0..200).map(|i| i as f32).collect();
+        assert_eq!(
+            producer.push_slice(&samples),
+            128,
+            "full ring drops the rest"
+        );
+
+        let mut out = [0.0_f32; 64];
+        assert_eq!(consumer.pop_slice(&mut out), 64);
+        assert_eq!(out[..], samples[..64]);
+        assert_eq!(producer.push_slice(&samples[128..]), 64);
+        let mut rest = vec![0.0_f32; 128];
+        assert_eq!(consumer.pop_slice(&mut rest), 128);
+        assert_eq!(rest[..], samples[64..192]);
+    }
+
+    #[test]
+    fn overfilled_ring_counts_the_dropped_samples() {
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let (producer, mut consumer) = create_audio_ring_with_capacity(64);
+        let mut producer = CountingProducer::new(producer, Arc::clone(&dropped));
+        assert_eq!(ring_fill_pct(&consumer), 0.0);
+
+        assert_eq!(producer.push_slice(&[0.5; 48]), 48);
+        assert_eq!(dropped.load(Ordering::Relaxed), 0);
+        assert_eq!(ring_fill_pct(&consumer), 75.0);
+
+        assert_eq!(producer.push_slice(&[0.5; 40]), 16);
+        assert_eq!(dropped.load(Ordering::Relaxed), 24);
+        assert_eq!(producer.push_slice(&[0.5; 10]), 0);
+        assert_eq!(dropped.load(Ordering::Relaxed), 34);
+        assert_eq!(ring_fill_pct(&consumer), 100.0);
+
+        let mut out = [0.0_f32; 32];
+        assert_eq!(consumer.pop_slice(&mut out), 32);
+        assert_eq!(ring_fill_pct(&consumer), 50.0);
+        assert_eq!(producer.push_slice(&[0.5; 8]), 8);
+        assert_eq!(dropped.load(Ordering::Relaxed), 34);
+    }
 }
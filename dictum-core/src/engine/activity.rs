@@ -0,0 +1,196 @@
+//! The level meter shown by the pill visualizer and settings page.
+//!
+//! Raw chunk RMS is too small and too jumpy to draw directly. [`ActivityLevel`]
+//! gates out the noise floor, scales by a sensitivity, saturates at a clip
+//! threshold and lets the result fall back with a fixed decay, so every UI
+//! that renders `AudioActivityEvent::level` shows the same motion.
+//!
+//! Visualizers that draw more than one bar can also ask for
+//! [`ActivityBins`]: [`ACTIVITY_BINS`] values per chunk, either a downsampled
+//! waveform or a coarse spectrum, in `AudioActivityEvent::bins`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::fingerprint::goertzel_power;
+
+/// Values in each `AudioActivityEvent::bins` array.
+pub const ACTIVITY_BINS: usize = 16;
+
+const SPECTRUM_LOW_HZ: f32 = 100.0;
+const SPECTRUM_HIGH_HZ: f32 = 8_000.0;
+/// Spectrum frames per second; sets each probe's bandwidth.
+const SPECTRUM_FRAME_RATE: u32 = 250;
+const PROBES_PER_BIN: usize = 3;
+
+/// Mapping from chunk RMS to a smoothed visual level in [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityLevel {
+    /// Gain applied to RMS above the noise gate. Default: 4.2.
+    pub sensitivity: f32,
+    /// RMS treated as silence. Default: 0.0015.
+    pub noise_gate: f32,
+    /// RMS at or above which the level is pinned at 1.0. Default: 0.32.
+    pub clip_threshold: f32,
+    /// Fraction of the previous level kept when the new one is lower, in
+    /// [0, 1). Default: 0.78.
+    pub decay: f32,
+}
+
+impl Default for ActivityLevel {
+    fn default() -> Self {
+        Self {
+            sensitivity: 4.2,
+            noise_gate: 0.0015,
+            clip_threshold: 0.32,
+            decay: 0.78,
+        }
+    }
+}
+
+impl ActivityLevel {
+    /// Level for a chunk at `rms`, given the `previous` level: rises
+    /// immediately, falls by `decay` per chunk.
+    pub fn level(&self, rms: f32, previous: f32) -> f32 {
+        let target = if rms >= self.clip_threshold {
+            1.0
+        } else {
+            ((rms - self.noise_gate).max(0.0) * self.sensitivity).clamp(0.0, 1.0)
+        };
+        target.max(previous * self.decay).clamp(0.0, 1.0)
+    }
+}
+
+/// What `AudioActivityEvent::bins` holds for each chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityBins {
+    /// Peak absolute sample of each of [`ACTIVITY_BINS`] equal slices of
+    /// the chunk, in [0, 1], oldest first.
+    Waveform,
+    /// Amplitude in [`ACTIVITY_BINS`] log-spaced bands from 100 Hz to
+    /// 8 kHz (capped below Nyquist), in [0, 1], lowest first.
+    Spectrum,
+}
+
+impl ActivityBins {
+    /// The bins for `samples` at `sample_rate`; all zero for an empty chunk.
+    pub fn compute(self, samples: &[f32], sample_rate: u32) -> [f32; ACTIVITY_BINS] {
+        let mut bins = [0f32; ACTIVITY_BINS];
+        if samples.is_empty() || sample_rate == 0 {
+            return bins;
+        }
+        match self {
+            Self::Waveform => {
+                for (i, bin) in bins.iter_mut().enumerate() {
+                    let first = i * samples.len() / ACTIVITY_BINS;
+                    let last = ((i + 1) * samples.len() / ACTIVITY_BINS).max(first + 1);
+                    *bin = samples[first..last.min(samples.len())]
+                        .iter()
+                        .fold(0f32, |peak, s| peak.max(s.abs()))
+                        .min(1.0);
+                }
+            }
+            Self::Spectrum => {
+                // Short frames widen each probe to about +-frame rate, so a
+                // few probes cover a band: coarse, but enough to draw bars.
+                let frame_len = (sample_rate / SPECTRUM_FRAME_RATE).max(1) as usize;
+                let frames: Vec<&[f32]> = if samples.len() < frame_len {
+                    vec![samples]
+                } else {
+                    samples.chunks_exact(frame_len).collect()
+                };
+                let high = SPECTRUM_HIGH_HZ.min(sample_rate as f32 * 0.45);
+                let ratio = (high / SPECTRUM_LOW_HZ).max(1.0);
+                let edge = |i: usize| SPECTRUM_LOW_HZ * ratio.powf(i as f32 / ACTIVITY_BINS as f32);
+                for (i, bin) in bins.iter_mut().enumerate() {
+                    let (low, high) = (edge(i), edge(i + 1));
+                    let loudest = (0..PROBES_PER_BIN)
+                        .map(|p| {
+                            let freq =
+                                low + (high - low) * (p as f32 + 0.5) / PROBES_PER_BIN as f32;
+                            // A sine of amplitude A has power A^2 * len / 4.
+                            let power: f32 = frames
+                                .iter()
+                                .map(|frame| {
+                                    goertzel_power(frame, freq, sample_rate) / frame.len() as f32
+                                })
+                                .sum();
+                            power / frames.len() as f32
+                        })
+                        .fold(0f32, f32::max);
+                    *bin = (2.0 * loudest.sqrt()).min(1.0);
+                }
+            }
+        }
+        bins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq * i as f32 / 16_000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn waveform_bins_follow_the_peaks_of_each_slice() {
+        // Silent first half, a 0.5-amplitude tone in the second.
+        let mut chunk = vec![0.0; 480];
+        chunk.extend(sine(1_000.0, 0.5, 480));
+        let bins = ActivityBins::Waveform.compute(&chunk, 16_000);
+
+        assert!(
+            bins[..ACTIVITY_BINS / 2].iter().all(|&b| b == 0.0),
+            "{bins:?}"
+        );
+        for &bin in &bins[ACTIVITY_BINS / 2..] {
+            assert!((0.45..=0.5).contains(&bin), "{bins:?}");
+        }
+        assert_eq!(
+            ActivityBins::Waveform.compute(&[], 16_000),
+            [0.0; ACTIVITY_BINS]
+        );
+    }
+
+    #[test]
+    fn spectrum_bins_peak_at_the_tone() {
+        let bins = ActivityBins::Spectrum.compute(&sine(1_000.0, 0.5, 960), 16_000);
+
+        // Band edges run 100 Hz .. 7.2 kHz; find the one holding 1 kHz.
+        let edge = |i: usize| 100.0 * 72f32.powf(i as f32 / ACTIVITY_BINS as f32);
+        let band = (0..ACTIVITY_BINS)
+            .find(|&i| (edge(i)..edge(i + 1)).contains(&1_000.0))
+            .unwrap();
+        let loudest = (0..ACTIVITY_BINS)
+            .max_by(|&a, &b| bins[a].total_cmp(&bins[b]))
+            .unwrap();
+        assert_eq!(loudest, band, "{bins:?}");
+        assert!((0.35..=0.55).contains(&bins[band]), "{bins:?}");
+        let far = bins[..band - 3].iter().chain(&bins[band + 4..]);
+        assert!(far.clone().all(|&b| b < bins[band] / 4.0), "{bins:?}");
+        assert!(bins.iter().all(|b| (0.0..=1.0).contains(b)));
+    }
+
+    #[test]
+    fn rms_maps_to_a_gated_scaled_and_decaying_level() {
+        let meter = ActivityLevel {
+            sensitivity: 5.0,
+            noise_gate: 0.01,
+            clip_threshold: 0.3,
+            decay: 0.5,
+        };
+        // (0.11 - 0.01) * 5 = 0.5
+        assert!((meter.level(0.11, 0.0) - 0.5).abs() < 1e-6);
+        assert_eq!(meter.level(0.005, 0.0), 0.0);
+        assert_eq!(meter.level(0.25, 0.0), 1.0);
+        assert_eq!(meter.level(0.3, 0.0), 1.0);
+        // Silence after a full-scale chunk halves the level each time.
+        assert_eq!(meter.level(0.0, 1.0), 0.5);
+        assert_eq!(meter.level(0.0, 0.5), 0.25);
+    }
+}
@@ -0,0 +1,189 @@
+//! Opt-in dump of each utterance's audio before and after resampling.
+//!
+//! With [`DebugAudioTap`] set in `EngineConfig::debug_audio`, every final
+//! writes two WAV files to the tap directory: the raw capture-rate stream
+//! and the same span after resampling to `target_sample_rate` (before any
+//! input gain). Listening to the pair shows whether bad transcripts come
+//! from the resampler or from the audio itself.
+//!
+//! Memory is bounded by two rolling buffers of `max_seconds` each, and disk
+//! by `max_utterances` pairs per listening session. Write failures are
+//! logged and never affect transcription.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::audio::wav::write_wav_mono;
+
+/// Where and how much utterance audio to dump.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugAudioTap {
+    /// Directory the WAV files are written to; created if missing.
+    pub dir: PathBuf,
+    /// Utterance pairs written per listening session. Default: 20.
+    #[serde(default = "default_max_utterances")]
+    pub max_utterances: usize,
+    /// Longest span dumped per utterance, in seconds; longer utterances
+    /// keep their tail. Default: 30.
+    #[serde(default = "default_max_seconds")]
+    pub max_seconds: u32,
+}
+
+fn default_max_utterances() -> usize {
+    20
+}
+
+fn default_max_seconds() -> u32 {
+    30
+}
+
+impl DebugAudioTap {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_utterances: default_max_utterances(),
+            max_seconds: default_max_seconds(),
+        }
+    }
+}
+
+/// Rolling capture-rate and target-rate audio for the running session.
+pub(crate) struct RateTap {
+    config: DebugAudioTap,
+    capture_rate: u32,
+    target_rate: u32,
+    capture: Vec<f32>,
+    target: Vec<f32>,
+    /// Distinguishes sessions, whose utterance ids restart at `utt-0`.
+    session_tag: String,
+    written: usize,
+}
+
+impl RateTap {
+    pub(crate) fn new(config: DebugAudioTap, capture_rate: u32, target_rate: u32) -> Self {
+        Self {
+            config,
+            capture_rate,
+            target_rate,
+            capture: Vec::new(),
+            target: Vec::new(),
+            session_tag: chrono::Local::now().format("%Y%m%d-%H%M%S").to_string(),
+            written: 0,
+        }
+    }
+
+    pub(crate) fn push_capture(&mut self, samples: &[f32]) {
+        let cap = self.limit(self.capture_rate);
+        push_rolling(&mut self.capture, samples, cap);
+    }
+
+    pub(crate) fn push_target(&mut self, samples: &[f32]) {
+        let cap = self.limit(self.target_rate);
+        push_rolling(&mut self.target, samples, cap);
+    }
+
+    /// Write the last `target_samples` of target-rate audio, and the
+    /// capture-rate audio covering the same time, for `utterance_id`.
+    /// Returns the two paths (capture, target) when written.
+    pub(crate) fn write_utterance(
+        &mut self,
+        utterance_id: &str,
+        target_samples: usize,
+    ) -> Option<(PathBuf, PathBuf)> {
+        if self.written >= self.config.max_utterances || target_samples == 0 {
+            return None;
+        }
+        let capture_samples = (target_samples as u64 * u64::from(self.capture_rate)
+            / u64::from(self.target_rate.max(1))) as usize;
+        let stem = format!("{}-{utterance_id}", self.session_tag);
+        let capture_path = self
+            .config
+            .dir
+            .join(format!("{stem}-capture-{}hz.wav", self.capture_rate));
+        let target_path = self
+            .config
+            .dir
+            .join(format!("{stem}-target-{}hz.wav", self.target_rate));
+        let written = std::fs::create_dir_all(&self.config.dir)
+            .map_err(|e| e.to_string())
+            .and_then(|()| {
+                write_tail(
+                    &capture_path,
+                    &self.capture,
+                    capture_samples,
+                    self.capture_rate,
+                )
+            })
+            .and_then(|()| {
+                write_tail(&target_path, &self.target, target_samples, self.target_rate)
+            });
+        if let Err(e) = written {
+            warn!(error = %e, dir = %self.config.dir.display(), "debug audio dump failed");
+            return None;
+        }
+        self.written += 1;
+        info!(
+            capture = %capture_path.display(),
+            target = %target_path.display(),
+            "debug audio dumped"
+        );
+        Some((capture_path, target_path))
+    }
+
+    fn limit(&self, rate: u32) -> usize {
+        rate as usize * self.config.max_seconds as usize
+    }
+}
+
+fn push_rolling(buf: &mut Vec<f32>, samples: &[f32], cap: usize) {
+    buf.extend_from_slice(samples);
+    if buf.len() > cap {
+        let excess = buf.len() - cap;
+        buf.drain(..excess);
+    }
+}
+
+fn write_tail(path: &Path, buf: &[f32], samples: usize, sample_rate: u32) -> Result<(), String> {
+    let tail = &buf[buf.len().saturating_sub(samples)..];
+    write_wav_mono(path, tail, sample_rate).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DebugAudioTap, RateTap};
+    use crate::audio::wav::read_wav_mono;
+
+    #[test]
+    fn utterance_is_dumped_at_both_rates_within_bounds() {
+        let dir = std::env::temp_dir().join(format!("dictum-tap-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut tap = RateTap::new(
+            DebugAudioTap {
+                max_utterances: 1,
+                max_seconds: 1,
+                ..DebugAudioTap::new(&dir)
+            },
+            48_000,
+            16_000,
+        );
+        // 1.5 s of each stream; only the last second is kept.
+        tap.push_capture(&vec![0.25; 72_000]);
+        tap.push_target(&vec![0.25; 24_000]);
+        assert_eq!((tap.capture.len(), tap.target.len()), (48_000, 16_000));
+
+        let (capture, target) = tap.write_utterance("utt-0", 8_000).expect("dumped");
+        let capture = read_wav_mono(capture).expect("capture wav");
+        let target = read_wav_mono(target).expect("target wav");
+        assert_eq!(
+            (capture.sample_rate, capture.samples.len()),
+            (48_000, 24_000)
+        );
+        assert_eq!((target.sample_rate, target.samples.len()), (16_000, 8_000));
+
+        assert!(tap.write_utterance("utt-1", 8_000).is_none(), "over budget");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,64 @@
+//! Scripted activity for checking the level visualizer without a microphone.
+//!
+//! Diagnostic only: [`DictumEngine::emit_test_activity`] plays one of these
+//! patterns on the activity channel so onboarding can tell "the visualizer is
+//! broken" apart from "the mic is dead". Nothing here touches audio hardware,
+//! VAD or the model.
+//!
+//! [`DictumEngine::emit_test_activity`]: super::DictumEngine::emit_test_activity
+
+use crate::ipc::events::AudioActivityEvent;
+
+/// Time between scripted frames, close to the live pipeline's chunk cadence.
+pub const TEST_ACTIVITY_FRAME_MS: u64 = 20;
+
+/// Frames played by a test pattern at or above this level are marked speech.
+const SPEECH_LEVEL: f32 = 0.1;
+
+/// A scripted activity sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestActivityPattern {
+    /// Level rises linearly from silence to a loud peak and falls back.
+    Ramp,
+    /// Three short loud bursts separated by near-silence.
+    Pulse,
+}
+
+impl TestActivityPattern {
+    /// RMS levels of the pattern, one per frame.
+    pub fn levels(self) -> Vec<f32> {
+        match self {
+            Self::Ramp => {
+                const STEPS: usize = 25;
+                const PEAK: f32 = 0.5;
+                let rise = (0..=STEPS).map(|i| PEAK * i as f32 / STEPS as f32);
+                let fall = (0..STEPS).rev().map(|i| PEAK * i as f32 / STEPS as f32);
+                rise.chain(fall).collect()
+            }
+            Self::Pulse => {
+                let mut levels = Vec::new();
+                for _ in 0..3 {
+                    levels.extend([0.01; 6]);
+                    levels.extend([0.4; 6]);
+                }
+                levels.extend([0.01; 6]);
+                levels
+            }
+        }
+    }
+
+    /// The activity events for this pattern, numbered from `seq` 0.
+    pub fn events(self) -> Vec<AudioActivityEvent> {
+        self.levels()
+            .into_iter()
+            .enumerate()
+            .map(|(i, rms)| AudioActivityEvent {
+                seq: i as u64,
+                rms,
+                is_speech: rms >= SPEECH_LEVEL,
+                level: None,
+                bins: None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,61 @@
+//! Loss-free delivery of finals for `BackpressurePolicy::GuaranteedFinals`.
+//!
+//! The broadcast channels drop the oldest events for a subscriber that falls
+//! more than their capacity behind, and the subscriber only learns about it
+//! through `RecvError::Lagged`. That is fine for partials and activity, which
+//! are superseded anyway, but a dropped final is lost dictation. Here every
+//! subscriber gets its own unbounded queue instead, so a stalled UI delays
+//! its finals rather than losing them.
+//!
+//! ## Memory
+//!
+//! Nothing bounds a queue: a subscriber that stops reading without dropping
+//! its receiver keeps every later final (one `TranscriptEvent`, typically a
+//! few hundred bytes) until it catches up or is dropped. Dropped receivers
+//! are pruned on the next send.
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::ipc::events::TranscriptEvent;
+
+/// Receiver returned by `DictumEngine::subscribe_finals_guaranteed`.
+pub type FinalReceiver = mpsc::UnboundedReceiver<TranscriptEvent>;
+
+/// How the engine delivers final transcripts to slow subscribers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// Broadcast only: a subscriber that lags past the channel capacity
+    /// misses events. Bounded memory.
+    #[default]
+    Lossy,
+    /// Finals are also queued, unbounded, for every
+    /// `subscribe_finals_guaranteed` receiver. Partials and activity stay
+    /// lossy. See the module docs for the memory tradeoff.
+    GuaranteedFinals,
+}
+
+/// Per-subscriber unbounded queues for final transcripts.
+#[derive(Debug, Clone, Default)]
+pub struct FinalFanout {
+    subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<TranscriptEvent>>>>,
+}
+
+impl FinalFanout {
+    pub fn subscribe(&self) -> FinalReceiver {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.subscribers.lock().push(tx);
+        rx
+    }
+
+    /// Queue `event` for every live subscriber. Returns how many got it.
+    pub fn send(&self, event: &TranscriptEvent) -> usize {
+        let mut subscribers = self.subscribers.lock();
+        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+        subscribers.len()
+    }
+}
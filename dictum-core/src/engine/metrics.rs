@@ -0,0 +1,339 @@
+//! Prometheus text exposition of pipeline diagnostics.
+//!
+//! Pure formatting over a [`DiagnosticsSnapshot`]; nothing here touches the
+//! live counters. Metric names are part of the public surface — scrapers and
+//! dashboards key on them — so rename only with a changelog entry.
+//!
+//! | Metric | Type | Meaning |
+//! |---|---|---|
+//! | `dictum_frames_in_total` | counter | Capture samples drained from the ring buffer |
+//! | `dictum_frames_resampled_total` | counter | Samples after resampling to the model rate |
+//! | `dictum_vad_windows_total` | counter | Chunks classified by the VAD |
+//! | `dictum_vad_speech_windows_total` | counter | Chunks classified as speech |
+//! | `dictum_inference_calls_total` | counter | Model invocations (partial and final) |
+//! | `dictum_inference_errors_total` | counter | Model invocations that returned an error |
+//! | `dictum_segments_emitted_total` | counter | Transcript segments broadcast |
+//! | `dictum_finals_emitted_total` | counter | Final segments, including fallbacks |
+//! | `dictum_fallback_emitted_total` | counter | Fallback placeholder finals |
+//! | `dictum_fallback_ratio` | gauge | `fallback_emitted / finals_emitted`, 0 when no finals |
+//! | `dictum_stage_duration_seconds` | histogram | Per-stage latency, label `stage` |
+//! | `dictum_stage_recent_duration_seconds` | summary | p50/p95/p99 over the recent window, label `stage` |
+//!
+//! `stage` is one of `drain`, `resample`, `vad`, `inference`.
+
+use std::fmt::Write as _;
+
+use super::pipeline::{DiagnosticsSnapshot, StageTimingSnapshot, LATENCY_BUCKETS_MS};
+
+const COUNTERS: [(&str, &str); 10] = [
+    (
+        "dictum_frames_in_total",
+        "Capture samples drained from the ring buffer.",
+    ),
+    (
+        "dictum_frames_dropped_total",
+        "Capture samples dropped because the ring buffer was full.",
+    ),
+    (
+        "dictum_frames_resampled_total",
+        "Samples produced after resampling to the model rate.",
+    ),
+    ("dictum_vad_windows_total", "Chunks classified by the VAD."),
+    (
+        "dictum_vad_speech_windows_total",
+        "Chunks classified as speech by the VAD.",
+    ),
+    (
+        "dictum_inference_calls_total",
+        "Speech model invocations, partial and final.",
+    ),
+    (
+        "dictum_inference_errors_total",
+        "Speech model invocations that returned an error.",
+    ),
+    (
+        "dictum_segments_emitted_total",
+        "Transcript segments broadcast to subscribers.",
+    ),
+    (
+        "dictum_finals_emitted_total",
+        "Final segments emitted, including fallback placeholders.",
+    ),
+    (
+        "dictum_fallback_emitted_total",
+        "Fallback placeholder finals emitted.",
+    ),
+];
+
+/// Render `snapshot` in the Prometheus text exposition format (0.0.4).
+pub fn render_prometheus(snapshot: &DiagnosticsSnapshot) -> String {
+    let mut out = String::new();
+
+    let counter_values = [
+        snapshot.frames_in,
+        snapshot.frames_dropped,
+        snapshot.frames_resampled,
+        snapshot.vad_windows,
+        snapshot.vad_speech,
+        snapshot.inference_calls,
+        snapshot.inference_errors,
+        snapshot.segments_emitted,
+        snapshot.finals_emitted,
+        snapshot.fallback_emitted,
+    ];
+    for ((name, help), value) in COUNTERS.iter().zip(counter_values) {
+        header(&mut out, name, help, "counter");
+        let _ = writeln!(out, "{name} {value}");
+    }
+
+    let fallback_ratio = if snapshot.finals_emitted == 0 {
+        0.0
+    } else {
+        snapshot.fallback_emitted as f64 / snapshot.finals_emitted as f64
+    };
+    header(
+        &mut out,
+        "dictum_fallback_ratio",
+        "Share of final segments that were fallback placeholders.",
+        "gauge",
+    );
+    let _ = writeln!(out, "dictum_fallback_ratio {fallback_ratio}");
+
+    header(
+        &mut out,
+        "dictum_ring_fill_percent",
+        "Ring buffer occupancy before the latest drain.",
+        "gauge",
+    );
+    let _ = writeln!(out, "dictum_ring_fill_percent {}", snapshot.ring_fill_pct);
+
+    let stages = [
+        ("drain", &snapshot.drain_ms),
+        ("resample", &snapshot.resample_ms),
+        ("vad", &snapshot.vad_ms),
+        ("inference", &snapshot.inference_ms),
+    ];
+
+    header(
+        &mut out,
+        "dictum_stage_duration_seconds",
+        "Pipeline stage latency.",
+        "histogram",
+    );
+    for (stage, timing) in stages {
+        write_histogram(&mut out, stage, timing);
+    }
+
+    header(
+        &mut out,
+        "dictum_stage_recent_duration_seconds",
+        "Pipeline stage latency percentiles over the most recent observations.",
+        "summary",
+    );
+    for (stage, timing) in stages {
+        let name = "dictum_stage_recent_duration_seconds";
+        for (quantile, ms) in [
+            ("0.5", timing.p50_ms),
+            ("0.95", timing.p95_ms),
+            ("0.99", timing.p99_ms),
+        ] {
+            let _ = writeln!(
+                out,
+                "{name}{{stage=\"{stage}\",quantile=\"{quantile}\"}} {}",
+                seconds(ms)
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{name}_sum{{stage=\"{stage}\"}} {}",
+            seconds(timing.sum_ms)
+        );
+        let _ = writeln!(out, "{name}_count{{stage=\"{stage}\"}} {}", timing.count);
+    }
+
+    out
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn write_histogram(out: &mut String, stage: &str, timing: &StageTimingSnapshot) {
+    let name = "dictum_stage_duration_seconds";
+    for (le, count) in LATENCY_BUCKETS_MS.iter().zip(timing.buckets) {
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{stage=\"{stage}\",le=\"{}\"}} {count}",
+            seconds(*le)
+        );
+    }
+    let _ = writeln!(
+        out,
+        "{name}_bucket{{stage=\"{stage}\",le=\"+Inf\"}} {}",
+        timing.count
+    );
+    let _ = writeln!(
+        out,
+        "{name}_sum{{stage=\"{stage}\"}} {}",
+        seconds(timing.sum_ms)
+    );
+    let _ = writeln!(out, "{name}_count{{stage=\"{stage}\"}} {}", timing.count);
+}
+
+fn seconds(ms: f64) -> f64 {
+    ms / 1_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::Ordering;
+
+    use super::render_prometheus;
+    use crate::engine::pipeline::PipelineDiagnostics;
+
+    /// Minimal exposition-format parser: returns `(name, labels, value)` per
+    /// sample and checks every sample belongs to a declared family.
+    fn parse(text: &str) -> Vec<(String, HashMap<String, String>, f64)> {
+        let valid_name = |name: &str| {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        };
+        let mut families: HashMap<String, String> = HashMap::new();
+        let mut samples = Vec::new();
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix("# ") {
+                let mut parts = comment.splitn(3, ' ');
+                let keyword = parts.next().unwrap();
+                let name = parts.next().expect("comment names a metric");
+                let rest = parts.next().expect("comment has a payload");
+                assert!(valid_name(name), "bad metric name in {line:?}");
+                if keyword == "TYPE" {
+                    assert!(
+                        ["counter", "gauge", "histogram", "summary"].contains(&rest),
+                        "bad type in {line:?}"
+                    );
+                    families.insert(name.to_string(), rest.to_string());
+                } else {
+                    assert_eq!(keyword, "HELP", "unknown comment {line:?}");
+                }
+                continue;
+            }
+            let (series, value) = line.rsplit_once(' ').expect("sample has a value");
+            let value: f64 = match value {
+                "+Inf" => f64::INFINITY,
+                v => v
+                    .parse()
+                    .unwrap_or_else(|_| panic!("bad value in {line:?}")),
+            };
+            let (name, labels) = match series.split_once('{') {
+                Some((name, rest)) => {
+                    let body = rest.strip_suffix('}').expect("closed label set");
+                    let labels = body
+                        .split(',')
+                        .map(|pair| {
+                            let (k, v) = pair.split_once('=').expect("label pair");
+                            assert!(valid_name(k), "bad label name in {line:?}");
+                            let v = v
+                                .strip_prefix('"')
+                                .and_then(|v| v.strip_suffix('"'))
+                                .expect("quoted label value");
+                            (k.to_string(), v.to_string())
+                        })
+                        .collect();
+                    (name, labels)
+                }
+                None => (series, HashMap::new()),
+            };
+            assert!(valid_name(name), "bad metric name in {line:?}");
+            let family = ["_bucket", "_sum", "_count"]
+                .iter()
+                .find_map(|suffix| {
+                    name.strip_suffix(suffix)
+                        .filter(|base| families.contains_key(*base))
+                })
+                .unwrap_or(name);
+            assert!(
+                families.contains_key(family),
+                "sample {name} has no TYPE line"
+            );
+            samples.push((name.to_string(), labels, value));
+        }
+        samples
+    }
+
+    #[test]
+    fn renders_valid_prometheus_text() {
+        let diagnostics = PipelineDiagnostics::default();
+        diagnostics.frames_in.store(48_000, Ordering::Relaxed);
+        diagnostics.frames_dropped.store(480, Ordering::Relaxed);
+        diagnostics.record_ring_fill(12.5);
+        diagnostics.inference_calls.store(5, Ordering::Relaxed);
+        diagnostics.inference_errors.store(1, Ordering::Relaxed);
+        diagnostics.finals_emitted.store(4, Ordering::Relaxed);
+        diagnostics.fallback_emitted.store(1, Ordering::Relaxed);
+        for ms in [40.0, 80.0, 120.0, 900.0, 4_000.0] {
+            diagnostics.record_inference(ms);
+        }
+
+        let text = render_prometheus(&diagnostics.snapshot());
+        let samples = parse(&text);
+        let value = |name: &str, labels: &[(&str, &str)]| {
+            samples
+                .iter()
+                .find(|(n, l, _)| {
+                    n == name
+                        && l.len() == labels.len()
+                        && labels
+                            .iter()
+                            .all(|(k, v)| l.get(*k).map(String::as_str) == Some(v))
+                })
+                .map(|(_, _, v)| *v)
+                .unwrap_or_else(|| panic!("missing {name} {labels:?}\n{text}"))
+        };
+
+        assert_eq!(value("dictum_frames_in_total", &[]), 48_000.0);
+        assert_eq!(value("dictum_frames_dropped_total", &[]), 480.0);
+        assert_eq!(value("dictum_ring_fill_percent", &[]), 12.5);
+        assert_eq!(value("dictum_inference_calls_total", &[]), 5.0);
+        assert_eq!(value("dictum_inference_errors_total", &[]), 1.0);
+        assert_eq!(value("dictum_fallback_ratio", &[]), 0.25);
+
+        let inference = [("stage", "inference")];
+        let bucket = |le: &str| {
+            value(
+                "dictum_stage_duration_seconds_bucket",
+                &[("stage", "inference"), ("le", le)],
+            )
+        };
+        assert_eq!(bucket("0.05"), 1.0);
+        assert_eq!(bucket("0.1"), 2.0);
+        assert_eq!(bucket("1"), 4.0);
+        assert_eq!(bucket("2.5"), 4.0);
+        assert_eq!(bucket("+Inf"), 5.0);
+        assert_eq!(
+            value("dictum_stage_duration_seconds_count", &inference),
+            5.0
+        );
+        assert!((value("dictum_stage_duration_seconds_sum", &inference) - 5.14).abs() < 1e-9);
+        assert_eq!(
+            value(
+                "dictum_stage_recent_duration_seconds",
+                &[("stage", "inference"), ("quantile", "0.5")],
+            ),
+            0.12
+        );
+        // Untouched stages still export a full, empty series.
+        assert_eq!(
+            value(
+                "dictum_stage_duration_seconds_bucket",
+                &[("stage", "vad"), ("le", "+Inf")],
+            ),
+            0.0
+        );
+    }
+}
@@ -17,36 +17,169 @@
 //! `cpal::Stream` is `!Send` on Windows/macOS (COM / CoreAudio thread affinity).
 //! `AudioCapture` is therefore created *inside* the `spawn_blocking` closure so
 //! it never crosses a thread boundary. A sync oneshot channel propagates any
-//! open-device errors back to the `start()` caller.
+//! open-device errors back to the `start()` caller. With a raised
+//! [`PipelinePriority`] the same closure runs on a dedicated thread instead
+//! (see [`priority`]).
 
+pub mod activity;
+pub mod audio_tap;
+pub mod calibration;
+pub mod fanout;
+pub mod metrics;
 pub mod pipeline;
+pub mod priority;
+pub mod stream;
 
+use std::ops::RangeInclusive;
+use std::path::Path;
 use std::sync::{
     atomic::{AtomicBool, AtomicU64, Ordering},
-    Arc,
+    mpsc, Arc,
 };
+use std::time::{Duration, Instant};
 
 use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tokio::sync::broadcast;
 use tracing::info;
 
+pub use activity::{ActivityBins, ActivityLevel, ACTIVITY_BINS};
+pub use audio_tap::DebugAudioTap;
+pub use calibration::TestActivityPattern;
+pub use fanout::{BackpressurePolicy, FinalReceiver};
+pub use priority::{PipelinePriority, ThreadPriorityFn};
+pub use stream::{EngineEvent, EngineEventStream};
+
 use crate::{
-    audio::AudioCapture,
-    buffering::create_audio_ring,
+    audio::{
+        agc::AgcConfig,
+        file::{FileCapture, FilePacing},
+        gain::GainLimits,
+        AudioCapture,
+    },
+    buffering::{
+        create_audio_ring_with_capacity, effective_ring_capacity, CountingProducer,
+        MAX_RING_CAPACITY, RING_CAPACITY,
+    },
     error::{DictumError, Result},
     inference::ModelHandle,
-    ipc::events::{AudioActivityEvent, EngineStatus, EngineStatusEvent, TranscriptEvent},
-    vad::{energy::EnergyVad, VoiceActivityDetector},
+    ipc::events::{
+        AudioActivityEvent, EngineStatus, EngineStatusEvent, ModelCapabilities, TranscriptEvent,
+        UtteranceBoundaryEvent,
+    },
+    sink::{spawn_output_sink, OutputSink},
+    vad::{
+        energy::{EnergyGate, EnergyVad},
+        LiveThreshold, VoiceActivityDetector,
+    },
 };
 
 #[cfg(feature = "onnx")]
-use crate::vad::SileroVad;
+use crate::vad::{HybridPolicy, HybridVad, SileroVad};
 
 /// Broadcast channel capacity: 256 transcript events buffered for slow consumers.
 const BROADCAST_CAP: usize = 256;
 
+/// How often `warm_up_with_timeout` looks at its cancel flag.
+const WARM_UP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Durations (ms) `EngineConfig::validate` accepts for `min_speech_samples`
+/// at `target_sample_rate`: shorter fires inference on clicks, longer
+/// swallows whole short answers.
+pub const MIN_SPEECH_MS_RANGE: RangeInclusive<u64> = 20..=10_000;
+/// Durations (ms) accepted for the speech cap
+/// (`EngineConfig::effective_max_speech_samples`).
+pub const MAX_SPEECH_MS_RANGE: RangeInclusive<u64> = 250..=1_800_000;
+
+/// How input level is normalised before VAD and inference.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputGainMode {
+    /// Boost each chunk independently toward a level derived from
+    /// `vad_threshold` (and `DICTUM_INPUT_GAIN_BOOST`). Never attenuates.
+    #[default]
+    AdaptiveBoost,
+    /// Smoothed automatic gain control that carries its gain across chunks.
+    Agc(AgcConfig),
+}
+
+/// How the pipeline decides which captured audio is speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// The VAD opens and closes utterances; silence flushes a final.
+    #[default]
+    VadGated,
+    /// Everything captured is speech: no VAD, no silence flush. Finals come
+    /// only from `max_speech_samples` and the terminal flush on stop, so
+    /// start/stop should follow the key press and release.
+    PushToTalk,
+}
+
+/// What the pipeline does when the model returns several segments for one
+/// inference pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentHandling {
+    /// Forward every segment with its own text, confidence and timing.
+    #[default]
+    Preserve,
+    /// Join them into one segment: texts space-separated, confidence
+    /// averaged, timing spanning the first to the last.
+    Merge,
+}
+
+/// Punctuation inferred from the silence after each final. A final already
+/// ending in punctuation is left alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PausePunctuation {
+    /// Pause (ms) from which a comma is inserted. Default: 250.
+    pub comma_after_ms: u32,
+    /// Pause (ms) from which a period is inserted and the next final is
+    /// capitalised. Default: 700.
+    pub period_after_ms: u32,
+}
+
+impl Default for PausePunctuation {
+    fn default() -> Self {
+        Self {
+            comma_after_ms: 250,
+            period_after_ms: 700,
+        }
+    }
+}
+
+/// Conditioning of each utterance on the previous final's text, for
+/// consistent names and style across long-form dictation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextCarry {
+    /// Most characters of the previous final passed on, taken from its end
+    /// and starting at a word boundary. Default: 200.
+    pub max_chars: usize,
+    /// Silence (ms) between utterances after which the carried text is
+    /// dropped, so a new train of thought starts unprompted. Default: 10000.
+    pub reset_after_silence_ms: u64,
+}
+
+impl Default for ContextCarry {
+    fn default() -> Self {
+        Self {
+            max_chars: 200,
+            reset_after_silence_ms: 10_000,
+        }
+    }
+}
+
 /// Configuration for `DictumEngine`.
-#[derive(Debug, Clone)]
+///
+/// Serializable so embedders can keep it in a config file (any serde
+/// format). Missing fields take their defaults, and deserializing runs
+/// [`EngineConfig::validate`]. The `onnx`-only fields are skipped in builds
+/// without that feature, and ignored if present in the input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, remote = "Self")]
 pub struct EngineConfig {
     /// Target sample rate for inference (Hz). Audio captured at other rates
     /// will be resampled (Phase 1). Default: 16000.
@@ -55,23 +188,122 @@ pub struct EngineConfig {
     pub vad_threshold: f32,
     /// VAD hangover in frames. Default: 8.
     pub vad_hangover_frames: u32,
+    /// Width of the VAD uncertainty band as a fraction of the threshold, in
+    /// [0, 1). Frames scoring within it below the threshold are
+    /// `Uncertain` and keep the pipeline in its current speech/silence
+    /// state, which stops boundary frames from flip-flopping. Applies to
+    /// the energy and Silero thresholds alike. `None` disables it.
+    /// Default: `None`.
+    pub vad_uncertain_band: Option<f32>,
+    /// What the energy VAD compares frames against: `vad_threshold` alone,
+    /// or also a tracked noise floor plus a margin. Default: `Static`.
+    pub energy_gate: EnergyGate,
     /// Silero VAD speech probability threshold in [0, 1].
     /// Default: 0.20.
     #[cfg(feature = "onnx")]
     pub silero_vad_threshold: f32,
     /// Minimum speech duration (samples at `target_sample_rate`) before
-    /// inference is triggered. Default: 8000 (0.5 s).
+    /// inference is triggered. Must come to a duration within
+    /// [`MIN_SPEECH_MS_RANGE`]; set it with
+    /// [`EngineConfig::set_min_speech_ms`] to stay in step with the rate.
+    /// Default: 4000 (0.25 s).
     pub min_speech_samples: usize,
     /// Maximum accumulated speech (samples) before a forced inference.
+    /// Ignored when `max_utterance_ms` is set.
     /// Default: 480000 (30 s at 16 kHz).
     pub max_speech_samples: usize,
+    /// Maximum utterance length (ms) before a forced inference. When set,
+    /// takes precedence over `max_speech_samples` and is converted at
+    /// `target_sample_rate`, so it keeps its meaning if the rate changes.
+    /// See [`EngineConfig::effective_max_speech_samples`]. Default: `None`.
+    pub max_utterance_ms: Option<u64>,
+    /// VAD-gated utterances, or push-to-talk that transcribes everything
+    /// between start and stop. Default: `VadGated`.
+    pub capture_mode: CaptureMode,
     /// Whether to emit partial inference updates during active speech.
     /// Partial decoding improves live preview but can increase CPU/GPU load.
     pub enable_partial_inference: bool,
+    /// Input level normalisation. Default: `InputGainMode::AdaptiveBoost`.
+    pub input_gain: InputGainMode,
+    /// Ceiling and noise guard for `InputGainMode::AdaptiveBoost`: chunks
+    /// that look like hiss or near-silence are passed through unboosted.
+    /// Default: `GainLimits::default()` (max 9x, hiss guard on).
+    pub gain_limits: GainLimits,
+    /// Periodically run a dummy inference during sustained silence so GPU
+    /// execution providers do not downclock between utterances. Trades idle
+    /// power for first-utterance latency. Default: false.
+    pub keep_warm: bool,
+    /// Idle time (ms) without any inference before a keep-warm call is made
+    /// when `keep_warm` is set. Default: 10000.
+    pub keep_warm_interval_ms: u64,
+    /// Run of exactly-zero capture samples (ms) after which the microphone
+    /// is reported as muted via a `Listening` status with
+    /// `pipeline::MIC_MUTED_DETAIL`. `0` disables detection. Default: 3000.
+    pub mute_detect_ms: u64,
+    /// Time after an end-of-utterance final (ms) during which a new speech
+    /// onset is ignored, so breath or lip noise does not start a spurious
+    /// fragment. `0` disables the cooldown. Default: 0.
+    pub post_final_cooldown_ms: u64,
+    /// Finals shorter than this (ms) are padded with the audio captured just
+    /// before speech onset, up to this length, so one-word answers give the
+    /// model enough context. Never reaches into the previous utterance.
+    /// Non-zero also turns on the model's short-utterance decode
+    /// (`SpeechModel::set_short_utterance_decode`). `0` disables both.
+    /// Default: 0.
+    pub short_utterance_pad_ms: u64,
+    /// Samples the capture → pipeline ring holds, rounded up to a power
+    /// of two (`buffering::effective_ring_capacity`). It must cover the
+    /// audio captured while a final's inference runs; push-to-talk setups
+    /// can shrink it to save memory. At most `buffering::MAX_RING_CAPACITY`.
+    /// Default: `buffering::RING_CAPACITY` (2^22, ≈ 87 s at 48 kHz).
+    pub ring_capacity_samples: usize,
+    /// Whether finals may be dropped for subscribers that fall behind.
+    /// `GuaranteedFinals` feeds `subscribe_finals_guaranteed` receivers
+    /// through unbounded queues. Default: `Lossy`.
+    pub backpressure: BackpressurePolicy,
+    /// Pass the previous final's text to the model as decoder context for
+    /// the next utterance (`SpeechModel::set_prompt_context`). `None`
+    /// decodes every utterance on its own. Default: `None`.
+    pub context_carry: Option<ContextCarry>,
+    /// Multi-segment model output: forwarded as-is or merged into one
+    /// segment per event. Default: `Preserve`.
+    pub segment_handling: SegmentHandling,
+    /// Punctuate each final from the silence the pipeline measures after
+    /// it. Finals are held until speech resumes or the pause reaches
+    /// `period_after_ms`, so they arrive that much later. Default: `None`.
+    pub pause_punctuation: Option<PausePunctuation>,
+    /// Dump each final's audio at the capture rate and after resampling,
+    /// for diagnosing resampler problems. `None` disables it. Default: `None`.
+    pub debug_audio: Option<DebugAudioTap>,
+    /// Attach a smoothed visual `level` to activity events so every UI
+    /// draws the same meter. `None` sends raw `rms` only. Default: `None`.
+    pub activity_level: Option<ActivityLevel>,
+    /// Attach [`ACTIVITY_BINS`] waveform or spectrum values to every
+    /// activity event, for visualizers that draw more than a level. Makes
+    /// each event several times larger. `None` disables it. Default: `None`.
+    pub activity_bins: Option<ActivityBins>,
+    /// OS priority of the pipeline thread. Anything above `Normal` moves the
+    /// pipeline off tokio's blocking pool onto its own thread. Default:
+    /// `Normal`.
+    pub pipeline_priority: PipelinePriority,
+    /// Attach an `AudioFingerprint` of each final's audio to its meta, so
+    /// consumers can spot the same audio dictated twice. Costs a few
+    /// Goertzel filters per frame. Default: false.
+    pub audio_fingerprint: bool,
     /// Override path for the Silero VAD ONNX model.
     /// `None` falls back to the platform default models directory.
     #[cfg(feature = "onnx")]
     pub silero_vad_path: Option<std::path::PathBuf>,
+    /// Silence fed through Silero VAD at start and after each utterance so
+    /// its recurrent state is warm before real speech arrives (ms, rounded
+    /// down to 32 ms windows). `0` disables priming. Default: 256.
+    #[cfg(feature = "onnx")]
+    pub silero_vad_prime_ms: u32,
+    /// Gate Silero VAD behind `EnergyVad` (`vad_threshold`) so the neural
+    /// model is skipped on frames the combination cannot change. `None`
+    /// uses Silero alone. Default: `None`.
+    #[cfg(feature = "onnx")]
+    pub hybrid_vad: Option<HybridPolicy>,
 }
 
 impl Default for EngineConfig {
@@ -80,14 +312,261 @@ impl Default for EngineConfig {
             target_sample_rate: 16_000,
             vad_threshold: 0.01, // Lowered from 0.02 for quieter microphones
             vad_hangover_frames: 8,
+            vad_uncertain_band: None,
+            energy_gate: EnergyGate::Static,
             #[cfg(feature = "onnx")]
             silero_vad_threshold: 0.20,
             min_speech_samples: 4_000, // Lowered from 8000 (0.25s instead of 0.5s)
             max_speech_samples: 480_000,
+            max_utterance_ms: None,
+            capture_mode: CaptureMode::VadGated,
             enable_partial_inference: true,
+            input_gain: InputGainMode::default(),
+            gain_limits: GainLimits::default(),
+            keep_warm: false,
+            keep_warm_interval_ms: 10_000,
+            mute_detect_ms: 3_000,
+            post_final_cooldown_ms: 0,
+            short_utterance_pad_ms: 0,
+            ring_capacity_samples: RING_CAPACITY,
+            backpressure: BackpressurePolicy::default(),
+            context_carry: None,
+            segment_handling: SegmentHandling::default(),
+            pause_punctuation: None,
+            debug_audio: None,
+            activity_level: None,
+            activity_bins: None,
+            pipeline_priority: PipelinePriority::Normal,
+            audio_fingerprint: false,
             #[cfg(feature = "onnx")]
             silero_vad_path: None,
+            #[cfg(feature = "onnx")]
+            silero_vad_prime_ms: 256,
+            #[cfg(feature = "onnx")]
+            hybrid_vad: None,
+        }
+    }
+}
+
+impl Serialize for EngineConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        EngineConfig::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EngineConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let config = EngineConfig::deserialize(deserializer)?;
+        config.validate().map_err(serde::de::Error::custom)?;
+        Ok(config)
+    }
+}
+
+impl EngineConfig {
+    /// Speech cap (samples at `target_sample_rate`) the pipeline flushes
+    /// at: `max_utterance_ms` converted when set, else `max_speech_samples`.
+    pub fn effective_max_speech_samples(&self) -> usize {
+        match self.max_utterance_ms {
+            Some(ms) => self.samples_for_ms(ms),
+            None => self.max_speech_samples,
+        }
+    }
+
+    /// Set `min_speech_samples` from a duration, converted at the current
+    /// `target_sample_rate`. Set the rate first.
+    pub fn set_min_speech_ms(&mut self, ms: u64) {
+        self.min_speech_samples = self.samples_for_ms(ms);
+    }
+
+    /// `min_speech_samples` as a duration at `target_sample_rate`.
+    pub fn min_speech_ms(&self) -> u64 {
+        self.ms_for_samples(self.min_speech_samples)
+    }
+
+    fn samples_for_ms(&self, ms: u64) -> usize {
+        (ms.saturating_mul(u64::from(self.target_sample_rate)) / 1000)
+            .try_into()
+            .unwrap_or(usize::MAX)
+    }
+
+    fn ms_for_samples(&self, samples: usize) -> u64 {
+        (samples as u64).saturating_mul(1000) / u64::from(self.target_sample_rate.max(1))
+    }
+
+    /// Check every invariant the pipeline relies on.
+    ///
+    /// Called by `DictumEngine::start_with_device`, so a bad config fails the
+    /// start call with `DictumError::InvalidConfig` instead of being silently
+    /// clamped (or misbehaving) deep inside the pipeline.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| Err(DictumError::InvalidConfig(msg));
+
+        if self.target_sample_rate == 0 {
+            return invalid("target_sample_rate must be non-zero".into());
+        }
+        if !self.vad_threshold.is_finite() || !(0.0..=1.0).contains(&self.vad_threshold) {
+            return invalid(format!(
+                "vad_threshold must be within [0, 1], got {}",
+                self.vad_threshold
+            ));
+        }
+        if let Some(band) = self.vad_uncertain_band {
+            if !band.is_finite() || !(0.0..1.0).contains(&band) {
+                return invalid(format!(
+                    "vad_uncertain_band must be within [0, 1), got {band}"
+                ));
+            }
+        }
+        if let EnergyGate::NoiseFloor(noise) = self.energy_gate {
+            if !(noise.alpha > 0.0 && noise.alpha <= 1.0) {
+                return invalid(format!(
+                    "energy_gate alpha must be within (0, 1], got {}",
+                    noise.alpha
+                ));
+            }
+            if !noise.margin_db.is_finite() || noise.margin_db < 0.0 {
+                return invalid(format!(
+                    "energy_gate margin_db must be finite and non-negative, got {}",
+                    noise.margin_db
+                ));
+            }
+            if !noise.speech_rise_db_per_sec.is_finite() || noise.speech_rise_db_per_sec < 0.0 {
+                return invalid(format!(
+                    "energy_gate speech_rise_db_per_sec must be finite and non-negative, got {}",
+                    noise.speech_rise_db_per_sec
+                ));
+            }
+        }
+        #[cfg(feature = "onnx")]
+        if !self.silero_vad_threshold.is_finite()
+            || !(0.0..=1.0).contains(&self.silero_vad_threshold)
+        {
+            return invalid(format!(
+                "silero_vad_threshold must be within [0, 1], got {}",
+                self.silero_vad_threshold
+            ));
+        }
+        if self.min_speech_samples == 0 {
+            return invalid("min_speech_samples must be non-zero".into());
+        }
+        if !(1..=MAX_RING_CAPACITY).contains(&self.ring_capacity_samples) {
+            return invalid(format!(
+                "ring_capacity_samples must be within 1..={MAX_RING_CAPACITY}, got {}",
+                self.ring_capacity_samples
+            ));
+        }
+        if self.max_utterance_ms == Some(0) {
+            return invalid("max_utterance_ms must be non-zero".into());
+        }
+        let max_speech_samples = self.effective_max_speech_samples();
+        let source = if self.max_utterance_ms.is_some() {
+            "max_utterance_ms"
+        } else {
+            "max_speech_samples"
+        };
+        if self.min_speech_samples > max_speech_samples {
+            return invalid(format!(
+                "min_speech_samples ({}) must not exceed max_speech_samples ({max_speech_samples}, from {source})",
+                self.min_speech_samples
+            ));
+        }
+        // Sample counts only mean something at a rate: catch values left
+        // over from a different `target_sample_rate`.
+        let min_speech_ms = self.min_speech_ms();
+        if !MIN_SPEECH_MS_RANGE.contains(&min_speech_ms) {
+            return invalid(format!(
+                "min_speech_samples ({}) is {min_speech_ms} ms at {} Hz, outside {}..={} ms",
+                self.min_speech_samples,
+                self.target_sample_rate,
+                MIN_SPEECH_MS_RANGE.start(),
+                MIN_SPEECH_MS_RANGE.end()
+            ));
+        }
+        let max_speech_ms = self.ms_for_samples(max_speech_samples);
+        if !MAX_SPEECH_MS_RANGE.contains(&max_speech_ms) {
+            return invalid(format!(
+                "max_speech_samples ({max_speech_samples}, from {source}) is {max_speech_ms} ms at {} Hz, outside {}..={} ms",
+                self.target_sample_rate,
+                MAX_SPEECH_MS_RANGE.start(),
+                MAX_SPEECH_MS_RANGE.end()
+            ));
+        }
+        if let InputGainMode::Agc(agc) = self.input_gain {
+            if !agc.target_rms.is_finite() || !(0.0..=1.0).contains(&agc.target_rms) {
+                return invalid(format!(
+                    "agc target_rms must be within [0, 1], got {}",
+                    agc.target_rms
+                ));
+            }
+            if !(agc.attack_ms >= 0.0 && agc.release_ms >= 0.0) {
+                return invalid(format!(
+                    "agc attack_ms/release_ms must be non-negative, got {}/{}",
+                    agc.attack_ms, agc.release_ms
+                ));
+            }
+            if !agc.max_gain.is_finite() || agc.max_gain <= 0.0 {
+                return invalid(format!(
+                    "agc max_gain must be positive, got {}",
+                    agc.max_gain
+                ));
+            }
+        }
+        let limits = self.gain_limits;
+        if !limits.max_gain.is_finite() || limits.max_gain < 1.0 {
+            return invalid(format!(
+                "gain_limits max_gain must be at least 1, got {}",
+                limits.max_gain
+            ));
+        }
+        if !limits.noise_floor_rms.is_finite() || limits.noise_floor_rms < 0.0 {
+            return invalid(format!(
+                "gain_limits noise_floor_rms must be non-negative, got {}",
+                limits.noise_floor_rms
+            ));
+        }
+        if limits
+            .hiss_correlation
+            .is_some_and(|c| !(-1.0..=1.0).contains(&c))
+        {
+            return invalid(format!(
+                "gain_limits hiss_correlation must be within [-1, 1], got {:?}",
+                limits.hiss_correlation
+            ));
+        }
+        if let Some(pause) = self.pause_punctuation {
+            if pause.comma_after_ms == 0 || pause.comma_after_ms > pause.period_after_ms {
+                return invalid(format!(
+                    "pause_punctuation needs 0 < comma_after_ms <= period_after_ms, got {}/{}",
+                    pause.comma_after_ms, pause.period_after_ms
+                ));
+            }
+        }
+        if let Some(tap) = &self.debug_audio {
+            if tap.max_seconds == 0 {
+                return invalid("debug_audio max_seconds must be non-zero".into());
+            }
         }
+        if let Some(meter) = self.activity_level {
+            let valid = meter.sensitivity.is_finite()
+                && meter.sensitivity > 0.0
+                && (0.0..1.0).contains(&meter.noise_gate)
+                && meter.clip_threshold > 0.0
+                && meter.clip_threshold <= 1.0
+                && (0.0..1.0).contains(&meter.decay);
+            if !valid {
+                return invalid(format!(
+                    "activity_level needs sensitivity > 0, noise_gate and decay in [0, 1) \
+                     and clip_threshold in (0, 1], got {meter:?}"
+                ));
+            }
+        }
+        if self.keep_warm && self.keep_warm_interval_ms == 0 {
+            return invalid("keep_warm_interval_ms must be non-zero when keep_warm is set".into());
+        }
+        if self.context_carry.is_some_and(|carry| carry.max_chars == 0) {
+            return invalid("context_carry max_chars must be non-zero".into());
+        }
+        Ok(())
     }
 }
 
@@ -105,22 +584,58 @@ pub struct DictumEngine {
     status: Arc<Mutex<EngineStatus>>,
     /// Broadcast sender for transcript events.
     transcript_tx: broadcast::Sender<TranscriptEvent>,
+    /// Broadcast sender for partial transcript events only.
+    partial_tx: broadcast::Sender<TranscriptEvent>,
+    /// Broadcast sender for final transcript events only.
+    final_tx: broadcast::Sender<TranscriptEvent>,
+    /// Loss-free final delivery under `BackpressurePolicy::GuaranteedFinals`.
+    final_fanout: fanout::FinalFanout,
     /// Broadcast sender for status events.
     status_tx: broadcast::Sender<EngineStatusEvent>,
     /// Broadcast sender for live VAD / level activity events.
     activity_tx: broadcast::Sender<AudioActivityEvent>,
+    /// Broadcast sender for utterance start/end events.
+    boundary_tx: broadcast::Sender<UtteranceBoundaryEvent>,
     /// Monotonically increasing event sequence counter.
     seq: Arc<AtomicU64>,
     /// Shared pipeline diagnostics counters.
     diagnostics: Arc<pipeline::PipelineDiagnostics>,
+    /// Asks the running pipeline to discard its accumulated state.
+    reset_requested: Arc<AtomicBool>,
+    capabilities_tx: broadcast::Sender<ModelCapabilities>,
+    capabilities: Arc<Mutex<Option<ModelCapabilities>>>,
+    /// Applies `pipeline_priority` on the pipeline thread.
+    thread_priority_fn: Mutex<ThreadPriorityFn>,
+    /// Disconnects once the current pipeline run has returned, terminal
+    /// flush included. `None` before the first `start()`.
+    pipeline_exit: Mutex<Option<mpsc::Receiver<()>>>,
+    /// Set once a warm-up was abandoned on timeout or cancellation. Its
+    /// thread may still hold the model lock, so the model is never used
+    /// again; build a new engine around another model instead.
+    warm_up_abandoned: AtomicBool,
+    /// Thresholds the running pipeline's VAD reads on every frame.
+    vad_thresholds: VadThresholds,
+}
+
+/// Whether the pipeline owning `exit`'s sender returned within `timeout`.
+pub(crate) fn pipeline_returned(exit: &mpsc::Receiver<()>, timeout: Duration) -> bool {
+    !matches!(
+        exit.recv_timeout(timeout),
+        Err(mpsc::RecvTimeoutError::Timeout)
+    )
 }
 
 impl DictumEngine {
     /// Create a new engine. Does not start capturing — call `warm_up()` then `start()`.
     pub fn new(config: EngineConfig, model: ModelHandle) -> Self {
+        let vad_thresholds = VadThresholds::from_config(&config);
         let (transcript_tx, _) = broadcast::channel(BROADCAST_CAP);
+        let (partial_tx, _) = broadcast::channel(BROADCAST_CAP);
+        let (final_tx, _) = broadcast::channel(BROADCAST_CAP);
         let (status_tx, _) = broadcast::channel(BROADCAST_CAP);
         let (activity_tx, _) = broadcast::channel(BROADCAST_CAP);
+        let (boundary_tx, _) = broadcast::channel(BROADCAST_CAP);
+        let (capabilities_tx, _) = broadcast::channel(4);
         let diagnostics = Arc::new(pipeline::PipelineDiagnostics::default());
 
         Self {
@@ -129,25 +644,135 @@ impl DictumEngine {
             running: Arc::new(AtomicBool::new(false)),
             status: Arc::new(Mutex::new(EngineStatus::Idle)),
             transcript_tx,
+            partial_tx,
+            final_tx,
+            final_fanout: fanout::FinalFanout::default(),
             status_tx,
             activity_tx,
+            boundary_tx,
             seq: Arc::new(AtomicU64::new(0)),
             diagnostics,
+            reset_requested: Arc::new(AtomicBool::new(false)),
+            capabilities_tx,
+            capabilities: Arc::new(Mutex::new(None)),
+            thread_priority_fn: Mutex::new(priority::set_current_thread_priority),
+            pipeline_exit: Mutex::new(None),
+            warm_up_abandoned: AtomicBool::new(false),
+            vad_thresholds,
         }
     }
 
     /// Warm up the speech model (load weights, run dummy inference).
     ///
-    /// Call once at application startup, before `start()`.
-    pub fn warm_up(&self) -> Result<()> {
+    /// Call once at application startup, before `start()`. On success the
+    /// model's capability report is returned, kept for
+    /// `model_capabilities`, and sent to `subscribe_capabilities` receivers.
+    /// Blocks for as long as the model takes; see `warm_up_with_timeout`.
+    pub fn warm_up(&self) -> Result<ModelCapabilities> {
+        self.check_model_usable()?;
         self.set_status(EngineStatus::WarmingUp, None);
         info!("warming up speech model");
-        self.model.0.lock().warm_up()?;
+        let capabilities = {
+            let mut model = self.model.0.lock();
+            model.warm_up()?;
+            model.capabilities()
+        };
+        Ok(self.finish_warm_up(capabilities))
+    }
+
+    /// `warm_up` on a separate thread, given up on after `timeout` or once
+    /// `cancel` is raised. Session creation cannot be interrupted, so an
+    /// abandoned warm-up keeps running in the background; the model is
+    /// marked unusable and the engine reports `EngineStatus::Error`. The
+    /// caller can then fall back to another model in a new engine.
+    ///
+    /// # Errors
+    /// - `DictumError::WarmUpTimeout` when `timeout` runs out.
+    /// - `DictumError::Cancelled` when `cancel` is raised first.
+    /// - Whatever the model's own `warm_up` returns.
+    pub fn warm_up_with_timeout(
+        &self,
+        timeout: Duration,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<ModelCapabilities> {
+        self.check_model_usable()?;
+        self.set_status(EngineStatus::WarmingUp, None);
+        info!(
+            timeout_ms = timeout.as_millis() as u64,
+            "warming up speech model"
+        );
+        let (done_tx, done_rx) = mpsc::channel::<Result<ModelCapabilities>>();
+        let model = self.model.clone();
+        std::thread::Builder::new()
+            .name("dictum-warm-up".into())
+            .spawn(move || {
+                let mut model = model.0.lock();
+                let _ = done_tx.send(model.warm_up().map(|()| model.capabilities()));
+            })?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            if cancel.is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                return Err(self.abandon_warm_up(DictumError::Cancelled));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(self.abandon_warm_up(DictumError::WarmUpTimeout(timeout)));
+            }
+            match done_rx.recv_timeout((deadline - now).min(WARM_UP_POLL_INTERVAL)) {
+                Ok(result) => return result.map(|capabilities| self.finish_warm_up(capabilities)),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(self.abandon_warm_up(DictumError::Inference(
+                        "warm-up thread exited without a result".into(),
+                    )));
+                }
+            }
+        }
+    }
+
+    fn finish_warm_up(&self, capabilities: ModelCapabilities) -> ModelCapabilities {
         self.set_status(EngineStatus::Idle, None);
-        info!("speech model ready");
+        info!(
+            backend = %capabilities.backend,
+            vocab_size = ?capabilities.vocab_size,
+            n_mels = ?capabilities.n_mels,
+            decoder_with_past = capabilities.decoder_with_past,
+            execution_provider = ?capabilities.execution_provider,
+            languages = ?capabilities.languages,
+            "speech model ready"
+        );
+        *self.capabilities.lock() = Some(capabilities.clone());
+        let _ = self.capabilities_tx.send(capabilities.clone());
+        capabilities
+    }
+
+    fn abandon_warm_up(&self, error: DictumError) -> DictumError {
+        self.warm_up_abandoned.store(true, Ordering::SeqCst);
+        tracing::warn!(error = %error, "abandoning speech model warm-up; the model is unusable");
+        self.set_status(EngineStatus::Error, Some(error.to_string()));
+        error
+    }
+
+    /// Fails fast once a warm-up was abandoned, before anything waits on a
+    /// model lock the abandoned thread may still hold.
+    fn check_model_usable(&self) -> Result<()> {
+        if self.warm_up_abandoned.load(Ordering::SeqCst) {
+            return Err(DictumError::NotWarmedUp);
+        }
         Ok(())
     }
 
+    /// Capability report from the last successful `warm_up`, if any.
+    pub fn model_capabilities(&self) -> Option<ModelCapabilities> {
+        self.capabilities.lock().clone()
+    }
+
+    /// Subscribe to capability reports, sent after each successful `warm_up`.
+    pub fn subscribe_capabilities(&self) -> broadcast::Receiver<ModelCapabilities> {
+        self.capabilities_tx.subscribe()
+    }
+
     /// Start audio capture and the pipeline.
     ///
     /// Blocks until the audio device is confirmed open (or fails), then returns.
@@ -155,6 +780,9 @@ impl DictumEngine {
     ///
     /// # Errors
     /// - `DictumError::AlreadyRunning` if already started.
+    /// - `DictumError::InvalidConfig` if the current config fails `EngineConfig::validate`.
+    /// - `DictumError::NotWarmedUp` if `warm_up()` has not loaded the model,
+    ///   or its warm-up was abandoned.
     /// - `DictumError::NoDefaultInputDevice` / `DictumError::AudioStream` on device error.
     pub fn start(&self) -> Result<()> {
         self.start_with_device(None)
@@ -162,44 +790,103 @@ impl DictumEngine {
 
     /// Start the engine using a preferred input device name.
     ///
-    /// If `preferred_input_device` is `None`, default input selection is used.
+    /// If `preferred_input_device` is `None`, the OS default input at the
+    /// time of this call is used (see `audio::device::select_input_device`).
     pub fn start_with_device(&self, preferred_input_device: Option<String>) -> Result<()> {
+        self.start_source(move |producer, running| {
+            let capture = AudioCapture::open_with_preference(
+                producer,
+                running,
+                preferred_input_device.as_deref(),
+            )?;
+            let sample_rate = capture.sample_rate;
+            Ok((capture, sample_rate))
+        })
+    }
+
+    /// Run the pipeline on a WAV file instead of a microphone, for
+    /// regression tests and batch jobs without an input device. Events
+    /// arrive exactly as from live capture. Once the file has been consumed
+    /// the pipeline flushes its final and ends on its own, leaving the
+    /// status `Stopped`; `stop()` ends it early.
+    ///
+    /// # Errors
+    /// As for `start`, with the file's read errors in place of device errors.
+    pub fn start_from_file(&self, path: impl AsRef<Path>, pacing: FilePacing) -> Result<()> {
+        let path = path.as_ref().to_path_buf();
+        self.start_source(move |producer, running| {
+            let capture = FileCapture::open(&path, producer, running, pacing)?;
+            let sample_rate = capture.sample_rate;
+            Ok((capture, sample_rate))
+        })
+    }
+
+    /// Shared start path. `open` runs on the pipeline thread, since a
+    /// capture handle may have to be dropped where it was created, and
+    /// returns the handle with its sample rate.
+    fn start_source<C>(
+        &self,
+        open: impl FnOnce(CountingProducer, Arc<AtomicBool>) -> Result<(C, u32)> + Send + 'static,
+    ) -> Result<()> {
         if self.running.load(Ordering::SeqCst) {
             return Err(DictumError::AlreadyRunning);
         }
+        self.config.read().validate()?;
+        self.check_model_usable()?;
+        if !self.model.is_warm() {
+            return Err(DictumError::NotWarmedUp);
+        }
 
         self.diagnostics.reset();
+        {
+            let mut model = self.model.0.lock();
+            model.begin_session();
+            model.set_short_utterance_decode(self.config.read().short_utterance_pad_ms > 0);
+            model.set_cancel_flag(Some(Arc::clone(&self.reset_requested)));
+        }
+        self.reset_requested.store(false, Ordering::SeqCst);
         self.running.store(true, Ordering::SeqCst);
         self.set_status(EngineStatus::Listening, None);
 
-        let (producer, consumer) = create_audio_ring();
+        let ring_capacity = effective_ring_capacity(self.config.read().ring_capacity_samples);
+        info!(ring_capacity, "audio ring allocated");
+        let (producer, consumer) = create_audio_ring_with_capacity(ring_capacity);
+        let producer =
+            CountingProducer::new(producer, Arc::clone(&self.diagnostics.frames_dropped));
 
         // Clone all Arc-wrapped state before moving into the closure.
         let config = self.config.read().clone();
         let model = self.model.clone();
         let running = Arc::clone(&self.running);
         let transcript_tx = self.transcript_tx.clone();
+        let partial_tx = self.partial_tx.clone();
+        let final_tx = self.final_tx.clone();
+        let final_fanout = self.final_fanout.clone();
         let status_tx = self.status_tx.clone();
         let activity_tx = self.activity_tx.clone();
+        let boundary_tx = self.boundary_tx.clone();
         let status = Arc::clone(&self.status);
         let seq = Arc::clone(&self.seq);
         let diagnostics = Arc::clone(&self.diagnostics);
-        let preferred_input_device = preferred_input_device.clone();
+        let reset_requested = Arc::clone(&self.reset_requested);
+        let vad_thresholds = self.vad_thresholds.clone();
 
         // Sync oneshot: pipeline thread signals open success/failure to start().
         // Carries the actual capture sample rate on success.
-        let (open_tx, open_rx) = std::sync::mpsc::channel::<Result<u32>>();
+        let (open_tx, open_rx) = mpsc::channel::<Result<u32>>();
+        // Never sent on: dropping the sender with the closure marks the exit.
+        let (exit_tx, exit_rx) = mpsc::channel::<()>();
+        *self.pipeline_exit.lock() = Some(exit_rx);
+        let priority = config.pipeline_priority;
+        let set_priority = *self.thread_priority_fn.lock();
 
-        tokio::task::spawn_blocking(move || {
-            // ── Open audio device (must happen on THIS thread — cpal::Stream is !Send) ──
-            let capture = match AudioCapture::open_with_preference(
-                producer,
-                Arc::clone(&running),
-                preferred_input_device.as_deref(),
-            ) {
-                Ok(c) => {
-                    let _ = open_tx.send(Ok(c.sample_rate));
-                    c
+        let run_pipeline = move || {
+            let _exit = exit_tx;
+            // ── Open audio source (must happen on THIS thread — cpal::Stream is !Send) ──
+            let (capture, capture_sample_rate) = match open(producer, Arc::clone(&running)) {
+                Ok(opened) => {
+                    let _ = open_tx.send(Ok(opened.1));
+                    opened
                 }
                 Err(e) => {
                     let _ = open_tx.send(Err(e));
@@ -207,60 +894,70 @@ impl DictumEngine {
                     return;
                 }
             };
-
-            let capture_sample_rate = capture.sample_rate;
+            let end_status = Arc::clone(&status);
+            let end_status_tx = status_tx.clone();
 
             // ── Select VAD ────────────────────────────────────────────────────────────
-            #[cfg(feature = "onnx")]
-            let vad: Box<dyn VoiceActivityDetector> = {
-                let path = config
-                    .silero_vad_path
-                    .clone()
-                    .unwrap_or_else(SileroVad::default_model_path);
-                let silero_threshold = config.silero_vad_threshold.clamp(0.03, 0.95);
-                match SileroVad::new(&path, silero_threshold) {
-                    Ok(v) => {
-                        info!(
-                            "using SileroVad from {:?} with threshold={}",
-                            path, silero_threshold
-                        );
-                        Box::new(v)
-                    }
-                    Err(e) => {
-                        tracing::warn!("SileroVad load failed ({e}), falling back to EnergyVad");
-                        Box::new(EnergyVad::new(
-                            config.vad_threshold,
-                            config.vad_hangover_frames,
-                        ))
-                    }
-                }
-            };
-
-            #[cfg(not(feature = "onnx"))]
-            let vad: Box<dyn VoiceActivityDetector> = Box::new(EnergyVad::new(
-                config.vad_threshold,
-                config.vad_hangover_frames,
-            ));
+            let mut vad = build_live_vad(&config, &vad_thresholds);
+            vad.warm();
 
             // ── Run pipeline ──────────────────────────────────────────────────────────
             pipeline::run(pipeline::PipelineContext {
                 config,
                 model,
                 vad,
+                vad_threshold: vad_thresholds.energy.clone(),
                 consumer,
                 running,
                 transcript_tx,
+                partial_tx,
+                final_tx,
+                final_fanout,
                 status_tx,
                 activity_tx,
+                boundary_tx,
                 status,
                 seq,
                 capture_sample_rate,
                 diagnostics,
+                reset_requested,
             });
 
+            // A source that ran out (end of file) stopped the run itself.
+            {
+                let mut status = end_status.lock();
+                if *status == EngineStatus::Listening {
+                    *status = EngineStatus::Stopped;
+                    let _ = end_status_tx.send(EngineStatusEvent {
+                        status: EngineStatus::Stopped,
+                        detail: Some("audio source ended".into()),
+                    });
+                }
+            }
+
             // Stream drops here, releasing the audio device on this thread.
             drop(capture);
-        });
+        };
+
+        if priority == PipelinePriority::Normal {
+            tokio::task::spawn_blocking(run_pipeline);
+        } else {
+            let spawned = std::thread::Builder::new()
+                .name("dictum-pipeline".into())
+                .spawn(move || {
+                    match set_priority(priority) {
+                        Ok(true) => info!(?priority, "pipeline thread priority raised"),
+                        Ok(false) => info!(?priority, "pipeline thread priority unsupported here"),
+                        Err(e) => tracing::warn!(?priority, "pipeline thread priority: {e}"),
+                    }
+                    run_pipeline();
+                });
+            if let Err(e) = spawned {
+                self.running.store(false, Ordering::SeqCst);
+                self.set_status(EngineStatus::Error, Some(e.to_string()));
+                return Err(e.into());
+            }
+        }
 
         // Block start() until device open is confirmed (receives actual sample rate).
         match open_rx.recv() {
@@ -299,39 +996,265 @@ impl DictumEngine {
         Ok(())
     }
 
+    /// Stop if running, then wait up to `timeout` for the pipeline to return.
+    ///
+    /// `stop()` only asks the pipeline to end: the utterance in progress is
+    /// flushed as a final afterwards, on the pipeline thread. Call this
+    /// before exiting so that final is broadcast first; it stays queued for
+    /// existing subscribers. Returns `false` if the pipeline was still
+    /// running at the deadline.
+    pub fn shutdown(&self, timeout: Duration) -> bool {
+        if self.running.load(Ordering::SeqCst) {
+            let _ = self.stop();
+        }
+        let exit = self.pipeline_exit.lock().take();
+        let returned = exit
+            .as_ref()
+            .is_none_or(|exit| pipeline_returned(exit, timeout));
+        if !returned {
+            *self.pipeline_exit.lock() = exit;
+        }
+        returned
+    }
+
+    /// Discard the utterance in progress without stopping capture.
+    ///
+    /// The pipeline drops its buffered speech, VAD and decoder state at the
+    /// start of its next iteration; nothing is transcribed from the discarded
+    /// audio. A decode already running is cancelled and its result dropped.
+    /// Unlike `stop()`, the device stays open and listening continues.
+    ///
+    /// # Errors
+    /// - `DictumError::NotRunning` if not currently running.
+    pub fn reset_session(&self) -> Result<()> {
+        if !self.running.load(Ordering::SeqCst) {
+            return Err(DictumError::NotRunning);
+        }
+        self.reset_requested.store(true, Ordering::SeqCst);
+        info!("session reset requested");
+        Ok(())
+    }
+
+    /// Switch the decode language, e.g. `Some("zh")`, or back to
+    /// auto-detect with `None`. Works while listening: the next utterance
+    /// uses the new language.
+    ///
+    /// # Errors
+    /// `DictumError::InvalidConfig` if the model does not support `language`.
+    pub fn set_language_hint(&self, language: Option<&str>) -> Result<()> {
+        self.model.0.lock().set_language_hint(language)
+    }
+
+    /// Language currently forced on decoding; `None` means auto-detect.
+    pub fn language_hint(&self) -> Option<String> {
+        self.model.0.lock().language_hint()
+    }
+
     /// Current engine status (snapshot).
     pub fn status(&self) -> EngineStatus {
         *self.status.lock()
     }
 
+    /// Whether a pipeline is running: `start()` succeeded and `stop()` has
+    /// not been called since. Unlike the status, this holds while a warning
+    /// or error is reported mid-capture, so decide between `start()` and
+    /// `stop()` on this.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Whether the engine is capturing and transcribing.
+    pub fn is_listening(&self) -> bool {
+        self.status() == EngineStatus::Listening
+    }
+
+    /// Whether the engine is not capturing: never started, or stopped.
+    pub fn is_idle(&self) -> bool {
+        matches!(self.status(), EngineStatus::Idle | EngineStatus::Stopped)
+    }
+
+    /// Whether an error status is raised: the last start or warm-up failed.
+    /// Warnings about a running pipeline (a degraded model, a stuck
+    /// pipeline) keep `Listening` and are reported in the detail.
+    pub fn is_error(&self) -> bool {
+        self.status() == EngineStatus::Error
+    }
+
+    /// Snapshot of the config the next `start()` will use (and, unless
+    /// changed since, the one the running pipeline was started with).
+    ///
+    /// Returned by value: `update_config` may replace it at any time, so a
+    /// reference could not outlive the lock. Use `target_sample_rate` here
+    /// to interpret sample offsets in events.
+    pub fn config(&self) -> EngineConfig {
+        self.config.read().clone()
+    }
+
+    /// Replace how the pipeline thread applies `pipeline_priority`, e.g. with
+    /// a platform call this crate cannot make without `unsafe`. Applies from
+    /// the next `start()`.
+    pub fn set_thread_priority_fn(&self, set_priority: ThreadPriorityFn) {
+        *self.thread_priority_fn.lock() = set_priority;
+    }
+
     /// Update the config used for future `start()` calls.
     ///
     /// This does not mutate an already-running pipeline; it applies on the next
-    /// listen session.
+    /// listen session. The VAD thresholds are the exception: they take
+    /// effect at once, as with `set_vad_threshold`.
     pub fn update_config(&self, config: EngineConfig) {
+        self.vad_thresholds.update(&config);
         *self.config.write() = config;
     }
 
-    /// Subscribe to live transcript events.
+    /// Change `vad_threshold` (RMS, within [0, 1]). A running pipeline's
+    /// VAD, input gain and RMS activity check use it from the next frame;
+    /// later starts keep it.
+    ///
+    /// # Errors
+    /// `InvalidConfig` if `threshold` is outside [0, 1].
+    pub fn set_vad_threshold(&self, threshold: f32) -> Result<()> {
+        if !threshold.is_finite() || !(0.0..=1.0).contains(&threshold) {
+            return Err(DictumError::InvalidConfig(format!(
+                "vad_threshold must be within [0, 1], got {threshold}"
+            )));
+        }
+        self.config.write().vad_threshold = threshold;
+        self.vad_thresholds.energy.set(threshold);
+        Ok(())
+    }
+
+    /// Change `silero_vad_threshold` (speech probability, within [0, 1]),
+    /// live like `set_vad_threshold`.
+    ///
+    /// # Errors
+    /// `InvalidConfig` if `threshold` is outside [0, 1].
+    #[cfg(feature = "onnx")]
+    pub fn set_silero_threshold(&self, threshold: f32) -> Result<()> {
+        if !threshold.is_finite() || !(0.0..=1.0).contains(&threshold) {
+            return Err(DictumError::InvalidConfig(format!(
+                "silero_vad_threshold must be within [0, 1], got {threshold}"
+            )));
+        }
+        self.config.write().silero_vad_threshold = threshold;
+        self.vad_thresholds.silero.set(silero_threshold(threshold));
+        Ok(())
+    }
+
+    /// Subscribe to live transcript events, partial and final.
     pub fn subscribe_transcripts(&self) -> broadcast::Receiver<TranscriptEvent> {
         self.transcript_tx.subscribe()
     }
 
+    /// Subscribe to partial transcript events only (e.g. live preview UI).
+    /// A burst of partials here cannot make a finals-only consumer lag.
+    pub fn subscribe_partials(&self) -> broadcast::Receiver<TranscriptEvent> {
+        self.partial_tx.subscribe()
+    }
+
+    /// Subscribe to final transcript events only (e.g. text injection),
+    /// including fallback placeholders.
+    pub fn subscribe_finals(&self) -> broadcast::Receiver<TranscriptEvent> {
+        self.final_tx.subscribe()
+    }
+
+    /// Subscribe to final transcript events without loss: each receiver has
+    /// its own unbounded queue, so a stalled consumer delays its finals
+    /// instead of missing them. Only fed while the config's `backpressure`
+    /// is `BackpressurePolicy::GuaranteedFinals`; see [`fanout`] for the
+    /// memory tradeoff.
+    pub fn subscribe_finals_guaranteed(&self) -> FinalReceiver {
+        self.final_fanout.subscribe()
+    }
+
+    /// Feed final transcripts to `sink` on a dedicated thread. The thread
+    /// exits once the engine is dropped.
+    pub fn attach_output_sink<S: OutputSink>(&self, sink: S) -> std::thread::JoinHandle<()> {
+        spawn_output_sink(self.subscribe_finals(), sink)
+    }
+
     /// Subscribe to live status change events.
     pub fn subscribe_status(&self) -> broadcast::Receiver<EngineStatusEvent> {
         self.status_tx.subscribe()
     }
 
+    /// Subscribe to transcripts, status, activity and periodic diagnostics
+    /// through one receiver. The per-kind subscriptions remain available.
+    pub fn subscribe_all(&self) -> EngineEventStream {
+        EngineEventStream::new(
+            self.transcript_tx.subscribe(),
+            self.status_tx.subscribe(),
+            self.activity_tx.subscribe(),
+            Arc::clone(&self.diagnostics),
+        )
+    }
+
     /// Subscribe to live voice activity events (RMS + speech classification).
     pub fn subscribe_activity(&self) -> broadcast::Receiver<AudioActivityEvent> {
         self.activity_tx.subscribe()
     }
 
+    /// Subscribe to utterance start/end events, sent when the pipeline
+    /// opens or closes an utterance.
+    pub fn subscribe_utterance_boundaries(&self) -> broadcast::Receiver<UtteranceBoundaryEvent> {
+        self.boundary_tx.subscribe()
+    }
+
+    /// Play a scripted activity `pattern` to activity subscribers, one frame
+    /// every [`calibration::TEST_ACTIVITY_FRAME_MS`], on a background thread.
+    ///
+    /// Diagnostic only, for checking the level visualizer without speaking;
+    /// no audio device is opened and no transcripts are produced.
+    ///
+    /// # Errors
+    /// - `DictumError::AlreadyRunning` while listening, so scripted frames
+    ///   never interleave with real ones.
+    pub fn emit_test_activity(
+        &self,
+        pattern: TestActivityPattern,
+    ) -> Result<std::thread::JoinHandle<()>> {
+        if self.running.load(Ordering::SeqCst) {
+            return Err(DictumError::AlreadyRunning);
+        }
+        let activity_tx = self.activity_tx.clone();
+        let frame = std::time::Duration::from_millis(calibration::TEST_ACTIVITY_FRAME_MS);
+        Ok(std::thread::spawn(move || {
+            for event in pattern.events() {
+                let _ = activity_tx.send(event);
+                std::thread::sleep(frame);
+            }
+        }))
+    }
+
+    /// Send `detail` to status subscribers under the current status, e.g. a
+    /// host-side warning about a final. The status value is not changed.
+    pub fn report_status_detail(&self, detail: impl Into<String>) {
+        let status = *self.status.lock();
+        let _ = self.status_tx.send(EngineStatusEvent {
+            status,
+            detail: Some(detail.into()),
+        });
+    }
+
+    /// Shared handle to the speech model, e.g. for offline file transcription.
+    ///
+    /// The model mutex is also taken by the live pipeline, so callers should
+    /// avoid long offline runs while the engine is listening.
+    pub fn model(&self) -> ModelHandle {
+        self.model.clone()
+    }
+
     /// Snapshot of pipeline counters for observability.
     pub fn pipeline_diagnostics_snapshot(&self) -> pipeline::DiagnosticsSnapshot {
         self.diagnostics.snapshot()
     }
 
+    /// Current diagnostics in Prometheus text exposition format, for a local
+    /// scrape endpoint. See [`metrics`] for the metric names.
+    pub fn metrics_prometheus(&self) -> String {
+        metrics::render_prometheus(&self.diagnostics.snapshot())
+    }
+
     // ── Internal helpers ─────────────────────────────────────────────────────
 
     fn set_status(&self, new_status: EngineStatus, detail: Option<String>) {
@@ -342,3 +1265,857 @@ impl DictumEngine {
         });
     }
 }
+
+/// The voice activity detector `config` asks for, as the live pipeline uses
+/// it: Silero (optionally behind an energy gate) with the `onnx` feature,
+/// falling back to `EnergyVad` when the model cannot be loaded. Not warmed.
+pub fn build_vad(config: &EngineConfig) -> Box<dyn VoiceActivityDetector> {
+    build_live_vad(config, &VadThresholds::from_config(config))
+}
+
+/// Live VAD thresholds: the detectors `build_live_vad` returns read them on
+/// every frame, so setting them retunes a running pipeline.
+#[derive(Debug, Clone)]
+pub struct VadThresholds {
+    /// `vad_threshold`, for `EnergyVad`.
+    pub energy: LiveThreshold,
+    /// `silero_vad_threshold`, as clamped for `SileroVad`.
+    #[cfg(feature = "onnx")]
+    pub silero: LiveThreshold,
+}
+
+impl VadThresholds {
+    pub fn from_config(config: &EngineConfig) -> Self {
+        Self {
+            energy: LiveThreshold::new(config.vad_threshold),
+            #[cfg(feature = "onnx")]
+            silero: LiveThreshold::new(silero_threshold(config.silero_vad_threshold)),
+        }
+    }
+
+    /// Store `config`'s thresholds.
+    pub fn update(&self, config: &EngineConfig) {
+        self.energy.set(config.vad_threshold);
+        #[cfg(feature = "onnx")]
+        self.silero
+            .set(silero_threshold(config.silero_vad_threshold));
+    }
+}
+
+/// The Silero threshold actually applied: extremes are clamped, since a
+/// probability cut-off of 0 or 1 makes everything speech or nothing.
+#[cfg(feature = "onnx")]
+fn silero_threshold(configured: f32) -> f32 {
+    configured.clamp(0.03, 0.95)
+}
+
+/// [`build_vad`] with its thresholds read live from `thresholds`.
+pub fn build_live_vad(
+    config: &EngineConfig,
+    thresholds: &VadThresholds,
+) -> Box<dyn VoiceActivityDetector> {
+    #[cfg(feature = "onnx")]
+    {
+        let path = config
+            .silero_vad_path
+            .clone()
+            .unwrap_or_else(SileroVad::default_model_path);
+        let silero_threshold = thresholds.silero.get();
+        // Silero consumes 512-sample (32 ms) windows at 16 kHz.
+        let prime_windows = (config.silero_vad_prime_ms / 32) as usize;
+        match SileroVad::new(&path, silero_threshold) {
+            Ok(v) => {
+                info!(
+                    "using SileroVad from {:?} with threshold={} prime_windows={}",
+                    path, silero_threshold, prime_windows
+                );
+                let mut silero = v
+                    .with_prime_windows(prime_windows)
+                    .with_live_threshold(thresholds.silero.clone());
+                if let Some(band) = config.vad_uncertain_band {
+                    silero = silero.with_uncertain_band(band);
+                }
+                let silero = Box::new(silero);
+                match config.hybrid_vad {
+                    Some(policy) => {
+                        info!(?policy, "gating SileroVad behind EnergyVad");
+                        Box::new(HybridVad::new(
+                            Box::new(energy_vad(config, thresholds)),
+                            silero,
+                            policy,
+                        ))
+                    }
+                    None => silero,
+                }
+            }
+            Err(e) => {
+                tracing::warn!("SileroVad load failed ({e}), falling back to EnergyVad");
+                Box::new(energy_vad(config, thresholds))
+            }
+        }
+    }
+
+    #[cfg(not(feature = "onnx"))]
+    Box::new(energy_vad(config, thresholds))
+}
+
+/// The configured `EnergyVad`, with the uncertainty band if one is set.
+fn energy_vad(config: &EngineConfig, thresholds: &VadThresholds) -> EnergyVad {
+    let vad = EnergyVad::new(config.vad_threshold, config.vad_hangover_frames)
+        .with_live_threshold(thresholds.energy.clone())
+        .with_gate(config.energy_gate);
+    match config.vad_uncertain_band {
+        Some(band) => vad.with_uncertain_band(band),
+        None => vad,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vad::energy::NoiseFloorConfig;
+
+    fn assert_invalid(config: EngineConfig, needle: &str) {
+        match config.validate() {
+            Err(DictumError::InvalidConfig(msg)) => {
+                assert!(msg.contains(needle), "unexpected message: {msg}")
+            }
+            other => panic!("expected InvalidConfig mentioning {needle}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(EngineConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn zero_sample_rate_is_rejected() {
+        let config = EngineConfig {
+            target_sample_rate: 0,
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "target_sample_rate");
+    }
+
+    #[test]
+    fn out_of_range_vad_threshold_is_rejected() {
+        for bad in [-0.1, 1.5, f32::NAN] {
+            let config = EngineConfig {
+                vad_threshold: bad,
+                ..EngineConfig::default()
+            };
+            assert_invalid(config, "vad_threshold");
+        }
+    }
+
+    #[test]
+    fn bad_agc_settings_are_rejected() {
+        let agc = AgcConfig::default();
+        let config = EngineConfig {
+            input_gain: InputGainMode::Agc(AgcConfig {
+                attack_ms: -1.0,
+                ..agc
+            }),
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "attack_ms");
+        let config = EngineConfig {
+            input_gain: InputGainMode::Agc(AgcConfig {
+                max_gain: 0.0,
+                ..agc
+            }),
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "max_gain");
+    }
+
+    #[test]
+    fn bad_noise_floor_settings_are_rejected() {
+        for (noise, needle) in [
+            (
+                NoiseFloorConfig {
+                    alpha: 0.0,
+                    ..NoiseFloorConfig::default()
+                },
+                "alpha",
+            ),
+            (
+                NoiseFloorConfig {
+                    margin_db: -3.0,
+                    ..NoiseFloorConfig::default()
+                },
+                "margin_db",
+            ),
+            (
+                NoiseFloorConfig {
+                    speech_rise_db_per_sec: f32::NAN,
+                    ..NoiseFloorConfig::default()
+                },
+                "speech_rise_db_per_sec",
+            ),
+        ] {
+            let config = EngineConfig {
+                energy_gate: EnergyGate::NoiseFloor(noise),
+                ..EngineConfig::default()
+            };
+            assert_invalid(config, needle);
+        }
+    }
+
+    #[cfg(feature = "onnx")]
+    #[test]
+    fn out_of_range_silero_threshold_is_rejected() {
+        let config = EngineConfig {
+            silero_vad_threshold: 1.2,
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "silero_vad_threshold");
+    }
+
+    #[test]
+    fn speech_sample_bounds_must_be_ordered() {
+        let config = EngineConfig {
+            min_speech_samples: 16_000,
+            max_speech_samples: 8_000,
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "must not exceed max_speech_samples");
+
+        let config = EngineConfig {
+            min_speech_samples: 0,
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "min_speech_samples must be non-zero");
+    }
+
+    #[test]
+    fn max_utterance_ms_sets_the_speech_cap() {
+        let config = EngineConfig {
+            target_sample_rate: 16_000,
+            max_utterance_ms: Some(2_000),
+            ..EngineConfig::default()
+        };
+        // Takes precedence over max_speech_samples (480000).
+        assert_eq!(config.effective_max_speech_samples(), 32_000);
+        assert!(config.validate().is_ok());
+
+        let config = EngineConfig {
+            min_speech_samples: 16_000,
+            max_utterance_ms: Some(500),
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "from max_utterance_ms");
+        let config = EngineConfig {
+            max_utterance_ms: Some(0),
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "max_utterance_ms must be non-zero");
+    }
+
+    #[test]
+    fn speech_durations_convert_and_are_checked_at_the_sample_rate() {
+        let mut config = EngineConfig {
+            target_sample_rate: 48_000,
+            ..EngineConfig::default()
+        };
+        config.set_min_speech_ms(250);
+        assert_eq!(config.min_speech_samples, 12_000);
+        assert_eq!(config.min_speech_ms(), 250);
+        assert!(config.validate().is_ok());
+
+        config.target_sample_rate = 16_000;
+        config.set_min_speech_ms(30);
+        assert_eq!(config.min_speech_samples, 480);
+        assert!(config.validate().is_ok());
+
+        // 4 samples is a quarter of a millisecond at 16 kHz.
+        let config = EngineConfig {
+            min_speech_samples: 4,
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "outside 20..=10000 ms");
+        // 420 samples tuned for 16 kHz (26 ms), left in place at 48 kHz.
+        let config = EngineConfig {
+            target_sample_rate: 48_000,
+            min_speech_samples: 420,
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "min_speech_samples (420) is 8 ms at 48000 Hz");
+        // A 100 ms speech cap flushes mid-word.
+        let config = EngineConfig {
+            min_speech_samples: 800,
+            max_speech_samples: 1_600,
+            ..EngineConfig::default()
+        };
+        assert_invalid(
+            config,
+            "max_speech_samples (1600, from max_speech_samples) is 100 ms",
+        );
+        let config = EngineConfig {
+            max_utterance_ms: Some(3_600_000),
+            ..EngineConfig::default()
+        };
+        assert_invalid(config, "from max_utterance_ms) is 3600000 ms");
+    }
+
+    #[test]
+    fn config_round_trips_through_json() {
+        let default = EngineConfig::default();
+        let json = serde_json::to_string(&default).unwrap();
+        assert_eq!(
+            serde_json::from_str::<EngineConfig>(&json).unwrap(),
+            default
+        );
+
+        let custom = EngineConfig {
+            vad_threshold: 0.002,
+            energy_gate: EnergyGate::NoiseFloor(NoiseFloorConfig {
+                margin_db: 9.0,
+                ..NoiseFloorConfig::default()
+            }),
+            max_speech_samples: 96_000,
+            max_utterance_ms: Some(6_000),
+            capture_mode: CaptureMode::PushToTalk,
+            input_gain: InputGainMode::Agc(AgcConfig {
+                max_gain: 4.0,
+                ..AgcConfig::default()
+            }),
+            backpressure: BackpressurePolicy::GuaranteedFinals,
+            context_carry: Some(ContextCarry::default()),
+            segment_handling: SegmentHandling::Merge,
+            debug_audio: Some(DebugAudioTap::new("/tmp/dictum-taps")),
+            activity_level: Some(ActivityLevel::default()),
+            activity_bins: Some(ActivityBins::Spectrum),
+            ring_capacity_samples: 1 << 16,
+            audio_fingerprint: true,
+            #[cfg(feature = "onnx")]
+            silero_vad_threshold: 0.05,
+            #[cfg(feature = "onnx")]
+            silero_vad_path: Some("models/silero_vad.onnx".into()),
+            #[cfg(feature = "onnx")]
+            hybrid_vad: Some(HybridPolicy::Or),
+            ..EngineConfig::default()
+        };
+        let json = serde_json::to_string(&custom).unwrap();
+        assert_eq!(serde_json::from_str::<EngineConfig>(&json).unwrap(), custom);
+    }
+
+    #[test]
+    fn partial_config_fills_defaults_and_tolerates_onnx_fields() {
+        // `silero_vad_threshold` is an onnx-only field; builds without the
+        // feature must still accept a file written by one with it.
+        let config: EngineConfig = serde_json::from_str(
+            r#"{"vad_threshold": 0.003, "silero_vad_threshold": 0.07, "input_gain": "adaptive_boost"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.vad_threshold, 0.003);
+        assert_eq!(
+            config.max_speech_samples,
+            EngineConfig::default().max_speech_samples
+        );
+        #[cfg(feature = "onnx")]
+        assert_eq!(config.silero_vad_threshold, 0.07);
+    }
+
+    #[test]
+    fn deserializing_an_invalid_config_fails_validation() {
+        let err = serde_json::from_str::<EngineConfig>(
+            r#"{"min_speech_samples": 16000, "max_speech_samples": 8000}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("max_speech_samples"), "{err}");
+    }
+
+    #[test]
+    fn config_getter_returns_the_current_snapshot() {
+        let built = EngineConfig {
+            target_sample_rate: 8_000,
+            vad_threshold: 0.004,
+            ..EngineConfig::default()
+        };
+        let engine = DictumEngine::new(
+            built.clone(),
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        assert_eq!(engine.config(), built);
+
+        let updated = EngineConfig {
+            target_sample_rate: 16_000,
+            ..built
+        };
+        engine.update_config(updated.clone());
+        assert_eq!(engine.config(), updated);
+    }
+
+    #[test]
+    fn start_rejects_invalid_config_without_running() {
+        let engine = DictumEngine::new(
+            EngineConfig {
+                target_sample_rate: 0,
+                ..EngineConfig::default()
+            },
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        assert!(matches!(engine.start(), Err(DictumError::InvalidConfig(_))));
+        assert!(!engine.running.load(Ordering::SeqCst));
+        assert_eq!(engine.status(), EngineStatus::Idle);
+    }
+
+    /// Only transcribes once `warm_up` has run.
+    struct ColdModel {
+        warm: bool,
+    }
+
+    impl crate::inference::SpeechModel for ColdModel {
+        fn warm_up(&mut self) -> Result<()> {
+            self.warm = true;
+            Ok(())
+        }
+
+        fn is_warm(&self) -> bool {
+            self.warm
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &crate::buffering::chunk::AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<crate::ipc::events::TranscriptSegment>> {
+            Ok(vec![])
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    #[test]
+    fn start_and_transcribe_before_warm_up_fail_early() {
+        let model = ModelHandle::new(ColdModel { warm: false });
+        let engine = DictumEngine::new(EngineConfig::default(), model.clone());
+        assert!(!model.is_warm());
+        assert!(matches!(engine.start(), Err(DictumError::NotWarmedUp)));
+        assert!(!engine.running.load(Ordering::SeqCst));
+        assert_eq!(engine.status(), EngineStatus::Idle);
+
+        // Rejected before the (missing) file is even opened.
+        let result =
+            crate::inference::transcribe_file(&mut *model.0.lock(), "/nonexistent/dictum.wav");
+        assert!(matches!(result, Err(DictumError::NotWarmedUp)));
+
+        engine.warm_up().expect("warm-up");
+        assert!(model.is_warm());
+        assert!(ModelHandle::new(crate::inference::stub::StubModel::new()).is_warm());
+    }
+
+    /// Stands in for a session stuck on stalled storage or a driver.
+    struct SlowWarmUpModel;
+
+    impl crate::inference::SpeechModel for SlowWarmUpModel {
+        fn warm_up(&mut self) -> Result<()> {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &crate::buffering::chunk::AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<crate::ipc::events::TranscriptSegment>> {
+            Ok(vec![])
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    #[test]
+    fn slow_warm_up_times_out_and_the_stub_takes_over() {
+        let engine = DictumEngine::new(EngineConfig::default(), ModelHandle::new(SlowWarmUpModel));
+        let started = Instant::now();
+        let result = engine.warm_up_with_timeout(Duration::from_millis(100), None);
+        assert!(
+            matches!(result, Err(DictumError::WarmUpTimeout(t)) if t == Duration::from_millis(100)),
+            "{result:?}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(engine.is_error());
+        assert_eq!(engine.model_capabilities(), None);
+        // Rejected without waiting on the lock the abandoned warm-up holds.
+        let started = Instant::now();
+        assert!(matches!(engine.start(), Err(DictumError::NotWarmedUp)));
+        assert!(matches!(engine.warm_up(), Err(DictumError::NotWarmedUp)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let cancel = AtomicBool::new(true);
+        let cancelled =
+            DictumEngine::new(EngineConfig::default(), ModelHandle::new(SlowWarmUpModel));
+        assert!(matches!(
+            cancelled.warm_up_with_timeout(Duration::from_secs(30), Some(&cancel)),
+            Err(DictumError::Cancelled)
+        ));
+
+        let fallback = DictumEngine::new(
+            EngineConfig::default(),
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        let capabilities = fallback
+            .warm_up_with_timeout(Duration::from_secs(5), None)
+            .expect("stub warm-up");
+        assert_eq!(capabilities.backend, "stub");
+        assert!(fallback.is_idle());
+    }
+
+    #[test]
+    fn warm_up_reports_and_broadcasts_model_capabilities() {
+        let engine = DictumEngine::new(
+            EngineConfig::default(),
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        assert_eq!(engine.model_capabilities(), None);
+        let mut rx = engine.subscribe_capabilities();
+
+        let reported = engine.warm_up().expect("stub warm-up");
+        assert_eq!(reported.backend, "stub");
+        assert_eq!(reported.vocab_size, None);
+        assert!(!reported.segment_timestamps);
+        assert_eq!(engine.model_capabilities(), Some(reported.clone()));
+        assert_eq!(rx.try_recv().expect("capabilities event"), reported);
+    }
+
+    /// Records what the engine asks of the short-utterance decode.
+    struct ShortUtteranceModel(Arc<Mutex<Option<bool>>>);
+
+    impl crate::inference::SpeechModel for ShortUtteranceModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &crate::buffering::chunk::AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<crate::ipc::events::TranscriptSegment>> {
+            Ok(vec![])
+        }
+
+        fn reset(&mut self) {}
+
+        fn set_short_utterance_decode(&mut self, enabled: bool) {
+            *self.0.lock() = Some(enabled);
+        }
+    }
+
+    #[test]
+    fn short_utterance_decode_follows_the_padding_setting() {
+        let path = std::env::temp_dir().join(format!(
+            "dictum-engine-short-utterance-{}.wav",
+            std::process::id()
+        ));
+        crate::audio::wav::write_wav_mono(&path, &vec![0.0; 16_000], 16_000).expect("write wav");
+        let runtime = tokio::runtime::Runtime::new().expect("runtime");
+        let _guard = runtime.enter();
+
+        for (pad_ms, expected) in [(0, false), (600, true)] {
+            let requested = Arc::new(Mutex::new(None));
+            let engine = DictumEngine::new(
+                EngineConfig {
+                    short_utterance_pad_ms: pad_ms,
+                    ..EngineConfig::default()
+                },
+                ModelHandle::new(ShortUtteranceModel(Arc::clone(&requested))),
+            );
+            engine.warm_up().expect("warm up");
+            engine
+                .start_from_file(&path, FilePacing::RealTime)
+                .expect("start from file");
+            engine.stop().expect("stop");
+            assert!(engine.shutdown(Duration::from_secs(5)));
+            assert_eq!(*requested.lock(), Some(expected), "pad {pad_ms} ms");
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn reported_details_keep_the_current_status() {
+        let engine = DictumEngine::new(
+            EngineConfig::default(),
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        let mut rx = engine.subscribe_status();
+        engine.report_status_detail("duplicate audio");
+
+        let event = rx.try_recv().expect("status event");
+        assert_eq!(event.status, EngineStatus::Idle);
+        assert_eq!(event.detail.as_deref(), Some("duplicate audio"));
+        assert!(engine.is_idle());
+    }
+
+    #[test]
+    fn status_predicates_follow_start_and_stop() {
+        let path = std::env::temp_dir().join(format!(
+            "dictum-engine-predicates-{}.wav",
+            std::process::id()
+        ));
+        // Long enough, played in real time, to still be running when checked.
+        crate::audio::wav::write_wav_mono(&path, &vec![0.0; 16_000 * 5], 16_000)
+            .expect("write wav");
+
+        let engine = DictumEngine::new(
+            EngineConfig::default(),
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        assert!(engine.is_idle() && !engine.is_running());
+        assert!(!engine.is_listening() && !engine.is_error());
+
+        engine.warm_up().expect("stub warm-up");
+        let runtime = tokio::runtime::Runtime::new().expect("runtime");
+        let _guard = runtime.enter();
+        engine
+            .start_from_file(&path, FilePacing::RealTime)
+            .expect("start from file");
+        assert!(engine.is_running() && engine.is_listening());
+        assert!(!engine.is_idle() && !engine.is_error());
+
+        engine.stop().expect("stop");
+        assert!(engine.is_idle());
+        assert!(!engine.is_running() && !engine.is_listening());
+        assert!(engine.shutdown(Duration::from_secs(5)));
+        let _ = std::fs::remove_file(&path);
+
+        // A failed start raises the error without leaving anything running.
+        assert!(engine
+            .start_from_file("/nonexistent/dictum.wav", FilePacing::RealTime)
+            .is_err());
+        assert!(engine.is_error() && !engine.is_running());
+        assert!(!engine.is_listening() && !engine.is_idle());
+    }
+
+    #[test]
+    fn shutdown_returns_after_the_buffered_final_is_sent() {
+        let path =
+            std::env::temp_dir().join(format!("dictum-engine-shutdown-{}.wav", std::process::id()));
+        // Tone throughout, played in real time: shutdown lands mid-utterance.
+        let samples: Vec<f32> = (0..16_000 * 5)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / 16_000.0).sin())
+            .collect();
+        crate::audio::wav::write_wav_mono(&path, &samples, 16_000).expect("write wav");
+
+        let engine = DictumEngine::new(
+            EngineConfig {
+                enable_partial_inference: false,
+                ..EngineConfig::default()
+            },
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        engine.warm_up().expect("stub warm-up");
+        let mut finals = engine.subscribe_finals();
+        let mut boundaries = engine.subscribe_utterance_boundaries();
+        let runtime = tokio::runtime::Runtime::new().expect("runtime");
+        let _guard = runtime.enter();
+        engine
+            .start_from_file(&path, FilePacing::RealTime)
+            .expect("start from file");
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while boundaries.try_recv().is_err() {
+            assert!(std::time::Instant::now() < deadline, "speech never started");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        // Past `min_speech_samples` of buffered speech.
+        std::thread::sleep(Duration::from_millis(400));
+        assert!(finals.try_recv().is_err(), "no final before shutdown");
+
+        assert!(engine.shutdown(Duration::from_secs(5)));
+        let event = finals
+            .try_recv()
+            .expect("final sent before shutdown returned");
+        assert_eq!(
+            event.segments[0].kind,
+            crate::ipc::events::SegmentKind::Final
+        );
+        assert!(!engine.is_running());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn start_from_file_runs_the_pipeline_headless() {
+        let path = std::env::temp_dir().join(format!(
+            "dictum-engine-from-file-{}.wav",
+            std::process::id()
+        ));
+        // One second of tone, then enough silence to close the utterance.
+        let mut samples: Vec<f32> = (0..16_000)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / 16_000.0).sin())
+            .collect();
+        samples.extend(std::iter::repeat_n(0.0, 8_000));
+        crate::audio::wav::write_wav_mono(&path, &samples, 16_000).expect("write wav");
+
+        let engine = DictumEngine::new(
+            EngineConfig {
+                enable_partial_inference: false,
+                ..EngineConfig::default()
+            },
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        engine.warm_up().expect("stub warm-up");
+        let mut finals = engine.subscribe_finals();
+        let runtime = tokio::runtime::Runtime::new().expect("runtime");
+        let _guard = runtime.enter();
+        engine
+            .start_from_file(&path, FilePacing::AsFastAsPossible)
+            .expect("start from file");
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        let event = loop {
+            match finals.try_recv() {
+                Ok(event) => break event,
+                Err(_) if std::time::Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(5))
+                }
+                Err(e) => panic!("no final from the file: {e}"),
+            }
+        };
+        assert!(
+            event.segments[0].text.starts_with("[stub:"),
+            "{:?}",
+            event.segments
+        );
+        assert!(engine.shutdown(Duration::from_secs(5)));
+        assert_eq!(engine.status(), EngineStatus::Stopped);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(
+            engine.start_from_file("/nonexistent/dictum.wav", FilePacing::RealTime),
+            Err(DictumError::Other(_))
+        ));
+        assert!(engine.is_error());
+    }
+
+    #[test]
+    fn vad_threshold_changes_reach_a_running_detector() {
+        let engine = DictumEngine::new(
+            EngineConfig {
+                vad_threshold: 0.02,
+                vad_hangover_frames: 0,
+                ..EngineConfig::default()
+            },
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        // The detector a started pipeline runs with.
+        let mut vad = build_live_vad(&engine.config(), &engine.vad_thresholds);
+        let chunk = crate::buffering::chunk::AudioChunk::new(vec![0.05; 512], 16_000);
+        assert_eq!(vad.classify(&chunk), crate::vad::VadDecision::Speech);
+
+        engine.set_vad_threshold(0.1).expect("valid threshold");
+        assert_eq!(vad.classify(&chunk), crate::vad::VadDecision::Silence);
+        assert_eq!(engine.config().vad_threshold, 0.1);
+
+        assert!(matches!(
+            engine.set_vad_threshold(1.5),
+            Err(DictumError::InvalidConfig(_))
+        ));
+        assert_eq!(vad.classify(&chunk), crate::vad::VadDecision::Silence);
+
+        engine.update_config(EngineConfig {
+            vad_threshold: 0.04,
+            ..engine.config()
+        });
+        assert_eq!(vad.classify(&chunk), crate::vad::VadDecision::Speech);
+    }
+
+    #[test]
+    fn reset_session_requires_running_engine() {
+        let engine = DictumEngine::new(
+            EngineConfig::default(),
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        assert!(matches!(
+            engine.reset_session(),
+            Err(DictumError::NotRunning)
+        ));
+        assert!(!engine.reset_requested.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn subscribe_all_merges_every_event_kind() {
+        let engine = DictumEngine::new(
+            EngineConfig::default(),
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        let mut events = engine
+            .subscribe_all()
+            .with_diagnostics_interval(Some(std::time::Duration::from_millis(10)));
+
+        engine.warm_up().expect("stub warm-up");
+        let _ = engine.transcript_tx.send(TranscriptEvent {
+            seq: 0,
+            segments: vec![],
+            meta: Default::default(),
+        });
+        let _ = engine.activity_tx.send(AudioActivityEvent {
+            seq: 0,
+            rms: 0.1,
+            is_speech: true,
+            level: None,
+            bins: None,
+        });
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("runtime");
+        let kinds = runtime.block_on(async {
+            let mut kinds = Vec::new();
+            while kinds.len() < 5 {
+                let event = tokio::time::timeout(std::time::Duration::from_secs(1), events.recv())
+                    .await
+                    .expect("event within timeout")
+                    .expect("open stream");
+                kinds.push(match event {
+                    EngineEvent::Status(_) => "status",
+                    EngineEvent::Transcript(_) => "transcript",
+                    EngineEvent::Activity(_) => "activity",
+                    EngineEvent::Diagnostics(_) => "diagnostics",
+                });
+            }
+            kinds
+        });
+        // Buffered events come out in priority order, then the timer fires.
+        assert_eq!(
+            kinds,
+            vec!["status", "status", "transcript", "activity", "diagnostics"]
+        );
+    }
+
+    #[test]
+    fn test_activity_plays_the_scripted_pattern() {
+        let engine = DictumEngine::new(
+            EngineConfig::default(),
+            ModelHandle::new(crate::inference::stub::StubModel::new()),
+        );
+        let mut activity = engine.subscribe_activity();
+
+        engine
+            .emit_test_activity(TestActivityPattern::Pulse)
+            .expect("idle engine")
+            .join()
+            .expect("emitter thread");
+
+        let expected = TestActivityPattern::Pulse.events();
+        let mut received = Vec::new();
+        while let Ok(event) = activity.try_recv() {
+            received.push((event.seq, event.rms, event.is_speech));
+        }
+        assert_eq!(received.len(), expected.len());
+        for (got, want) in received.iter().zip(&expected) {
+            assert_eq!(*got, (want.seq, want.rms, want.is_speech));
+        }
+        assert_eq!(received.iter().filter(|(_, _, speech)| *speech).count(), 18);
+        assert!(!received.first().unwrap().2 && !received.last().unwrap().2);
+
+        engine.running.store(true, Ordering::SeqCst);
+        assert!(matches!(
+            engine.emit_test_activity(TestActivityPattern::Ramp),
+            Err(DictumError::AlreadyRunning)
+        ));
+    }
+}
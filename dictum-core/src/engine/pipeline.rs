@@ -17,9 +17,10 @@
 //! This entire loop runs in `spawn_blocking`, keeping the Tokio async
 //! executor free for I/O (Tauri IPC, file system, etc.).
 
+use std::collections::VecDeque;
 use std::sync::OnceLock;
 use std::sync::{
-    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering},
     Arc,
 };
 use std::time::{Duration, Instant};
@@ -29,26 +30,45 @@ use tokio::sync::broadcast;
 use tracing::{debug, error, info, info_span, warn, Span};
 
 use crate::{
-    audio::resample::RateConverter,
-    buffering::{chunk::AudioChunk, AudioConsumer, Consumer},
-    engine::EngineConfig,
+    audio::{
+        agc::AutomaticGainControl, fingerprint::AudioFingerprint, gain::GainLimits,
+        resample::RateConverter,
+    },
+    buffering::{chunk::AudioChunk, ring_fill_pct, AudioConsumer, Consumer},
+    engine::{
+        audio_tap::RateTap, fanout::FinalFanout, ActivityLevel, BackpressurePolicy, CaptureMode,
+        ContextCarry, EngineConfig, InputGainMode, PausePunctuation, SegmentHandling,
+    },
     inference::ModelHandle,
     ipc::events::{
-        AudioActivityEvent, EngineStatus, EngineStatusEvent, SegmentKind, TranscriptEvent,
-        TranscriptSegment,
+        AudioActivityEvent, BoundaryKind, EmptyReason, EngineStatus, EngineStatusEvent,
+        SegmentKind, TranscriptEvent, TranscriptMeta, TranscriptSegment, TranscriptSource,
+        UtteranceBoundaryEvent,
     },
-    vad::{VadDecision, VoiceActivityDetector},
+    vad::{LiveThreshold, VadDecision, VoiceActivityDetector},
 };
 
 pub struct PipelineDiagnostics {
     pub frames_in: AtomicUsize,
+    /// Capture samples a full ring turned away. Shared with the capture
+    /// callback's `CountingProducer`.
+    pub frames_dropped: Arc<AtomicUsize>,
+    /// Ring occupancy (percent, `f32` bits) before the latest drain.
+    ring_fill_pct: AtomicU32,
+    /// The VAD's latest noise floor estimate (`f32` bits; NaN when it
+    /// tracks none).
+    vad_noise_floor: AtomicU32,
     pub frames_resampled: AtomicUsize,
     pub vad_windows: AtomicUsize,
     pub vad_speech: AtomicUsize,
     pub inference_calls: AtomicUsize,
     pub inference_errors: AtomicUsize,
     pub segments_emitted: AtomicUsize,
+    /// Final segments emitted, including fallback placeholders.
+    pub finals_emitted: AtomicUsize,
     pub fallback_emitted: AtomicUsize,
+    /// Times [`REPEATED_FINAL_LIMIT`] identical finals came out in a row.
+    pub repeated_finals: AtomicUsize,
     drain_ms: Mutex<StageWindow>,
     resample_ms: Mutex<StageWindow>,
     vad_ms: Mutex<StageWindow>,
@@ -59,13 +79,18 @@ impl Default for PipelineDiagnostics {
     fn default() -> Self {
         Self {
             frames_in: AtomicUsize::new(0),
+            frames_dropped: Arc::new(AtomicUsize::new(0)),
+            ring_fill_pct: AtomicU32::new(0),
+            vad_noise_floor: AtomicU32::new(f32::NAN.to_bits()),
             frames_resampled: AtomicUsize::new(0),
             vad_windows: AtomicUsize::new(0),
             vad_speech: AtomicUsize::new(0),
             inference_calls: AtomicUsize::new(0),
             inference_errors: AtomicUsize::new(0),
             segments_emitted: AtomicUsize::new(0),
+            finals_emitted: AtomicUsize::new(0),
             fallback_emitted: AtomicUsize::new(0),
+            repeated_finals: AtomicUsize::new(0),
             drain_ms: Mutex::new(StageWindow::default()),
             resample_ms: Mutex::new(StageWindow::default()),
             vad_ms: Mutex::new(StageWindow::default()),
@@ -77,19 +102,33 @@ impl Default for PipelineDiagnostics {
 impl PipelineDiagnostics {
     pub fn reset(&self) {
         self.frames_in.store(0, Ordering::Relaxed);
+        self.frames_dropped.store(0, Ordering::Relaxed);
+        self.ring_fill_pct.store(0, Ordering::Relaxed);
+        self.record_noise_floor(None);
         self.frames_resampled.store(0, Ordering::Relaxed);
         self.vad_windows.store(0, Ordering::Relaxed);
         self.vad_speech.store(0, Ordering::Relaxed);
         self.inference_calls.store(0, Ordering::Relaxed);
         self.inference_errors.store(0, Ordering::Relaxed);
         self.segments_emitted.store(0, Ordering::Relaxed);
+        self.finals_emitted.store(0, Ordering::Relaxed);
         self.fallback_emitted.store(0, Ordering::Relaxed);
+        self.repeated_finals.store(0, Ordering::Relaxed);
         self.drain_ms.lock().clear();
         self.resample_ms.lock().clear();
         self.vad_ms.lock().clear();
         self.inference_ms.lock().clear();
     }
 
+    pub fn record_ring_fill(&self, pct: f32) {
+        self.ring_fill_pct.store(pct.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn record_noise_floor(&self, floor: Option<f32>) {
+        let bits = floor.unwrap_or(f32::NAN).to_bits();
+        self.vad_noise_floor.store(bits, Ordering::Relaxed);
+    }
+
     pub fn record_drain(&self, elapsed_ms: f64) {
         self.drain_ms.lock().record(elapsed_ms);
     }
@@ -109,13 +148,19 @@ impl PipelineDiagnostics {
     pub fn snapshot(&self) -> DiagnosticsSnapshot {
         DiagnosticsSnapshot {
             frames_in: self.frames_in.load(Ordering::Relaxed),
+            frames_dropped: self.frames_dropped.load(Ordering::Relaxed),
+            ring_fill_pct: f32::from_bits(self.ring_fill_pct.load(Ordering::Relaxed)),
+            vad_noise_floor: Some(f32::from_bits(self.vad_noise_floor.load(Ordering::Relaxed)))
+                .filter(|floor| !floor.is_nan()),
             frames_resampled: self.frames_resampled.load(Ordering::Relaxed),
             vad_windows: self.vad_windows.load(Ordering::Relaxed),
             vad_speech: self.vad_speech.load(Ordering::Relaxed),
             inference_calls: self.inference_calls.load(Ordering::Relaxed),
             inference_errors: self.inference_errors.load(Ordering::Relaxed),
             segments_emitted: self.segments_emitted.load(Ordering::Relaxed),
+            finals_emitted: self.finals_emitted.load(Ordering::Relaxed),
             fallback_emitted: self.fallback_emitted.load(Ordering::Relaxed),
+            repeated_finals: self.repeated_finals.load(Ordering::Relaxed),
             drain_ms: self.drain_ms.lock().snapshot(),
             resample_ms: self.resample_ms.lock().snapshot(),
             vad_ms: self.vad_ms.lock().snapshot(),
@@ -127,19 +172,35 @@ impl PipelineDiagnostics {
 #[derive(Debug, Clone, Copy)]
 pub struct DiagnosticsSnapshot {
     pub frames_in: usize,
+    pub frames_dropped: usize,
+    /// Ring occupancy in percent, sampled before each drain.
+    pub ring_fill_pct: f32,
+    /// Background noise RMS the VAD currently gates against, if it tracks
+    /// one (`EnergyGate::NoiseFloor`).
+    pub vad_noise_floor: Option<f32>,
     pub frames_resampled: usize,
     pub vad_windows: usize,
     pub vad_speech: usize,
     pub inference_calls: usize,
     pub inference_errors: usize,
     pub segments_emitted: usize,
+    pub finals_emitted: usize,
     pub fallback_emitted: usize,
+    pub repeated_finals: usize,
     pub drain_ms: StageTimingSnapshot,
     pub resample_ms: StageTimingSnapshot,
     pub vad_ms: StageTimingSnapshot,
     pub inference_ms: StageTimingSnapshot,
 }
 
+/// Upper bounds (inclusive) of the cumulative stage latency histogram.
+/// Observations above the last bound are only counted in `count`.
+pub const LATENCY_BUCKETS_MS: [f64; 12] = [
+    0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1_000.0, 2_500.0,
+];
+
+/// Percentiles cover the most recent 512 observations; `count`, `sum_ms` and
+/// `buckets` are cumulative since the last reset.
 #[derive(Debug, Clone, Copy)]
 pub struct StageTimingSnapshot {
     pub count: usize,
@@ -148,6 +209,9 @@ pub struct StageTimingSnapshot {
     pub p95_ms: f64,
     pub p99_ms: f64,
     pub max_ms: f64,
+    pub sum_ms: f64,
+    /// Observations `<=` each bound in [`LATENCY_BUCKETS_MS`] (cumulative).
+    pub buckets: [usize; LATENCY_BUCKETS_MS.len()],
 }
 
 #[derive(Debug)]
@@ -157,6 +221,8 @@ struct StageWindow {
     count: usize,
     sum_ms: f64,
     max_ms: f64,
+    /// Per-bucket (non-cumulative) counts for [`LATENCY_BUCKETS_MS`].
+    buckets: [usize; LATENCY_BUCKETS_MS.len()],
 }
 
 impl Default for StageWindow {
@@ -167,6 +233,7 @@ impl Default for StageWindow {
             count: 0,
             sum_ms: 0.0,
             max_ms: 0.0,
+            buckets: [0; LATENCY_BUCKETS_MS.len()],
         }
     }
 }
@@ -177,6 +244,7 @@ impl StageWindow {
         self.count = 0;
         self.sum_ms = 0.0;
         self.max_ms = 0.0;
+        self.buckets = [0; LATENCY_BUCKETS_MS.len()];
     }
 
     fn record(&mut self, elapsed_ms: f64) {
@@ -194,6 +262,9 @@ impl StageWindow {
         if value > self.max_ms {
             self.max_ms = value;
         }
+        if let Some(bucket) = LATENCY_BUCKETS_MS.iter().position(|&le| value <= le) {
+            self.buckets[bucket] = self.buckets[bucket].saturating_add(1);
+        }
     }
 
     fn snapshot(&self) -> StageTimingSnapshot {
@@ -205,6 +276,8 @@ impl StageWindow {
                 p95_ms: 0.0,
                 p99_ms: 0.0,
                 max_ms: 0.0,
+                sum_ms: 0.0,
+                buckets: [0; LATENCY_BUCKETS_MS.len()],
             };
         }
 
@@ -220,6 +293,11 @@ impl StageWindow {
             sorted[idx.min(n - 1)]
         };
 
+        let mut buckets = self.buckets;
+        for i in 1..buckets.len() {
+            buckets[i] = buckets[i].saturating_add(buckets[i - 1]);
+        }
+
         StageTimingSnapshot {
             count: self.count,
             mean_ms: if self.count == 0 {
@@ -231,6 +309,8 @@ impl StageWindow {
             p95_ms: percentile(0.95),
             p99_ms: percentile(0.99),
             max_ms: self.max_ms,
+            sum_ms: self.sum_ms,
+            buckets,
         }
     }
 }
@@ -240,15 +320,31 @@ pub struct PipelineContext {
     pub config: EngineConfig,
     pub model: ModelHandle,
     pub vad: Box<dyn VoiceActivityDetector>,
+    /// The energy threshold the VAD reads (`VadThresholds::energy`), also
+    /// used for input gain and RMS activity so they follow its live changes
+    /// rather than `config.vad_threshold` as it was at start.
+    pub vad_threshold: LiveThreshold,
     pub consumer: AudioConsumer,
     pub running: Arc<AtomicBool>,
+    /// Every transcript event, partial and final.
     pub transcript_tx: broadcast::Sender<TranscriptEvent>,
+    /// Events whose segments are all partials.
+    pub partial_tx: broadcast::Sender<TranscriptEvent>,
+    /// Final-only events, including fallback placeholders.
+    pub final_tx: broadcast::Sender<TranscriptEvent>,
+    /// Loss-free copy of `final_tx` under `BackpressurePolicy::GuaranteedFinals`.
+    pub final_fanout: FinalFanout,
     pub status_tx: broadcast::Sender<EngineStatusEvent>,
     pub activity_tx: broadcast::Sender<AudioActivityEvent>,
+    /// Utterance start/end, alongside the per-chunk activity events.
+    pub boundary_tx: broadcast::Sender<UtteranceBoundaryEvent>,
     pub status: Arc<Mutex<EngineStatus>>,
     pub seq: Arc<AtomicU64>,
     pub capture_sample_rate: u32,
     pub diagnostics: Arc<PipelineDiagnostics>,
+    /// Set by `DictumEngine::reset_session`; the loop discards everything
+    /// accumulated so far and clears the flag.
+    pub reset_requested: Arc<AtomicBool>,
 }
 
 /// Chunk size drained from the ring buffer per iteration.
@@ -265,6 +361,37 @@ const PARTIAL_MIN_INTERVAL_MS: u64 = 500;
 const PARTIAL_MIN_NEW_SAMPLES: usize = 8_000;
 const MAX_FLUSH_RETRY_TAIL_SECONDS: usize = 12;
 const MAX_FLUSH_CONTINUATION_OVERLAP_MS: usize = 1_600;
+/// Finals considered when judging whether the model is broken.
+const DEGRADED_MODEL_WINDOW_FINALS: usize = 6;
+/// Fallback share of the window that raises the degraded-model warning.
+const DEGRADED_MODEL_FALLBACK_RATE: f32 = 0.8;
+/// Fallback share of the window at which the warning is cleared again.
+const RECOVERED_MODEL_FALLBACK_RATE: f32 = 0.34;
+/// Identical consecutive finals taken as a stuck pipeline (stale buffer or
+/// a model replaying its prompt) rather than a speaker repeating themselves.
+pub const REPEATED_FINAL_LIMIT: usize = 4;
+/// Finals shorter than this many words never count as repeats: "yes",
+/// "next" or "new line" said over and over is ordinary dictation.
+const REPEATED_FINAL_MIN_WORDS: usize = 4;
+/// `EngineStatusEvent::detail` sent while the input stream is all zeros.
+pub const MIC_MUTED_DETAIL: &str =
+    "Microphone appears muted: input is silent. Check the hardware mute switch or OS privacy settings.";
+
+/// Send an utterance boundary to subscribers, if there are any.
+fn send_boundary(ctx: &PipelineContext, utterance_id: &str, kind: BoundaryKind, at_sample: u64) {
+    let _ = ctx.boundary_tx.send(UtteranceBoundaryEvent {
+        utterance_id: utterance_id.to_owned(),
+        kind,
+        at_sample,
+    });
+}
+
+/// Clear the open utterance id, sending its `End` boundary.
+fn close_utterance(ctx: &PipelineContext, active: &mut Option<String>, at_sample: u64) {
+    if let Some(uid) = active.take() {
+        send_boundary(ctx, &uid, BoundaryKind::End, at_sample);
+    }
+}
 
 /// Run the blocking pipeline until `ctx.running` becomes false.
 pub fn run(mut ctx: PipelineContext) {
@@ -290,33 +417,90 @@ pub fn run(mut ctx: PipelineContext) {
         );
     }
 
+    // Stateful AGC when selected; otherwise each chunk is boosted on its own.
+    let mut agc = match ctx.config.input_gain {
+        InputGainMode::Agc(config) => Some(AutomaticGainControl::new(
+            config,
+            ctx.config.target_sample_rate,
+        )),
+        InputGainMode::AdaptiveBoost => None,
+    };
+
     // Temporary scratch buffer (stack allocation, reused each iteration)
     let mut raw = vec![0f32; DRAIN_CHUNK];
     // Accumulated speech samples awaiting inference
-    let mut speech_buf: Vec<f32> = Vec::with_capacity(ctx.config.max_speech_samples);
+    let max_speech_samples = ctx.config.effective_max_speech_samples();
+    let mut speech_buf: Vec<f32> = Vec::with_capacity(max_speech_samples);
     // Rolling audio window (up to max_speech_samples), used as a stop-time
     // rescue inference source when VAD fails to mark speech.
-    let mut recent_audio_buf: Vec<f32> = Vec::with_capacity(ctx.config.max_speech_samples);
+    let mut recent_audio_buf: Vec<f32> = Vec::with_capacity(max_speech_samples);
     // Whether we were in speech on the previous frame
     let mut was_speech = false;
     // Stable utterance ID for the current speech region.
     let mut active_utterance_id: Option<String> = None;
     let mut next_utterance_id = 0u64;
+    // `TranscriptMeta::revision` of the last event for `active_utterance_id`.
+    let mut utterance_revision = 0u32;
     // Independent sequence for activity events.
     let mut activity_seq = 0u64;
+    // Last meter level sent, for `activity_level` decay.
+    let mut meter_level = 0.0f32;
     // Utterance span for tracing
     let mut utterance_span: Option<Span> = None;
-    // Consecutive final inference calls that produced empty output.
-    let mut empty_final_streak = 0usize;
+    // Empty-final streak and windowed fallback rate.
+    let mut final_health = FinalHealth::new();
+    // Latest partial, promoted if its utterance's final decode fails.
+    let mut last_partial: Option<Box<SalvagedPartial>> = None;
     // Count of final outputs (real or fallback) emitted in this session.
     let mut final_output_count = 0usize;
     // Samples with elevated RMS, independent of VAD decisions.
     let mut rms_active_samples = 0usize;
     // Partial inference throttling for long speech regions.
-    let mut last_partial_infer_at: Option<Instant> = None;
-    let mut last_partial_infer_samples = 0usize;
+    let mut partial_mark = PartialMark::default();
+    // Whether a partial has been emitted for `active_utterance_id` yet. The
+    // first one skips the throttle so the UI reacts at speech onset.
+    let mut partial_fired_for_utterance = false;
     // Speech accumulated since the last successful final emission.
     let mut new_speech_samples_since_final = 0usize;
+    // Target-rate samples seen since capture start, and the stream offset
+    // at which `speech_buf` ends. `speech_buf` is always a contiguous run of
+    // the stream, so its start is `speech_buf_end - speech_buf.len()`.
+    let mut stream_samples = 0u64;
+    let mut speech_buf_end = 0u64;
+    // Stream offset until which speech onsets are ignored after a final.
+    let post_final_cooldown_samples =
+        ctx.config.post_final_cooldown_ms * u64::from(ctx.config.target_sample_rate) / 1000;
+    let mut cooldown_until_sample = 0u64;
+    // Short finals borrow preceding audio up to this length, never reaching
+    // back into the previous utterance.
+    let short_utterance_samples = (ctx.config.short_utterance_pad_ms
+        * u64::from(ctx.config.target_sample_rate)
+        / 1000) as usize;
+    let mut preroll_floor_sample = 0u64;
+    // The last final, while the silence after it decides its punctuation.
+    let mut pause_hold = PauseHold::default();
+    // Sustained all-zero input, when enabled.
+    let mut mute_detector = (ctx.config.mute_detect_ms > 0)
+        .then(|| MuteDetector::new(ctx.config.mute_detect_ms, ctx.capture_sample_rate));
+    // Previous final's text for decoder conditioning, when enabled.
+    let mut carried = ctx
+        .config
+        .context_carry
+        .map(|carry| CarriedContext::new(carry, ctx.config.target_sample_rate));
+    // Per-utterance audio dumps before and after resampling, when enabled.
+    let mut rate_tap = ctx
+        .config
+        .debug_audio
+        .clone()
+        .map(|tap| RateTap::new(tap, ctx.capture_sample_rate, ctx.config.target_sample_rate));
+    // Idle-time dummy inference, when enabled.
+    let mut keep_warm = ctx.config.keep_warm.then(|| {
+        KeepWarmTimer::new(
+            Duration::from_millis(ctx.config.keep_warm_interval_ms),
+            ctx.diagnostics.inference_calls.load(Ordering::Relaxed),
+            Instant::now(),
+        )
+    });
 
     loop {
         // ── 0. Check running flag ─────────────────────────────────────────
@@ -324,7 +508,47 @@ pub fn run(mut ctx: PipelineContext) {
             break;
         }
 
+        // ── 0b. Discard the session on request ────────────────────────────
+        if ctx.reset_requested.swap(false, Ordering::AcqRel) {
+            // Audio still queued in the ring belongs to the discarded session,
+            // but still advances the stream offset.
+            let mut discarded = 0u64;
+            loop {
+                let popped = ctx.consumer.pop_slice(&mut raw);
+                if popped == 0 {
+                    break;
+                }
+                discarded += popped as u64;
+            }
+            stream_samples += discarded * u64::from(ctx.config.target_sample_rate)
+                / u64::from(ctx.capture_sample_rate.max(1));
+            pause_hold.release(&ctx, stream_samples, false);
+            info!(
+                utterance_id = ?active_utterance_id,
+                discarded_samples = speech_buf.len(),
+                "session reset — discarding buffered speech"
+            );
+            speech_buf.clear();
+            recent_audio_buf.clear();
+            ctx.vad.reset();
+            ctx.vad.warm();
+            ctx.model.0.lock().reset();
+            if let Some(carry) = carried.as_mut() {
+                carry.clear();
+            }
+            was_speech = false;
+            close_utterance(&ctx, &mut active_utterance_id, speech_buf_end);
+            utterance_span = None;
+            rms_active_samples = 0;
+            partial_mark = PartialMark::default();
+            partial_fired_for_utterance = false;
+            new_speech_samples_since_final = 0;
+            continue;
+        }
+
         // ── 1. Drain ring buffer ──────────────────────────────────────────
+        ctx.diagnostics
+            .record_ring_fill(ring_fill_pct(&ctx.consumer));
         let drain_started = Instant::now();
         let n = ctx.consumer.pop_slice(&mut raw);
         ctx.diagnostics
@@ -337,26 +561,53 @@ pub fn run(mut ctx: PipelineContext) {
         }
 
         ctx.diagnostics.frames_in.fetch_add(n, Ordering::Relaxed);
+        if let Some(muted) = mute_detector.as_mut().and_then(|d| d.observe(&raw[..n])) {
+            report_mute_change(&ctx, muted);
+        }
 
         // ── 2. Resample to target rate ────────────────────────────────────
         let resample_started = Instant::now();
         let resampled = resampler.process(&raw[..n]);
         ctx.diagnostics
             .record_resample(resample_started.elapsed().as_secs_f64() * 1000.0);
+        if let Some(tap) = rate_tap.as_mut() {
+            tap.push_capture(&raw[..n]);
+            tap.push_target(&resampled);
+        }
         if resampled.is_empty() {
-            // Partial chunk — waiting for more data to fill rubato's input buffer
+            if resampler.pending_input() > 0 {
+                // Partial chunk — rubato is still filling its input block.
+                // Keep the meter moving with this input's level and hold the
+                // last VAD decision until the block is classified.
+                let rms = compute_rms(&raw[..n]);
+                let activity = AudioActivityEvent {
+                    seq: advance_seq(&mut activity_seq),
+                    rms,
+                    is_speech: was_speech,
+                    level: advance_meter(ctx.config.activity_level, rms, &mut meter_level),
+                    bins: ctx
+                        .config
+                        .activity_bins
+                        .map(|bins| bins.compute(&raw[..n], ctx.capture_sample_rate)),
+                };
+                let _ = ctx.activity_tx.send(activity);
+            }
             continue;
         }
         ctx.diagnostics
             .frames_resampled
             .fetch_add(resampled.len(), Ordering::Relaxed);
         let mut chunk = AudioChunk::new(resampled, ctx.config.target_sample_rate);
-        apply_adaptive_input_gain(&mut chunk.samples, ctx.config.vad_threshold);
-        append_rolling_samples(
-            &mut recent_audio_buf,
-            &chunk.samples,
-            ctx.config.max_speech_samples,
-        );
+        stream_samples += chunk.samples.len() as u64;
+        match agc.as_mut() {
+            Some(agc) => agc.process(&mut chunk.samples),
+            None => apply_adaptive_input_gain(
+                &mut chunk.samples,
+                ctx.vad_threshold.get(),
+                &ctx.config.gain_limits,
+            ),
+        }
+        append_rolling_samples(&mut recent_audio_buf, &chunk.samples, max_speech_samples);
 
         debug!(
             raw = n,
@@ -367,23 +618,36 @@ pub fn run(mut ctx: PipelineContext) {
         // ── 3. VAD ───────────────────────────────────────────────────────
         ctx.diagnostics.vad_windows.fetch_add(1, Ordering::Relaxed);
         let rms = compute_rms(&chunk.samples);
-        if rms >= ctx.config.vad_threshold {
+        if rms >= ctx.vad_threshold.get() {
             rms_active_samples = rms_active_samples.saturating_add(chunk.samples.len());
         }
-        let vad_started = Instant::now();
-        let decision = ctx.vad.classify(&chunk);
-        ctx.diagnostics
-            .record_vad(vad_started.elapsed().as_secs_f64() * 1000.0);
+        let decision = match ctx.config.capture_mode {
+            // The key is held: quiet or hesitant speech is still speech.
+            CaptureMode::PushToTalk => VadDecision::Speech,
+            CaptureMode::VadGated => {
+                let vad_started = Instant::now();
+                // A borderline frame keeps the current state rather than flipping it.
+                let decision = ctx.vad.classify(&chunk).resolve(was_speech);
+                ctx.diagnostics
+                    .record_vad(vad_started.elapsed().as_secs_f64() * 1000.0);
+                ctx.diagnostics.record_noise_floor(ctx.vad.noise_floor());
+                decision
+            }
+        };
         let is_speech = matches!(decision, VadDecision::Speech);
         if is_speech {
             ctx.diagnostics.vad_speech.fetch_add(1, Ordering::Relaxed);
         }
         let activity = AudioActivityEvent {
-            seq: activity_seq,
+            seq: advance_seq(&mut activity_seq),
             rms,
             is_speech,
+            level: advance_meter(ctx.config.activity_level, rms, &mut meter_level),
+            bins: ctx
+                .config
+                .activity_bins
+                .map(|bins| bins.compute(&chunk.samples, chunk.sample_rate)),
         };
-        activity_seq = activity_seq.saturating_add(1);
         let _ = ctx.activity_tx.send(activity);
 
         // Log audio level periodically for diagnostics
@@ -397,19 +661,41 @@ pub fn run(mut ctx: PipelineContext) {
             );
         }
 
+        // A breath or lip click right after a final would otherwise open a
+        // new fragment; only onsets are suppressed, never ongoing speech.
+        let chunk_start_sample = stream_samples - chunk.samples.len() as u64;
+        let decision = if decision == VadDecision::Speech
+            && !was_speech
+            && chunk_start_sample < cooldown_until_sample
+        {
+            debug!(
+                chunk_start_sample,
+                cooldown_until_sample, "speech onset ignored during post-final cooldown"
+            );
+            VadDecision::Silence
+        } else {
+            decision
+        };
+
         match decision {
             VadDecision::Speech => {
+                if active_utterance_id.is_none() {
+                    pause_hold.release(&ctx, chunk_start_sample, true);
+                }
                 was_speech = true;
                 speech_buf.extend_from_slice(&chunk.samples);
+                speech_buf_end = stream_samples;
                 new_speech_samples_since_final =
                     new_speech_samples_since_final.saturating_add(chunk.samples.len());
 
                 if active_utterance_id.is_none() {
                     let uid = format!("utt-{}", next_utterance_id);
                     next_utterance_id += 1;
+                    send_boundary(&ctx, &uid, BoundaryKind::Start, chunk_start_sample);
                     active_utterance_id = Some(uid.clone());
-                    last_partial_infer_at = None;
-                    last_partial_infer_samples = 0;
+                    utterance_revision = 0;
+                    partial_mark = PartialMark::default();
+                    partial_fired_for_utterance = false;
                     let span = info_span!(
                         "utterance",
                         utterance_id = %uid,
@@ -424,21 +710,34 @@ pub fn run(mut ctx: PipelineContext) {
                     debug!(samples = speech_buf.len(), "speech accumulating");
                 }
 
-                if speech_buf.len() >= ctx.config.max_speech_samples {
+                if speech_buf.len() >= max_speech_samples {
                     warn!("max_speech_samples reached — forcing inference flush");
-                    let outcome = flush_inference(
+                    if let Some(tap) = rate_tap.as_mut() {
+                        tap.write_utterance(
+                            active_utterance_id.as_deref().unwrap_or("utt"),
+                            utterance_span_samples(&speech_buf, speech_buf_end, stream_samples),
+                        );
+                    }
+                    let mut outcome = flush_inference(
                         &mut ctx,
                         &speech_buf,
                         false,
                         active_utterance_id.as_deref(),
+                        Some(speech_buf_end),
+                        &mut utterance_revision,
+                        carried.as_mut(),
                     );
-                    let emitted_primary = matches!(&outcome, FlushOutcome::Emitted);
-                    if handle_final_flush_result(
+                    pause_hold.hold(&ctx, &mut outcome, stream_samples);
+                    let emitted_primary = matches!(&outcome, FlushOutcome::Emitted { .. });
+                    let finalized = handle_final_flush_result(
                         &mut ctx,
                         outcome,
                         active_utterance_id.as_deref(),
-                        &mut empty_final_streak,
-                    ) {
+                        &mut utterance_revision,
+                        &mut final_health,
+                        last_partial.as_deref(),
+                    );
+                    if finalized {
                         final_output_count = final_output_count.saturating_add(1);
                         new_speech_samples_since_final = 0;
                     }
@@ -447,18 +746,22 @@ pub fn run(mut ctx: PipelineContext) {
                             .saturating_mul(MAX_FLUSH_CONTINUATION_OVERLAP_MS)
                             / 1000;
                         retain_tail_samples(&mut speech_buf, continuation_overlap_samples.max(1));
-                        active_utterance_id = None;
+                        close_utterance(&ctx, &mut active_utterance_id, speech_buf_end);
                         utterance_span = None;
-                        last_partial_infer_at = Some(Instant::now());
-                        last_partial_infer_samples = 0;
+                        partial_mark.record(Instant::now(), 0);
                         was_speech = true;
                     } else {
                         let retry_tail_samples = (ctx.config.target_sample_rate as usize)
                             .saturating_mul(MAX_FLUSH_RETRY_TAIL_SECONDS)
                             .max(ctx.config.min_speech_samples);
                         retain_tail_samples(&mut speech_buf, retry_tail_samples);
-                        last_partial_infer_at = Some(Instant::now());
-                        last_partial_infer_samples = speech_buf.len();
+                        if finalized {
+                            // A fallback final closed this id; the retried
+                            // tail must not send partials for it.
+                            close_utterance(&ctx, &mut active_utterance_id, speech_buf_end);
+                            utterance_span = None;
+                        }
+                        partial_mark.retained(Instant::now(), speech_buf.len());
                         warn!(
                             retained_samples = speech_buf.len(),
                             retry_tail_samples,
@@ -469,64 +772,118 @@ pub fn run(mut ctx: PipelineContext) {
                     && speech_buf.len() >= ctx.config.min_speech_samples
                 {
                     let now = Instant::now();
-                    let enough_time = last_partial_infer_at
-                        .map(|t| {
-                            now.duration_since(t) >= Duration::from_millis(PARTIAL_MIN_INTERVAL_MS)
-                        })
-                        .unwrap_or(true);
-                    let new_samples = speech_buf.len().saturating_sub(last_partial_infer_samples);
-                    let partial_delta_threshold =
-                        PARTIAL_MIN_NEW_SAMPLES.min(ctx.config.min_speech_samples.max(1));
-                    if enough_time && new_samples >= partial_delta_threshold {
-                        flush_inference(
+                    if partial_due(
+                        !partial_fired_for_utterance,
+                        partial_mark.since(now),
+                        partial_mark.new_samples(speech_buf.len()),
+                        ctx.config.min_speech_samples,
+                    ) {
+                        if let FlushOutcome::Emitted {
+                            salvage: Some(salvage),
+                            ..
+                        } = flush_inference(
                             &mut ctx,
                             &speech_buf,
                             true,
                             active_utterance_id.as_deref(),
-                        );
-                        last_partial_infer_at = Some(now);
-                        last_partial_infer_samples = speech_buf.len();
+                            Some(speech_buf_end),
+                            &mut utterance_revision,
+                            carried.as_mut(),
+                        ) {
+                            last_partial = Some(salvage);
+                        }
+                        // Count attempts, not emissions: an empty first partial
+                        // must not retrigger on every chunk.
+                        partial_fired_for_utterance = true;
+                        partial_mark.record(now, speech_buf.len());
                     }
                 }
             }
 
-            VadDecision::Silence => {
+            // `Uncertain` was resolved to the current state above.
+            VadDecision::Silence | VadDecision::Uncertain => {
+                // Announced before the final decode, so subscribers can
+                // time how long the final takes.
+                let closing = active_utterance_id.take();
+                if let Some(uid) = &closing {
+                    send_boundary(&ctx, uid, BoundaryKind::End, speech_buf_end);
+                }
                 if was_speech && speech_buf.len() >= ctx.config.min_speech_samples {
                     debug!(
                         samples = speech_buf.len(),
                         "end of utterance — running final inference"
                     );
-                    let outcome = flush_inference(
-                        &mut ctx,
+                    let padded = pad_short_utterance(
                         &speech_buf,
+                        speech_buf_end,
+                        &recent_audio_buf,
+                        stream_samples,
+                        preroll_floor_sample,
+                        short_utterance_samples,
+                    );
+                    if let Some(padded) = &padded {
+                        debug!(
+                            speech_samples = speech_buf.len(),
+                            padded_samples = padded.len(),
+                            "short utterance — prepending pre-roll context"
+                        );
+                    }
+                    if let Some(tap) = rate_tap.as_mut() {
+                        tap.write_utterance(
+                            closing.as_deref().unwrap_or("utt"),
+                            utterance_span_samples(&speech_buf, speech_buf_end, stream_samples),
+                        );
+                    }
+                    let mut outcome = flush_inference(
+                        &mut ctx,
+                        padded.as_deref().unwrap_or(&speech_buf),
                         false,
-                        active_utterance_id.as_deref(),
+                        closing.as_deref(),
+                        Some(speech_buf_end),
+                        &mut utterance_revision,
+                        carried.as_mut(),
                     );
+                    pause_hold.hold(&ctx, &mut outcome, stream_samples);
                     if handle_final_flush_result(
                         &mut ctx,
                         outcome,
-                        active_utterance_id.as_deref(),
-                        &mut empty_final_streak,
+                        closing.as_deref(),
+                        &mut utterance_revision,
+                        &mut final_health,
+                        last_partial.as_deref(),
                     ) {
                         final_output_count = final_output_count.saturating_add(1);
                         new_speech_samples_since_final = 0;
+                        cooldown_until_sample = stream_samples + post_final_cooldown_samples;
                     }
                 }
                 if was_speech {
+                    preroll_floor_sample = speech_buf_end;
                     speech_buf.clear();
                     ctx.vad.reset();
+                    ctx.vad.warm();
                     ctx.model.0.lock().reset();
-                    active_utterance_id = None;
                     utterance_span = None;
-                    last_partial_infer_at = None;
-                    last_partial_infer_samples = 0;
+                    partial_mark = PartialMark::default();
+                    partial_fired_for_utterance = false;
                     new_speech_samples_since_final = 0;
+                } else if let Some(timer) = keep_warm.as_mut() {
+                    let inference_calls = ctx.diagnostics.inference_calls.load(Ordering::Relaxed);
+                    if timer.due(inference_calls, Instant::now()) {
+                        run_keep_warm(&ctx);
+                    }
                 }
                 was_speech = false;
+                pause_hold.release_if_sentence_over(&ctx, stream_samples);
             }
         }
     }
 
+    // Stopping mid-utterance still closes it.
+    if let Some(uid) = active_utterance_id.as_deref() {
+        send_boundary(&ctx, uid, BoundaryKind::End, speech_buf_end);
+    }
+
     // Force a terminal final flush on stop to avoid losing speech when the
     // user releases push-to-talk / toggles stop before silence is detected.
     if !speech_buf.is_empty() {
@@ -536,13 +893,37 @@ pub fn run(mut ctx: PipelineContext) {
                 buffered_samples = speech_buf.len(),
                 "stop requested with buffered speech — forcing final flush"
             );
-            let outcome =
-                flush_inference(&mut ctx, &speech_buf, false, active_utterance_id.as_deref());
+            let padded = pad_short_utterance(
+                &speech_buf,
+                speech_buf_end,
+                &recent_audio_buf,
+                stream_samples,
+                preroll_floor_sample,
+                short_utterance_samples,
+            );
+            if let Some(tap) = rate_tap.as_mut() {
+                tap.write_utterance(
+                    active_utterance_id.as_deref().unwrap_or("utt"),
+                    utterance_span_samples(&speech_buf, speech_buf_end, stream_samples),
+                );
+            }
+            let mut outcome = flush_inference(
+                &mut ctx,
+                padded.as_deref().unwrap_or(&speech_buf),
+                false,
+                active_utterance_id.as_deref(),
+                Some(speech_buf_end),
+                &mut utterance_revision,
+                carried.as_mut(),
+            );
+            pause_hold.hold(&ctx, &mut outcome, stream_samples);
             if handle_final_flush_result(
                 &mut ctx,
                 outcome,
                 active_utterance_id.as_deref(),
-                &mut empty_final_streak,
+                &mut utterance_revision,
+                &mut final_health,
+                last_partial.as_deref(),
             ) {
                 final_output_count = final_output_count.saturating_add(1);
             }
@@ -568,8 +949,17 @@ pub fn run(mut ctx: PipelineContext) {
             "no final output emitted despite sustained RMS activity — attempting rescue final inference"
         );
         if !recent_audio_buf.is_empty() {
-            let outcome = flush_inference(&mut ctx, &recent_audio_buf, false, None);
-            if handle_final_flush_result(&mut ctx, outcome, None, &mut empty_final_streak) {
+            let mut outcome = flush_inference(
+                &mut ctx,
+                &recent_audio_buf,
+                false,
+                None,
+                Some(stream_samples),
+                &mut 0,
+                None,
+            );
+            pause_hold.hold(&ctx, &mut outcome, stream_samples);
+            if handle_final_flush_result(&mut ctx, outcome, None, &mut 0, &mut final_health, None) {
                 final_output_count = final_output_count.saturating_add(1);
             }
         }
@@ -582,23 +972,49 @@ pub fn run(mut ctx: PipelineContext) {
             rms_fallback_threshold,
             "rescue inference produced no final output — forcing fallback segment"
         );
-        emit_fallback_event(&mut ctx, None);
+        emit_fallback_event(&mut ctx, None, &mut 0, None);
     }
+    pause_hold.release(&ctx, stream_samples, false);
 
     let snap = ctx.diagnostics.snapshot();
     info!(
         frames_in = snap.frames_in,
+        frames_dropped = snap.frames_dropped,
         frames_resampled = snap.frames_resampled,
         vad_windows = snap.vad_windows,
         vad_speech = snap.vad_speech,
         inference_calls = snap.inference_calls,
         inference_errors = snap.inference_errors,
         segments_emitted = snap.segments_emitted,
+        finals_emitted = snap.finals_emitted,
         fallback_emitted = snap.fallback_emitted,
         "pipeline stopped — diagnostics"
     );
 }
 
+/// Target-rate samples from the start of `speech_buf` to the stream head,
+/// i.e. the utterance plus the silence that ended it.
+fn utterance_span_samples(speech_buf: &[f32], speech_buf_end: u64, stream_samples: u64) -> usize {
+    let start = speech_buf_end.saturating_sub(speech_buf.len() as u64);
+    stream_samples.saturating_sub(start) as usize
+}
+
+/// Return `seq` and step it, wrapping at `u64::MAX` like the shared
+/// transcript counter's `fetch_add`.
+fn advance_seq(seq: &mut u64) -> u64 {
+    let current = *seq;
+    *seq = current.wrapping_add(1);
+    current
+}
+
+/// The meter level for a chunk at `rms`, updating `previous`; `None` when
+/// the engine sends raw levels only.
+fn advance_meter(meter: Option<ActivityLevel>, rms: f32, previous: &mut f32) -> Option<f32> {
+    let level = meter?.level(rms, *previous);
+    *previous = level;
+    Some(level)
+}
+
 fn empty_sleep_ms() -> u64 {
     static EMPTY_SLEEP_MS: OnceLock<u64> = OnceLock::new();
     *EMPTY_SLEEP_MS.get_or_init(|| {
@@ -610,19 +1026,261 @@ fn empty_sleep_ms() -> u64 {
     })
 }
 
-/// Run inference on `samples` and broadcast the result.
+/// When the last partial ran and how much speech was buffered then, for
+/// `partial_due`.
+#[derive(Debug, Default)]
+struct PartialMark {
+    at: Option<Instant>,
+    samples: usize,
+}
+
+impl PartialMark {
+    fn record(&mut self, now: Instant, buffered: usize) {
+        self.at = Some(now);
+        self.samples = buffered;
+    }
+
+    /// The speech buffer was cut down to `buffered` samples. The mark moves
+    /// down with it: left above the buffer, no audio would count as new
+    /// until the buffer regrew past its old length, stalling partials.
+    fn retained(&mut self, now: Instant, buffered: usize) {
+        self.at = Some(now);
+        self.samples = self.samples.min(buffered);
+    }
+
+    fn since(&self, now: Instant) -> Option<Duration> {
+        self.at.map(|at| now.duration_since(at))
+    }
+
+    fn new_samples(&self, buffered: usize) -> usize {
+        buffered.saturating_sub(self.samples)
+    }
+}
+
+/// Whether a partial inference should run now. Callers have already checked
+/// that at least `min_speech_samples` are buffered.
+///
+/// The first partial of an utterance always runs; later ones are throttled to
+/// one per `PARTIAL_MIN_INTERVAL_MS` and need enough new audio since the last.
+fn partial_due(
+    first_for_utterance: bool,
+    since_last_partial: Option<Duration>,
+    new_samples: usize,
+    min_speech_samples: usize,
+) -> bool {
+    if first_for_utterance {
+        return true;
+    }
+    let enough_time = since_last_partial
+        .map(|elapsed| elapsed >= Duration::from_millis(PARTIAL_MIN_INTERVAL_MS))
+        .unwrap_or(true);
+    let partial_delta_threshold = PARTIAL_MIN_NEW_SAMPLES.min(min_speech_samples.max(1));
+    enough_time && new_samples >= partial_delta_threshold
+}
+
+/// Decides when an idle model gets a keep-warm call. Any real inference
+/// restarts the interval, so keep-warm only fires after sustained silence.
+#[derive(Debug)]
+struct KeepWarmTimer {
+    interval: Duration,
+    inference_calls: usize,
+    last_activity: Instant,
+}
+
+impl KeepWarmTimer {
+    fn new(interval: Duration, inference_calls: usize, now: Instant) -> Self {
+        Self {
+            interval,
+            inference_calls,
+            last_activity: now,
+        }
+    }
+
+    fn due(&mut self, inference_calls: usize, now: Instant) -> bool {
+        if inference_calls != self.inference_calls {
+            self.inference_calls = inference_calls;
+            self.last_activity = now;
+            return false;
+        }
+        if now.saturating_duration_since(self.last_activity) < self.interval {
+            return false;
+        }
+        self.last_activity = now;
+        true
+    }
+}
+
+/// Watches raw capture for the all-zero stream a muted microphone produces.
+/// Real silence still carries noise, so only exact zeros count.
+#[derive(Debug)]
+struct MuteDetector {
+    window_samples: u64,
+    zero_run: u64,
+    muted: bool,
+}
+
+impl MuteDetector {
+    fn new(window_ms: u64, sample_rate: u32) -> Self {
+        Self {
+            window_samples: (window_ms * u64::from(sample_rate) / 1000).max(1),
+            zero_run: 0,
+            muted: false,
+        }
+    }
+
+    /// Feed raw capture samples. Returns the new state when it changes.
+    fn observe(&mut self, samples: &[f32]) -> Option<bool> {
+        match samples.iter().rposition(|&s| s != 0.0) {
+            Some(last_signal) => self.zero_run = (samples.len() - 1 - last_signal) as u64,
+            None => self.zero_run = self.zero_run.saturating_add(samples.len() as u64),
+        }
+        let muted = self.zero_run >= self.window_samples;
+        if muted == self.muted {
+            return None;
+        }
+        self.muted = muted;
+        Some(muted)
+    }
+}
+
+/// The previous final's text, carried into the next utterance's decode
+/// under `EngineConfig::context_carry`.
+#[derive(Debug)]
+struct CarriedContext {
+    max_chars: usize,
+    reset_after_samples: u64,
+    text: String,
+    /// Stream offset at which the utterance behind `text` ended.
+    ended_at_sample: u64,
+}
+
+impl CarriedContext {
+    fn new(config: ContextCarry, sample_rate: u32) -> Self {
+        Self {
+            max_chars: config.max_chars,
+            reset_after_samples: config.reset_after_silence_ms * u64::from(sample_rate) / 1000,
+            text: String::new(),
+            ended_at_sample: 0,
+        }
+    }
+
+    /// Context for audio starting at `start_sample`. Carried text older
+    /// than the reset window is dropped first.
+    fn prompt_for(&mut self, start_sample: Option<u64>) -> Option<&str> {
+        if let Some(start) = start_sample {
+            let silence = start.saturating_sub(self.ended_at_sample);
+            if !self.text.is_empty() && silence > self.reset_after_samples {
+                debug!(
+                    silence_samples = silence,
+                    "dropping carried context after silence"
+                );
+                self.text.clear();
+            }
+        }
+        (!self.text.is_empty()).then_some(self.text.as_str())
+    }
+
+    /// Carry the end of `text`, cut to `max_chars` at a word boundary.
+    fn record(&mut self, text: &str, end_sample: Option<u64>) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let skip = text.chars().count().saturating_sub(self.max_chars);
+        let tail = match text.char_indices().nth(skip) {
+            Some((at, _)) if skip > 0 => {
+                let tail = &text[at..];
+                if text[..at].ends_with(char::is_whitespace) {
+                    tail
+                } else {
+                    // Drop the partial first word unless it is all there is.
+                    tail.find(char::is_whitespace)
+                        .map_or(tail, |space| tail[space..].trim_start())
+                }
+            }
+            _ => text,
+        };
+        self.text = tail.to_string();
+        if let Some(end) = end_sample {
+            self.ended_at_sample = end;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.text.clear();
+    }
+}
+
+fn report_mute_change(ctx: &PipelineContext, muted: bool) {
+    if muted {
+        warn!("input stream is all zeros — microphone appears muted");
+    } else {
+        info!("input signal returned after mute");
+    }
+    let _ = ctx.status_tx.send(EngineStatusEvent {
+        status: EngineStatus::Listening,
+        detail: muted.then(|| MIC_MUTED_DETAIL.to_string()),
+    });
+}
+
+/// Dummy inference during silence. Produces no events; failures are only
+/// logged since the next real utterance will surface a broken model anyway.
+fn run_keep_warm(ctx: &PipelineContext) {
+    let started = Instant::now();
+    match ctx.model.0.lock().keep_warm() {
+        Ok(()) => debug!(
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "keep-warm inference"
+        ),
+        Err(e) => warn!(error = %e, "keep-warm inference failed"),
+    }
+}
+
 enum FlushOutcome {
-    Emitted,
-    Empty,
+    /// Something was broadcast: for a partial, what it said; for a final,
+    /// its text.
+    Emitted {
+        salvage: Option<Box<SalvagedPartial>>,
+        final_text: Option<String>,
+        /// A final not broadcast yet: under pause punctuation it waits in
+        /// [`PauseHold`] for the silence after it.
+        held: Option<Box<TranscriptEvent>>,
+    },
+    /// Nothing to emit; why, when the model said.
+    Empty(Option<EmptyReason>),
     Error,
+    /// A session reset arrived before or during the decode; whatever it
+    /// produced belongs to discarded audio and was dropped.
+    Cancelled,
+}
+
+/// The last partial emitted for an utterance. If that utterance's final
+/// decode then fails, this text is promoted to the final instead of the
+/// `[speech captured]` placeholder.
+struct SalvagedPartial {
+    utterance_id: String,
+    segments: Vec<TranscriptSegment>,
+    meta: TranscriptMeta,
 }
 
+/// Run inference on `samples` and broadcast the result. `revision` is the
+/// utterance's update counter; it is bumped and stamped on the event when
+/// something is emitted. With `carry`, the model is prompted with the
+/// previous final and an emitted final becomes the next prompt. Under
+/// pause punctuation a final is returned in `FlushOutcome::Emitted::held`
+/// instead of broadcast.
 fn flush_inference(
     ctx: &mut PipelineContext,
     samples: &[f32],
     partial: bool,
     utterance_id: Option<&str>,
+    audio_end_sample: Option<u64>,
+    revision: &mut u32,
+    mut carry: Option<&mut CarriedContext>,
 ) -> FlushOutcome {
+    if ctx.reset_requested.load(Ordering::Acquire) {
+        return FlushOutcome::Cancelled;
+    }
     ctx.diagnostics
         .inference_calls
         .fetch_add(1, Ordering::Relaxed);
@@ -630,14 +1288,35 @@ fn flush_inference(
     let samples_len = samples.len();
     let chunk = AudioChunk::new(samples.to_vec(), ctx.config.target_sample_rate);
 
-    let mut segments = {
+    let (mut segments, mut meta, latency) = {
         let mut model = ctx.model.0.lock();
+        if let Some(carry) = carry.as_deref_mut() {
+            let start_sample = audio_end_sample.map(|end| end.saturating_sub(samples_len as u64));
+            model.set_prompt_context(carry.prompt_for(start_sample));
+        }
         let inference_started = Instant::now();
-        let result = model.transcribe(&chunk, partial);
+        // While running, `stop()` cuts the decode short. The terminal flush
+        // runs after the flag is cleared and decodes in full.
+        let result = if ctx.running.load(Ordering::Acquire) {
+            model.transcribe_cancellable(&chunk, partial, Arc::clone(&ctx.running))
+        } else {
+            model.transcribe(&chunk, partial)
+        };
+        let latency = inference_started.elapsed();
         ctx.diagnostics
-            .record_inference(inference_started.elapsed().as_secs_f64() * 1000.0);
+            .record_inference(latency.as_secs_f64() * 1000.0);
+        // The model may have stopped early (`set_cancel_flag`) or finished
+        // regardless; either way the text is for audio the reset discarded.
+        if ctx.reset_requested.load(Ordering::Acquire) {
+            info!(
+                utterance_id = ?utterance_id,
+                partial,
+                "session reset during inference — dropping result"
+            );
+            return FlushOutcome::Cancelled;
+        }
         match result {
-            Ok(segs) => segs,
+            Ok(segs) => (segs, model.last_transcript_meta(), latency),
             Err(e) => {
                 ctx.diagnostics
                     .inference_errors
@@ -653,19 +1332,22 @@ fn flush_inference(
             utterance_id = ?utterance_id,
             samples = samples_len,
             partial,
+            reason = ?meta.empty_reason,
             "inference returned empty segments — model may not be loaded or audio too short"
         );
-        return FlushOutcome::Empty;
+        return FlushOutcome::Empty(meta.empty_reason);
     }
 
+    if ctx.config.segment_handling == SegmentHandling::Merge {
+        merge_segments(&mut segments);
+    }
     ctx.diagnostics
         .segments_emitted
         .fetch_add(segments.len(), Ordering::Relaxed);
-
-    if let Some(utterance_id) = utterance_id {
-        for segment in &mut segments {
-            segment.id = utterance_id.to_string();
-        }
+    if !partial {
+        ctx.diagnostics
+            .finals_emitted
+            .fetch_add(1, Ordering::Relaxed);
     }
 
     let text_preview: String = segments
@@ -674,118 +1356,700 @@ fn flush_inference(
         .collect::<Vec<_>>()
         .join(" | ");
 
+    meta.latency_ms = Some(latency.as_millis().min(u32::MAX as u128) as u32);
+    meta.audio_ms = Some(samples_ms(samples_len, ctx.config.target_sample_rate));
+    meta.snr_db = meta
+        .snr_db
+        .or_else(|| estimate_snr_db(samples, ctx.config.target_sample_rate));
+    meta.confidence = meta.confidence.or_else(|| mean_confidence(&segments));
+    meta.is_fallback = Some(false);
+    if !partial && ctx.config.audio_fingerprint {
+        meta.audio_fingerprint = AudioFingerprint::compute(samples, ctx.config.target_sample_rate);
+    }
+    if let (false, Some(end)) = (partial, audio_end_sample) {
+        meta.audio_start_sample = Some(end.saturating_sub(samples_len as u64));
+        meta.audio_end_sample = Some(end);
+    }
+    if !partial {
+        mark_superseded_partials(&mut meta, utterance_id, *revision);
+    }
+    *revision = revision.saturating_add(1);
+    meta.revision = Some(*revision);
+
     let seq = ctx.seq.fetch_add(1, Ordering::Relaxed);
-    let event = TranscriptEvent { seq, segments };
+    // Models leave ids empty; the pipeline is the only place they are set.
+    let segment_id = utterance_id
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| format!("rescue-{seq}"));
+    for segment in &mut segments {
+        segment.id = segment_id.clone();
+    }
+    let salvage = match (partial, utterance_id) {
+        (true, Some(id)) => Some(Box::new(SalvagedPartial {
+            utterance_id: id.to_owned(),
+            segments: segments.clone(),
+            meta: meta.clone(),
+        })),
+        _ => None,
+    };
+    let final_text = (!partial).then(|| {
+        segments
+            .iter()
+            .map(|s| s.text.trim())
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    if let (Some(text), Some(carry)) = (&final_text, carry) {
+        carry.record(text, audio_end_sample);
+    }
+    let event = TranscriptEvent {
+        seq,
+        segments,
+        meta,
+    };
 
-    let emit_result = ctx.transcript_tx.send(event);
+    if !partial && ctx.config.pause_punctuation.is_some() {
+        info!(
+            utterance_id = ?utterance_id,
+            samples = samples_len,
+            text_preview = %text_preview,
+            "final held for pause punctuation"
+        );
+        return FlushOutcome::Emitted {
+            salvage,
+            final_text,
+            held: Some(Box::new(event)),
+        };
+    }
+    let emit_success = broadcast_transcript(ctx, event);
     info!(
         utterance_id = ?utterance_id,
         samples = samples_len,
         partial,
         text_preview = %text_preview,
-        emit_success = emit_result.is_ok(),
+        emit_success,
         "transcript emitted"
     );
-    FlushOutcome::Emitted
+    FlushOutcome::Emitted {
+        salvage,
+        final_text,
+        held: None,
+    }
 }
 
-fn handle_final_flush_result(
-    ctx: &mut PipelineContext,
-    outcome: FlushOutcome,
-    utterance_id: Option<&str>,
-    empty_final_streak: &mut usize,
-) -> bool {
-    match outcome {
-        FlushOutcome::Emitted => {
-            if *empty_final_streak > 0 {
-                *empty_final_streak = 0;
-                let _ = ctx.status_tx.send(EngineStatusEvent {
-                    status: EngineStatus::Listening,
-                    detail: None,
-                });
-            }
-            true
-        }
-        FlushOutcome::Empty => {
-            *empty_final_streak = empty_final_streak.saturating_add(1);
-            warn!(
-                utterance_id = ?utterance_id,
-                empty_final_streak = *empty_final_streak,
-                "final inference produced empty output"
-            );
-
-            if *empty_final_streak >= EMPTY_FINAL_STREAK_FOR_FALLBACK {
-                emit_fallback_event(ctx, utterance_id);
-                let _ = ctx.status_tx.send(EngineStatusEvent {
-                    status: EngineStatus::Listening,
-                    detail: Some(
-                        "Transcription degraded: speech detected but model returned empty output; using fallback."
-                            .into(),
-                    ),
-                });
-                true
-            } else {
-                false
-            }
-        }
-        FlushOutcome::Error => {
-            emit_fallback_event(ctx, utterance_id);
-            let _ = ctx.status_tx.send(EngineStatusEvent {
-                status: EngineStatus::Listening,
-                detail: Some("Transcription error: inference failed during finalization.".into()),
-            });
-            true
-        }
+/// Collapse `segments` into one for `SegmentHandling::Merge`. The merged
+/// segment is final if any input was.
+fn merge_segments(segments: &mut Vec<TranscriptSegment>) {
+    if segments.len() < 2 {
+        return;
     }
+    let text = segments
+        .iter()
+        .map(|s| s.text.trim())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let kind = if segments.iter().any(|s| s.kind == SegmentKind::Final) {
+        SegmentKind::Final
+    } else {
+        SegmentKind::Partial
+    };
+    let confidence = mean_confidence(segments);
+    let start_ms = segments.first().and_then(|s| s.start_ms);
+    let end_ms = segments.last().and_then(|s| s.end_ms);
+    let word_timings = segments
+        .iter_mut()
+        .flat_map(|s| std::mem::take(&mut s.word_timings))
+        .collect();
+    let mut merged = segments.swap_remove(0);
+    segments.clear();
+    merged.text = text;
+    merged.kind = kind;
+    merged.confidence = confidence;
+    merged.start_ms = start_ms;
+    merged.end_ms = end_ms;
+    merged.word_timings = word_timings;
+    segments.push(merged);
 }
 
-fn emit_fallback_event(ctx: &mut PipelineContext, utterance_id: Option<&str>) {
-    let seq = ctx.seq.fetch_add(1, Ordering::Relaxed);
-    let fallback_id = utterance_id
-        .map(ToOwned::to_owned)
-        .unwrap_or_else(|| format!("fallback-{seq}"));
-    let event = TranscriptEvent {
-        seq,
-        segments: vec![TranscriptSegment {
-            id: fallback_id,
-            text: FALLBACK_TEXT.to_string(),
-            kind: SegmentKind::Final,
-            confidence: None,
-        }],
-    };
-    let emitted = ctx.transcript_tx.send(event).is_ok();
-    if emitted {
-        ctx.diagnostics
-            .segments_emitted
-            .fetch_add(1, Ordering::Relaxed);
-        ctx.diagnostics
-            .fallback_emitted
-            .fetch_add(1, Ordering::Relaxed);
+/// Punctuation for a pause of `gap_ms` after a final.
+fn pause_mark(pause: PausePunctuation, gap_ms: u32) -> Option<char> {
+    if gap_ms >= pause.period_after_ms {
+        Some('.')
+    } else if gap_ms >= pause.comma_after_ms {
+        Some(',')
+    } else {
+        None
     }
-    warn!(
-        utterance_id = ?utterance_id,
-        emitted,
-        fallback_text = FALLBACK_TEXT,
-        "emitted fallback transcript segment"
-    );
 }
 
-fn compute_rms(samples: &[f32]) -> f32 {
-    if samples.is_empty() {
-        return 0.0;
-    }
-    let sum_sq = samples.iter().map(|s| s * s).sum::<f32>();
-    (sum_sq / samples.len() as f32).sqrt()
+/// Finals held back under `EngineConfig::pause_punctuation` until the
+/// silence after them is measured, then sent ending in the mark it calls
+/// for: a comma when speech resumes after a short pause, a period once the
+/// pause is long enough.
+#[derive(Default)]
+struct PauseHold {
+    held: Option<TranscriptEvent>,
+    /// The last final sent ended a sentence, so the next one starts one.
+    sentence_ended: bool,
 }
 
-fn apply_adaptive_input_gain(samples: &mut [f32], vad_threshold: f32) {
-    if samples.is_empty() {
-        return;
-    }
-    let rms = compute_rms(samples);
-    if rms <= 3e-5 {
-        return;
+impl PauseHold {
+    /// Take the final `outcome` holds back, sending any earlier one first.
+    fn hold(&mut self, ctx: &PipelineContext, outcome: &mut FlushOutcome, now_sample: u64) {
+        if let FlushOutcome::Emitted { held, .. } = outcome {
+            if let Some(event) = held.take() {
+                self.release(ctx, now_sample, false);
+                self.held = Some(*event);
+            }
+        }
     }
+
+    /// Send the held final once the pause after it, up to `now_sample`,
+    /// is long enough for a period.
+    fn release_if_sentence_over(&mut self, ctx: &PipelineContext, now_sample: u64) {
+        let Some(pause) = ctx.config.pause_punctuation else {
+            return;
+        };
+        if self.pause_ms(ctx, now_sample) >= Some(pause.period_after_ms) {
+            self.release(ctx, now_sample, false);
+        }
+    }
+
+    /// Send the held final, punctuated by the pause from its end to
+    /// `now_sample`. A comma only goes between speech, so it needs
+    /// `speech_resumed`.
+    fn release(&mut self, ctx: &PipelineContext, now_sample: u64, speech_resumed: bool) {
+        let Some(pause_ms) = self.pause_ms(ctx, now_sample) else {
+            return;
+        };
+        let (Some(pause), Some(mut event)) = (ctx.config.pause_punctuation, self.held.take())
+        else {
+            return;
+        };
+        let mark = match pause_mark(pause, pause_ms) {
+            Some(',') if !speech_resumed => None,
+            mark => mark,
+        };
+        self.sentence_ended = punctuate_final(&mut event.segments, mark, self.sentence_ended);
+        debug!(pause_ms, mark = ?mark, "sending final held for pause punctuation");
+        broadcast_transcript(ctx, event);
+    }
+
+    /// Silence (ms) between the held final's end and `now_sample`; `None`
+    /// when nothing is held.
+    fn pause_ms(&self, ctx: &PipelineContext, now_sample: u64) -> Option<u32> {
+        let end = self
+            .held
+            .as_ref()?
+            .meta
+            .audio_end_sample
+            .unwrap_or(now_sample);
+        Some(samples_ms(
+            now_sample.saturating_sub(end) as usize,
+            ctx.config.target_sample_rate,
+        ))
+    }
+}
+
+/// End a final's text with `mark` unless it already ends in punctuation,
+/// capitalising its start when `sentence_start`. Returns whether the text
+/// now ends a sentence.
+fn punctuate_final(
+    segments: &mut [TranscriptSegment],
+    mark: Option<char>,
+    sentence_start: bool,
+) -> bool {
+    if sentence_start {
+        if let Some(segment) = segments.iter_mut().find(|s| !s.text.trim().is_empty()) {
+            let text = segment.text.trim_start();
+            let mut chars = text.chars();
+            if let Some(first) = chars.next() {
+                segment.text = first.to_uppercase().chain(chars).collect();
+            }
+        }
+    }
+    let Some(segment) = segments
+        .iter_mut()
+        .rev()
+        .find(|s| !s.text.trim().is_empty())
+    else {
+        return false;
+    };
+    segment.text.truncate(segment.text.trim_end().len());
+    match mark {
+        Some(mark) if !segment.text.ends_with(|c: char| c.is_ascii_punctuation()) => {
+            segment.text.push(mark);
+        }
+        _ => {}
+    }
+    segment.text.ends_with(['.', '?', '!'])
+}
+
+/// Whether `event` belongs on the partial channel: only when every segment is
+/// a partial. Anything carrying a final goes to the final channel.
+fn is_partial_event(event: &TranscriptEvent) -> bool {
+    !event.segments.is_empty()
+        && event
+            .segments
+            .iter()
+            .all(|segment| segment.kind == SegmentKind::Partial)
+}
+
+/// Split channel for `event`.
+fn transcript_channel_for<'a>(
+    ctx: &'a PipelineContext,
+    event: &TranscriptEvent,
+) -> &'a broadcast::Sender<TranscriptEvent> {
+    if is_partial_event(event) {
+        &ctx.partial_tx
+    } else {
+        &ctx.final_tx
+    }
+}
+
+/// Send on the combined channel and on the partial/final channel, and queue
+/// finals on the fan-out when guaranteed. Returns `true` if anyone received it.
+fn broadcast_transcript(ctx: &PipelineContext, event: TranscriptEvent) -> bool {
+    let guaranteed_ok = ctx.config.backpressure == BackpressurePolicy::GuaranteedFinals
+        && !is_partial_event(&event)
+        && ctx.final_fanout.send(&event) > 0;
+    let split_ok = transcript_channel_for(ctx, &event)
+        .send(event.clone())
+        .is_ok();
+    let combined_ok = ctx.transcript_tx.send(event).is_ok();
+    split_ok || combined_ok || guaranteed_ok
+}
+
+/// Final-output quality across utterances: the per-call empty streak that
+/// triggers a fallback, and the windowed fallback rate that flags a model
+/// which never produces text at all.
+struct FinalHealth {
+    /// Consecutive final inference calls that produced empty output.
+    empty_streak: usize,
+    /// `(finals_emitted, fallback_emitted)` diagnostics after each of the
+    /// last `DEGRADED_MODEL_WINDOW_FINALS` finals, oldest first.
+    marks: VecDeque<(usize, usize)>,
+    /// Whether the degraded-model warning is currently raised.
+    degraded: bool,
+    /// Text of the last emitted final and how many times in a row it came.
+    last_final: Option<String>,
+    repeat_streak: usize,
+    /// Whether the repeated-final warning is currently raised.
+    repeating: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModelHealthChange {
+    Degraded { fallbacks: usize, finals: usize },
+    Recovered,
+}
+
+impl FinalHealth {
+    fn new() -> Self {
+        Self {
+            empty_streak: 0,
+            marks: VecDeque::from([(0, 0)]),
+            degraded: false,
+            last_final: None,
+            repeat_streak: 0,
+            repeating: false,
+        }
+    }
+
+    /// Count `text` against the last final. Returns the streak length when
+    /// it reaches [`REPEATED_FINAL_LIMIT`] (once per streak). Finals under
+    /// [`REPEATED_FINAL_MIN_WORDS`] start a new streak instead.
+    fn observe_final_text(&mut self, text: &str) -> Option<usize> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        if text.split_whitespace().count() < REPEATED_FINAL_MIN_WORDS {
+            self.last_final = None;
+            self.repeat_streak = 1;
+            return None;
+        }
+        if self.last_final.as_deref() == Some(text) {
+            self.repeat_streak = self.repeat_streak.saturating_add(1);
+        } else {
+            self.last_final = Some(text.to_owned());
+            self.repeat_streak = 1;
+        }
+        (self.repeat_streak == REPEATED_FINAL_LIMIT).then_some(self.repeat_streak)
+    }
+
+    /// Record the current cumulative counters and report a state change once
+    /// a full window of finals is available.
+    fn observe(
+        &mut self,
+        finals_emitted: usize,
+        fallback_emitted: usize,
+    ) -> Option<ModelHealthChange> {
+        if self.marks.back().map(|&(finals, _)| finals) == Some(finals_emitted) {
+            return None;
+        }
+        self.marks.push_back((finals_emitted, fallback_emitted));
+        while self.marks.len() > DEGRADED_MODEL_WINDOW_FINALS + 1 {
+            self.marks.pop_front();
+        }
+        let (first_finals, first_fallbacks) = *self.marks.front()?;
+        let finals = finals_emitted.saturating_sub(first_finals);
+        let fallbacks = fallback_emitted.saturating_sub(first_fallbacks);
+        if finals < DEGRADED_MODEL_WINDOW_FINALS {
+            return None;
+        }
+        let rate = fallbacks as f32 / finals as f32;
+        if !self.degraded && rate >= DEGRADED_MODEL_FALLBACK_RATE {
+            self.degraded = true;
+            Some(ModelHealthChange::Degraded { fallbacks, finals })
+        } else if self.degraded && rate <= RECOVERED_MODEL_FALLBACK_RATE {
+            self.degraded = false;
+            Some(ModelHealthChange::Recovered)
+        } else {
+            None
+        }
+    }
+}
+
+/// Raise or clear the degraded-model warning from the latest diagnostics.
+///
+/// Capture keeps running, so the status stays `Listening` and the warning
+/// travels in `detail`: an `Error` status here would make status-driven
+/// toggles try to start an engine that is still running.
+fn update_model_health(ctx: &mut PipelineContext, health: &mut FinalHealth) {
+    let finals = ctx.diagnostics.finals_emitted.load(Ordering::Relaxed);
+    let fallbacks = ctx.diagnostics.fallback_emitted.load(Ordering::Relaxed);
+    let change = health.observe(finals, fallbacks);
+    report_model_health(ctx, health, change);
+}
+
+fn report_model_health(
+    ctx: &PipelineContext,
+    health: &FinalHealth,
+    change: Option<ModelHealthChange>,
+) {
+    match change {
+        Some(ModelHealthChange::Degraded { fallbacks, finals }) => {
+            error!(
+                fallbacks,
+                finals, "most recent finals produced no text — model may be misconfigured"
+            );
+            let _ = ctx.status_tx.send(EngineStatusEvent {
+                status: EngineStatus::Listening,
+                detail: Some(format!(
+                    "Model may be misconfigured: {fallbacks} of the last {finals} utterances produced no text. Check the selected model files or switch model profile."
+                )),
+            });
+        }
+        Some(ModelHealthChange::Recovered) => {
+            info!("model output recovered");
+            // The repeated-final warning, if up, keeps its detail.
+            if !health.repeating {
+                let _ = ctx.status_tx.send(EngineStatusEvent {
+                    status: EngineStatus::Listening,
+                    detail: None,
+                });
+            }
+        }
+        None => {}
+    }
+}
+
+fn handle_final_flush_result(
+    ctx: &mut PipelineContext,
+    outcome: FlushOutcome,
+    utterance_id: Option<&str>,
+    revision: &mut u32,
+    health: &mut FinalHealth,
+    salvage: Option<&SalvagedPartial>,
+) -> bool {
+    let handled = handle_final_outcome(ctx, outcome, utterance_id, revision, health, salvage);
+    update_model_health(ctx, health);
+    handled
+}
+
+fn handle_final_outcome(
+    ctx: &mut PipelineContext,
+    outcome: FlushOutcome,
+    utterance_id: Option<&str>,
+    revision: &mut u32,
+    health: &mut FinalHealth,
+    salvage: Option<&SalvagedPartial>,
+) -> bool {
+    // While the degraded-model or repeated-final warning is up, per-call
+    // notices must not replace it with a `Listening` status.
+    let report_status = !(health.degraded || health.repeating);
+    match outcome {
+        FlushOutcome::Emitted { final_text, .. } => {
+            if health.empty_streak > 0 {
+                health.empty_streak = 0;
+                if report_status {
+                    let _ = ctx.status_tx.send(EngineStatusEvent {
+                        status: EngineStatus::Listening,
+                        detail: None,
+                    });
+                }
+            }
+            if let Some(text) = final_text {
+                check_repeated_final(ctx, health, &text, utterance_id);
+            }
+            true
+        }
+        FlushOutcome::Empty(reason) => {
+            health.empty_streak = health.empty_streak.saturating_add(1);
+            warn!(
+                utterance_id = ?utterance_id,
+                empty_final_streak = health.empty_streak,
+                reason = ?reason,
+                "final inference produced empty output"
+            );
+
+            if health.empty_streak >= EMPTY_FINAL_STREAK_FOR_FALLBACK {
+                emit_fallback_event(ctx, utterance_id, revision, reason);
+                if report_status {
+                    let _ = ctx.status_tx.send(EngineStatusEvent {
+                        status: EngineStatus::Listening,
+                        detail: Some(
+                            "Transcription degraded: speech detected but model returned empty output; using fallback."
+                                .into(),
+                        ),
+                    });
+                }
+                true
+            } else {
+                // Say why nothing was typed; the next emitted final clears it.
+                if let (true, Some(reason)) = (report_status, reason) {
+                    let _ = ctx.status_tx.send(EngineStatusEvent {
+                        status: EngineStatus::Listening,
+                        detail: Some(format!("Nothing typed: {}.", reason.description())),
+                    });
+                }
+                false
+            }
+        }
+        FlushOutcome::Error => {
+            let salvage = salvage.filter(|p| Some(p.utterance_id.as_str()) == utterance_id);
+            if let Some(salvage) = salvage {
+                emit_salvaged_final(ctx, salvage, revision);
+                if report_status {
+                    let _ = ctx.status_tx.send(EngineStatusEvent {
+                        status: EngineStatus::Listening,
+                        detail: Some(
+                            "Transcription error: final inference failed; kept the last partial."
+                                .into(),
+                        ),
+                    });
+                }
+                return true;
+            }
+            emit_fallback_event(ctx, utterance_id, revision, None);
+            if report_status {
+                let _ = ctx.status_tx.send(EngineStatusEvent {
+                    status: EngineStatus::Listening,
+                    detail: Some(
+                        "Transcription error: inference failed during finalization.".into(),
+                    ),
+                });
+            }
+            true
+        }
+        // Not a model failure: no fallback, and the health streaks stand.
+        FlushOutcome::Cancelled => false,
+    }
+}
+
+/// Warn once the same final has come out [`REPEATED_FINAL_LIMIT`] times in
+/// a row, and clear the warning when a different one follows. Like the
+/// degraded-model warning, this is a `Listening` detail, not an `Error`.
+fn check_repeated_final(
+    ctx: &mut PipelineContext,
+    health: &mut FinalHealth,
+    text: &str,
+    utterance_id: Option<&str>,
+) {
+    if let Some(repeats) = health.observe_final_text(text) {
+        ctx.diagnostics
+            .repeated_finals
+            .fetch_add(1, Ordering::Relaxed);
+        error!(
+            utterance_id = ?utterance_id,
+            repeats,
+            text_preview = %text.chars().take(50).collect::<String>(),
+            "identical finals repeated — pipeline may be stuck"
+        );
+        health.repeating = true;
+        if !health.degraded {
+            let _ = ctx.status_tx.send(EngineStatusEvent {
+                status: EngineStatus::Listening,
+                detail: Some(format!(
+                    "Pipeline may be stuck: the same text was transcribed {repeats} times in a row. Stop and restart dictation if this was not what you said."
+                )),
+            });
+        }
+    } else if health.repeating && health.repeat_streak == 1 {
+        info!("repeated finals stopped");
+        health.repeating = false;
+        if !health.degraded {
+            let _ = ctx.status_tx.send(EngineStatusEvent {
+                status: EngineStatus::Listening,
+                detail: None,
+            });
+        }
+    }
+}
+
+/// Fill in a final's `partial_emitted` and `supersedes`. `revision` is the
+/// utterance's counter before the final bumps it: anything above zero was a
+/// partial, all of which carried `utterance_id`.
+fn mark_superseded_partials(meta: &mut TranscriptMeta, utterance_id: Option<&str>, revision: u32) {
+    let partial_emitted = revision > 0 && utterance_id.is_some();
+    meta.partial_emitted = Some(partial_emitted);
+    meta.supersedes = utterance_id
+        .filter(|_| partial_emitted)
+        .map(|id| vec![id.to_owned()])
+        .unwrap_or_default();
+}
+
+/// Re-send `salvage` as the utterance's final.
+fn emit_salvaged_final(ctx: &mut PipelineContext, salvage: &SalvagedPartial, revision: &mut u32) {
+    let mut meta = salvage.meta.clone();
+    mark_superseded_partials(&mut meta, Some(&salvage.utterance_id), *revision);
+    *revision = revision.saturating_add(1);
+    let segments: Vec<TranscriptSegment> = salvage
+        .segments
+        .iter()
+        .map(|segment| TranscriptSegment {
+            kind: SegmentKind::Final,
+            ..segment.clone()
+        })
+        .collect();
+    let segment_count = segments.len();
+    let event = TranscriptEvent {
+        seq: ctx.seq.fetch_add(1, Ordering::Relaxed),
+        segments,
+        meta: TranscriptMeta {
+            revision: Some(*revision),
+            ..meta
+        },
+    };
+    let emitted = broadcast_transcript(ctx, event);
+    if emitted {
+        ctx.diagnostics
+            .segments_emitted
+            .fetch_add(segment_count, Ordering::Relaxed);
+        ctx.diagnostics
+            .finals_emitted
+            .fetch_add(1, Ordering::Relaxed);
+    }
+    warn!(
+        utterance_id = %salvage.utterance_id,
+        emitted,
+        "final inference failed; promoted the last partial to the final"
+    );
+}
+
+fn emit_fallback_event(
+    ctx: &mut PipelineContext,
+    utterance_id: Option<&str>,
+    revision: &mut u32,
+    empty_reason: Option<EmptyReason>,
+) {
+    let mut meta = TranscriptMeta {
+        source: Some(TranscriptSource::Placeholder),
+        is_fallback: Some(true),
+        empty_reason,
+        ..TranscriptMeta::default()
+    };
+    mark_superseded_partials(&mut meta, utterance_id, *revision);
+    *revision = revision.saturating_add(1);
+    let seq = ctx.seq.fetch_add(1, Ordering::Relaxed);
+    let fallback_id = utterance_id
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| format!("fallback-{seq}"));
+    let event = TranscriptEvent {
+        seq,
+        segments: vec![TranscriptSegment {
+            id: fallback_id,
+            text: FALLBACK_TEXT.to_string(),
+            kind: SegmentKind::Final,
+            confidence: None,
+            start_ms: None,
+            end_ms: None,
+            word_timings: Vec::new(),
+            language: None,
+        }],
+        meta: TranscriptMeta {
+            revision: Some(*revision),
+            ..meta
+        },
+    };
+    let emitted = broadcast_transcript(ctx, event);
+    if emitted {
+        ctx.diagnostics
+            .segments_emitted
+            .fetch_add(1, Ordering::Relaxed);
+        ctx.diagnostics
+            .finals_emitted
+            .fetch_add(1, Ordering::Relaxed);
+        ctx.diagnostics
+            .fallback_emitted
+            .fetch_add(1, Ordering::Relaxed);
+    }
+    warn!(
+        utterance_id = ?utterance_id,
+        emitted,
+        fallback_text = FALLBACK_TEXT,
+        "emitted fallback transcript segment"
+    );
+}
+
+fn compute_rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum_sq = samples.iter().map(|s| s * s).sum::<f32>();
+    (sum_sq / samples.len() as f32).sqrt()
+}
+
+/// Frame length used by [`estimate_snr_db`].
+const SNR_FRAME_MS: usize = 20;
+
+fn samples_ms(samples: usize, sample_rate: u32) -> u32 {
+    (samples as u64 * 1000 / sample_rate.max(1) as u64).min(u32::MAX as u64) as u32
+}
+
+/// Rough SNR of an utterance: loud frames (90th percentile RMS) against
+/// quiet frames (10th percentile). `None` when the audio is too short to
+/// have both, or is digital silence.
+fn estimate_snr_db(samples: &[f32], sample_rate: u32) -> Option<f32> {
+    let frame = (sample_rate as usize * SNR_FRAME_MS / 1000).max(1);
+    let mut levels: Vec<f32> = samples.chunks_exact(frame).map(compute_rms).collect();
+    if levels.len() < 5 {
+        return None;
+    }
+    levels.sort_by(|a, b| a.total_cmp(b));
+    let noise = levels[levels.len() / 10];
+    let signal = levels[levels.len() * 9 / 10];
+    if noise <= f32::EPSILON || signal <= f32::EPSILON {
+        return None;
+    }
+    Some(20.0 * (signal / noise).log10())
+}
+
+fn mean_confidence(segments: &[TranscriptSegment]) -> Option<f32> {
+    let known: Vec<f32> = segments.iter().filter_map(|s| s.confidence).collect();
+    if known.is_empty() {
+        return None;
+    }
+    Some(known.iter().sum::<f32>() / known.len() as f32)
+}
+
+fn apply_adaptive_input_gain(samples: &mut [f32], vad_threshold: f32, limits: &GainLimits) {
+    if samples.is_empty() {
+        return;
+    }
+    let rms = compute_rms(samples);
     // Boost very quiet microphones/speakers toward a working speech band so
     // whisper-level input can still pass VAD and inference.
     let configured_boost = std::env::var("DICTUM_INPUT_GAIN_BOOST")
@@ -797,7 +2061,7 @@ fn apply_adaptive_input_gain(samples: &mut [f32], vad_threshold: f32) {
     if rms >= target_rms {
         return;
     }
-    let gain = (target_rms / rms).clamp(1.0, 9.0);
+    let gain = limits.limit(samples, rms, target_rms / rms);
     if gain <= 1.03 {
         return;
     }
@@ -824,6 +2088,39 @@ fn append_rolling_samples(buf: &mut Vec<f32>, samples: &[f32], max_len: usize) {
     buf.extend_from_slice(samples);
 }
 
+/// Prepend the audio that preceded `speech` so a one-word final reaches
+/// `target_len` samples. `recent` is the rolling capture window ending at
+/// stream offset `recent_end`; `speech` ends at `speech_end`. Pre-roll never
+/// reaches back before `floor` (the end of the previous utterance). Returns
+/// `None` when the utterance is long enough or no pre-roll is available.
+fn pad_short_utterance(
+    speech: &[f32],
+    speech_end: u64,
+    recent: &[f32],
+    recent_end: u64,
+    floor: u64,
+    target_len: usize,
+) -> Option<Vec<f32>> {
+    if speech.is_empty() || speech.len() >= target_len {
+        return None;
+    }
+    let speech_start = speech_end.checked_sub(speech.len() as u64)?;
+    let recent_start = recent_end.checked_sub(recent.len() as u64)?;
+    let preroll_start = speech_start
+        .saturating_sub((target_len - speech.len()) as u64)
+        .max(floor)
+        .max(recent_start);
+    if preroll_start >= speech_start {
+        return None;
+    }
+    let from = (preroll_start - recent_start) as usize;
+    let to = (speech_start - recent_start) as usize;
+    let mut padded = Vec::with_capacity(to - from + speech.len());
+    padded.extend_from_slice(&recent[from..to]);
+    padded.extend_from_slice(speech);
+    Some(padded)
+}
+
 fn retain_tail_samples(buf: &mut Vec<f32>, tail_len: usize) {
     if tail_len == 0 {
         buf.clear();
@@ -849,7 +2146,7 @@ mod tests {
     use crate::buffering::{create_audio_ring, Producer};
     use crate::error::{DictumError, Result};
     use crate::inference::SpeechModel;
-    use crate::ipc::events::{SegmentKind, TranscriptSegment};
+    use crate::ipc::events::{seq_follows, SegmentKind, TranscriptSegment, UtteranceRevisions};
 
     struct ScriptedVad {
         decisions: Vec<VadDecision>,
@@ -928,6 +2225,10 @@ mod tests {
                 },
                 kind,
                 confidence: None,
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
             }])
         }
 
@@ -936,57 +2237,318 @@ mod tests {
         }
     }
 
-    fn recv_event_with_timeout(
-        rx: &mut broadcast::Receiver<TranscriptEvent>,
-        timeout: Duration,
-    ) -> TranscriptEvent {
-        let start = Instant::now();
-        loop {
-            match rx.try_recv() {
-                Ok(ev) => return ev,
-                Err(TryRecvError::Empty) => {
-                    if start.elapsed() >= timeout {
-                        panic!("timed out waiting for transcript event");
-                    }
-                    thread::sleep(Duration::from_millis(5));
-                }
-                Err(TryRecvError::Lagged(_)) => continue,
-                Err(TryRecvError::Closed) => panic!("transcript channel closed unexpectedly"),
-            }
+    /// Returns nothing, as a model does after dropping a low-quality decode.
+    struct FilteringModel;
+
+    impl SpeechModel for FilteringModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
         }
-    }
 
-    fn assert_no_event_for(rx: &mut broadcast::Receiver<TranscriptEvent>, timeout: Duration) {
-        let start = Instant::now();
-        loop {
-            match rx.try_recv() {
-                Ok(ev) => panic!("expected no event, got seq={}", ev.seq),
-                Err(TryRecvError::Empty) => {
-                    if start.elapsed() >= timeout {
-                        return;
-                    }
-                    thread::sleep(Duration::from_millis(5));
-                }
-                Err(TryRecvError::Lagged(_)) => continue,
-                Err(TryRecvError::Closed) => return,
-            }
+        fn transcribe(
+            &mut self,
+            _chunk: &AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            Ok(vec![])
         }
-    }
 
-    fn base_config() -> EngineConfig {
-        let mut cfg = EngineConfig::default();
-        cfg.target_sample_rate = 16_000;
-        cfg.min_speech_samples = 960;
-        cfg.max_speech_samples = 8_000;
-        cfg
+        fn reset(&mut self) {}
+
+        fn last_transcript_meta(&self) -> TranscriptMeta {
+            TranscriptMeta {
+                empty_reason: Some(EmptyReason::LowQualityFiltered),
+                ..TranscriptMeta::default()
+            }
+        }
     }
 
-    #[test]
-    fn flush_inference_emits_events_and_increments_seq() {
-        let (_producer, consumer) = create_audio_ring();
-        let (transcript_tx, mut transcript_rx) = broadcast::channel(8);
-        let (status_tx, _) = broadcast::channel(8);
-        let (activity_tx, _) = broadcast::channel(8);
+    /// Returns a sentence-split final with per-segment timing.
+    struct SplitModel;
+
+    impl SpeechModel for SplitModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            let sentences = [
+                ("First one.", 0.9, 0, 800),
+                ("Second one.", 0.5, 900, 1_600),
+            ];
+            Ok(sentences
+                .into_iter()
+                .map(|(text, confidence, start, end)| TranscriptSegment {
+                    id: String::new(),
+                    text: text.into(),
+                    kind: SegmentKind::Final,
+                    confidence: Some(confidence),
+                    start_ms: Some(start),
+                    end_ms: Some(end),
+                    word_timings: Vec::new(),
+                    language: None,
+                })
+                .collect())
+        }
+
+        fn reset(&mut self) {}
+
+        fn is_warm(&self) -> bool {
+            true
+        }
+    }
+
+    /// Records the prompt context each transcription was conditioned on and
+    /// finalizes as "utterance N".
+    #[derive(Default)]
+    struct PromptModel {
+        prompt: Option<String>,
+        seen: Arc<Mutex<Vec<Option<String>>>>,
+        finals: usize,
+    }
+
+    impl SpeechModel for PromptModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &AudioChunk,
+            partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            self.seen.lock().push(self.prompt.clone());
+            if !partial {
+                self.finals += 1;
+            }
+            Ok(vec![TranscriptSegment {
+                id: String::new(),
+                text: format!("utterance {}", self.finals),
+                kind: if partial {
+                    SegmentKind::Partial
+                } else {
+                    SegmentKind::Final
+                },
+                confidence: None,
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
+            }])
+        }
+
+        fn reset(&mut self) {
+            self.prompt = None;
+        }
+
+        fn set_prompt_context(&mut self, context: Option<&str>) {
+            self.prompt = context.map(str::to_string);
+        }
+    }
+
+    /// Counts keep-warm calls separately from real transcriptions.
+    struct KeepWarmModel {
+        transcribes: Arc<AtomicUsize>,
+        keep_warms: Arc<AtomicUsize>,
+    }
+
+    impl SpeechModel for KeepWarmModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            self.transcribes.fetch_add(1, Ordering::Relaxed);
+            Ok(vec![])
+        }
+
+        fn reset(&mut self) {}
+
+        fn keep_warm(&mut self) -> Result<()> {
+            self.keep_warms.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    /// Finalizes every utterance as the same text.
+    /// Finalizes each utterance as the next of the queued texts.
+    struct SpokenModel(std::collections::VecDeque<&'static str>);
+
+    impl SpeechModel for SpokenModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            Ok(self
+                .0
+                .pop_front()
+                .map(|text| TranscriptSegment {
+                    id: String::new(),
+                    text: text.into(),
+                    kind: SegmentKind::Final,
+                    confidence: None,
+                    start_ms: None,
+                    end_ms: None,
+                    word_timings: Vec::new(),
+                    language: None,
+                })
+                .into_iter()
+                .collect())
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    struct FixedTextModel(&'static str);
+
+    impl SpeechModel for FixedTextModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &AudioChunk,
+            partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            Ok(vec![TranscriptSegment {
+                id: String::new(),
+                text: self.0.into(),
+                kind: if partial {
+                    SegmentKind::Partial
+                } else {
+                    SegmentKind::Final
+                },
+                confidence: None,
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
+            }])
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    /// Mimics a model that decodes nothing from very short audio.
+    struct LengthSensitiveModel {
+        min_samples: usize,
+    }
+
+    impl SpeechModel for LengthSensitiveModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            chunk: &AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            if chunk.samples.len() < self.min_samples {
+                return Ok(vec![]);
+            }
+            Ok(vec![TranscriptSegment {
+                id: String::new(),
+                text: "yes".into(),
+                kind: SegmentKind::Final,
+                confidence: None,
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
+            }])
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn recv_event_with_timeout<T: Clone>(rx: &mut broadcast::Receiver<T>, timeout: Duration) -> T {
+        let start = Instant::now();
+        loop {
+            match rx.try_recv() {
+                Ok(ev) => return ev,
+                Err(TryRecvError::Empty) => {
+                    if start.elapsed() >= timeout {
+                        panic!("timed out waiting for event");
+                    }
+                    thread::sleep(Duration::from_millis(5));
+                }
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(TryRecvError::Closed) => panic!("event channel closed unexpectedly"),
+            }
+        }
+    }
+
+    fn assert_no_event_for(rx: &mut broadcast::Receiver<TranscriptEvent>, timeout: Duration) {
+        let start = Instant::now();
+        loop {
+            match rx.try_recv() {
+                Ok(ev) => panic!("expected no event, got seq={}", ev.seq),
+                Err(TryRecvError::Empty) => {
+                    if start.elapsed() >= timeout {
+                        return;
+                    }
+                    thread::sleep(Duration::from_millis(5));
+                }
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(TryRecvError::Closed) => return,
+            }
+        }
+    }
+
+    fn base_config() -> EngineConfig {
+        let mut cfg = EngineConfig::default();
+        cfg.target_sample_rate = 16_000;
+        cfg.min_speech_samples = 960;
+        cfg.max_speech_samples = 8_000;
+        cfg
+    }
+
+    /// Context with fresh channels, an idle status and a VAD that hears only
+    /// silence. Tests override what they observe with struct-update syntax.
+    fn test_ctx(model: ModelHandle, consumer: AudioConsumer) -> PipelineContext {
+        PipelineContext {
+            config: base_config(),
+            model,
+            vad: Box::new(ScriptedVad::new(vec![], Arc::new(AtomicUsize::new(0)))),
+            vad_threshold: LiveThreshold::new(base_config().vad_threshold),
+            consumer,
+            running: Arc::new(AtomicBool::new(true)),
+            transcript_tx: broadcast::channel(16).0,
+            partial_tx: broadcast::channel(16).0,
+            final_tx: broadcast::channel(16).0,
+            final_fanout: FinalFanout::default(),
+            status_tx: broadcast::channel(8).0,
+            activity_tx: broadcast::channel(8).0,
+            boundary_tx: broadcast::channel(8).0,
+            status: Arc::new(Mutex::new(EngineStatus::Idle)),
+            seq: Arc::new(AtomicU64::new(0)),
+            capture_sample_rate: 16_000,
+            diagnostics: Arc::new(PipelineDiagnostics::default()),
+            reset_requested: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[test]
+    fn flush_inference_emits_events_and_increments_seq() {
+        let (_producer, consumer) = create_audio_ring();
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(8);
+        let (status_tx, _) = broadcast::channel(8);
+        let (activity_tx, _) = broadcast::channel(8);
 
         let calls = Arc::new(Mutex::new(Vec::new()));
         let model_resets = Arc::new(AtomicUsize::new(0));
@@ -1005,22 +2567,33 @@ mod tests {
         let seq = Arc::new(AtomicU64::new(0));
 
         let mut ctx = PipelineContext {
-            config: base_config(),
-            model,
             vad,
-            consumer,
             running,
             transcript_tx,
             status_tx,
             activity_tx,
-            status: Arc::new(Mutex::new(EngineStatus::Idle)),
             seq: Arc::clone(&seq),
-            capture_sample_rate: 16_000,
-            diagnostics: Arc::new(PipelineDiagnostics::default()),
+            ..test_ctx(model, consumer)
         };
 
-        flush_inference(&mut ctx, &vec![0.1; 960], true, Some("utt-test"));
-        flush_inference(&mut ctx, &vec![0.1; 960], false, Some("utt-test"));
+        flush_inference(
+            &mut ctx,
+            &vec![0.1; 960],
+            true,
+            Some("utt-test"),
+            None,
+            &mut 0,
+            None,
+        );
+        flush_inference(
+            &mut ctx,
+            &vec![0.1; 960],
+            false,
+            Some("utt-test"),
+            None,
+            &mut 0,
+            None,
+        );
 
         let first = recv_event_with_timeout(&mut transcript_rx, Duration::from_millis(200));
         let second = recv_event_with_timeout(&mut transcript_rx, Duration::from_millis(200));
@@ -1032,56 +2605,1137 @@ mod tests {
         assert_eq!(second.segments[0].kind, SegmentKind::Final);
         assert_eq!(second.segments[0].id, "utt-test");
         assert_eq!(seq.load(Ordering::Relaxed), 2);
+        assert_eq!(second.meta.audio_ms, Some(60));
+        assert!(second.meta.latency_ms.is_some());
+        assert_eq!(second.meta.is_fallback, Some(false));
     }
 
     #[test]
-    fn flush_inference_skips_empty_and_error_results() {
+    fn final_names_the_partial_it_supersedes() {
+        let (_producer, consumer) = create_audio_ring();
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(8);
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> =
+            Box::new(ScriptedVad::new(vec![], Arc::new(AtomicUsize::new(0))));
+        let mut ctx = PipelineContext {
+            vad,
+            transcript_tx,
+            ..test_ctx(model, consumer)
+        };
+        let samples = vec![0.1; 960];
+
+        // One utterance with a partial, then one finalized without any.
+        let mut revision = 0;
+        for partial in [true, false] {
+            flush_inference(
+                &mut ctx,
+                &samples,
+                partial,
+                Some("utt-1"),
+                None,
+                &mut revision,
+                None,
+            );
+        }
+        flush_inference(&mut ctx, &samples, false, Some("utt-2"), None, &mut 0, None);
+
+        let interim = recv_event_with_timeout(&mut transcript_rx, Duration::from_millis(200));
+        let committed = recv_event_with_timeout(&mut transcript_rx, Duration::from_millis(200));
+        let direct = recv_event_with_timeout(&mut transcript_rx, Duration::from_millis(200));
+
+        assert!(interim.meta.supersedes.is_empty());
+        assert_eq!(interim.meta.partial_emitted, None);
+        assert_eq!(
+            committed.meta.supersedes,
+            vec![interim.segments[0].id.clone()]
+        );
+        assert_eq!(committed.meta.partial_emitted, Some(true));
+        assert!(direct.meta.supersedes.is_empty());
+        assert_eq!(direct.meta.partial_emitted, Some(false));
+    }
+
+    #[test]
+    fn split_channels_carry_only_their_kind() {
         let (_producer, consumer) = create_audio_ring();
         let (transcript_tx, mut transcript_rx) = broadcast::channel(8);
+        let (partial_tx, mut partial_rx) = broadcast::channel(8);
+        let (final_tx, mut final_rx) = broadcast::channel(8);
         let (status_tx, _) = broadcast::channel(8);
         let (activity_tx, _) = broadcast::channel(8);
 
-        let calls = Arc::new(Mutex::new(Vec::new()));
-        let model_resets = Arc::new(AtomicUsize::new(0));
         let model = ModelHandle::new(TestModel {
-            calls,
-            resets: model_resets,
-            empty_partial: true,
+            calls: Arc::new(Mutex::new(Vec::new())),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
             empty_final: false,
-            fail_final: true,
+            fail_final: false,
         });
-
-        let vad_resets = Arc::new(AtomicUsize::new(0));
-        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(vec![], vad_resets));
-
-        let seq = Arc::new(AtomicU64::new(0));
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![VadDecision::Speech],
+            Arc::new(AtomicUsize::new(0)),
+        ));
         let mut ctx = PipelineContext {
-            config: base_config(),
-            model,
             vad,
-            consumer,
-            running: Arc::new(AtomicBool::new(true)),
             transcript_tx,
+            partial_tx,
+            final_tx,
             status_tx,
             activity_tx,
-            status: Arc::new(Mutex::new(EngineStatus::Idle)),
-            seq: Arc::clone(&seq),
-            capture_sample_rate: 16_000,
-            diagnostics: Arc::new(PipelineDiagnostics::default()),
+            ..test_ctx(model, consumer)
         };
 
-        flush_inference(&mut ctx, &vec![0.1; 960], true, Some("utt-test"));
-        flush_inference(&mut ctx, &vec![0.1; 960], false, Some("utt-test"));
+        flush_inference(
+            &mut ctx,
+            &vec![0.1; 960],
+            true,
+            Some("utt-a"),
+            None,
+            &mut 0,
+            None,
+        );
+        flush_inference(
+            &mut ctx,
+            &vec![0.1; 960],
+            false,
+            Some("utt-a"),
+            None,
+            &mut 0,
+            None,
+        );
+        flush_inference(
+            &mut ctx,
+            &vec![0.1; 960],
+            true,
+            Some("utt-b"),
+            None,
+            &mut 0,
+            None,
+        );
+        emit_fallback_event(&mut ctx, Some("utt-b"), &mut 0, None);
+
+        let timeout = Duration::from_millis(200);
+        for _ in 0..2 {
+            let event = recv_event_with_timeout(&mut partial_rx, timeout);
+            assert!(event
+                .segments
+                .iter()
+                .all(|s| s.kind == SegmentKind::Partial));
+        }
+        assert_no_event_for(&mut partial_rx, Duration::from_millis(20));
 
-        assert_no_event_for(&mut transcript_rx, Duration::from_millis(100));
-        assert_eq!(seq.load(Ordering::Relaxed), 0);
+        for _ in 0..2 {
+            let event = recv_event_with_timeout(&mut final_rx, timeout);
+            assert!(event.segments.iter().all(|s| s.kind == SegmentKind::Final));
+        }
+        assert_no_event_for(&mut final_rx, Duration::from_millis(20));
+
+        // The combined channel still carries everything, in order.
+        let seqs: Vec<u64> = (0..4)
+            .map(|_| recv_event_with_timeout(&mut transcript_rx, timeout).seq)
+            .collect();
+        assert_eq!(seqs, vec![0, 1, 2, 3]);
     }
 
     #[test]
-    fn run_emits_partial_then_final_on_speech_then_silence() {
+    fn partials_arriving_after_their_final_are_not_applied() {
+        let (_producer, consumer) = create_audio_ring();
+        let (partial_tx, mut partial_rx) = broadcast::channel(8);
+        let (final_tx, mut final_rx) = broadcast::channel(8);
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> =
+            Box::new(ScriptedVad::new(vec![], Arc::new(AtomicUsize::new(0))));
+        let mut ctx = PipelineContext {
+            vad,
+            partial_tx,
+            final_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let mut revision = 0;
+        for partial in [true, true, false] {
+            flush_inference(
+                &mut ctx,
+                &vec![0.1; 960],
+                partial,
+                Some("utt-a"),
+                None,
+                &mut revision,
+                None,
+            );
+        }
+        // A fresh utterance restarts its own counter.
+        flush_inference(
+            &mut ctx,
+            &vec![0.1; 960],
+            true,
+            Some("utt-b"),
+            None,
+            &mut 0,
+            None,
+        );
+
+        let timeout = Duration::from_millis(200);
+        // A consumer that drains the final channel first sees the partials
+        // late; they must not overwrite the committed text.
+        let mut revisions = UtteranceRevisions::default();
+        let final_event = recv_event_with_timeout(&mut final_rx, timeout);
+        assert_eq!(final_event.meta.revision, Some(3));
+        assert!(revisions.accept(&final_event));
+
+        let late: Vec<TranscriptEvent> = (0..3)
+            .map(|_| recv_event_with_timeout(&mut partial_rx, timeout))
+            .collect();
+        let late_revisions: Vec<Option<u32>> = late.iter().map(|e| e.meta.revision).collect();
+        assert_eq!(late_revisions, vec![Some(1), Some(2), Some(1)]);
+        assert!(!revisions.accept(&late[0]));
+        assert!(!revisions.accept(&late[1]));
+        assert!(
+            revisions.accept(&late[2]),
+            "other utterances are unaffected"
+        );
+        // Replays of an applied update are dropped too.
+        assert!(!revisions.accept(&final_event));
+    }
+
+    #[test]
+    fn final_health_trips_on_fallback_rate_and_recovers() {
+        let mut health = FinalHealth::new();
+        // Five fallbacks: not a full window yet.
+        for n in 1..=5 {
+            assert_eq!(health.observe(n, n), None);
+        }
+        assert_eq!(
+            health.observe(6, 6),
+            Some(ModelHealthChange::Degraded {
+                fallbacks: 6,
+                finals: 6
+            })
+        );
+        // Unchanged counters (e.g. a partial) are ignored; no repeat alarm.
+        assert_eq!(health.observe(6, 6), None);
+        assert_eq!(health.observe(7, 7), None);
+        // Real finals push fallbacks out of the window.
+        let mut change = None;
+        for n in 8..=14 {
+            change = change.or(health.observe(n, 7));
+        }
+        assert_eq!(change, Some(ModelHealthChange::Recovered));
+        assert!(!health.degraded);
+    }
+
+    #[test]
+    fn consistently_empty_model_reports_degradation_while_listening() {
+        let (_producer, consumer) = create_audio_ring();
+        let (transcript_tx, _transcript_rx) = broadcast::channel(64);
+        let (status_tx, mut status_rx) = broadcast::channel(64);
+        let (activity_tx, _) = broadcast::channel(8);
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: true,
+            empty_final: true,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> =
+            Box::new(ScriptedVad::new(vec![], Arc::new(AtomicUsize::new(0))));
+        let status = Arc::new(Mutex::new(EngineStatus::Listening));
+        let mut ctx = PipelineContext {
+            vad,
+            transcript_tx,
+            status_tx,
+            activity_tx,
+            status: Arc::clone(&status),
+            ..test_ctx(model, consumer)
+        };
+
+        let mut health = FinalHealth::new();
+        for i in 0..(DEGRADED_MODEL_WINDOW_FINALS * EMPTY_FINAL_STREAK_FOR_FALLBACK) {
+            let id = format!("utt-{i}");
+            let mut revision = 0;
+            let outcome = flush_inference(
+                &mut ctx,
+                &vec![0.1; 960],
+                false,
+                Some(&id),
+                None,
+                &mut revision,
+                None,
+            );
+            handle_final_flush_result(
+                &mut ctx,
+                outcome,
+                Some(&id),
+                &mut revision,
+                &mut health,
+                None,
+            );
+        }
+
+        // Capture is still running: the warning must not flip the status.
+        assert_eq!(*status.lock(), EngineStatus::Listening);
+        let mut saw_warning = false;
+        while let Ok(event) = status_rx.try_recv() {
+            assert_eq!(event.status, EngineStatus::Listening);
+            saw_warning |= event.detail.unwrap_or_default().contains("misconfigured");
+        }
+        assert!(saw_warning, "expected a degraded-model status detail");
+    }
+
+    /// Flush `n` finals through the health checks, as the loop would.
+    fn flush_finals(ctx: &mut PipelineContext, health: &mut FinalHealth, n: usize) {
+        for i in 0..n {
+            let id = format!("utt-{i}");
+            let mut revision = 0;
+            let outcome = flush_inference(
+                ctx,
+                &vec![0.1; 960],
+                false,
+                Some(&id),
+                None,
+                &mut revision,
+                None,
+            );
+            handle_final_flush_result(ctx, outcome, Some(&id), &mut revision, health, None);
+        }
+    }
+
+    #[test]
+    fn identical_finals_warn_of_a_stuck_pipeline_while_listening() {
+        let (_producer, consumer) = create_audio_ring();
+        let (status_tx, mut status_rx) = broadcast::channel(64);
+        let status = Arc::new(Mutex::new(EngineStatus::Listening));
+        let diagnostics = Arc::new(PipelineDiagnostics::default());
+        let mut ctx = PipelineContext {
+            status_tx,
+            status: Arc::clone(&status),
+            diagnostics: Arc::clone(&diagnostics),
+            ..test_ctx(
+                ModelHandle::new(FixedTextModel("the quick brown fox jumps")),
+                consumer,
+            )
+        };
+
+        let mut health = FinalHealth::new();
+        flush_finals(&mut ctx, &mut health, REPEATED_FINAL_LIMIT - 1);
+        assert_eq!(diagnostics.repeated_finals.load(Ordering::Relaxed), 0);
+        assert!(status_rx.try_recv().is_err());
+
+        flush_finals(&mut ctx, &mut health, 1);
+        assert_eq!(diagnostics.repeated_finals.load(Ordering::Relaxed), 1);
+        // Capture is still running, so the status must stay `Listening`.
+        assert_eq!(*status.lock(), EngineStatus::Listening);
+        let event = status_rx.try_recv().expect("stuck pipeline status");
+        assert_eq!(event.status, EngineStatus::Listening);
+        assert!(event
+            .detail
+            .unwrap_or_default()
+            .contains("4 times in a row"));
+
+        // The warning fires once per streak, not on every further repeat.
+        flush_finals(&mut ctx, &mut health, 1);
+        assert_eq!(diagnostics.repeated_finals.load(Ordering::Relaxed), 1);
+        assert!(status_rx.try_recv().is_err());
+
+        // Recovery of the model alone must not clear the repetition warning.
+        health.degraded = true;
+        report_model_health(&ctx, &health, Some(ModelHealthChange::Recovered));
+        assert!(status_rx.try_recv().is_err());
+
+        // A different final clears it.
+        health.degraded = false;
+        check_repeated_final(&mut ctx, &mut health, "something else", None);
+        let event = status_rx.try_recv().expect("recovery status");
+        assert_eq!(event.status, EngineStatus::Listening);
+        assert_eq!(event.detail, None);
+    }
+
+    #[test]
+    fn repeating_a_short_phrase_is_not_a_stuck_pipeline() {
+        let (_producer, consumer) = create_audio_ring();
+        let (status_tx, mut status_rx) = broadcast::channel(64);
+        let diagnostics = Arc::new(PipelineDiagnostics::default());
+        let mut ctx = PipelineContext {
+            status_tx,
+            diagnostics: Arc::clone(&diagnostics),
+            ..test_ctx(ModelHandle::new(FixedTextModel("Yes.")), consumer)
+        };
+
+        // A user answering "yes" to a run of prompts.
+        let mut health = FinalHealth::new();
+        flush_finals(&mut ctx, &mut health, REPEATED_FINAL_LIMIT * 2);
+        assert_eq!(diagnostics.repeated_finals.load(Ordering::Relaxed), 0);
+        assert!(!health.repeating);
+        assert!(status_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn consecutive_finals_report_increasing_sample_ranges() {
+        let (mut producer, consumer) = create_audio_ring();
+        // Five 960-sample chunks: speech, silence, silence, speech, silence.
+        producer.push_slice(&vec![0.3; 960 * 5]);
+
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![
+                VadDecision::Speech,
+                VadDecision::Silence,
+                VadDecision::Silence,
+                VadDecision::Speech,
+                VadDecision::Silence,
+            ],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+
+        let (final_tx, mut final_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.enable_partial_inference = false;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            final_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let first = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        let second = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        let range = |event: &TranscriptEvent| {
+            (
+                event.meta.audio_start_sample.expect("start sample"),
+                event.meta.audio_end_sample.expect("end sample"),
+            )
+        };
+        assert_eq!(range(&first), (0, 960));
+        assert_eq!(range(&second), (960 * 3, 960 * 4));
+        assert!(range(&first).1 <= range(&second).0);
+    }
+
+    #[test]
+    fn push_to_talk_ignores_vad_silence_until_stop() {
+        let (mut producer, consumer) = create_audio_ring();
+        // Five 960-sample chunks the VAD would split into two utterances.
+        producer.push_slice(&vec![0.3; 960 * 5]);
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::clone(&calls),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![
+                VadDecision::Speech,
+                VadDecision::Silence,
+                VadDecision::Silence,
+                VadDecision::Speech,
+                VadDecision::Silence,
+            ],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+
+        let (final_tx, mut final_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.enable_partial_inference = false;
+        cfg.capture_mode = CaptureMode::PushToTalk;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            final_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        assert_no_event_for(&mut final_rx, Duration::from_millis(300));
+        assert!(calls.lock().is_empty(), "no mid-stream flush");
+
+        // Releasing the key stops the engine, which flushes everything.
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+        let event = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        assert_eq!(event.meta.audio_start_sample, Some(0));
+        assert_eq!(event.meta.audio_end_sample, Some(960 * 5));
+        assert_eq!(*calls.lock(), vec![false]);
+        assert!(final_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn each_speech_cycle_sends_one_start_and_one_end_boundary() {
+        let (mut producer, consumer) = create_audio_ring();
+        producer.push_slice(&vec![0.3; 960 * 6]);
+
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![
+                VadDecision::Speech,
+                VadDecision::Speech,
+                VadDecision::Silence,
+                VadDecision::Speech,
+                VadDecision::Speech,
+                VadDecision::Silence,
+            ],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+
+        let (final_tx, mut final_rx) = broadcast::channel(16);
+        let (boundary_tx, mut boundary_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.enable_partial_inference = false;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            final_tx,
+            boundary_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let first = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        let second = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        let mut boundaries = Vec::new();
+        while let Ok(event) = boundary_rx.try_recv() {
+            boundaries.push((event.utterance_id, event.kind, event.at_sample));
+        }
+        let id = |event: &TranscriptEvent| event.segments[0].id.clone();
+        assert_eq!(
+            boundaries,
+            vec![
+                (id(&first), BoundaryKind::Start, 0),
+                (id(&first), BoundaryKind::End, 1_920),
+                (id(&second), BoundaryKind::Start, 2_880),
+                (id(&second), BoundaryKind::End, 4_800),
+            ]
+        );
+    }
+
+    #[test]
+    fn uncertain_frames_keep_the_current_vad_state() {
+        let (mut producer, consumer) = create_audio_ring();
+        // Seven 960-sample chunks: speech, uncertain, speech, silence,
+        // uncertain, uncertain, silence.
+        producer.push_slice(&vec![0.3; 960 * 7]);
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::clone(&calls),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![
+                VadDecision::Speech,
+                VadDecision::Uncertain,
+                VadDecision::Speech,
+                VadDecision::Silence,
+                VadDecision::Uncertain,
+                VadDecision::Uncertain,
+                VadDecision::Silence,
+            ],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+
+        let (final_tx, mut final_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.enable_partial_inference = false;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            final_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let first = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        assert_no_event_for(&mut final_rx, Duration::from_millis(50));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        // The uncertain frame inside speech extended the utterance; the ones
+        // after it ended stayed silent instead of opening a new one.
+        assert_eq!(first.meta.audio_start_sample, Some(0));
+        assert_eq!(first.meta.audio_end_sample, Some(960 * 3));
+        assert_eq!(calls.lock().len(), 1);
+    }
+
+    #[test]
+    fn onset_within_post_final_cooldown_starts_no_utterance() {
+        let (mut producer, consumer) = create_audio_ring();
+        // Six 960-sample (60 ms) chunks: speech, silence, blip, silence,
+        // speech, silence. The cooldown ends 100 ms after the first final.
+        producer.push_slice(&vec![0.3; 960 * 6]);
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::clone(&calls),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![
+                VadDecision::Speech,
+                VadDecision::Silence,
+                VadDecision::Speech,
+                VadDecision::Silence,
+                VadDecision::Speech,
+                VadDecision::Silence,
+            ],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+
+        let (final_tx, mut final_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.enable_partial_inference = false;
+        cfg.post_final_cooldown_ms = 100;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            final_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let first = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        let second = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        assert_no_event_for(&mut final_rx, Duration::from_millis(50));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        assert_eq!(first.meta.audio_start_sample, Some(0));
+        // The blip at 1920 was swallowed; the next utterance is the one at 3840.
+        assert_eq!(second.meta.audio_start_sample, Some(960 * 4));
+        assert_eq!(calls.lock().len(), 2);
+    }
+
+    #[test]
+    fn short_utterance_is_padded_with_preroll() {
+        let (mut producer, consumer) = create_audio_ring();
+        // 240 ms of room noise, then a 0.3 s "yes", then silence.
+        producer.push_slice(&vec![0.01; 960 * 4]);
+        producer.push_slice(&vec![0.3; 960 * 5]);
+        producer.push_slice(&vec![0.01; 960]);
+
+        let mut script = vec![VadDecision::Silence; 4];
+        script.extend([VadDecision::Speech; 5]);
+        script.push(VadDecision::Silence);
+        let vad: Box<dyn VoiceActivityDetector> =
+            Box::new(ScriptedVad::new(script, Arc::new(AtomicUsize::new(0))));
+
+        let (final_tx, mut final_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.enable_partial_inference = false;
+        cfg.short_utterance_pad_ms = 600;
+        // Keep the whole stream in the rolling window.
+        cfg.max_speech_samples = 16_000;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            final_tx,
+            ..test_ctx(
+                // Needs 0.5 s of audio, more than the bare utterance.
+                ModelHandle::new(LengthSensitiveModel { min_samples: 8_000 }),
+                consumer,
+            )
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let event = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        assert_eq!(event.segments[0].text, "yes");
+        assert!(event.meta.is_fallback != Some(true));
+        // All 240 ms of pre-roll were used (less than the 600 ms target).
+        assert_eq!(event.meta.audio_start_sample, Some(0));
+        assert_eq!(event.meta.audio_end_sample, Some(960 * 9));
+    }
+
+    #[test]
+    fn preroll_padding_stops_at_previous_utterance() {
+        let recent: Vec<f32> = (0..100).map(|i| i as f32).collect();
+        // `recent` covers stream offsets 1000..1100; speech is 1080..1090.
+        let speech = &recent[80..90];
+        let padded = pad_short_utterance(speech, 1090, &recent, 1100, 1075, 30).expect("padded");
+        assert_eq!(padded.first().copied(), Some(75.0));
+        assert_eq!(padded.len(), 15);
+        // Long enough already, or nothing before it: left alone.
+        assert!(pad_short_utterance(speech, 1090, &recent, 1100, 0, 10).is_none());
+        assert!(pad_short_utterance(speech, 1090, &recent, 1100, 1080, 30).is_none());
+    }
+
+    #[test]
+    fn keep_warm_fires_periodically_during_sustained_silence() {
+        let (mut producer, consumer) = create_audio_ring();
+        let transcribes = Arc::new(AtomicUsize::new(0));
+        let keep_warms = Arc::new(AtomicUsize::new(0));
+        let model = ModelHandle::new(KeepWarmModel {
+            transcribes: Arc::clone(&transcribes),
+            keep_warms: Arc::clone(&keep_warms),
+        });
+        let vad: Box<dyn VoiceActivityDetector> =
+            Box::new(ScriptedVad::new(vec![], Arc::new(AtomicUsize::new(0))));
+
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.keep_warm = true;
+        cfg.keep_warm_interval_ms = 20;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            transcript_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        // ~200 ms of silent audio, delivered in real time.
+        for _ in 0..40 {
+            producer.push_slice(&[0.0; 320]);
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_no_event_for(&mut transcript_rx, Duration::from_millis(20));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        let warms = keep_warms.load(Ordering::Relaxed);
+        assert!(warms >= 3, "expected periodic keep-warm calls, got {warms}");
+        assert_eq!(transcribes.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn keep_warm_timer_restarts_after_real_inference() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let mut timer = KeepWarmTimer::new(interval, 0, start);
+        assert!(!timer.due(0, start + Duration::from_millis(50)));
+        assert!(timer.due(0, start + interval));
+        // An utterance ran in between: the interval starts over from there.
+        assert!(!timer.due(1, start + Duration::from_millis(150)));
+        assert!(!timer.due(1, start + Duration::from_millis(200)));
+        assert!(timer.due(1, start + Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn mute_detector_fires_after_window_of_exact_zeros() {
+        // 50 ms at 16 kHz = 800 samples.
+        let mut detector = MuteDetector::new(50, 16_000);
+        assert_eq!(detector.observe(&[0.0; 320]), None);
+        assert_eq!(detector.observe(&[0.0; 320]), None);
+        assert_eq!(detector.observe(&[0.0; 320]), Some(true));
+        assert_eq!(detector.observe(&[0.0; 320]), None);
+        // Any signal ends the mute; quiet noise is not a mute.
+        let mut noise = [0.0; 320];
+        noise[319] = 1e-4;
+        assert_eq!(detector.observe(&noise), Some(false));
+        for _ in 0..10 {
+            assert_eq!(detector.observe(&[1e-4; 320]), None);
+        }
+    }
+
+    #[test]
+    fn all_zero_input_reports_muted_status() {
+        let (mut producer, consumer) = create_audio_ring();
+        let vad: Box<dyn VoiceActivityDetector> =
+            Box::new(ScriptedVad::new(vec![], Arc::new(AtomicUsize::new(0))));
+        let (status_tx, mut status_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.mute_detect_ms = 50;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            status_tx,
+            activity_tx: broadcast::channel(64).0,
+            status: Arc::new(Mutex::new(EngineStatus::Listening)),
+            ..test_ctx(
+                ModelHandle::new(TestModel {
+                    calls: Arc::new(Mutex::new(Vec::new())),
+                    resets: Arc::new(AtomicUsize::new(0)),
+                    empty_partial: false,
+                    empty_final: false,
+                    fail_final: false,
+                }),
+                consumer,
+            )
+        };
+        let handle = thread::spawn(move || run(ctx));
+
+        // 40 ms of zeros: still inside the window.
+        producer.push_slice(&[0.0; 640]);
+        thread::sleep(Duration::from_millis(30));
+        assert!(matches!(status_rx.try_recv(), Err(TryRecvError::Empty)));
+
+        producer.push_slice(&[0.0; 320]);
+        let muted = recv_event_with_timeout(&mut status_rx, Duration::from_secs(2));
+        assert_eq!(muted.status, EngineStatus::Listening);
+        assert_eq!(muted.detail.as_deref(), Some(MIC_MUTED_DETAIL));
+
+        producer.push_slice(&[0.001; 320]);
+        let unmuted = recv_event_with_timeout(&mut status_rx, Duration::from_secs(2));
+        assert_eq!(unmuted.detail, None);
+
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+    }
+
+    #[test]
+    fn first_partial_bypasses_throttle() {
+        let just_now = Some(Duration::from_millis(10));
+        // Throttled: too soon and too little new audio since the last partial.
+        assert!(!partial_due(false, just_now, 100, 4_000));
+        // The first partial of an utterance fires regardless.
+        assert!(partial_due(true, just_now, 100, 4_000));
+        // Later partials resume normal gating.
+        assert!(partial_due(
+            false,
+            Some(Duration::from_millis(PARTIAL_MIN_INTERVAL_MS)),
+            4_000,
+            4_000
+        ));
+    }
+
+    #[test]
+    fn partials_resume_after_the_buffer_is_cut_back() {
+        let start = Instant::now();
+        let min_speech = 4_000;
+        let mut mark = PartialMark::default();
+        let mut buf = vec![0.1; 100_000];
+        mark.record(start, buf.len());
+
+        // A max-length flush yields nothing and keeps a shorter tail.
+        retain_tail_samples(&mut buf, 40_000);
+        let retried = start + Duration::from_millis(100);
+        mark.retained(retried, buf.len());
+
+        // Growing the tail counts as new audio, though the buffer is still
+        // well below the length of the last partial.
+        buf.extend(vec![0.1; PARTIAL_MIN_NEW_SAMPLES]);
+        let later = retried + Duration::from_millis(PARTIAL_MIN_INTERVAL_MS);
+        assert_eq!(mark.new_samples(buf.len()), PARTIAL_MIN_NEW_SAMPLES);
+        assert!(partial_due(
+            false,
+            mark.since(later),
+            mark.new_samples(buf.len()),
+            min_speech
+        ));
+
+        // Still throttled right after the retry.
+        assert!(!partial_due(
+            false,
+            mark.since(retried),
+            mark.new_samples(buf.len()),
+            min_speech
+        ));
+    }
+
+    #[test]
+    fn snr_estimate_compares_loud_and_quiet_frames() {
+        // 10 frames of noise at 0.001 then 10 frames of speech at 0.1: 40 dB.
+        let mut samples = vec![0.001f32; 320 * 10];
+        samples.extend(std::iter::repeat_n(0.1f32, 320 * 10));
+        let snr = estimate_snr_db(&samples, 16_000).expect("snr");
+        assert!((snr - 40.0).abs() < 0.5, "snr {snr}");
+
+        assert_eq!(estimate_snr_db(&[0.1; 320], 16_000), None);
+        assert_eq!(estimate_snr_db(&[0.0; 3_200], 16_000), None);
+    }
+
+    #[test]
+    fn flush_inference_skips_empty_and_error_results() {
+        let (_producer, consumer) = create_audio_ring();
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(8);
+        let (status_tx, _) = broadcast::channel(8);
+        let (activity_tx, _) = broadcast::channel(8);
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let model_resets = Arc::new(AtomicUsize::new(0));
+        let model = ModelHandle::new(TestModel {
+            calls,
+            resets: model_resets,
+            empty_partial: true,
+            empty_final: false,
+            fail_final: true,
+        });
+
+        let vad_resets = Arc::new(AtomicUsize::new(0));
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(vec![], vad_resets));
+
+        let seq = Arc::new(AtomicU64::new(0));
+        let mut ctx = PipelineContext {
+            vad,
+            transcript_tx,
+            status_tx,
+            activity_tx,
+            seq: Arc::clone(&seq),
+            ..test_ctx(model, consumer)
+        };
+
+        flush_inference(
+            &mut ctx,
+            &vec![0.1; 960],
+            true,
+            Some("utt-test"),
+            None,
+            &mut 0,
+            None,
+        );
+        flush_inference(
+            &mut ctx,
+            &vec![0.1; 960],
+            false,
+            Some("utt-test"),
+            None,
+            &mut 0,
+            None,
+        );
+
+        assert_no_event_for(&mut transcript_rx, Duration::from_millis(100));
+        assert_eq!(seq.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn run_emits_partial_then_final_on_speech_then_silence() {
+        let (mut producer, consumer) = create_audio_ring();
+        producer.push_slice(&vec![0.2; 960]);
+        producer.push_slice(&vec![0.0; 960]);
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let model_resets = Arc::new(AtomicUsize::new(0));
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::clone(&calls),
+            resets: Arc::clone(&model_resets),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+
+        let vad_resets = Arc::new(AtomicUsize::new(0));
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![VadDecision::Speech, VadDecision::Silence],
+            Arc::clone(&vad_resets),
+        ));
+
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(16);
+        let (status_tx, _) = broadcast::channel(8);
+        let (activity_tx, _) = broadcast::channel(8);
+        let running = Arc::new(AtomicBool::new(true));
+
+        let ctx = PipelineContext {
+            vad,
+            running: Arc::clone(&running),
+            transcript_tx,
+            status_tx,
+            activity_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+
+        let first = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
+        let second = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
+
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        assert_eq!(first.segments[0].kind, SegmentKind::Partial);
+        assert_eq!(second.segments[0].kind, SegmentKind::Final);
+        assert_eq!(first.segments[0].id, second.segments[0].id);
+        assert_eq!(&*calls.lock(), &vec![true, false]);
+        assert_eq!(vad_resets.load(Ordering::Relaxed), 1);
+        assert_eq!(model_resets.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn failed_final_promotes_the_last_partial() {
+        let (mut producer, consumer) = create_audio_ring();
+        producer.push_slice(&vec![0.2; 960]);
+        producer.push_slice(&vec![0.0; 960]);
+
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: true,
+        });
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![VadDecision::Speech, VadDecision::Silence],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let diagnostics = Arc::new(PipelineDiagnostics::default());
+
+        let ctx = PipelineContext {
+            vad,
+            running: Arc::clone(&running),
+            transcript_tx,
+            diagnostics: Arc::clone(&diagnostics),
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let partial = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
+        let last = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        assert_eq!(partial.segments[0].kind, SegmentKind::Partial);
+        assert_eq!(last.segments[0].kind, SegmentKind::Final);
+        assert_eq!(last.segments[0].text, "partial");
+        assert_eq!(last.segments[0].id, partial.segments[0].id);
+        assert_eq!(last.meta.is_fallback, Some(false));
+        assert_eq!(last.meta.revision, Some(2));
+        assert_eq!(diagnostics.fallback_emitted.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn empty_final_reports_why_nothing_was_typed() {
+        let (mut producer, consumer) = create_audio_ring();
+        producer.push_slice(&vec![0.2; 960]);
+        producer.push_slice(&vec![0.0; 960]);
+
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![VadDecision::Speech, VadDecision::Silence],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+        let (status_tx, mut status_rx) = broadcast::channel(8);
+        let running = Arc::new(AtomicBool::new(true));
+
+        let ctx = PipelineContext {
+            vad,
+            running: Arc::clone(&running),
+            status_tx,
+            ..test_ctx(ModelHandle::new(FilteringModel), consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let detail = loop {
+            let event: EngineStatusEvent =
+                recv_event_with_timeout(&mut status_rx, Duration::from_secs(1));
+            if let Some(detail) = event.detail {
+                break detail;
+            }
+        };
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        assert_eq!(detail, "Nothing typed: output filtered as low-quality.");
+    }
+
+    #[test]
+    fn run_forces_final_flush_when_max_speech_samples_reached() {
+        let (mut producer, consumer) = create_audio_ring();
+        producer.push_slice(&vec![0.3; 960]);
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let model_resets = Arc::new(AtomicUsize::new(0));
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::clone(&calls),
+            resets: Arc::clone(&model_resets),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+
+        let vad_resets = Arc::new(AtomicUsize::new(0));
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![VadDecision::Speech],
+            Arc::clone(&vad_resets),
+        ));
+
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(16);
+        let (status_tx, _) = broadcast::channel(8);
+        let (activity_tx, _) = broadcast::channel(8);
+        let running = Arc::new(AtomicBool::new(true));
+
+        let mut cfg = base_config();
+        cfg.min_speech_samples = 4_000;
+        cfg.max_speech_samples = 960;
+
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            running: Arc::clone(&running),
+            transcript_tx,
+            status_tx,
+            activity_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let event = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        assert_eq!(event.segments[0].kind, SegmentKind::Final);
+        assert_eq!(event.segments[0].id, "utt-0");
+        assert_eq!(&*calls.lock(), &vec![false]);
+        assert_eq!(vad_resets.load(Ordering::Relaxed), 1);
+        assert_eq!(model_resets.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn run_forces_final_flush_on_stop_with_buffered_speech() {
         let (mut producer, consumer) = create_audio_ring();
-        producer.push_slice(&vec![0.2; 960]);
-        producer.push_slice(&vec![0.0; 960]);
+        producer.push_slice(&vec![0.3; 960]);
 
         let calls = Arc::new(Mutex::new(Vec::new()));
         let model_resets = Arc::new(AtomicUsize::new(0));
@@ -1095,7 +3749,7 @@ mod tests {
 
         let vad_resets = Arc::new(AtomicUsize::new(0));
         let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
-            vec![VadDecision::Speech, VadDecision::Silence],
+            vec![VadDecision::Speech],
             Arc::clone(&vad_resets),
         ));
 
@@ -1104,29 +3758,30 @@ mod tests {
         let (activity_tx, _) = broadcast::channel(8);
         let running = Arc::new(AtomicBool::new(true));
 
+        let mut cfg = base_config();
+        cfg.min_speech_samples = 960;
+        cfg.max_speech_samples = 8_000;
+
         let ctx = PipelineContext {
-            config: base_config(),
-            model,
+            config: cfg,
             vad,
-            consumer,
             running: Arc::clone(&running),
             transcript_tx,
             status_tx,
             activity_tx,
-            status: Arc::new(Mutex::new(EngineStatus::Idle)),
-            seq: Arc::new(AtomicU64::new(0)),
-            capture_sample_rate: 16_000,
-            diagnostics: Arc::new(PipelineDiagnostics::default()),
+            ..test_ctx(model, consumer)
         };
 
         let handle = thread::spawn(move || run(ctx));
 
-        let first = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
-        let second = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
-
+        // Let one speech chunk process, then stop without a silence frame.
+        std::thread::sleep(Duration::from_millis(30));
         running.store(false, Ordering::SeqCst);
         handle.join().expect("pipeline thread panicked");
 
+        let first = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
+        let second = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
+
         assert_eq!(first.segments[0].kind, SegmentKind::Partial);
         assert_eq!(second.segments[0].kind, SegmentKind::Final);
         assert_eq!(first.segments[0].id, second.segments[0].id);
@@ -1136,7 +3791,7 @@ mod tests {
     }
 
     #[test]
-    fn run_forces_final_flush_when_max_speech_samples_reached() {
+    fn run_discards_buffered_speech_after_reset_request() {
         let (mut producer, consumer) = create_audio_ring();
         producer.push_slice(&vec![0.3; 960]);
 
@@ -1160,99 +3815,160 @@ mod tests {
         let (status_tx, _) = broadcast::channel(8);
         let (activity_tx, _) = broadcast::channel(8);
         let running = Arc::new(AtomicBool::new(true));
+        let reset_requested = Arc::new(AtomicBool::new(false));
 
         let mut cfg = base_config();
-        cfg.min_speech_samples = 4_000;
-        cfg.max_speech_samples = 960;
+        cfg.enable_partial_inference = false;
 
         let ctx = PipelineContext {
             config: cfg,
-            model,
             vad,
-            consumer,
             running: Arc::clone(&running),
             transcript_tx,
             status_tx,
             activity_tx,
-            status: Arc::new(Mutex::new(EngineStatus::Idle)),
-            seq: Arc::new(AtomicU64::new(0)),
-            capture_sample_rate: 16_000,
-            diagnostics: Arc::new(PipelineDiagnostics::default()),
+            reset_requested: Arc::clone(&reset_requested),
+            ..test_ctx(model, consumer)
         };
 
         let handle = thread::spawn(move || run(ctx));
-        let event = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
+
+        // Buffer one speech chunk, discard it, then stop: without the reset
+        // the stop path would force a final flush of that chunk.
+        std::thread::sleep(Duration::from_millis(30));
+        reset_requested.store(true, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(30));
         running.store(false, Ordering::SeqCst);
         handle.join().expect("pipeline thread panicked");
 
-        assert_eq!(event.segments[0].kind, SegmentKind::Final);
-        assert_eq!(event.segments[0].id, "utt-0");
-        assert_eq!(&*calls.lock(), &vec![false]);
+        assert_no_event_for(&mut transcript_rx, Duration::from_millis(50));
+        assert!(calls.lock().is_empty());
+        assert!(!reset_requested.load(Ordering::SeqCst));
         assert_eq!(vad_resets.load(Ordering::Relaxed), 1);
         assert_eq!(model_resets.load(Ordering::Relaxed), 1);
     }
 
+    /// Takes `SLOW_DECODE` per call unless its cancel flag is raised first,
+    /// in which case it gives up like the ONNX decoder does.
+    struct SlowDecodeModel {
+        started: Arc<AtomicBool>,
+        returned: Arc<AtomicBool>,
+        cancel: Option<Arc<AtomicBool>>,
+    }
+
+    const SLOW_DECODE: Duration = Duration::from_secs(2);
+
+    impl SpeechModel for SlowDecodeModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            self.started.store(true, Ordering::SeqCst);
+            let started = Instant::now();
+            let result = loop {
+                if self
+                    .cancel
+                    .as_ref()
+                    .is_some_and(|c| c.load(Ordering::SeqCst))
+                {
+                    break Err(DictumError::Cancelled);
+                }
+                if started.elapsed() >= SLOW_DECODE {
+                    break Ok(vec![TranscriptSegment {
+                        id: String::new(),
+                        text: "stale".into(),
+                        kind: SegmentKind::Final,
+                        confidence: None,
+                        start_ms: None,
+                        end_ms: None,
+                        word_timings: Vec::new(),
+                        language: None,
+                    }]);
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            };
+            self.returned.store(true, Ordering::SeqCst);
+            result
+        }
+
+        fn reset(&mut self) {}
+
+        fn set_cancel_flag(&mut self, flag: Option<Arc<AtomicBool>>) {
+            self.cancel = flag;
+        }
+    }
+
     #[test]
-    fn run_forces_final_flush_on_stop_with_buffered_speech() {
+    fn reset_during_a_slow_decode_cancels_it_and_emits_nothing() {
         let (mut producer, consumer) = create_audio_ring();
-        producer.push_slice(&vec![0.3; 960]);
+        producer.push_slice(&vec![0.2; 960]);
+        producer.push_slice(&vec![0.0; 960]);
 
-        let calls = Arc::new(Mutex::new(Vec::new()));
-        let model_resets = Arc::new(AtomicUsize::new(0));
-        let model = ModelHandle::new(TestModel {
-            calls: Arc::clone(&calls),
-            resets: Arc::clone(&model_resets),
-            empty_partial: false,
-            empty_final: false,
-            fail_final: false,
+        let started = Arc::new(AtomicBool::new(false));
+        let returned = Arc::new(AtomicBool::new(false));
+        let reset_requested = Arc::new(AtomicBool::new(false));
+        let model = ModelHandle::new(SlowDecodeModel {
+            started: Arc::clone(&started),
+            returned: Arc::clone(&returned),
+            cancel: None,
         });
+        // What `DictumEngine::start` does.
+        model
+            .0
+            .lock()
+            .set_cancel_flag(Some(Arc::clone(&reset_requested)));
 
-        let vad_resets = Arc::new(AtomicUsize::new(0));
         let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
-            vec![VadDecision::Speech],
-            Arc::clone(&vad_resets),
+            vec![VadDecision::Speech, VadDecision::Silence],
+            Arc::new(AtomicUsize::new(0)),
         ));
-
         let (transcript_tx, mut transcript_rx) = broadcast::channel(16);
         let (status_tx, _) = broadcast::channel(8);
-        let (activity_tx, _) = broadcast::channel(8);
         let running = Arc::new(AtomicBool::new(true));
+        let diagnostics = Arc::new(PipelineDiagnostics::default());
 
         let mut cfg = base_config();
-        cfg.min_speech_samples = 960;
-        cfg.max_speech_samples = 8_000;
+        cfg.enable_partial_inference = false;
 
         let ctx = PipelineContext {
             config: cfg,
-            model,
             vad,
-            consumer,
             running: Arc::clone(&running),
             transcript_tx,
             status_tx,
-            activity_tx,
-            status: Arc::new(Mutex::new(EngineStatus::Idle)),
-            seq: Arc::new(AtomicU64::new(0)),
-            capture_sample_rate: 16_000,
-            diagnostics: Arc::new(PipelineDiagnostics::default()),
+            diagnostics: Arc::clone(&diagnostics),
+            reset_requested: Arc::clone(&reset_requested),
+            ..test_ctx(model, consumer)
         };
 
         let handle = thread::spawn(move || run(ctx));
 
-        // Let one speech chunk process, then stop without a silence frame.
+        let wait_until = |flag: &AtomicBool| {
+            let deadline = Instant::now() + SLOW_DECODE;
+            while !flag.load(Ordering::SeqCst) {
+                assert!(Instant::now() < deadline, "timed out");
+                std::thread::sleep(Duration::from_millis(2));
+            }
+        };
+        wait_until(&started);
+        let reset_at = Instant::now();
+        reset_requested.store(true, Ordering::SeqCst);
+        wait_until(&returned);
+        let cancel_latency = reset_at.elapsed();
         std::thread::sleep(Duration::from_millis(30));
         running.store(false, Ordering::SeqCst);
         handle.join().expect("pipeline thread panicked");
 
-        let first = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
-        let second = recv_event_with_timeout(&mut transcript_rx, Duration::from_secs(1));
-
-        assert_eq!(first.segments[0].kind, SegmentKind::Partial);
-        assert_eq!(second.segments[0].kind, SegmentKind::Final);
-        assert_eq!(first.segments[0].id, second.segments[0].id);
-        assert_eq!(&*calls.lock(), &vec![true, false]);
-        assert_eq!(vad_resets.load(Ordering::Relaxed), 1);
-        assert_eq!(model_resets.load(Ordering::Relaxed), 1);
+        assert!(cancel_latency < SLOW_DECODE / 2, "{cancel_latency:?}");
+        assert_no_event_for(&mut transcript_rx, Duration::from_millis(50));
+        let snap = diagnostics.snapshot();
+        assert_eq!(snap.inference_errors, 0, "a cancel is not a model failure");
+        assert_eq!(snap.finals_emitted, 0);
     }
 
     #[test]
@@ -1287,17 +4003,12 @@ mod tests {
 
         let ctx = PipelineContext {
             config: cfg,
-            model,
             vad,
-            consumer,
             running: Arc::clone(&running),
             transcript_tx,
             status_tx,
             activity_tx,
-            status: Arc::new(Mutex::new(EngineStatus::Idle)),
-            seq: Arc::new(AtomicU64::new(0)),
-            capture_sample_rate: 16_000,
-            diagnostics: Arc::new(PipelineDiagnostics::default()),
+            ..test_ctx(model, consumer)
         };
 
         let handle = thread::spawn(move || run(ctx));
@@ -1346,18 +4057,14 @@ mod tests {
         cfg.max_speech_samples = 8_000;
 
         let ctx = PipelineContext {
+            vad_threshold: LiveThreshold::new(cfg.vad_threshold),
             config: cfg,
-            model,
             vad,
-            consumer,
             running: Arc::clone(&running),
             transcript_tx,
             status_tx,
             activity_tx,
-            status: Arc::new(Mutex::new(EngineStatus::Idle)),
-            seq: Arc::new(AtomicU64::new(0)),
-            capture_sample_rate: 16_000,
-            diagnostics: Arc::new(PipelineDiagnostics::default()),
+            ..test_ctx(model, consumer)
         };
 
         let handle = thread::spawn(move || run(ctx));
@@ -1372,4 +4079,493 @@ mod tests {
         assert_eq!(vad_resets.load(Ordering::Relaxed), 0);
         assert_eq!(model_resets.load(Ordering::Relaxed), 0);
     }
+
+    #[test]
+    fn rms_activity_follows_a_vad_threshold_changed_mid_run() {
+        let (mut producer, consumer) = create_audio_ring();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::clone(&calls),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![VadDecision::Silence],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let threshold = LiveThreshold::new(0.01);
+
+        let mut cfg = base_config();
+        cfg.vad_threshold = 0.01;
+        let ctx = PipelineContext {
+            config: cfg,
+            vad,
+            vad_threshold: threshold.clone(),
+            running: Arc::clone(&running),
+            transcript_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        std::thread::sleep(Duration::from_millis(10));
+        // As `set_vad_threshold` does: the 0.02 RMS audio that would have
+        // counted at 0.01 (see the rescue test above) is now below it, even
+        // after the input gain boost.
+        threshold.set(0.3);
+        producer.push_slice(&vec![0.02; 960]);
+        std::thread::sleep(Duration::from_millis(30));
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        assert!(calls.lock().is_empty(), "rescue ran: {:?}", calls.lock());
+        assert!(matches!(
+            transcript_rx.try_recv(),
+            Err(TryRecvError::Closed)
+        ));
+    }
+
+    #[test]
+    fn activity_keeps_flowing_while_the_resampler_buffers() {
+        let (mut producer, consumer) = create_audio_ring();
+        let model = ModelHandle::new(TestModel {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            resets: Arc::new(AtomicUsize::new(0)),
+            empty_partial: false,
+            empty_final: false,
+            fail_final: false,
+        });
+        let vad: Box<dyn VoiceActivityDetector> = Box::new(ScriptedVad::new(
+            vec![VadDecision::Silence],
+            Arc::new(AtomicUsize::new(0)),
+        ));
+        let (activity_tx, mut activity_rx) = broadcast::channel(32);
+        let running = Arc::new(AtomicBool::new(true));
+        let diagnostics = Arc::new(PipelineDiagnostics::default());
+
+        let ctx = PipelineContext {
+            vad,
+            running: Arc::clone(&running),
+            activity_tx,
+            // 48 kHz capture: the resampler needs 960 input samples per block.
+            capture_sample_rate: 48_000,
+            diagnostics: Arc::clone(&diagnostics),
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        // Four 300-sample reads: three only fill the resampler's input block.
+        for _ in 0..4 {
+            producer.push_slice(&[0.1; 300]);
+            std::thread::sleep(Duration::from_millis(40));
+        }
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+
+        assert_eq!(diagnostics.frames_in.load(Ordering::Relaxed), 1_200);
+        assert_eq!(diagnostics.vad_windows.load(Ordering::Relaxed), 1);
+        let mut events = Vec::new();
+        while let Ok(event) = activity_rx.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(events.len(), 4, "one activity event per read: {events:?}");
+        assert!(events.iter().enumerate().all(|(i, e)| e.seq == i as u64));
+        assert!(events[..3]
+            .iter()
+            .all(|e| !e.is_speech && (e.rms - 0.1).abs() < 1e-4));
+    }
+
+    #[test]
+    fn slow_guaranteed_subscriber_receives_every_final() {
+        let (_producer, consumer) = create_audio_ring();
+        let final_fanout = FinalFanout::default();
+        let mut slow = final_fanout.subscribe();
+        let (final_tx, mut lossy) = broadcast::channel(2);
+        let ctx = PipelineContext {
+            config: EngineConfig {
+                backpressure: BackpressurePolicy::GuaranteedFinals,
+                ..base_config()
+            },
+            transcript_tx: broadcast::channel(2).0,
+            partial_tx: broadcast::channel(2).0,
+            final_tx,
+            final_fanout,
+            status: Arc::new(Mutex::new(EngineStatus::Listening)),
+            ..test_ctx(
+                ModelHandle::new(crate::inference::stub::StubModel::new()),
+                consumer,
+            )
+        };
+        let event = |kind: SegmentKind, text: String| TranscriptEvent {
+            seq: 0,
+            segments: vec![TranscriptSegment {
+                id: "utt-1".into(),
+                text,
+                kind,
+                confidence: None,
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
+            }],
+            meta: TranscriptMeta::default(),
+        };
+
+        // The consumer stalls between reads while finals keep coming.
+        let reader = thread::spawn(move || {
+            let mut texts = Vec::new();
+            while let Some(event) = slow.blocking_recv() {
+                texts.push(event.segments[0].text.clone());
+                std::thread::sleep(Duration::from_millis(2));
+            }
+            texts
+        });
+        for i in 0..20 {
+            broadcast_transcript(&ctx, event(SegmentKind::Partial, format!("partial {i}")));
+            assert!(broadcast_transcript(
+                &ctx,
+                event(SegmentKind::Final, format!("final {i}"))
+            ));
+        }
+        drop(ctx);
+
+        let texts = reader.join().expect("reader thread panicked");
+        let expected: Vec<String> = (0..20).map(|i| format!("final {i}")).collect();
+        assert_eq!(texts, expected, "no finals lost, partials not queued");
+        // The lossy broadcast subscriber of the same finals lagged.
+        assert!(matches!(lossy.try_recv(), Err(TryRecvError::Lagged(_))));
+    }
+
+    #[test]
+    fn previous_final_is_carried_until_a_long_silence() {
+        let (_producer, consumer) = create_audio_ring();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let model = ModelHandle::new(PromptModel {
+            seen: Arc::clone(&seen),
+            ..PromptModel::default()
+        });
+        let mut ctx = PipelineContext {
+            status: Arc::new(Mutex::new(EngineStatus::Listening)),
+            ..test_ctx(model, consumer)
+        };
+        let mut carry = CarriedContext::new(
+            ContextCarry {
+                max_chars: 200,
+                reset_after_silence_ms: 1_000,
+            },
+            16_000,
+        );
+        let audio = vec![0.1; 960];
+
+        // First final: nothing to carry yet.
+        flush_inference(
+            &mut ctx,
+            &audio,
+            false,
+            Some("utt-0"),
+            Some(960),
+            &mut 0,
+            Some(&mut carry),
+        );
+        // Next utterance 0.1 s later: its partial and final both see it.
+        flush_inference(
+            &mut ctx,
+            &audio,
+            true,
+            Some("utt-1"),
+            Some(3_520),
+            &mut 0,
+            Some(&mut carry),
+        );
+        flush_inference(
+            &mut ctx,
+            &audio,
+            false,
+            Some("utt-1"),
+            Some(4_000),
+            &mut 0,
+            Some(&mut carry),
+        );
+        // 2 s of silence exceeds the 1 s reset window.
+        flush_inference(
+            &mut ctx,
+            &audio,
+            false,
+            Some("utt-2"),
+            Some(37_000),
+            &mut 0,
+            Some(&mut carry),
+        );
+        // Disabled carry leaves the model unprompted.
+        ctx.model.0.lock().reset();
+        flush_inference(
+            &mut ctx,
+            &audio,
+            false,
+            Some("utt-3"),
+            Some(38_000),
+            &mut 0,
+            None,
+        );
+
+        let prompt = |text: &str| Some(text.to_string());
+        assert_eq!(
+            *seen.lock(),
+            vec![
+                None,
+                prompt("utterance 1"),
+                prompt("utterance 1"),
+                None,
+                None,
+            ]
+        );
+
+        // Long finals are cut to `max_chars` from the end, at a word.
+        let mut short = CarriedContext::new(
+            ContextCarry {
+                max_chars: 12,
+                reset_after_silence_ms: 1_000,
+            },
+            16_000,
+        );
+        short.record("  the quick brown fox ", Some(100));
+        assert_eq!(short.prompt_for(Some(200)), Some("brown fox"));
+        short.record("jumped over", Some(300));
+        assert_eq!(short.prompt_for(Some(400)), Some("jumped over"));
+    }
+
+    #[test]
+    fn multi_segment_finals_reach_subscribers_intact() {
+        for handling in [SegmentHandling::Preserve, SegmentHandling::Merge] {
+            let (_producer, consumer) = create_audio_ring();
+            let (final_tx, mut final_rx) = broadcast::channel(4);
+            let mut ctx = PipelineContext {
+                config: EngineConfig {
+                    segment_handling: handling,
+                    ..base_config()
+                },
+                transcript_tx: broadcast::channel(4).0,
+                partial_tx: broadcast::channel(4).0,
+                final_tx,
+                status_tx: broadcast::channel(4).0,
+                activity_tx: broadcast::channel(4).0,
+                status: Arc::new(Mutex::new(EngineStatus::Listening)),
+                ..test_ctx(ModelHandle::new(SplitModel), consumer)
+            };
+
+            flush_inference(
+                &mut ctx,
+                &vec![0.1; 25_600],
+                false,
+                Some("utt-split"),
+                Some(25_600),
+                &mut 0,
+                None,
+            );
+            let event = final_rx.try_recv().expect("final emitted");
+            let summary: Vec<_> = event
+                .segments
+                .iter()
+                .map(|s| {
+                    (
+                        s.id.as_str(),
+                        s.text.as_str(),
+                        s.confidence,
+                        s.start_ms,
+                        s.end_ms,
+                    )
+                })
+                .collect();
+            match handling {
+                SegmentHandling::Preserve => assert_eq!(
+                    summary,
+                    vec![
+                        ("utt-split", "First one.", Some(0.9), Some(0), Some(800)),
+                        (
+                            "utt-split",
+                            "Second one.",
+                            Some(0.5),
+                            Some(900),
+                            Some(1_600)
+                        ),
+                    ]
+                ),
+                SegmentHandling::Merge => assert_eq!(
+                    summary,
+                    vec![(
+                        "utt-split",
+                        "First one. Second one.",
+                        Some(0.7),
+                        Some(0),
+                        Some(1_600)
+                    )]
+                ),
+            }
+            assert_eq!(
+                ctx.diagnostics.snapshot().segments_emitted,
+                event.segments.len()
+            );
+        }
+    }
+
+    #[test]
+    fn pause_punctuation_marks_finals_by_the_silence_after_them() {
+        let (mut producer, consumer) = create_audio_ring();
+        let mut decisions = Vec::new();
+        // 960-sample chunks at 16 kHz are 60 ms: pauses of 300 ms, 720 ms,
+        // then a stop 60 ms after the last word.
+        for (speech, silence) in [(2, 5), (2, 12), (2, 1)] {
+            producer.push_slice(&vec![0.3; 960 * speech]);
+            producer.push_slice(&vec![0.0; 960 * silence]);
+            decisions.extend(std::iter::repeat_n(VadDecision::Speech, speech));
+            decisions.extend(std::iter::repeat_n(VadDecision::Silence, silence));
+        }
+        let model = ModelHandle::new(SpokenModel(
+            vec!["send the report", "then call Sam", "and Alex"].into(),
+        ));
+        let (final_tx, mut final_rx) = broadcast::channel(16);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut cfg = base_config();
+        cfg.enable_partial_inference = false;
+        cfg.pause_punctuation = Some(PausePunctuation::default());
+        let ctx = PipelineContext {
+            config: cfg,
+            vad: Box::new(ScriptedVad::new(decisions, Arc::new(AtomicUsize::new(0)))),
+            running: Arc::clone(&running),
+            final_tx,
+            ..test_ctx(model, consumer)
+        };
+
+        let handle = thread::spawn(move || run(ctx));
+        let text = |event: TranscriptEvent| event.segments[0].text.clone();
+        let first = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        let second = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        assert_eq!(text(first), "send the report,");
+        assert_eq!(text(second), "then call Sam.");
+        // The last final waits for the pause after it, cut short by stop.
+        assert!(final_rx.try_recv().is_err());
+        running.store(false, Ordering::SeqCst);
+        handle.join().expect("pipeline thread panicked");
+        let last = recv_event_with_timeout(&mut final_rx, Duration::from_secs(1));
+        assert_eq!(text(last), "And Alex");
+    }
+
+    #[test]
+    fn sequence_counters_wrap_consistently_near_max() {
+        let (_producer, consumer) = create_audio_ring();
+        let (transcript_tx, mut transcript_rx) = broadcast::channel(4);
+        let mut ctx = PipelineContext {
+            transcript_tx,
+            partial_tx: broadcast::channel(4).0,
+            final_tx: broadcast::channel(4).0,
+            status_tx: broadcast::channel(4).0,
+            activity_tx: broadcast::channel(4).0,
+            status: Arc::new(Mutex::new(EngineStatus::Listening)),
+            seq: Arc::new(AtomicU64::new(u64::MAX)),
+            ..test_ctx(
+                ModelHandle::new(crate::inference::stub::StubModel::new()),
+                consumer,
+            )
+        };
+        for id in ["utt-a", "utt-b"] {
+            flush_inference(
+                &mut ctx,
+                &vec![0.1; 960],
+                false,
+                Some(id),
+                None,
+                &mut 0,
+                None,
+            );
+        }
+        let first = transcript_rx.try_recv().expect("first event").seq;
+        let second = transcript_rx.try_recv().expect("second event").seq;
+        assert_eq!((first, second), (u64::MAX, 0));
+        assert!(seq_follows(second, first));
+
+        let mut activity_seq = u64::MAX;
+        let emitted = [
+            advance_seq(&mut activity_seq),
+            advance_seq(&mut activity_seq),
+        ];
+        assert_eq!(emitted, [first, second]);
+        assert!(seq_follows(emitted[1], emitted[0]));
+    }
+
+    #[test]
+    fn low_level_noise_is_not_boosted_into_speech() {
+        use crate::vad::energy::EnergyVad;
+
+        let vad_threshold = EngineConfig::default().vad_threshold;
+        let mut state = 0x2545_f491_u32;
+        let hiss: Vec<f32> = (0..16_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * 0.004
+            })
+            .collect();
+        assert!(compute_rms(&hiss) < vad_threshold);
+
+        let speech_chunks = |limits: GainLimits| {
+            let mut vad = EnergyVad::new(vad_threshold, 0);
+            hiss.chunks(320)
+                .filter(|chunk| {
+                    let mut samples = chunk.to_vec();
+                    apply_adaptive_input_gain(&mut samples, vad_threshold, &limits);
+                    let chunk = AudioChunk::new(samples, 16_000);
+                    matches!(vad.classify(&chunk), VadDecision::Speech)
+                })
+                .count()
+        };
+        assert_eq!(speech_chunks(GainLimits::default()), 0);
+        // Without the hiss guard the same noise is boosted over the threshold.
+        let unguarded = GainLimits {
+            hiss_correlation: None,
+            ..GainLimits::default()
+        };
+        assert_eq!(speech_chunks(unguarded), 50);
+    }
+
+    #[test]
+    fn quiet_speech_over_noise_gets_the_same_boost_with_the_hiss_guard() {
+        // A 140 Hz voice with eight harmonics and a 4 Hz syllable envelope,
+        // over white noise about 10 dB down (0 dB in the envelope's dips).
+        let mut state = 0x2545_f491_u32;
+        let noisy_speech: Vec<f32> = (0..16_000)
+            .map(|i| {
+                let t = i as f32 / 16_000.0;
+                let envelope = 0.65 - 0.35 * (std::f32::consts::TAU * 4.0 * t).cos();
+                let voice: f32 = (1..=8)
+                    .map(|k| (std::f32::consts::TAU * 140.0 * k as f32 * t).sin() / k as f32)
+                    .sum();
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                let noise = (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * 0.0015;
+                0.004 * envelope * voice + noise
+            })
+            .collect();
+
+        let vad_threshold = EngineConfig::default().vad_threshold;
+        let unguarded = GainLimits {
+            hiss_correlation: None,
+            ..GainLimits::default()
+        };
+        for (i, chunk) in noisy_speech.chunks(320).enumerate() {
+            let mut guarded_out = chunk.to_vec();
+            apply_adaptive_input_gain(&mut guarded_out, vad_threshold, &GainLimits::default());
+            let mut unguarded_out = chunk.to_vec();
+            apply_adaptive_input_gain(&mut unguarded_out, vad_threshold, &unguarded);
+            assert!(
+                compute_rms(&unguarded_out) > compute_rms(chunk) * 1.5,
+                "chunk {i} not boosted at all"
+            );
+            assert_eq!(guarded_out, unguarded_out, "chunk {i}");
+        }
+    }
 }
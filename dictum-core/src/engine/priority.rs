@@ -0,0 +1,81 @@
+//! OS scheduling priority for the pipeline thread.
+//!
+//! On a loaded machine a normal-priority pipeline can fall behind the audio
+//! callback, which runs at real-time (Windows: `TIME_CRITICAL`) priority;
+//! the ring then fills and frames are dropped. Raising the pipeline above
+//! normal, but below the callback, keeps draining ahead of capture at the
+//! cost of taking CPU from the rest of the system while inference runs.
+//! Embedders get the default scheduler unless they opt in.
+//!
+//! Tokio's blocking pool reuses threads across tasks, so a raised pipeline
+//! runs on a dedicated thread instead, keeping the priority from leaking
+//! into unrelated work.
+//!
+//! This crate forbids `unsafe`, so the built-in setter only covers Linux,
+//! where `rustix` wraps per-thread `setpriority` safely. Elsewhere it is a
+//! no-op; hosts can supply their own with
+//! [`DictumEngine::set_thread_priority_fn`](super::DictumEngine::set_thread_priority_fn).
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+/// Scheduling priority requested for the pipeline thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelinePriority {
+    /// Leave the thread at the OS default.
+    #[default]
+    Normal,
+    /// Above normal, below the audio callback.
+    AboveNormal,
+}
+
+/// Applies a [`PipelinePriority`] to the calling thread. Returns whether the
+/// priority was changed (`false` for a no-op).
+pub type ThreadPriorityFn = fn(PipelinePriority) -> io::Result<bool>;
+
+/// Linux nice value used for [`PipelinePriority::AboveNormal`].
+#[cfg(target_os = "linux")]
+const ABOVE_NORMAL_NICE: i32 = -5;
+
+/// The built-in [`ThreadPriorityFn`]: per-thread nice on Linux, a no-op on
+/// other platforms.
+///
+/// # Errors
+/// The OS error when the priority cannot be raised, typically
+/// `PermissionDenied` without `CAP_SYS_NICE` or a matching `RLIMIT_NICE`.
+pub fn set_current_thread_priority(priority: PipelinePriority) -> io::Result<bool> {
+    if priority == PipelinePriority::Normal {
+        return Ok(false);
+    }
+    #[cfg(target_os = "linux")]
+    {
+        rustix::process::setpriority_process(Some(rustix::thread::gettid()), ABOVE_NORMAL_NICE)?;
+        Ok(true)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setting_priority_succeeds_or_reports_a_permission_error() {
+        assert!(!set_current_thread_priority(PipelinePriority::Normal).expect("no-op"));
+
+        // Run on a throwaway thread so the test runner's threads keep theirs.
+        let raised =
+            std::thread::spawn(|| set_current_thread_priority(PipelinePriority::AboveNormal))
+                .join()
+                .expect("priority thread panicked");
+        match raised {
+            Ok(changed) => assert_eq!(changed, cfg!(target_os = "linux")),
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::PermissionDenied, "{e}"),
+        }
+    }
+}
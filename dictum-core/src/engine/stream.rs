@@ -0,0 +1,148 @@
+//! One receiver for every engine event.
+//!
+//! Hosts that forward everything to a UI otherwise subscribe to transcripts,
+//! status and activity separately and run a loop per channel. The merged
+//! stream polls all of them in a fixed priority order (status, transcript,
+//! activity, diagnostics), so one loop sees a consistent interleaving.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
+
+use super::pipeline::{DiagnosticsSnapshot, PipelineDiagnostics};
+use crate::ipc::events::{AudioActivityEvent, EngineStatusEvent, TranscriptEvent};
+
+/// Default period of `EngineEvent::Diagnostics` snapshots.
+pub const DEFAULT_DIAGNOSTICS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Any event the engine publishes.
+#[derive(Debug, Clone)]
+pub enum EngineEvent {
+    Transcript(TranscriptEvent),
+    Status(EngineStatusEvent),
+    Activity(AudioActivityEvent),
+    /// Periodic snapshot of pipeline counters and stage timings.
+    Diagnostics(Box<DiagnosticsSnapshot>),
+}
+
+/// Merged receiver returned by `DictumEngine::subscribe_all`.
+pub struct EngineEventStream {
+    transcripts: Option<broadcast::Receiver<TranscriptEvent>>,
+    status: Option<broadcast::Receiver<EngineStatusEvent>>,
+    activity: Option<broadcast::Receiver<AudioActivityEvent>>,
+    diagnostics: Arc<PipelineDiagnostics>,
+    diagnostics_interval: Option<Duration>,
+    next_diagnostics: Option<Instant>,
+}
+
+enum Next {
+    Transcript(TranscriptEvent),
+    Status(EngineStatusEvent),
+    Activity(AudioActivityEvent),
+    DiagnosticsDue,
+    Lagged(u64),
+    TranscriptsClosed,
+    StatusClosed,
+    ActivityClosed,
+}
+
+impl EngineEventStream {
+    pub(crate) fn new(
+        transcripts: broadcast::Receiver<TranscriptEvent>,
+        status: broadcast::Receiver<EngineStatusEvent>,
+        activity: broadcast::Receiver<AudioActivityEvent>,
+        diagnostics: Arc<PipelineDiagnostics>,
+    ) -> Self {
+        Self {
+            transcripts: Some(transcripts),
+            status: Some(status),
+            activity: Some(activity),
+            diagnostics,
+            diagnostics_interval: Some(DEFAULT_DIAGNOSTICS_INTERVAL),
+            next_diagnostics: None,
+        }
+    }
+
+    /// Change how often diagnostics snapshots are produced. `None` turns
+    /// them off.
+    pub fn with_diagnostics_interval(mut self, interval: Option<Duration>) -> Self {
+        self.diagnostics_interval = interval.filter(|i| !i.is_zero());
+        self.next_diagnostics = None;
+        self
+    }
+
+    /// Wait for the next event from any source.
+    ///
+    /// # Errors
+    /// `RecvError::Lagged(n)` when this receiver fell behind on one of the
+    /// channels and `n` events were dropped (the stream stays usable), and
+    /// `RecvError::Closed` once the engine is gone and every channel drained.
+    pub async fn recv(&mut self) -> Result<EngineEvent, RecvError> {
+        loop {
+            if self.transcripts.is_none() && self.status.is_none() && self.activity.is_none() {
+                return Err(RecvError::Closed);
+            }
+            let diagnostics_at = self.diagnostics_interval.map(|interval| {
+                *self
+                    .next_diagnostics
+                    .get_or_insert_with(|| Instant::now() + interval)
+            });
+
+            let next = tokio::select! {
+                biased;
+                r = recv_from(&mut self.status) => match r {
+                    Ok(event) => Next::Status(event),
+                    Err(RecvError::Lagged(n)) => Next::Lagged(n),
+                    Err(RecvError::Closed) => Next::StatusClosed,
+                },
+                r = recv_from(&mut self.transcripts) => match r {
+                    Ok(event) => Next::Transcript(event),
+                    Err(RecvError::Lagged(n)) => Next::Lagged(n),
+                    Err(RecvError::Closed) => Next::TranscriptsClosed,
+                },
+                r = recv_from(&mut self.activity) => match r {
+                    Ok(event) => Next::Activity(event),
+                    Err(RecvError::Lagged(n)) => Next::Lagged(n),
+                    Err(RecvError::Closed) => Next::ActivityClosed,
+                },
+                _ = sleep_until_opt(diagnostics_at), if diagnostics_at.is_some() => {
+                    Next::DiagnosticsDue
+                }
+            };
+
+            match next {
+                Next::Transcript(event) => return Ok(EngineEvent::Transcript(event)),
+                Next::Status(event) => return Ok(EngineEvent::Status(event)),
+                Next::Activity(event) => return Ok(EngineEvent::Activity(event)),
+                Next::DiagnosticsDue => {
+                    self.next_diagnostics = self
+                        .diagnostics_interval
+                        .map(|interval| Instant::now() + interval);
+                    return Ok(EngineEvent::Diagnostics(Box::new(
+                        self.diagnostics.snapshot(),
+                    )));
+                }
+                Next::Lagged(n) => return Err(RecvError::Lagged(n)),
+                Next::TranscriptsClosed => self.transcripts = None,
+                Next::StatusClosed => self.status = None,
+                Next::ActivityClosed => self.activity = None,
+            }
+        }
+    }
+}
+
+async fn recv_from<T: Clone>(rx: &mut Option<broadcast::Receiver<T>>) -> Result<T, RecvError> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn sleep_until_opt(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
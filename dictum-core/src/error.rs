@@ -18,12 +18,27 @@ pub enum DictumError {
     #[error("inference error: {0}")]
     Inference(String),
 
+    #[error("invalid engine config: {0}")]
+    InvalidConfig(String),
+
     #[error("engine is already running")]
     AlreadyRunning,
 
     #[error("engine is not running")]
     NotRunning,
 
+    #[error("speech model is not warmed up — call warm_up() first")]
+    NotWarmedUp,
+
+    #[error("speech model warm-up did not finish within {0:?}")]
+    WarmUpTimeout(std::time::Duration),
+
+    #[error("inference cancelled")]
+    Cancelled,
+
+    #[error("invalid WAV file: {0}")]
+    InvalidWav(String),
+
     #[error("ONNX session error: {0}")]
     OnnxSession(String),
 
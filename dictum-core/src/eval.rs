@@ -0,0 +1,71 @@
+//! Transcript accuracy metrics for benchmarking model profiles.
+
+/// Lowercase, strip punctuation, and split into words for scoring.
+///
+/// Apostrophes are kept so contractions ("don't") score as one word.
+pub fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Word error rate: `(substitutions + deletions + insertions) / reference words`.
+///
+/// Both strings are normalized with [`normalize_words`] first. Returns `None`
+/// when the reference has no words, since the rate is undefined. The value can
+/// exceed 1.0 when the hypothesis inserts many extra words.
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> Option<f32> {
+    let reference = normalize_words(reference);
+    let hypothesis = normalize_words(hypothesis);
+    if reference.is_empty() {
+        return None;
+    }
+    Some(edit_distance(&reference, &hypothesis) as f32 / reference.len() as f32)
+}
+
+/// Levenshtein distance over word tokens, single rolling row.
+fn edit_distance(reference: &[String], hypothesis: &[String]) -> usize {
+    let mut row: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, ref_word) in reference.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, hyp_word) in hypothesis.iter().enumerate() {
+            let substitution = diagonal + usize::from(ref_word != hyp_word);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[hypothesis.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wer_counts_substitution_deletion_and_insertion() {
+        // "quick" -> "quack" (sub), "brown" dropped (del), "today" added (ins).
+        let wer = word_error_rate("The quick brown fox jumps.", "the quack fox jumps today")
+            .expect("wer");
+        assert!((wer - 3.0 / 5.0).abs() < 1e-6, "wer={wer}");
+    }
+
+    #[test]
+    fn wer_ignores_case_and_punctuation() {
+        let wer =
+            word_error_rate("Hello, world! Don't stop.", "hello world don't stop").expect("wer");
+        assert_eq!(wer, 0.0);
+    }
+
+    #[test]
+    fn wer_is_undefined_for_empty_reference() {
+        assert_eq!(word_error_rate("  ...  ", "anything"), None);
+        assert_eq!(word_error_rate("one two", ""), Some(1.0));
+    }
+}
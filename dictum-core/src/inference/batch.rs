@@ -0,0 +1,262 @@
+//! Bounded parallel transcription of many files.
+//!
+//! Decoder state is per model instance, so concurrency comes from a pool of
+//! independent models rather than from sharing one session: each worker
+//! thread owns one `ModelHandle` and pulls the next file from a shared queue.
+//! Memory use is therefore bounded by the pool size, not by the batch size.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use tracing::debug;
+
+use crate::error::{DictumError, Result};
+use crate::inference::{transcribe_file, ModelHandle, SpeechModel};
+use crate::ipc::events::TranscriptSegment;
+
+/// Reported once per file as it finishes, from the worker that ran it.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchProgress<'a> {
+    /// Position of the file in the input list.
+    pub index: usize,
+    pub path: &'a Path,
+    /// Files finished so far, including this one.
+    pub completed: usize,
+    pub total: usize,
+    pub ok: bool,
+}
+
+/// Transcribes a list of WAV files on a fixed pool of models.
+pub struct BatchTranscriber {
+    workers: Vec<ModelHandle>,
+}
+
+impl BatchTranscriber {
+    /// Use `workers` as the pool; its length is the concurrency limit. The
+    /// models must already be warmed up.
+    ///
+    /// # Errors
+    /// `InvalidConfig` when `workers` is empty, `NotWarmedUp` when any of
+    /// them is cold.
+    pub fn new(workers: Vec<ModelHandle>) -> Result<Self> {
+        if workers.is_empty() {
+            return Err(DictumError::InvalidConfig(
+                "batch transcription needs at least one worker model".into(),
+            ));
+        }
+        if !workers.iter().all(ModelHandle::is_warm) {
+            return Err(DictumError::NotWarmedUp);
+        }
+        Ok(Self { workers })
+    }
+
+    /// Build a pool of `concurrency` models from `make_model`, warming each
+    /// one up.
+    ///
+    /// # Errors
+    /// `InvalidConfig` when `concurrency` is zero, or the first error from
+    /// creating or warming up a model.
+    pub fn from_factory<M, F>(concurrency: usize, mut make_model: F) -> Result<Self>
+    where
+        M: SpeechModel,
+        F: FnMut() -> Result<M>,
+    {
+        if concurrency == 0 {
+            return Err(DictumError::InvalidConfig(
+                "batch concurrency must be at least 1".into(),
+            ));
+        }
+        let workers = (0..concurrency)
+            .map(|_| {
+                let mut model = make_model()?;
+                model.warm_up()?;
+                Ok(ModelHandle::new(model))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(workers)
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Transcribe every file, at most `concurrency()` at a time. Results are
+    /// in input order; a failing file does not stop the rest.
+    pub fn run(
+        &self,
+        files: &[PathBuf],
+        on_progress: impl Fn(BatchProgress<'_>) + Sync,
+    ) -> Vec<Result<Vec<TranscriptSegment>>> {
+        let total = files.len();
+        let next = AtomicUsize::new(0);
+        let completed = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::scope(|scope| {
+            for (worker_id, model) in self.workers.iter().take(total).enumerate() {
+                let tx = tx.clone();
+                let (next, completed, on_progress) = (&next, &completed, &on_progress);
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = files.get(index) else {
+                        break;
+                    };
+                    debug!(worker_id, index, path = ?path, "batch transcription started");
+                    let result = transcribe_file(&mut *model.0.lock(), path);
+                    on_progress(BatchProgress {
+                        index,
+                        path,
+                        completed: completed.fetch_add(1, Ordering::Relaxed) + 1,
+                        total,
+                        ok: result.is_ok(),
+                    });
+                    let _ = tx.send((index, result));
+                });
+            }
+        });
+        drop(tx);
+
+        let mut results: Vec<Option<Result<Vec<TranscriptSegment>>>> =
+            (0..total).map(|_| None).collect();
+        for (index, result) in rx {
+            results[index] = Some(result);
+        }
+        results
+            .into_iter()
+            .map(|result| result.expect("every batch index is processed exactly once"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use parking_lot::Mutex;
+
+    use super::BatchTranscriber;
+    use crate::buffering::chunk::AudioChunk;
+    use crate::error::Result;
+    use crate::inference::SpeechModel;
+    use crate::ipc::events::{SegmentKind, TranscriptSegment};
+
+    /// Reports each file's length as its text. Shorter files finish first so
+    /// completion order differs from input order.
+    struct LengthModel {
+        active: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    impl SpeechModel for LengthModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            chunk: &AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            let now_active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now_active, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(chunk.samples.len() as u64 / 40));
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            Ok(vec![TranscriptSegment {
+                id: String::new(),
+                text: chunk.samples.len().to_string(),
+                kind: SegmentKind::Final,
+                confidence: None,
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
+            }])
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn write_test_wav(index: usize, samples: usize) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("dictum-batch-{}-{index}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).expect("create wav");
+        for _ in 0..samples {
+            writer.write_sample(100_i16).expect("write");
+        }
+        writer.finalize().expect("finalize");
+        path
+    }
+
+    #[test]
+    fn concurrent_batch_preserves_input_order() {
+        let lengths = [2_400, 400, 1_600, 800, 2_000, 1_200];
+        let files: Vec<PathBuf> = lengths
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| write_test_wav(i, n))
+            .collect();
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let batch = BatchTranscriber::from_factory(3, || {
+            Ok(LengthModel {
+                active: Arc::clone(&active),
+                peak: Arc::clone(&peak),
+            })
+        })
+        .expect("build pool");
+
+        let progress = Mutex::new(Vec::new());
+        let results = batch.run(&files, |p| {
+            assert_eq!(p.total, lengths.len());
+            progress.lock().push((p.index, p.completed, p.ok));
+        });
+        for file in &files {
+            let _ = std::fs::remove_file(file);
+        }
+
+        let texts: Vec<String> = results
+            .into_iter()
+            .map(|r| r.expect("transcribe").remove(0).text)
+            .collect();
+        let expected: Vec<String> = lengths.iter().map(|n| n.to_string()).collect();
+        assert_eq!(texts, expected);
+
+        let progress = progress.into_inner();
+        let mut completed: Vec<usize> = progress.iter().map(|&(_, c, _)| c).collect();
+        completed.sort_unstable();
+        assert_eq!(completed, (1..=lengths.len()).collect::<Vec<_>>());
+        assert!(progress.iter().all(|&(_, _, ok)| ok));
+
+        let peak = peak.load(Ordering::SeqCst);
+        assert!((2..=3).contains(&peak), "peak concurrency {peak}");
+    }
+
+    #[test]
+    fn missing_file_fails_alone() {
+        let good = write_test_wav(100, 400);
+        let files = vec![PathBuf::from("/nonexistent/dictum.wav"), good.clone()];
+        let batch = BatchTranscriber::from_factory(2, || {
+            Ok(LengthModel {
+                active: Arc::new(AtomicUsize::new(0)),
+                peak: Arc::new(AtomicUsize::new(0)),
+            })
+        })
+        .expect("build pool");
+
+        let results = batch.run(&files, |_| {});
+        let _ = std::fs::remove_file(&good);
+        assert!(results[0].is_err());
+        assert_eq!(results[1].as_ref().expect("second file")[0].text, "400");
+    }
+}
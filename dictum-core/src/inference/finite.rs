@@ -0,0 +1,85 @@
+//! Cheap NaN/Inf guards for tensors coming out of (or going into) a model.
+//!
+//! Some GPU execution providers occasionally emit NaNs. Argmax over a row
+//! containing NaN silently picks an arbitrary token, so decode carries on and
+//! produces garbage. These helpers turn that into a `DictumError::Inference`.
+
+use crate::error::{DictumError, Result};
+
+/// Default number of probes for [`ensure_finite_sampled`].
+pub const DEFAULT_FINITE_PROBES: usize = 4096;
+
+/// Check every element of `data`. Use for small slices such as a single
+/// logit row.
+pub fn ensure_finite(data: &[f32], what: &str) -> Result<()> {
+    match data.iter().position(|v| !v.is_finite()) {
+        Some(index) => Err(non_finite_error(what, index, data[index])),
+        None => Ok(()),
+    }
+}
+
+/// Check roughly `max_probes` evenly spaced elements of `data`, always
+/// including the last one. NaNs from a broken kernel tend to spread across
+/// the whole tensor, so a strided scan catches them without touching every
+/// value.
+pub fn ensure_finite_sampled(data: &[f32], max_probes: usize, what: &str) -> Result<()> {
+    if data.len() <= max_probes.max(1) {
+        return ensure_finite(data, what);
+    }
+    let stride = data.len().div_ceil(max_probes.max(1));
+    let last = data.len() - 1;
+    for index in (0..data.len()).step_by(stride).chain(std::iter::once(last)) {
+        let v = data[index];
+        if !v.is_finite() {
+            return Err(non_finite_error(what, index, v));
+        }
+    }
+    Ok(())
+}
+
+fn non_finite_error(what: &str, index: usize, value: f32) -> DictumError {
+    DictumError::Inference(format!(
+        "non-finite value {value} in {what} at index {index}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ensure_finite, ensure_finite_sampled};
+    use crate::error::DictumError;
+
+    #[test]
+    fn nan_in_logit_row_is_an_inference_error() {
+        let mut row = vec![0.5f32; 51_865];
+        assert!(ensure_finite(&row, "logits").is_ok());
+
+        row[17] = f32::NAN;
+        match ensure_finite(&row, "logits") {
+            Err(DictumError::Inference(msg)) => {
+                assert!(msg.contains("logits"), "{msg}");
+                assert!(msg.contains("index 17"), "{msg}");
+            }
+            other => panic!("expected inference error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn sampled_scan_hits_stride_points_and_last_element() {
+        let mut data = vec![1.0f32; 10_000];
+        assert!(ensure_finite_sampled(&data, 100, "encoder").is_ok());
+
+        data[9_999] = f32::INFINITY;
+        assert!(ensure_finite_sampled(&data, 100, "encoder").is_err());
+
+        data[9_999] = 1.0;
+        data[500] = f32::NEG_INFINITY; // stride is 100
+        assert!(ensure_finite_sampled(&data, 100, "encoder").is_err());
+    }
+
+    #[test]
+    fn sampled_scan_checks_everything_when_small() {
+        let data = [0.0, 1.0, f32::NAN];
+        assert!(ensure_finite_sampled(&data, 4096, "mel").is_err());
+        assert!(ensure_finite_sampled(&[], 4096, "mel").is_ok());
+    }
+}
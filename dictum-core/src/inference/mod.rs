@@ -7,21 +7,40 @@
 //! stateful — beam search caches, RNN hidden states, etc. All mutation is
 //! therefore serialised through `ModelHandle`'s `parking_lot::Mutex`.
 
+pub mod batch;
+pub mod finite;
+pub mod postprocess;
 pub mod stub;
 
 #[cfg(feature = "onnx")]
 pub mod onnx;
 
+#[cfg(feature = "whisper-cpp")]
+pub mod whisper_cpp;
+
+pub use batch::{BatchProgress, BatchTranscriber};
+
 #[cfg(feature = "onnx")]
-pub use onnx::{OnnxModel, OnnxModelConfig};
+pub use crate::audio::wav::WavSampleFormat;
+#[cfg(feature = "onnx")]
+pub use onnx::{
+    DecodeStrategy, DecodeTask, OnnxModel, OnnxModelConfig, PrefixSearchConfig,
+    TokenizerDecodeConfig, TruncationRetryConfig,
+};
+
+#[cfg(feature = "whisper-cpp")]
+pub use whisper_cpp::{WhisperCppConfig, WhisperCppModel};
 
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use parking_lot::Mutex;
 
+use crate::audio::wav::read_wav_mono;
 use crate::buffering::chunk::AudioChunk;
-use crate::error::Result;
-use crate::ipc::events::TranscriptSegment;
+use crate::error::{DictumError, Result};
+use crate::ipc::events::{ModelCapabilities, TranscriptMeta, TranscriptSegment};
 
 /// Contract for speech recognition backends.
 pub trait SpeechModel: Send + 'static {
@@ -32,6 +51,21 @@ pub trait SpeechModel: Send + 'static {
     /// Returns an error if model files are missing or corrupt.
     fn warm_up(&mut self) -> Result<()>;
 
+    /// Whether `warm_up` has loaded everything `transcribe` needs. Default:
+    /// always warm, for backends with nothing to load.
+    fn is_warm(&self) -> bool {
+        true
+    }
+
+    /// What this model supports, as far as is known after `warm_up`.
+    /// Default: an `"unknown"` backend with no optional features.
+    fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            backend: "unknown".into(),
+            ..ModelCapabilities::default()
+        }
+    }
+
     /// Transcribe a mono f32 audio chunk.
     ///
     /// # Parameters
@@ -41,10 +75,114 @@ pub trait SpeechModel: Send + 'static {
     ///
     /// # Returns
     /// A list of `TranscriptSegment`s. May be empty if no speech was detected.
+    /// Leave each segment's `id` empty: ids are owned by the caller (the
+    /// pipeline stamps the utterance id, `transcribe_file` a per-file id).
     fn transcribe(&mut self, chunk: &AudioChunk, partial: bool) -> Result<Vec<TranscriptSegment>>;
 
+    /// Like `transcribe`, but decoding winds down early once `running` is
+    /// cleared, returning what it has so far rather than an error. The
+    /// pipeline passes its running flag so `stop()` does not wait out a
+    /// long final. Default: ignores `running`.
+    fn transcribe_cancellable(
+        &mut self,
+        chunk: &AudioChunk,
+        partial: bool,
+        running: Arc<AtomicBool>,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let _ = running;
+        self.transcribe(chunk, partial)
+    }
+
     /// Reset all internal decoder state (e.g. between utterances).
     fn reset(&mut self);
+
+    /// Metadata about the most recent `transcribe` call (language, which
+    /// recogniser produced the text, ...). The pipeline adds timing and
+    /// signal measurements on top. Default: nothing known.
+    fn last_transcript_meta(&self) -> TranscriptMeta {
+        TranscriptMeta::default()
+    }
+
+    /// Run a minimal dummy inference so the execution provider stays warm
+    /// during long silences. Must not touch decoder state used by
+    /// `transcribe`. Default: no-op, for backends that do not idle down.
+    ///
+    /// # Errors
+    /// Returns an error if the dummy inference fails.
+    fn keep_warm(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by the engine at the start of every listening session, so
+    /// per-session state (budgets, counters) can start fresh. Default: no-op.
+    fn begin_session(&mut self) {}
+
+    /// Watch `flag` during `transcribe`: once it is raised, a decode in
+    /// progress gives up between steps with `DictumError::Cancelled`. The
+    /// engine installs its session-reset flag here. Default: ignored, and
+    /// the pipeline drops the late result instead.
+    fn set_cancel_flag(&mut self, flag: Option<Arc<AtomicBool>>) {
+        let _ = flag;
+    }
+
+    /// Give finals short enough to be one-word answers the backend's
+    /// dedicated short-utterance decode. The engine enables it at start
+    /// when `short_utterance_pad_ms` pads such finals. Default: ignored.
+    fn set_short_utterance_decode(&mut self, enabled: bool) {
+        let _ = enabled;
+    }
+
+    /// Condition the following `transcribe` calls on `context`, typically
+    /// the previous utterance's text (Whisper's `<|startofprev|>` prompt).
+    /// `None` clears it, as does `reset`. Default: ignored.
+    fn set_prompt_context(&mut self, context: Option<&str>) {
+        let _ = context;
+    }
+
+    /// Force the decode language to `language` (a code such as `"zh"`), or
+    /// auto-detect with `None`. Applies from the next `transcribe` call.
+    /// Default: ignored, for backends without language control.
+    ///
+    /// # Errors
+    /// `InvalidConfig` if the backend does not support `language`.
+    fn set_language_hint(&mut self, language: Option<&str>) -> Result<()> {
+        let _ = language;
+        Ok(())
+    }
+
+    /// Language currently forced on decoding; `None` means auto-detect.
+    fn language_hint(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Transcribe a whole WAV file as a single final utterance.
+///
+/// The file is downmixed to mono; resampling is left to the model. The model
+/// is `reset` afterwards so the call does not leak decoder state into live
+/// dictation sharing the same handle.
+///
+/// Segment ids are `<path>#<index>`: stable across runs on the same file and
+/// unique across files.
+///
+/// # Errors
+/// `NotWarmedUp` if the model has not been warmed up, before the file is read.
+pub fn transcribe_file(
+    model: &mut dyn SpeechModel,
+    path: impl AsRef<Path>,
+) -> Result<Vec<TranscriptSegment>> {
+    if !model.is_warm() {
+        return Err(DictumError::NotWarmedUp);
+    }
+    let path = path.as_ref();
+    let chunk = read_wav_mono(path)?;
+    let segments = model.transcribe(&chunk, false);
+    model.reset();
+    let mut segments = segments?;
+    for (index, segment) in segments.iter_mut().enumerate() {
+        segment.id = format!("{}#{index}", path.display());
+    }
+    Ok(segments)
 }
 
 /// Thread-safe reference-counted handle to any `SpeechModel` implementor.
@@ -60,6 +198,11 @@ impl ModelHandle {
     pub fn new<M: SpeechModel>(model: M) -> Self {
         Self(Arc::new(Mutex::new(model)))
     }
+
+    /// Whether the wrapped model is ready to transcribe.
+    pub fn is_warm(&self) -> bool {
+        self.0.lock().is_warm()
+    }
 }
 
 impl std::fmt::Debug for ModelHandle {
@@ -67,3 +210,89 @@ impl std::fmt::Debug for ModelHandle {
         f.debug_struct("ModelHandle").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{transcribe_file, SpeechModel};
+    use crate::buffering::chunk::AudioChunk;
+    use crate::error::Result;
+    use crate::ipc::events::{SegmentKind, TranscriptSegment};
+
+    /// Returns two segments per call, ids left empty per the trait contract.
+    struct TwoSegmentModel;
+
+    impl SpeechModel for TwoSegmentModel {
+        fn warm_up(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn transcribe(
+            &mut self,
+            _chunk: &AudioChunk,
+            _partial: bool,
+        ) -> Result<Vec<TranscriptSegment>> {
+            Ok(["hello", "world"]
+                .into_iter()
+                .map(|text| TranscriptSegment {
+                    id: String::new(),
+                    text: text.into(),
+                    kind: SegmentKind::Final,
+                    confidence: None,
+                    start_ms: None,
+                    end_ms: None,
+                    word_timings: Vec::new(),
+                    language: None,
+                })
+                .collect())
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn write_test_wav(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "dictum-transcribe-file-{}-{name}.wav",
+            std::process::id()
+        ));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).expect("create wav");
+        for _ in 0..1_600 {
+            writer.write_sample(0_i16).expect("write");
+        }
+        writer.finalize().expect("finalize");
+        path
+    }
+
+    #[test]
+    fn transcribe_file_assigns_stable_unique_segment_ids() {
+        let a = write_test_wav("a");
+        let b = write_test_wav("b");
+        let ids = |path| -> Vec<String> {
+            transcribe_file(&mut TwoSegmentModel, path)
+                .expect("transcribe")
+                .into_iter()
+                .map(|s| s.id)
+                .collect()
+        };
+
+        let first_ids = ids(&a);
+        let repeat_ids = ids(&a);
+        let other_ids = ids(&b);
+        let _ = std::fs::remove_file(&a);
+        let _ = std::fs::remove_file(&b);
+
+        assert_eq!(first_ids.len(), 2);
+        assert!(first_ids.iter().all(|id| !id.is_empty()));
+        assert_ne!(first_ids[0], first_ids[1]);
+        assert_eq!(first_ids, repeat_ids, "ids are stable for the same file");
+        assert!(
+            first_ids.iter().all(|id| !other_ids.contains(id)),
+            "ids do not collide across files"
+        );
+    }
+}
@@ -24,11 +24,13 @@
 //! Stops at EOT `50257` or 224 tokens. Partial mode caps at 10 steps.
 
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::OnceLock;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{
-    collections::{HashMap, HashSet},
+    cell::Cell,
+    collections::{HashMap, HashSet, VecDeque},
     iter::FromIterator,
 };
 
@@ -42,13 +44,20 @@ use ort::{
 use reqwest::blocking::multipart;
 use rustfft::{num_complex::Complex, FftPlanner};
 use tokenizers::Tokenizer;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::{
+    audio::gain::GainLimits,
+    audio::wav::{encode_wav, WavSampleFormat},
     buffering::chunk::AudioChunk,
     error::{DictumError, Result},
+    inference::finite::{ensure_finite, ensure_finite_sampled, DEFAULT_FINITE_PROBES},
+    inference::postprocess::postprocess_transcript_text,
     inference::SpeechModel,
-    ipc::events::{SegmentKind, TranscriptSegment},
+    ipc::events::{
+        EmptyReason, ModelCapabilities, SegmentKind, TranscriptMeta, TranscriptSegment,
+        TranscriptSource, WordTiming,
+    },
 };
 
 static DEBUG_TRANSCRIBE: OnceLock<bool> = OnceLock::new();
@@ -69,20 +78,125 @@ enum DecodeLanguageHint {
     Russian,
 }
 
+impl DecodeLanguageHint {
+    /// ISO 639-1 code of a forced language; `None` for auto-detect.
+    fn code(self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            Self::English => Some("en"),
+            Self::Mandarin => Some("zh"),
+            Self::Russian => Some("ru"),
+        }
+    }
+}
+
+impl DecodeLanguageHint {
+    /// Parse a code or name (`"zh"`, `"mandarin"`, ...). Empty and `"auto"`
+    /// mean auto-detect; anything else unknown is `None`.
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "auto" => Some(Self::Auto),
+            "en" | "eng" | "english" => Some(Self::English),
+            "zh" | "zh-cn" | "zh-hans" | "mandarin" | "chinese" => Some(Self::Mandarin),
+            "ru" | "rus" | "russian" => Some(Self::Russian),
+            _ => None,
+        }
+    }
+}
+
+/// Initial language hint, from `DICTUM_LANGUAGE_HINT`.
 fn decode_language_hint() -> DecodeLanguageHint {
-    match std::env::var("DICTUM_LANGUAGE_HINT")
+    std::env::var("DICTUM_LANGUAGE_HINT")
+        .ok()
+        .and_then(|raw| DecodeLanguageHint::parse(&raw))
+        .unwrap_or(DecodeLanguageHint::Auto)
+}
+
+/// What the decoder is asked to produce.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeTask {
+    /// Text in the spoken language.
+    #[default]
+    Transcribe,
+    /// English text, whatever the spoken language. Needs a multilingual
+    /// export; English-only models transcribe instead.
+    Translate,
+}
+
+impl DecodeTask {
+    fn token(self) -> &'static str {
+        match self {
+            Self::Transcribe => "<|transcribe|>",
+            Self::Translate => "<|translate|>",
+        }
+    }
+
+    /// Language of the text decoded from speech in `spoken`: translation
+    /// is English whatever was said.
+    fn text_language(self, spoken: Option<&str>) -> Option<&str> {
+        match self {
+            Self::Transcribe => spoken,
+            Self::Translate => Some("en"),
+        }
+    }
+}
+
+/// Widest beam [`DecodeStrategy::Beam`] runs; wider requests are clamped.
+pub const MAX_BEAM_WIDTH: usize = 8;
+
+/// How finals pick tokens from the decoder's logits. Partials always decode
+/// greedily.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeStrategy {
+    /// Take the best token at every step.
+    #[default]
+    Greedy,
+    /// Keep the `width` best hypotheses and pick the most likely one at the
+    /// end. Slower — every hypothesis re-runs the decoder on its whole
+    /// sequence — but more robust on accented speech. `width` is clamped
+    /// to [`MAX_BEAM_WIDTH`]; below 2 it is greedy.
+    Beam { width: usize },
+}
+
+impl DecodeStrategy {
+    /// Beam width to run, or `None` for greedy.
+    fn beam_width(self) -> Option<usize> {
+        match self {
+            Self::Beam { width } if width >= 2 => Some(width.min(MAX_BEAM_WIDTH)),
+            _ => None,
+        }
+    }
+}
+
+/// Task from `DICTUM_DECODE_TASK` (`transcribe` or `translate`).
+fn decode_task() -> DecodeTask {
+    match std::env::var("DICTUM_DECODE_TASK")
         .unwrap_or_default()
         .trim()
         .to_ascii_lowercase()
         .as_str()
     {
-        "en" | "eng" | "english" => DecodeLanguageHint::English,
-        "zh" | "zh-cn" | "zh-hans" | "mandarin" | "chinese" => DecodeLanguageHint::Mandarin,
-        "ru" | "rus" | "russian" => DecodeLanguageHint::Russian,
-        _ => DecodeLanguageHint::Auto,
+        "translate" => DecodeTask::Translate,
+        _ => DecodeTask::Transcribe,
     }
 }
 
+/// Control tokens decoding must never emit. The task token in the prefix
+/// is left out for `Translate`.
+fn suppressed_control_tokens(task: DecodeTask) -> Vec<&'static str> {
+    [
+        "<|startoftranscript|>",
+        "<|startofprev|>",
+        "<|translate|>",
+        "<|transcribe|>",
+        "<|notimestamps|>",
+        "<|nospeech|>",
+    ]
+    .into_iter()
+    .filter(|&token| task == DecodeTask::Transcribe || token != task.token())
+    .collect()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CloudMode {
     LocalOnly,
@@ -136,16 +250,458 @@ fn reliability_confidence_threshold() -> f32 {
         .unwrap_or(0.62)
 }
 
+fn cloud_wav_format() -> WavSampleFormat {
+    match std::env::var("DICTUM_CLOUD_WAV_FORMAT")
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "float32" | "f32" => WavSampleFormat::Float32,
+        _ => WavSampleFormat::Pcm16,
+    }
+}
+
+fn cpu_fallback_on_degenerate_enabled() -> bool {
+    std::env::var("DICTUM_CPU_FALLBACK_ON_DEGENERATE")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// `DICTUM_MEL_TARGET_RMS`, clamped to a range the gain limits can reach.
+fn mel_target_rms() -> f32 {
+    std::env::var("DICTUM_MEL_TARGET_RMS")
+        .ok()
+        .and_then(|v| v.trim().parse::<f32>().ok())
+        .filter(|v| v.is_finite())
+        .map(|v| v.clamp(0.01, 0.5))
+        .unwrap_or(DEFAULT_MEL_TARGET_RMS)
+}
+
+fn encoder_crop_enabled() -> bool {
+    std::env::var("DICTUM_ENCODER_CROP")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// `DICTUM_TOKENIZER_CLEANUP=0` turns off the post-decode text cleanup.
+fn tokenizer_cleanup_enabled() -> bool {
+    std::env::var("DICTUM_TOKENIZER_CLEANUP")
+        .map(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
+        .unwrap_or(true)
+}
+
+fn eot_min_steps_per_second() -> f32 {
+    std::env::var("DICTUM_EOT_MIN_STEPS_PER_SECOND")
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|v| v.is_finite())
+        .map(|v| v.clamp(0.0, 4.0))
+        .unwrap_or(EOT_MIN_STEPS_PER_SECOND)
+}
+
+/// Earliest decode step at which EOT may be emitted. A fixed floor lets a
+/// few seconds of real speech end on an immediate EOT (an empty final), so
+/// for finals the floor grows with the audio length, up to
+/// `EOT_MIN_STEPS_CAP`. Partials keep the minimal floor.
+fn min_decode_steps_before_eot(audio_seconds: f32, partial: bool, steps_per_second: f32) -> usize {
+    if partial {
+        return 1;
+    }
+    let scaled = (audio_seconds.max(0.0) * steps_per_second).floor() as usize;
+    (2 + scaled).min(EOT_MIN_STEPS_CAP)
+}
+
+/// `(max, fast)` decode-step limits for a chunk. Finals get an estimate from
+/// the audio length; with `short_utterances`, one-word finals (up to
+/// `SHORT_UTTERANCE_SECONDS`) get a small fixed budget instead, since the
+/// length estimate would leave room for the decoder to ramble past a short
+/// answer. Finals decoding `timestamps` get extra steps for the timestamp
+/// tokens around each segment.
+fn decode_step_budget(
+    audio_seconds: f32,
+    partial: bool,
+    short_utterances: bool,
+    timestamps: bool,
+) -> (usize, usize) {
+    let timestamp_steps = if timestamps && !partial {
+        (audio_seconds.max(0.0) * TIMESTAMP_TOKENS_PER_SECOND).ceil() as usize
+            + TIMESTAMP_TOKEN_OVERHEAD
+    } else {
+        0
+    };
+    // Heuristic: estimated tokens/s + prompt allowance. Clamp to avoid runaway
+    // decoding when EOT is not emitted promptly.
+    let adaptive_final_steps = ((audio_seconds.max(0.0) * TOKENS_PER_SECOND_ESTIMATE).ceil()
+        as usize
+        + DECODE_TOKEN_OVERHEAD
+        + timestamp_steps)
+        .clamp(MIN_FINAL_TOKENS, MAX_TOKENS);
+    if partial {
+        let steps = PARTIAL_MAX_TOKENS.min(adaptive_final_steps);
+        return (steps, steps);
+    }
+    if short_utterances && audio_seconds <= SHORT_UTTERANCE_SECONDS {
+        let steps = SHORT_UTTERANCE_DECODE_STEPS + timestamp_steps;
+        return (steps, steps);
+    }
+    let short_cap = if audio_seconds <= 4.0 {
+        96
+    } else if audio_seconds <= 8.0 {
+        128
+    } else {
+        160
+    };
+    (
+        adaptive_final_steps,
+        adaptive_final_steps.clamp(MIN_FINAL_TOKENS, short_cap + timestamp_steps),
+    )
+}
+
+/// Which tokens Whisper's timestamp rules allow at the next decode step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimestampRule {
+    /// Whether text tokens (ids below EOT) may come next.
+    text: bool,
+    /// Timestamp tokens that may come next, inclusive; `None` allows none.
+    timestamps: Option<(i64, i64)>,
+}
+
+/// Whisper's timestamp rules for the token after `generated` (the decoded
+/// tokens, prefix removed), given that step's `logits`:
+/// - decoding opens with a timestamp no later than
+///   `MAX_INITIAL_TIMESTAMP_STEPS`;
+/// - timestamps come in pairs: one after text must be followed by a second
+///   (or EOT), and a completed pair by text (or EOT);
+/// - timestamps never go backwards;
+/// - when the timestamps together outweigh the likeliest text token, a
+///   timestamp is taken.
+fn timestamp_rule(generated: &[i64], timestamp_begin: i64, logits: &[f32]) -> TimestampRule {
+    let last_vocab = logits.len() as i64 - 1;
+    let is_timestamp = |t: i64| t >= timestamp_begin;
+    let last_was_timestamp = generated.last().is_some_and(|&t| is_timestamp(t));
+    let penultimate_was_timestamp =
+        generated.len() < 2 || is_timestamp(generated[generated.len() - 2]);
+    let (mut text, lo, hi) = match generated.iter().rev().find(|&&t| is_timestamp(t)) {
+        None if generated.is_empty() => (
+            false,
+            timestamp_begin,
+            (timestamp_begin + MAX_INITIAL_TIMESTAMP_STEPS).min(last_vocab),
+        ),
+        None => (true, timestamp_begin, last_vocab),
+        // A segment may close at the time it opened; the next opens later.
+        Some(&latest) => (
+            !last_was_timestamp || penultimate_was_timestamp,
+            if last_was_timestamp {
+                latest
+            } else {
+                latest + 1
+            },
+            last_vocab,
+        ),
+    };
+    let pair_complete = last_was_timestamp && penultimate_was_timestamp;
+    let timestamps = (!pair_complete && lo <= hi).then_some((lo, hi));
+    if let (true, Some((lo, hi))) = (text, timestamps) {
+        let allowed = &logits[lo as usize..=hi as usize];
+        let max_text = logits[..(timestamp_begin as usize).min(logits.len())]
+            .iter()
+            .copied()
+            .fold(f32::NEG_INFINITY, f32::max);
+        let max_timestamp = allowed.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let timestamp_mass = max_timestamp
+            + allowed
+                .iter()
+                .map(|v| (v - max_timestamp).exp())
+                .sum::<f32>()
+                .ln();
+        text = timestamp_mass <= max_text;
+    }
+    TimestampRule { text, timestamps }
+}
+
+/// Per-step logit adjustments shared by greedy and beam decoding:
+/// suppression, phrase bias, the repetition penalty and timestamp rules.
+#[derive(Clone, Copy)]
+struct TokenRules<'a> {
+    eot_id: i64,
+    /// First timestamp token; `Some` suppresses every timestamp token.
+    timestamp_begin: Option<i64>,
+    /// First timestamp token when timestamps are decoded; `Some` applies
+    /// [`timestamp_rule`] each step.
+    timestamp_rules: Option<i64>,
+    begin_suppress_tokens: &'a [i64],
+    always_suppress_tokens: &'a [i64],
+    phrase_bias_token_ids: &'a HashSet<i64>,
+    partial: bool,
+    min_decode_steps_before_eot: usize,
+}
+
+impl TokenRules<'_> {
+    /// Scorer for the token following `tokens`, whose first `prefix_len`
+    /// are the decode prefix, given that step's `logits`, in the form
+    /// [`select_next_token`] takes.
+    fn scorer(
+        &self,
+        prefix_len: usize,
+        tokens: &[i64],
+        logits: &[f32],
+    ) -> impl Fn(i64, f32) -> Option<f32> + '_ {
+        let step = tokens.len().saturating_sub(prefix_len);
+        let banned_no_repeat: HashSet<i64> = if self.partial {
+            HashSet::new()
+        } else {
+            HashSet::from_iter(banned_next_tokens_no_repeat_ngram(
+                tokens,
+                prefix_len,
+                NO_REPEAT_NGRAM_SIZE,
+            ))
+        };
+        let generated = tokens.get(prefix_len..).unwrap_or(&[]);
+        let timestamp_rule = self
+            .timestamp_rules
+            .map(|tb| (tb, timestamp_rule(generated, tb, logits)));
+        let mut tail_counts: HashMap<i64, usize> = HashMap::new();
+        for &tok in generated.iter().rev().take(MAX_TOKEN_TAIL_HISTORY) {
+            *tail_counts.entry(tok).or_insert(0) += 1;
+        }
+
+        move |token_id, v| {
+            let tail_count = tail_counts.get(&token_id).copied().unwrap_or(0);
+            let phrase_bias = if self.phrase_bias_token_ids.contains(&token_id) {
+                PHRASE_BIAS_LOGIT_BOOST
+            } else {
+                0.0
+            };
+            let penalized = v + phrase_bias - TOKEN_REPEAT_PENALTY * tail_count as f32;
+            let suppressed_for_begin = step == 0 && self.begin_suppress_tokens.contains(&token_id);
+            let suppressed_always = self.always_suppress_tokens.contains(&token_id);
+            let suppressed_early_eot =
+                token_id == self.eot_id && step < self.min_decode_steps_before_eot;
+            let suppressed_no_repeat = banned_no_repeat.contains(&token_id);
+            let suppressed_tail_repetition = !self.partial
+                && tail_count >= MAX_TAIL_TOKEN_OCCURRENCES
+                && token_id != self.eot_id;
+            let is_ts = self
+                .timestamp_begin
+                .map(|tb| token_id >= tb)
+                .unwrap_or(false);
+            let suppressed_by_timestamp_rule = timestamp_rule.is_some_and(|(tb, rule)| {
+                if token_id >= tb {
+                    !rule
+                        .timestamps
+                        .is_some_and(|(lo, hi)| (lo..=hi).contains(&token_id))
+                } else {
+                    token_id < self.eot_id && !rule.text
+                }
+            });
+            let suppressed = is_ts
+                || suppressed_by_timestamp_rule
+                || suppressed_for_begin
+                || suppressed_always
+                || suppressed_early_eot
+                || suppressed_no_repeat
+                || suppressed_tail_repetition;
+            (!suppressed).then_some(penalized)
+        }
+    }
+}
+
+/// One beam search hypothesis: its full token sequence and the summed
+/// log-probability of its generated tokens.
+#[derive(Debug, Clone)]
+struct Hypothesis {
+    tokens: Vec<i64>,
+    log_prob: f32,
+}
+
+impl Hypothesis {
+    /// Log-probability per generated token, so short hypotheses are not
+    /// favoured just for being short.
+    fn score(&self, prefix_len: usize) -> f32 {
+        self.log_prob / self.tokens.len().saturating_sub(prefix_len).max(1) as f32
+    }
+}
+
+/// Beam search from `prefix`. `step_logits` returns the decoder's logits
+/// for the position after a token sequence. Every step each live
+/// hypothesis is extended by its `width` best tokens under `rules`, and the
+/// `width` most likely extensions survive. A hypothesis finishes at EOT or
+/// when its generated tail starts repeating; the search stops once `width`
+/// have finished. Returns the best-scoring finished hypothesis (the best
+/// live one if none finished) and why the search ended.
+fn beam_search(
+    prefix: &[i64],
+    width: usize,
+    max_steps: usize,
+    rules: &TokenRules<'_>,
+    deadline: &DecodeDeadline,
+    mut step_logits: impl FnMut(&[i64]) -> Result<Vec<f32>>,
+) -> Result<(Vec<i64>, DecodeEnd)> {
+    let width = width.clamp(1, MAX_BEAM_WIDTH);
+    let mut live = vec![Hypothesis {
+        tokens: prefix.to_vec(),
+        log_prob: 0.0,
+    }];
+    let mut finished: Vec<Hypothesis> = Vec::new();
+
+    let end = drive_decode_steps(max_steps, deadline, |_| {
+        // (log-probability, parent hypothesis, next token)
+        let mut expansions: Vec<(f32, usize, i64)> = Vec::new();
+        for (parent, hypothesis) in live.iter().enumerate() {
+            let logits = step_logits(&hypothesis.tokens)?;
+            ensure_finite(&logits, "decoder logits")?;
+            let score = rules.scorer(prefix.len(), &hypothesis.tokens, &logits);
+            let mut scored: Vec<(i64, f32)> = logits
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| score(i as i64, v).map(|s| (i as i64, s)))
+                .collect();
+            if scored.is_empty() {
+                // Everything suppressed: this hypothesis can only end.
+                expansions.push((hypothesis.log_prob, parent, rules.eot_id));
+                continue;
+            }
+            let max = scored
+                .iter()
+                .map(|&(_, s)| s)
+                .fold(f32::NEG_INFINITY, f32::max);
+            let log_norm = max
+                + scored
+                    .iter()
+                    .map(|&(_, s)| (s - max).exp())
+                    .sum::<f32>()
+                    .ln();
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            for &(token, s) in scored.iter().take(width) {
+                expansions.push((hypothesis.log_prob + s - log_norm, parent, token));
+            }
+        }
+        // Stable, so ties keep the earlier hypothesis and lower token.
+        expansions.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut next_live = Vec::with_capacity(width);
+        for (log_prob, parent, token) in expansions {
+            if next_live.len() == width {
+                break;
+            }
+            let mut tokens = live[parent].tokens.clone();
+            tokens.push(token);
+            let hypothesis = Hypothesis { tokens, log_prob };
+            let generated = &hypothesis.tokens[prefix.len()..];
+            if token == rules.eot_id || has_repeating_tail_pattern(generated) {
+                finished.push(hypothesis);
+            } else {
+                next_live.push(hypothesis);
+            }
+        }
+        live = next_live;
+        Ok(!live.is_empty() && finished.len() < width)
+    })?;
+
+    let pool = if finished.is_empty() {
+        &live
+    } else {
+        &finished
+    };
+    let best = pool
+        .iter()
+        .max_by(|a, b| a.score(prefix.len()).total_cmp(&b.score(prefix.len())))
+        .map(|h| h.tokens.clone())
+        .unwrap_or_else(|| prefix.to_vec());
+    Ok((best, end))
+}
+
+/// Whether `decoder` takes only token ids and encoder states, so any
+/// number of hypotheses can share it without a KV cache each.
+fn decoder_runs_without_cache(decoder: &Session) -> bool {
+    decoder
+        .inputs()
+        .iter()
+        .all(|input| matches!(input.name(), "input_ids" | "encoder_hidden_states"))
+}
+
+/// Best `(index, score)` in a logit row. `score` returns the adjusted logit
+/// for a token, or `None` when the token is suppressed at this step. Ties
+/// keep the lowest index. `None` when every token is suppressed.
+fn select_next_token(
+    last_row: &[f32],
+    mut score: impl FnMut(i64, f32) -> Option<f32>,
+) -> Option<(usize, f32)> {
+    last_row
+        .iter()
+        .enumerate()
+        .fold(None::<(usize, f32)>, |best, (i, &v)| {
+            match (score(i as i64, v), best) {
+                (None, _) => best,
+                (Some(s), Some((_, b))) if b >= s => best,
+                (Some(s), _) => Some((i, s)),
+            }
+        })
+}
+
+/// Read a suppress-token list: either a bare JSON array of ids or an object
+/// with a `suppress_tokens` array (as in Whisper's `generation_config.json`).
+/// Every id must be inside the tokenizer's vocabulary.
+fn load_suppress_tokens(path: &Path, vocab_size: usize) -> Result<Vec<i64>> {
+    let invalid = |msg: String| DictumError::InvalidConfig(format!("{}: {msg}", path.display()));
+    let raw = std::fs::read_to_string(path)?;
+    let value: serde_json::Value =
+        serde_json::from_str(&raw).map_err(|e| invalid(format!("invalid JSON: {e}")))?;
+    let list = match &value {
+        serde_json::Value::Array(list) => list,
+        serde_json::Value::Object(map) => match map.get("suppress_tokens") {
+            Some(serde_json::Value::Array(list)) => list,
+            _ => return Err(invalid("expected a `suppress_tokens` array".into())),
+        },
+        _ => return Err(invalid("expected an array of token ids".into())),
+    };
+    let mut ids = Vec::with_capacity(list.len());
+    for item in list {
+        let id = item
+            .as_i64()
+            .ok_or_else(|| invalid(format!("token id must be an integer, got {item}")))?;
+        if id < 0 || id as usize >= vocab_size {
+            return Err(invalid(format!(
+                "token id {id} is outside the vocabulary (size {vocab_size})"
+            )));
+        }
+        ids.push(id);
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Resolve `OnnxModelConfig::extra_suppress_tokens` to ids. Each entry is
+/// a vocabulary entry exactly as the tokenizer spells it.
+fn resolve_suppress_tokens(tokenizer: &Tokenizer, tokens: &[String]) -> Result<Vec<i64>> {
+    let mut ids = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let id = tokenizer.token_to_id(token).ok_or_else(|| {
+            DictumError::InvalidConfig(format!(
+                "suppress token {token:?} is not in the tokenizer's vocabulary"
+            ))
+        })?;
+        ids.push(i64::from(id));
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Trimmed, lowercased, non-empty phrase bias terms.
+fn normalize_phrase_bias_terms<'a>(raw: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    raw.into_iter()
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 fn phrase_bias_terms_from_env() -> Vec<String> {
     std::env::var("DICTUM_PHRASE_BIAS_TERMS")
         .ok()
-        .map(|raw| {
-            raw.lines()
-                .flat_map(|line| line.split(','))
-                .map(|s| s.trim().to_ascii_lowercase())
-                .filter(|s| !s.is_empty())
-                .collect::<Vec<_>>()
-        })
+        .map(|raw| normalize_phrase_bias_terms(raw.lines().flat_map(|line| line.split(','))))
         .unwrap_or_default()
 }
 
@@ -158,6 +714,10 @@ const HOP: usize = 160;
 const N_MELS: usize = 80;
 const N_FRAMES: usize = 3_000;
 const MEL_SAMPLES: usize = 480_000;
+/// Cropped encoder inputs are rounded up to a multiple of this many frames
+/// (1 s). Even, as the encoder's stride-2 conv requires, and coarse enough
+/// that execution providers see only a handful of distinct shapes.
+const ENCODER_CROP_GRANULARITY: usize = 100;
 
 // ── Decoder constants ────────────────────────────────────────────────────────
 const EOT: i64 = 50257; // <|endoftext|> for this tokenizer export
@@ -166,6 +726,11 @@ const ENGLISH_FALLBACK: i64 = 50259;
 const TRANSCRIBE_FALLBACK: i64 = 50359;
 const NOTIMESTAMPS_FALLBACK: i64 = 50363;
 const MAX_TOKENS: usize = 224;
+/// Decoder positions in every Whisper size.
+const N_TEXT_CTX: usize = 448;
+/// Carried-context tokens kept after `<|startofprev|>`: room is left for the
+/// longest SOT prefix and a full `MAX_TOKENS` decode.
+const MAX_PROMPT_TOKENS: usize = N_TEXT_CTX - MAX_TOKENS - 8;
 const PARTIAL_MAX_TOKENS: usize = 10;
 const MIN_FINAL_TOKENS: usize = 24;
 const REPEAT_TOKEN_BREAK_THRESHOLD: usize = 14;
@@ -176,6 +741,32 @@ const TOKEN_REPEAT_PENALTY: f32 = 0.14;
 const PHRASE_BIAS_LOGIT_BOOST: f32 = 0.45;
 const TOKENS_PER_SECOND_ESTIMATE: f32 = 6.8;
 const DECODE_TOKEN_OVERHEAD: usize = 12;
+/// Default decode steps per second of final audio before EOT is allowed.
+const EOT_MIN_STEPS_PER_SECOND: f32 = 0.5;
+/// Ceiling on the EOT floor so genuine silence cannot force long decodes.
+const EOT_MIN_STEPS_CAP: usize = 6;
+/// Finals up to this long are treated as one-word answers.
+const SHORT_UTTERANCE_SECONDS: f32 = 0.8;
+/// Fixed decode budget for one-word finals: a word, punctuation and EOT.
+const SHORT_UTTERANCE_DECODE_STEPS: usize = 12;
+/// Extra decode steps per second of final audio when timestamps are
+/// decoded: a begin/end pair per segment of about two seconds.
+const TIMESTAMP_TOKENS_PER_SECOND: f32 = 1.0;
+/// Extra decode steps for the first segment's timestamp pair.
+const TIMESTAMP_TOKEN_OVERHEAD: usize = 2;
+/// Latest first timestamp decoding may open with, in timestamp steps (1 s),
+/// as in Whisper's `max_initial_timestamp`.
+const MAX_INITIAL_TIMESTAMP_STEPS: i64 = 50;
+/// Default wall-clock budget for all greedy decoding of one chunk (ms).
+const DEFAULT_MAX_DECODE_MS: u64 = 10_000;
+const DEFAULT_MEL_TARGET_RMS: f32 = 0.10;
+/// Level normalisation of audio handed to the OS and cloud fallbacks: up to
+/// 15x, no noise guard (those engines do their own).
+const FALLBACK_GAIN_LIMITS: GainLimits = GainLimits {
+    max_gain: 15.0,
+    noise_floor_rms: 1e-6,
+    hiss_correlation: None,
+};
 
 // ── Model config ─────────────────────────────────────────────────────────────
 
@@ -184,6 +775,318 @@ pub struct OnnxModelConfig {
     pub decoder_path: PathBuf,
     pub decoder_with_past_path: Option<PathBuf>,
     pub tokenizer_path: PathBuf,
+    /// JSON list of token ids that decoding must never emit, merged with the
+    /// built-in special-token suppression at warm-up. `None` uses the
+    /// built-in set only.
+    pub suppress_tokens_path: Option<PathBuf>,
+    /// Tokens decoding must never emit, spelled as in the tokenizer's
+    /// vocabulary (`<|nocaptions|>`, `Ġum`, ...). Resolved at warm-up,
+    /// which fails on a token the vocabulary lacks; merged with
+    /// `suppress_tokens_path`. Default: empty.
+    pub extra_suppress_tokens: Vec<String>,
+    /// Words whose tokens get a logit boost, e.g. a session's domain
+    /// vocabulary. Resolved at warm-up. Empty falls back to
+    /// `DICTUM_PHRASE_BIAS_TERMS` (newline- or comma-separated), read on
+    /// every transcribe. Default: empty.
+    pub phrase_bias_terms: Vec<String>,
+    /// Run the encoder on a mel cropped to the audio length instead of the
+    /// full 30 s window. Only for exports with a dynamic frame axis; ignored
+    /// (with a warning) when the encoder declares a fixed input length.
+    pub crop_encoder_input: bool,
+    /// Rebuild all sessions on the CPU execution provider, once, when GPU
+    /// finals keep decoding into repetition loops. Some drivers produce
+    /// subtly wrong math that only shows up that way.
+    pub cpu_fallback_on_degenerate: bool,
+    /// Sample format of the WAV uploaded to the cloud fallback.
+    pub cloud_wav_format: WavSampleFormat,
+    /// When a final that ran out of decode steps is re-decoded with the
+    /// full budget.
+    pub truncation_retry: TruncationRetryConfig,
+    /// Wall-clock budget (ms) for all greedy decoding of one chunk,
+    /// including prefix fallbacks and retries. When it runs out the decode
+    /// stops and keeps the tokens produced so far, so a loop the repetition
+    /// breakers miss cannot stall dictation. `0` disables the budget.
+    /// Default: 10000.
+    pub max_decode_ms: u64,
+    /// Ceiling and noise guard for the level normalisation applied before
+    /// the log-mel, so a hissy but otherwise silent final is not boosted
+    /// into something the decoder transcribes. Default: max gain 15, hiss
+    /// guard on.
+    pub mel_gain_limits: GainLimits,
+    /// RMS the audio is normalised to before the log-mel, within
+    /// `mel_gain_limits`. Decode quality is sensitive to it, so it may need
+    /// tuning per model export or environment. Default: 0.10, or
+    /// `DICTUM_MEL_TARGET_RMS`.
+    pub mel_target_rms: f32,
+    /// How decoded token ids become text.
+    pub tokenizer_decode: TokenizerDecodeConfig,
+    /// Transcribe, or translate to English. Default: `Transcribe`, or
+    /// `DICTUM_DECODE_TASK`.
+    pub task: DecodeTask,
+    /// Which decode prefixes to try, derived from the language hint unless
+    /// overridden.
+    pub prefix_search: PrefixSearchConfig,
+    /// Let finals decode Whisper's `<|x.xx|>` timestamp tokens and report
+    /// them as `TranscriptSegment::word_timings` (plus the segment's
+    /// `start_ms`/`end_ms`). Drops `<|notimestamps|>` from the decode
+    /// prefixes and applies Whisper's timestamp rules (paired, monotonic
+    /// timestamps); finals get a larger decode budget for the extra tokens.
+    /// Partials are unaffected. Default: `false`.
+    pub emit_timestamps: bool,
+    /// Greedy or beam search for finals. Beam search needs a decoder that
+    /// runs without a KV cache input and falls back to greedy otherwise.
+    /// Default: `Greedy`.
+    pub decode_strategy: DecodeStrategy,
+}
+
+/// Options for turning token ids into text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenizerDecodeConfig {
+    /// Leave special tokens (`<|en|>`, `<|notimestamps|>`, ...) out of the
+    /// text. Default: true.
+    pub skip_special_tokens: bool,
+    /// Clean the decoded text: collapse runs of spaces, keeping line
+    /// breaks, and, when warm-up found that the tokenizer has no byte-level
+    /// decoder (some exports drop it), map leftover byte-level markers such
+    /// as `Ġ` back to the bytes they stand for. Default: true, or
+    /// `DICTUM_TOKENIZER_CLEANUP`.
+    pub clean_up_text: bool,
+}
+
+impl Default for TokenizerDecodeConfig {
+    fn default() -> Self {
+        Self {
+            skip_special_tokens: true,
+            clean_up_text: tokenizer_cleanup_enabled(),
+        }
+    }
+}
+
+/// Which decode prefixes `transcribe` tries for a chunk, in which order,
+/// and how many. Each prefix tried on an empty result is a full greedy
+/// decode, so the limits left at `None` follow the language hint: a forced
+/// language decodes once with its own prefix, auto-detect works through
+/// the auto prefixes and then `fallback_languages`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixSearchConfig {
+    /// Prefixes tried at the normal step budget before the rest are
+    /// considered. `None`: 1.
+    pub fast_prefix_limit: Option<usize>,
+    /// Most prefixes tried for one chunk, including full-budget retries and
+    /// refinement. `None`: 1 with a forced language, every candidate with
+    /// auto-detect.
+    pub max_prefixes: Option<usize>,
+    /// Languages auto-detect falls back to, in order, as Whisper language
+    /// codes. Codes the tokenizer lacks are skipped. Default: zh, ru, en.
+    pub fallback_languages: Vec<String>,
+}
+
+impl Default for PrefixSearchConfig {
+    fn default() -> Self {
+        Self {
+            fast_prefix_limit: None,
+            max_prefixes: None,
+            fallback_languages: ["zh", "ru", "en"].map(String::from).to_vec(),
+        }
+    }
+}
+
+impl PrefixSearchConfig {
+    /// `(fast, max)` prefix counts for `hint`, out of `candidates`. Both
+    /// are at least 1 and `fast` never exceeds `max`.
+    fn limits(&self, hint: DecodeLanguageHint, candidates: usize) -> (usize, usize) {
+        let max = self
+            .max_prefixes
+            .unwrap_or(match hint {
+                DecodeLanguageHint::Auto => candidates,
+                _ => 1,
+            })
+            .clamp(1, candidates.max(1));
+        let fast = self.fast_prefix_limit.unwrap_or(1).clamp(1, max);
+        (fast, max)
+    }
+
+    /// The prefixes `transcribe` tries for `hint`: the first of
+    /// `candidates` up to the limit, with how many of them run at the
+    /// normal step budget.
+    fn select(
+        &self,
+        hint: DecodeLanguageHint,
+        candidates: Vec<Vec<i64>>,
+    ) -> (usize, Vec<Vec<i64>>) {
+        let (fast, max) = self.limits(hint, candidates.len());
+        (fast, candidates.into_iter().take(max).collect())
+    }
+}
+
+/// Thresholds for suspecting that a final stopped at the decode-step
+/// ceiling is cut short, and how many full-budget retries that may cost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TruncationRetryConfig {
+    /// Audio at least this long (seconds) with at most
+    /// `long_audio_max_words` words is suspect.
+    pub long_audio_seconds: f32,
+    pub long_audio_max_words: usize,
+    /// Same, for medium-length audio.
+    pub medium_audio_seconds: f32,
+    pub medium_audio_max_words: usize,
+    /// Retries allowed per listening session; `None` is unlimited and
+    /// `Some(0)` disables the retry.
+    pub session_budget: Option<usize>,
+}
+
+impl Default for TruncationRetryConfig {
+    fn default() -> Self {
+        Self {
+            long_audio_seconds: 10.0,
+            long_audio_max_words: 8,
+            medium_audio_seconds: 6.0,
+            medium_audio_max_words: 4,
+            session_budget: None,
+        }
+    }
+}
+
+impl TruncationRetryConfig {
+    fn suspects_truncation(&self, text: &str, audio_seconds: f32) -> bool {
+        let words = text.split_whitespace().count();
+        (audio_seconds >= self.long_audio_seconds && words <= self.long_audio_max_words)
+            || (audio_seconds >= self.medium_audio_seconds && words <= self.medium_audio_max_words)
+    }
+}
+
+/// Truncation retries spent in the current session.
+#[derive(Debug, Default)]
+struct RetryBudget {
+    used: usize,
+}
+
+impl RetryBudget {
+    /// Take one retry if `limit` allows it.
+    fn try_spend(&mut self, limit: Option<usize>) -> bool {
+        if limit.is_some_and(|limit| self.used >= limit) {
+            return false;
+        }
+        self.used = self.used.saturating_add(1);
+        true
+    }
+
+    /// Whether a final gets a second decode at the full step budget: when
+    /// the fast decode found nothing, or, while `limit` allows, when it
+    /// looks cut short. A retry is spent only when it actually runs, so
+    /// partials and decodes whose fast budget already is the full one leave
+    /// the budget untouched.
+    fn full_budget_retry(
+        &mut self,
+        limit: Option<usize>,
+        partial: bool,
+        text_empty: bool,
+        suspects_truncation: bool,
+        (max_decode_steps, fast_decode_steps): (usize, usize),
+    ) -> bool {
+        if partial || max_decode_steps <= fast_decode_steps {
+            return false;
+        }
+        if text_empty {
+            return true;
+        }
+        if !suspects_truncation {
+            return false;
+        }
+        let spent = self.try_spend(limit);
+        if !spent {
+            debug!("truncation retry budget exhausted; keeping fast decode");
+        }
+        spent
+    }
+}
+
+/// Point in time after which greedy decoding stops (`max_decode_ms`), the
+/// flag that cancels it outright (`SpeechModel::set_cancel_flag`), and the
+/// running flag whose clearing cuts it short
+/// (`SpeechModel::transcribe_cancellable`).
+#[derive(Debug, Clone)]
+struct DecodeDeadline {
+    at: Option<Instant>,
+    cancel: Option<Arc<AtomicBool>>,
+    running: Option<Arc<AtomicBool>>,
+}
+
+impl DecodeDeadline {
+    /// `budget_ms` from now; `0` never expires.
+    fn after(budget_ms: u64) -> Self {
+        Self {
+            at: (budget_ms > 0).then(|| Instant::now() + Duration::from_millis(budget_ms)),
+            cancel: None,
+            running: None,
+        }
+    }
+
+    fn with_cancel(mut self, cancel: Option<Arc<AtomicBool>>) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    fn expired(&self) -> bool {
+        self.at.is_some_and(|at| Instant::now() >= at)
+    }
+
+    fn with_running(mut self, running: Option<Arc<AtomicBool>>) -> Self {
+        self.running = running;
+        self
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Acquire))
+    }
+
+    fn interrupted(&self) -> bool {
+        self.running
+            .as_ref()
+            .is_some_and(|flag| !flag.load(Ordering::Acquire))
+    }
+}
+
+/// Why a run of decode steps ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DecodeEnd {
+    /// All `max_steps` ran.
+    StepLimit,
+    /// The step reported EOT or a repetition break.
+    Stopped,
+    /// The deadline passed between steps.
+    Deadline,
+    /// The cancel flag was raised between steps.
+    Cancelled,
+    /// The running flag was cleared between steps; the tokens so far stand.
+    Interrupted,
+}
+
+/// Run `step(index)` up to `max_steps` times until it returns `false`,
+/// checking `deadline` and its flags before each step. A step already
+/// running is never interrupted, so a budget is overrun by at most one step.
+fn drive_decode_steps(
+    max_steps: usize,
+    deadline: &DecodeDeadline,
+    mut step: impl FnMut(usize) -> Result<bool>,
+) -> Result<DecodeEnd> {
+    for index in 0..max_steps {
+        if deadline.cancelled() {
+            return Ok(DecodeEnd::Cancelled);
+        }
+        if deadline.interrupted() {
+            return Ok(DecodeEnd::Interrupted);
+        }
+        if deadline.expired() {
+            return Ok(DecodeEnd::Deadline);
+        }
+        if !step(index)? {
+            return Ok(DecodeEnd::Stopped);
+        }
+    }
+    Ok(DecodeEnd::StepLimit)
 }
 
 impl Default for OnnxModelConfig {
@@ -195,18 +1098,46 @@ impl Default for OnnxModelConfig {
             decoder_path: dir.join("decoder_model.onnx"),
             decoder_with_past_path: decoder_with_past.exists().then_some(decoder_with_past),
             tokenizer_path: dir.join("tokenizer.json"),
+            suppress_tokens_path: None,
+            extra_suppress_tokens: Vec::new(),
+            phrase_bias_terms: Vec::new(),
+            crop_encoder_input: encoder_crop_enabled(),
+            cpu_fallback_on_degenerate: cpu_fallback_on_degenerate_enabled(),
+            cloud_wav_format: cloud_wav_format(),
+            truncation_retry: TruncationRetryConfig::default(),
+            max_decode_ms: DEFAULT_MAX_DECODE_MS,
+            mel_gain_limits: GainLimits {
+                max_gain: 15.0,
+                noise_floor_rms: 1e-6,
+                ..GainLimits::default()
+            },
+            mel_target_rms: mel_target_rms(),
+            tokenizer_decode: TokenizerDecodeConfig::default(),
+            task: decode_task(),
+            prefix_search: PrefixSearchConfig::default(),
+            emit_timestamps: false,
+            decode_strategy: DecodeStrategy::Greedy,
         }
     }
 }
 
+/// Directory `OnnxModelConfig::default` loads from, after
+/// `DICTUM_MODEL_DIR` / `DICTUM_MODEL_PROFILE` resolution.
+pub fn resolved_models_dir() -> PathBuf {
+    selected_models_dir()
+}
+
 fn selected_models_dir() -> PathBuf {
+    let root = default_models_dir();
     if let Ok(explicit) = std::env::var("DICTUM_MODEL_DIR") {
-        let p = PathBuf::from(explicit.trim());
         if !explicit.trim().is_empty() {
-            return p;
+            match validate_explicit_models_dir(Path::new(explicit.trim()), &root) {
+                Ok(dir) => return dir,
+                Err(e) => warn!("{e}; using the default models dir"),
+            }
         }
     }
-    let default_dir = default_models_dir();
+    let default_dir = canonical_dir(&root).unwrap_or(root);
     if let Ok(profile) = std::env::var("DICTUM_MODEL_PROFILE") {
         let profile = profile.trim().to_ascii_lowercase();
         if !profile.is_empty() {
@@ -230,6 +1161,68 @@ fn selected_models_dir() -> PathBuf {
     default_dir
 }
 
+/// Canonical models directories by the path they were requested as. Models
+/// dirs are often symlinks to network storage, where every lookup is slow,
+/// so each is resolved once per process.
+static CANONICAL_DIRS: OnceLock<parking_lot::Mutex<HashMap<PathBuf, PathBuf>>> = OnceLock::new();
+
+/// `dir` with symlinks resolved, cached. `None` (and nothing cached, so a
+/// later download is picked up) when it does not exist.
+fn canonical_dir(dir: &Path) -> Option<PathBuf> {
+    let cache = CANONICAL_DIRS.get_or_init(Default::default);
+    if let Some(hit) = cache.lock().get(dir) {
+        return Some(hit.clone());
+    }
+    let resolved = strip_verbatim_prefix(std::fs::canonicalize(dir).ok()?);
+    cache.lock().insert(dir.to_path_buf(), resolved.clone());
+    Some(resolved)
+}
+
+/// `canonicalize` returns `\\?\C:\...` on Windows, which some APIs and
+/// every log reader handle badly. Plain drive paths do not need it.
+fn strip_verbatim_prefix(path: PathBuf) -> PathBuf {
+    #[cfg(windows)]
+    if let Some(rest) = path.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+        if !rest.starts_with("UNC\\") {
+            return PathBuf::from(rest);
+        }
+    }
+    path
+}
+
+/// Check a `DICTUM_MODEL_DIR` value against the models `root` and resolve
+/// it. A directory that does not exist yet is returned as given (warm-up
+/// reports the missing files). Pointing outside `root` is allowed, with a
+/// warning, since custom model locations are what the variable is for.
+///
+/// # Errors
+/// A message naming both paths when `dir` climbs out of `root` with `..`:
+/// it reads as a path into the models dir but is not one, which is how an
+/// injected value would try to load files from elsewhere.
+fn validate_explicit_models_dir(dir: &Path, root: &Path) -> std::result::Result<PathBuf, String> {
+    let Some(resolved) = canonical_dir(dir) else {
+        return Ok(dir.to_path_buf());
+    };
+    let root_resolved = canonical_dir(root).unwrap_or_else(|| root.to_path_buf());
+    if resolved.starts_with(&root_resolved) {
+        return Ok(resolved);
+    }
+    let climbs = dir
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir));
+    if climbs {
+        return Err(format!(
+            "DICTUM_MODEL_DIR {dir:?} resolves to {resolved:?}, outside the models dir {root_resolved:?}"
+        ));
+    }
+    warn!(
+        model_dir = ?resolved,
+        models_root = ?root_resolved,
+        "DICTUM_MODEL_DIR is outside the default models dir"
+    );
+    Ok(resolved)
+}
+
 fn has_required_whisper_files(dir: &Path) -> bool {
     dir.join("encoder_model.onnx").exists()
         && dir.join("decoder_model.onnx").exists()
@@ -284,8 +1277,25 @@ fn ort_execution_preference() -> OrtExecutionPreference {
     }
 }
 
-fn create_session(model_path: &Path) -> Result<Session> {
-    let pref = ort_execution_preference();
+/// Label for the execution providers a set of sessions registered, in
+/// first-seen order: `"directml"`, `"cpu"`, or `"directml+cpu"` when some
+/// sessions fell back. `None` before any session is built.
+fn registered_providers_label(providers: &[&'static str]) -> Option<String> {
+    let mut seen: Vec<&str> = Vec::new();
+    for provider in providers {
+        if !seen.contains(provider) {
+            seen.push(provider);
+        }
+    }
+    (!seen.is_empty()).then(|| seen.join("+"))
+}
+
+/// Session for `model_path` and the execution provider it registered.
+fn create_session(model_path: &Path) -> Result<(Session, &'static str)> {
+    create_session_with(model_path, ort_execution_preference())
+}
+
+fn session_builder(pref: OrtExecutionPreference) -> Result<SessionBuilder> {
     let logical_cores = std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(4);
@@ -314,7 +1324,7 @@ fn create_session(model_path: &Path) -> Result<Session> {
         .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
         .unwrap_or(pref == OrtExecutionPreference::DirectML);
 
-    let mut builder = SessionBuilder::new()
+    let builder = SessionBuilder::new()
         .map_err(|e| DictumError::OnnxSession(e.to_string()))?
         .with_intra_threads(intra_threads)
         .map_err(|e| DictumError::OnnxSession(e.to_string()))?
@@ -328,56 +1338,72 @@ fn create_session(model_path: &Path) -> Result<Session> {
         intra_threads,
         inter_threads, parallel_execution, logical_cores, "ONNX session threading configured"
     );
+    Ok(builder)
+}
+
+fn cpu_session_builder(pref: OrtExecutionPreference) -> Result<SessionBuilder> {
+    session_builder(pref)?
+        .with_execution_providers([ep::CPU::default().build()])
+        .map_err(|e| DictumError::OnnxSession(e.to_string()))
+}
 
+fn create_session_with(
+    model_path: &Path,
+    pref: OrtExecutionPreference,
+) -> Result<(Session, &'static str)> {
     #[cfg(target_os = "windows")]
-    {
-        builder = match pref {
-            OrtExecutionPreference::Cpu => {
-                info!("ONNX EP preference=cpu");
-                builder
-                    .with_execution_providers([ep::CPU::default().build()])
-                    .map_err(|e| DictumError::OnnxSession(e.to_string()))?
-            }
-            OrtExecutionPreference::DirectML => {
-                info!("ONNX EP preference=directml (strict)");
-                builder
-                    .with_execution_providers([
-                        ep::DirectML::default()
-                            .with_device_id(0)
-                            .build()
-                            .error_on_failure(),
-                        ep::CPU::default().build(),
-                    ])
-                    .map_err(|e| DictumError::OnnxSession(e.to_string()))?
-            }
-            OrtExecutionPreference::Auto => {
-                info!("ONNX EP preference=auto (directml -> cpu)");
-                builder
-                    .with_execution_providers([
-                        ep::DirectML::default()
-                            .with_device_id(0)
-                            .build()
-                            .fail_silently(),
-                        ep::CPU::default().build(),
-                    ])
-                    .map_err(|e| DictumError::OnnxSession(e.to_string()))?
+    let (builder, provider) = match pref {
+        OrtExecutionPreference::Cpu => {
+            info!("ONNX EP preference=cpu");
+            (cpu_session_builder(pref)?, "cpu")
+        }
+        OrtExecutionPreference::DirectML => {
+            info!("ONNX EP preference=directml (strict)");
+            let builder = session_builder(pref)?
+                .with_execution_providers([
+                    ep::DirectML::default()
+                        .with_device_id(0)
+                        .build()
+                        .error_on_failure(),
+                    ep::CPU::default().build(),
+                ])
+                .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+            (builder, "directml")
+        }
+        OrtExecutionPreference::Auto => {
+            info!("ONNX EP preference=auto (directml -> cpu)");
+            // Registered strictly so a DirectML failure is seen here and the
+            // provider actually in use is known.
+            let directml = session_builder(pref)?.with_execution_providers([
+                ep::DirectML::default()
+                    .with_device_id(0)
+                    .build()
+                    .error_on_failure(),
+                ep::CPU::default().build(),
+            ]);
+            match directml {
+                Ok(builder) => (builder, "directml"),
+                Err(e) => {
+                    warn!(error = %e, "DirectML EP unavailable; using CPU EP");
+                    (cpu_session_builder(OrtExecutionPreference::Cpu)?, "cpu")
+                }
             }
-        };
-    }
+        }
+    };
 
     #[cfg(not(target_os = "windows"))]
-    {
+    let (builder, provider) = {
         if pref == OrtExecutionPreference::DirectML {
             warn!("DICTUM_ORT_EP=directml requested on non-Windows host; using CPU EP");
         }
-        builder = builder
-            .with_execution_providers([ep::CPU::default().build()])
-            .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
-    }
+        (cpu_session_builder(pref)?, "cpu")
+    };
 
-    builder
+    info!(provider, "ONNX execution provider registered");
+    let session = builder
         .commit_from_file(model_path)
-        .map_err(|e| DictumError::OnnxSession(e.to_string()))
+        .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+    Ok((session, provider))
 }
 
 // ── OnnxModel ────────────────────────────────────────────────────────────────
@@ -392,7 +1418,86 @@ pub struct OnnxModel {
     mel_filters: Vec<Vec<f32>>,
     hann_window: Vec<f32>,
     fft: Arc<dyn rustfft::Fft<f32>>,
-    utterance_count: u64,
+    last_meta: TranscriptMeta,
+    /// Ids from `OnnxModelConfig::suppress_tokens_path` and
+    /// `extra_suppress_tokens`, resolved at warm-up.
+    extra_suppress_tokens: Vec<i64>,
+    /// Ids for `OnnxModelConfig::phrase_bias_terms`, resolved at warm-up.
+    phrase_bias_token_ids: HashSet<i64>,
+    /// The tokenizer's language tokens, for detecting the spoken language
+    /// when no hint is set. Resolved at warm-up; empty for English-only
+    /// exports.
+    language_tokens: Vec<(i64, String)>,
+    /// Language token the last final detected, reused by partials instead
+    /// of a detection pass of their own. Cleared at warm-up.
+    detected_language: Option<i64>,
+    /// `OnnxModelConfig::crop_encoder_input`, cleared at warm-up if the
+    /// encoder has a fixed frame axis.
+    crop_encoder: bool,
+    /// Armed at warm-up when `cpu_fallback_on_degenerate` is set and the
+    /// sessions may be running on a GPU.
+    cpu_fallback: Option<DegenerateOutputGuard>,
+    /// Execution provider each loaded session registered, encoder first.
+    execution_providers: Vec<&'static str>,
+    /// Language forced on decoding; starts from `DICTUM_LANGUAGE_HINT` and
+    /// can be changed between calls with `set_language_hint`.
+    language_hint: DecodeLanguageHint,
+    truncation_retries: RetryBudget,
+    /// Fixed small decode budget for one-word finals; set by
+    /// `set_short_utterance_decode`, off until then.
+    short_utterance_decode: bool,
+    /// Set by `set_prompt_context`, cleared by `reset`.
+    prompt_context: Option<String>,
+    /// Encoder output dims declared by the model (`last_hidden_state`
+    /// `[1, frames, d_model]`), read at warm-up. `None` for a dynamic axis.
+    encoder_dims: EncoderDims,
+    /// Whether the tokenizer's decode leaves byte-level markers in the
+    /// text, probed at warm-up.
+    byte_level_text: bool,
+    /// Set by `set_cancel_flag`; raised to abandon the decode in progress.
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+/// Declared `[frames, d_model]` of the encoder output. Used when the output
+/// tensor itself does not carry a usable shape.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct EncoderDims {
+    n_frames: Option<usize>,
+    d_model: Option<usize>,
+}
+
+impl EncoderDims {
+    fn from_shape(shape: &[i64]) -> Self {
+        let dim = |i: usize| shape.get(i).copied().filter(|&d| d > 0).map(|d| d as usize);
+        Self {
+            n_frames: dim(1),
+            d_model: dim(2),
+        }
+    }
+
+    /// `(frames, d_model)` of an encoder output with `shape` and `len`
+    /// elements: the runtime shape when it has both dims, else the declared
+    /// ones. Different exports use different widths (384 for small, 512 for
+    /// base, ...), so there is no safe default.
+    ///
+    /// # Errors
+    /// `DictumError::Inference` when `d_model` is unknown or the dims do not
+    /// match `len`.
+    fn resolve(&self, shape: &[i64], len: usize) -> Result<(usize, usize)> {
+        let runtime = Self::from_shape(shape);
+        let d_model = runtime.d_model.or(self.d_model).ok_or_else(|| {
+            DictumError::Inference(format!(
+                "cannot determine encoder d_model: output shape {shape:?} and no declared width"
+            ))
+        })?;
+        let n_frames = runtime.n_frames.or(self.n_frames).unwrap_or(len / d_model);
+        if n_frames * d_model != len {
+            return Err(DictumError::Inference(format!(
+                "encoder output has {len} values, expected {n_frames} frames x {d_model} d_model"
+            )));
+        }
+        Ok((n_frames, d_model))
+    }
 }
 
 impl OnnxModel {
@@ -401,6 +1506,7 @@ impl OnnxModel {
         let mel_filters = build_mel_filters(FFT_SIZE, 16_000, N_MELS, 0.0, 8_000.0);
         let fft = Arc::from(FftPlanner::<f32>::new().plan_fft_forward(FFT_SIZE));
 
+        let crop_encoder = config.crop_encoder_input;
         Self {
             config,
             encoder: None,
@@ -411,19 +1517,58 @@ impl OnnxModel {
             mel_filters,
             hann_window,
             fft,
-            utterance_count: 0,
+            last_meta: TranscriptMeta::default(),
+            extra_suppress_tokens: Vec::new(),
+            phrase_bias_token_ids: HashSet::new(),
+            language_tokens: Vec::new(),
+            detected_language: None,
+            crop_encoder,
+            cpu_fallback: None,
+            execution_providers: Vec::new(),
+            language_hint: decode_language_hint(),
+            truncation_retries: RetryBudget::default(),
+            short_utterance_decode: false,
+            prompt_context: None,
+            encoder_dims: EncoderDims::default(),
+            byte_level_text: false,
+            cancel: None,
+        }
+    }
+
+    /// Mel frames fed to the encoder for `active_samples` of audio.
+    fn encoder_input_frames(&self, active_samples: usize) -> usize {
+        if !self.crop_encoder {
+            return N_FRAMES;
         }
+        active_mel_frames(active_samples)
+            .div_ceil(ENCODER_CROP_GRANULARITY)
+            .saturating_mul(ENCODER_CROP_GRANULARITY)
+            .min(N_FRAMES)
     }
 
-    fn log_mel_spectrogram(&self, samples: &[f32], active_samples: usize) -> Array3<f32> {
+    /// Log-mel of `samples`, `n_frames` wide. Frames past the active audio
+    /// are left as padding.
+    fn log_mel_spectrogram(
+        &self,
+        samples: &[f32],
+        active_samples: usize,
+        n_frames: usize,
+    ) -> Result<Array3<f32>> {
         let mut normalized = samples.to_vec();
-        normalize_rms_in_place(&mut normalized, 0.10);
+        normalize_rms_in_place(
+            &mut normalized,
+            self.config.mel_target_rms,
+            &self.config.mel_gain_limits,
+        );
         let centered = reflect_pad(&normalized, N_FFT / 2);
-        let active_samples = active_samples.min(MEL_SAMPLES);
-        let active_frames = ((active_samples + N_FFT + HOP - 1) / HOP).clamp(1, N_FRAMES);
+        let n_frames = n_frames.clamp(1, N_FRAMES);
+        let active_frames = active_mel_frames(active_samples).min(n_frames);
 
-        let mut mel = Array3::<f32>::zeros((1, self.n_mels, N_FRAMES));
+        let mut mel = Array3::<f32>::zeros((1, self.n_mels, n_frames));
         let mut fft_buf = vec![Complex::new(0.0f32, 0.0); FFT_SIZE];
+        // Running sum of raw energies: any NaN/Inf propagates into it, and it
+        // must be checked before the clamp below (`max` drops NaNs).
+        let mut energy_sum = 0.0f32;
 
         // Most utterances are far shorter than 30s. Skip FFT work for guaranteed
         // zero-padded tail frames to reduce frontend CPU time.
@@ -445,14 +1590,20 @@ impl OnnxModel {
                     energy += self.mel_filters[m][k] * fft_buf[k].norm_sqr();
                 }
                 mel[[0, m, frame]] = energy;
+                energy_sum += energy;
             }
         }
+        if !energy_sum.is_finite() {
+            return Err(DictumError::Inference(format!(
+                "non-finite mel energy ({energy_sum}) over {active_frames} frames"
+            )));
+        }
 
         mel.mapv_inplace(|v| v.max(1e-10).log10());
         let max_val = mel.iter().copied().fold(f32::NEG_INFINITY, f32::max);
         mel.mapv_inplace(|v| v.max(max_val - 8.0));
         mel.mapv_inplace(|v| (v + 4.0) / 4.0);
-        mel
+        Ok(mel)
     }
 
     /// Greedy decode, returning the full token sequence including SOT prefix.
@@ -466,15 +1617,13 @@ impl OnnxModel {
         enc_d_model: usize,
         max_decode_steps: usize,
         prefix: &[i64],
-        eot_id: i64,
-        timestamp_begin: Option<i64>,
-        begin_suppress_tokens: &[i64],
-        always_suppress_tokens: &[i64],
-        phrase_bias_token_ids: &HashSet<i64>,
-        partial: bool,
+        rules: &TokenRules<'_>,
+        deadline: &DecodeDeadline,
     ) -> Result<Vec<i64>> {
+        let TokenRules {
+            eot_id, partial, ..
+        } = *rules;
         let max_steps = max_decode_steps.clamp(1, MAX_TOKENS);
-        let min_decode_steps_before_eot = if partial { 1 } else { 2 };
         let debug_mode = is_debug_transcribe();
         let mut tokens: Vec<i64> = prefix.to_vec();
         let mut repeated_token_count = 0usize;
@@ -494,27 +1643,8 @@ impl OnnxModel {
             );
         }
 
-        for step in 0..max_steps {
+        let end = drive_decode_steps(max_steps, deadline, |step| {
             let seq = tokens.len();
-            let banned_no_repeat = if partial {
-                HashSet::new()
-            } else {
-                HashSet::from_iter(banned_next_tokens_no_repeat_ngram(
-                    &tokens,
-                    prefix.len(),
-                    NO_REPEAT_NGRAM_SIZE,
-                ))
-            };
-            let generated = tokens.get(prefix.len()..).unwrap_or(&[]);
-            let mut tail_counts: HashMap<i64, usize> = HashMap::new();
-            for &tok in generated
-                .iter()
-                .rev()
-                .take(MAX_TOKEN_TAIL_HISTORY.min(generated.len()))
-            {
-                *tail_counts.entry(tok).or_insert(0) += 1;
-            }
-
             let mut dec_out = if step > 0
                 && !with_past_input_names.is_empty()
                 && with_past_input_names
@@ -610,64 +1740,21 @@ impl OnnxModel {
             let vocab_size = logit_data.len() / seq;
             let start = (seq - 1) * vocab_size;
             let last_row = &logit_data[start..start + vocab_size];
-
-            let (next, _next_logit) = last_row
-                .iter()
-                .enumerate()
-                .fold(
-                    (None::<(usize, f32)>, None::<(usize, f32)>),
-                    |(best_non_ts, best_any), (i, &v)| {
-                        let token_id = i as i64;
-                        let tail_count = tail_counts.get(&token_id).copied().unwrap_or(0);
-                        let phrase_bias = if phrase_bias_token_ids.contains(&token_id) {
-                            PHRASE_BIAS_LOGIT_BOOST
-                        } else {
-                            0.0
-                        };
-                        let penalized = v + phrase_bias - TOKEN_REPEAT_PENALTY * tail_count as f32;
-                        let suppressed_for_begin =
-                            step == 0 && begin_suppress_tokens.contains(&token_id);
-                        let suppressed_always = always_suppress_tokens.contains(&token_id);
-                        let suppressed_early_eot =
-                            token_id == eot_id && step < min_decode_steps_before_eot;
-                        let suppressed_no_repeat = banned_no_repeat.contains(&token_id);
-                        let suppressed_tail_repetition = !partial
-                            && tail_count >= MAX_TAIL_TOKEN_OCCURRENCES
-                            && token_id != eot_id;
-                        let next_best_any = match best_any {
-                            Some((_, b)) if b >= penalized => best_any,
-                            _ => Some((i, penalized)),
-                        };
-                        let is_ts = timestamp_begin.map(|tb| (i as i64) >= tb).unwrap_or(false);
-                        let next_best_non_ts = if is_ts
-                            || suppressed_for_begin
-                            || suppressed_always
-                            || suppressed_early_eot
-                            || suppressed_no_repeat
-                            || suppressed_tail_repetition
-                        {
-                            best_non_ts
-                        } else {
-                            match best_non_ts {
-                                Some((_, b)) if b >= penalized => best_non_ts,
-                                _ => Some((i, penalized)),
-                            }
-                        };
-                        (next_best_non_ts, next_best_any)
-                    },
-                )
-                .0
-                .or_else(|| {
-                    last_row
-                        .iter()
-                        .enumerate()
-                        .max_by(|(_, a): &(usize, &f32), (_, b)| {
-                            a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
-                        })
-                        .map(|(i, v)| (i, *v))
-                })
-                .map(|(i, score)| (i as i64, score))
-                .unwrap_or((eot_id, f32::NEG_INFINITY));
+            ensure_finite(last_row, "decoder logits")?;
+
+            let (next, _next_logit) =
+                select_next_token(last_row, rules.scorer(prefix.len(), &tokens, last_row))
+                    .or_else(|| {
+                        last_row
+                            .iter()
+                            .enumerate()
+                            .max_by(|(_, a): &(usize, &f32), (_, b)| {
+                                a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+                            })
+                            .map(|(i, v)| (i, *v))
+                    })
+                    .map(|(i, score)| (i as i64, score))
+                    .unwrap_or((eot_id, f32::NEG_INFINITY));
 
             tokens.push(next);
             if tokens.len() >= 2 && tokens[tokens.len() - 2] == next {
@@ -692,7 +1779,7 @@ impl OnnxModel {
                 if debug_mode {
                     info!("DICTUM_DEBUG_TRANSCRIBE: EOT reached at step {}", step);
                 }
-                break;
+                return Ok(false);
             }
             if repeated_token_count >= REPEAT_TOKEN_BREAK_THRESHOLD {
                 debug!(
@@ -700,7 +1787,7 @@ impl OnnxModel {
                     token = next,
                     "breaking decode early due to repeated-token loop"
                 );
-                break;
+                return Ok(false);
             }
             let generated = tokens.get(prefix.len()..).unwrap_or(&[]);
             if has_repeating_tail_pattern(generated) {
@@ -709,13 +1796,107 @@ impl OnnxModel {
                     generated_tokens = generated.len(),
                     "breaking decode early due to repeating tail pattern"
                 );
-                break;
+                return Ok(false);
             }
+            Ok(true)
+        })?;
+        if end == DecodeEnd::Cancelled {
+            debug!(partial, "decode cancelled");
+            return Err(DictumError::Cancelled);
+        }
+        if end == DecodeEnd::Interrupted {
+            debug!(
+                generated_tokens = tokens.len().saturating_sub(prefix.len()),
+                partial, "decode interrupted by stop; keeping tokens decoded so far"
+            );
+        }
+        if end == DecodeEnd::Deadline {
+            warn!(
+                generated_tokens = tokens.len().saturating_sub(prefix.len()),
+                partial, "decode time budget exhausted; keeping tokens decoded so far"
+            );
+        }
+
+        Ok(tokens)
+    }
+
+    /// Logits for the token after `tokens`, from a full (uncached) run of
+    /// the plain decoder.
+    fn next_token_logits(
+        decoder: &mut Session,
+        enc_data: &[f32],
+        enc_n_frames: usize,
+        enc_d_model: usize,
+        tokens: &[i64],
+    ) -> Result<Vec<f32>> {
+        let seq = tokens.len();
+        let input_ids = TensorRef::from_array_view(([1_i64, seq as i64], tokens))
+            .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+        let encoder_hidden_states = TensorRef::from_array_view((
+            [1_i64, enc_n_frames as i64, enc_d_model as i64],
+            enc_data,
+        ))
+        .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+        let dec_out = decoder
+            .run(ort::inputs![
+                "input_ids"             => input_ids,
+                "encoder_hidden_states" => encoder_hidden_states,
+            ])
+            .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+        let (_, logit_data) = dec_out["logits"]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+        let vocab_size = logit_data.len() / seq;
+        Ok(logit_data[(seq - 1) * vocab_size..].to_vec())
+    }
+
+    /// Beam search decode, returning the best full token sequence including
+    /// SOT prefix. `step_logits` re-runs the decoder on a whole hypothesis,
+    /// since one KV cache cannot be shared between hypotheses.
+    fn beam_decode(
+        max_decode_steps: usize,
+        prefix: &[i64],
+        width: usize,
+        rules: &TokenRules<'_>,
+        deadline: &DecodeDeadline,
+        step_logits: impl FnMut(&[i64]) -> Result<Vec<f32>>,
+    ) -> Result<Vec<i64>> {
+        let max_steps = max_decode_steps.clamp(1, MAX_TOKENS);
+        let (tokens, end) = beam_search(prefix, width, max_steps, rules, deadline, step_logits)?;
+        if end == DecodeEnd::Cancelled {
+            debug!("beam decode cancelled");
+            return Err(DictumError::Cancelled);
+        }
+        if end == DecodeEnd::Interrupted {
+            debug!(
+                width,
+                "beam decode interrupted by stop; keeping the best hypothesis so far"
+            );
+        }
+        if end == DecodeEnd::Deadline {
+            warn!(
+                generated_tokens = tokens.len().saturating_sub(prefix.len()),
+                width, "beam decode time budget exhausted; keeping the best hypothesis so far"
+            );
         }
-
         Ok(tokens)
     }
 
+    /// Encoder forward pass on an all-zero mel spectrogram.
+    fn run_dummy_encoder(&mut self) -> Result<()> {
+        // Array3<f32> has Ix3: Dimension + 'static → OwnedTensorArrayData satisfied.
+        let dummy = Array3::<f32>::zeros((1, self.n_mels, N_FRAMES));
+        let dummy_val = Value::from_array(dummy)
+            .map_err(|e: ort::Error| DictumError::OnnxSession(e.to_string()))?;
+        let enc = self
+            .encoder
+            .as_mut()
+            .ok_or_else(|| DictumError::OnnxSession("encoder not loaded".into()))?;
+        enc.run(ort::inputs!["input_features" => dummy_val])
+            .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+        Ok(())
+    }
+
     fn token_id_or(tokenizer: &Tokenizer, token: &str, fallback: i64) -> i64 {
         tokenizer
             .token_to_id(token)
@@ -726,13 +1907,28 @@ impl OnnxModel {
     fn decode_prefix_candidates(
         tokenizer: &Tokenizer,
         language_hint: DecodeLanguageHint,
+        task: DecodeTask,
+        fallback_languages: &[String],
     ) -> Vec<Vec<i64>> {
         let sot = Self::token_id_or(tokenizer, "<|startoftranscript|>", SOT_FALLBACK);
         let en = Self::token_id_or(tokenizer, "<|en|>", ENGLISH_FALLBACK);
         let transcribe = Self::token_id_or(tokenizer, "<|transcribe|>", TRANSCRIBE_FALLBACK);
+        // Exports without a translate token can only transcribe.
+        let task_token = match task {
+            DecodeTask::Transcribe => transcribe,
+            DecodeTask::Translate => tokenizer
+                .token_to_id(task.token())
+                .map(|id| id as i64)
+                .unwrap_or(transcribe),
+        };
         let notimestamps = Self::token_id_or(tokenizer, "<|notimestamps|>", NOTIMESTAMPS_FALLBACK);
-        let zh = tokenizer.token_to_id("<|zh|>").map(|id| id as i64);
-        let ru = tokenizer.token_to_id("<|ru|>").map(|id| id as i64);
+        // English keeps its fallback id for exports that spell it oddly.
+        let language_token = |code: &str| match code {
+            "en" => Some(en),
+            _ => tokenizer
+                .token_to_id(&format!("<|{code}|>"))
+                .map(|id| id as i64),
+        };
 
         let mut out: Vec<Vec<i64>> = Vec::new();
         let mut push_prefix = |prefix: Vec<i64>| {
@@ -741,39 +1937,27 @@ impl OnnxModel {
             }
         };
 
-        match language_hint {
-            DecodeLanguageHint::English => {
-                push_prefix(vec![sot, en, transcribe, notimestamps]);
-                push_prefix(vec![sot, en, transcribe]);
-            }
-            DecodeLanguageHint::Mandarin => {
-                if let Some(zh) = zh {
-                    push_prefix(vec![sot, zh, transcribe, notimestamps]);
-                    push_prefix(vec![sot, zh, transcribe]);
-                }
-            }
-            DecodeLanguageHint::Russian => {
-                if let Some(ru) = ru {
-                    push_prefix(vec![sot, ru, transcribe, notimestamps]);
-                    push_prefix(vec![sot, ru, transcribe]);
-                }
-            }
-            DecodeLanguageHint::Auto => {}
+        if let Some(lang) = language_hint.code().and_then(language_token) {
+            push_prefix(vec![sot, lang, task_token, notimestamps]);
+            push_prefix(vec![sot, lang, task_token]);
         }
 
         // Auto-detect first so multilingual dictation (English + Mandarin + Russian)
         // doesn't get forced through an English token.
-        push_prefix(vec![sot, transcribe, notimestamps]);
-        push_prefix(vec![sot, transcribe]);
-        if let Some(zh) = zh {
-            push_prefix(vec![sot, zh, transcribe, notimestamps]);
-        }
-        if let Some(ru) = ru {
-            push_prefix(vec![sot, ru, transcribe, notimestamps]);
+        push_prefix(vec![sot, task_token, notimestamps]);
+        push_prefix(vec![sot, task_token]);
+        for code in fallback_languages {
+            let code = code.trim().to_ascii_lowercase();
+            let Some(lang) = language_token(&code) else {
+                continue;
+            };
+            push_prefix(vec![sot, lang, task_token, notimestamps]);
+            // Slang-heavy English sometimes only decodes without the
+            // no-timestamps token.
+            if code == "en" {
+                push_prefix(vec![sot, lang, task_token]);
+            }
         }
-        // Keep English fallback late in the stack for slang-heavy English inputs.
-        push_prefix(vec![sot, en, transcribe, notimestamps]);
-        push_prefix(vec![sot, en, transcribe]);
         out
     }
 }
@@ -802,7 +1986,10 @@ impl SpeechModel for OnnxModel {
             "loading OnnxModel encoder from {:?}",
             self.config.encoder_path
         );
-        self.encoder = Some(create_session(&self.config.encoder_path)?);
+        self.execution_providers.clear();
+        let (encoder, provider) = create_session(&self.config.encoder_path)?;
+        self.encoder = Some(encoder);
+        self.execution_providers.push(provider);
 
         let encoder = self.encoder.as_ref().unwrap();
         info!("  encoder inputs:");
@@ -813,6 +2000,23 @@ impl SpeechModel for OnnxModel {
         for output in encoder.outputs().iter() {
             info!("    {}", output.name());
         }
+        let hidden_state = encoder
+            .outputs()
+            .iter()
+            .find(|o| o.name() == "last_hidden_state")
+            .or_else(|| encoder.outputs().first());
+        self.encoder_dims = hidden_state
+            .and_then(|o| o.dtype().tensor_shape())
+            .map(|shape| EncoderDims::from_shape(shape))
+            .unwrap_or_default();
+        info!(
+            n_frames = ?self.encoder_dims.n_frames,
+            d_model = ?self.encoder_dims.d_model,
+            "  encoder output dims"
+        );
+        if self.encoder_dims.d_model.is_none() {
+            warn!("encoder does not declare d_model; relying on the runtime output shape");
+        }
         if let Some(shape) = encoder
             .inputs()
             .first()
@@ -834,12 +2038,30 @@ impl SpeechModel for OnnxModel {
                 }
             }
         }
+        self.crop_encoder = self.config.crop_encoder_input;
+        if self.crop_encoder {
+            let fixed_frames = encoder
+                .inputs()
+                .first()
+                .and_then(|i| i.dtype().tensor_shape())
+                .and_then(|s| s.get(2).copied())
+                .filter(|&frames| frames > 0);
+            if let Some(frames) = fixed_frames {
+                warn!(
+                    frames,
+                    "encoder input has a fixed frame axis; encoder cropping disabled"
+                );
+                self.crop_encoder = false;
+            }
+        }
 
         info!(
             "loading OnnxModel decoder from {:?}",
             self.config.decoder_path
         );
-        self.decoder = Some(create_session(&self.config.decoder_path)?);
+        let (decoder, provider) = create_session(&self.config.decoder_path)?;
+        self.decoder = Some(decoder);
+        self.execution_providers.push(provider);
 
         let decoder = self.decoder.as_ref().unwrap();
         info!("  decoder inputs:");
@@ -850,6 +2072,17 @@ impl SpeechModel for OnnxModel {
         for output in decoder.outputs().iter() {
             info!("    {}", output.name());
         }
+        if let Some(width) = self.config.decode_strategy.beam_width() {
+            if decoder_runs_without_cache(decoder) {
+                info!(width, "beam search enabled for final decodes");
+            } else {
+                warn!(
+                    width,
+                    "decoder takes KV cache inputs that cannot be split across beams; \
+                     decoding greedily"
+                );
+            }
+        }
 
         if let Some(path) = self
             .config
@@ -858,7 +2091,9 @@ impl SpeechModel for OnnxModel {
             .filter(|p| p.exists())
         {
             info!("loading OnnxModel decoder_with_past from {:?}", path);
-            self.decoder_with_past = Some(create_session(path)?);
+            let (decoder_with_past, provider) = create_session(path)?;
+            self.decoder_with_past = Some(decoder_with_past);
+            self.execution_providers.push(provider);
             let decoder_with_past = self.decoder_with_past.as_ref().unwrap();
             info!("  decoder_with_past inputs:");
             for input in decoder_with_past.inputs().iter() {
@@ -881,26 +2116,226 @@ impl SpeechModel for OnnxModel {
 
         let tokenizer = self.tokenizer.as_ref().unwrap();
         info!("  tokenizer vocab size: {}", tokenizer.get_vocab_size(true));
+        self.byte_level_text = leaves_byte_level_markers(tokenizer);
+        self.language_tokens = language_tokens(tokenizer);
+        self.detected_language = None;
+        info!("  language tokens: {}", self.language_tokens.len());
+        if self.byte_level_text {
+            if self.config.tokenizer_decode.clean_up_text {
+                warn!("  tokenizer decode leaves byte-level markers; cleaning decoded text");
+            } else {
+                warn!("  tokenizer decode leaves byte-level markers and text cleanup is off");
+            }
+        }
+        if self.config.task == DecodeTask::Translate
+            && tokenizer
+                .token_to_id(DecodeTask::Translate.token())
+                .is_none()
+        {
+            warn!(
+                "  translate task requested but the tokenizer has no <|translate|>; transcribing"
+            );
+        }
+
+        let mut extra_suppress_tokens = match &self.config.suppress_tokens_path {
+            Some(path) => {
+                let ids = load_suppress_tokens(path, tokenizer.get_vocab_size(true))?;
+                info!("  suppress tokens: {} from {:?}", ids.len(), path);
+                ids
+            }
+            None => Vec::new(),
+        };
+        if !self.config.extra_suppress_tokens.is_empty() {
+            let ids = resolve_suppress_tokens(tokenizer, &self.config.extra_suppress_tokens)?;
+            info!("  suppress tokens: {} from config", ids.len());
+            extra_suppress_tokens.extend(ids);
+            extra_suppress_tokens.sort_unstable();
+            extra_suppress_tokens.dedup();
+        }
+        self.extra_suppress_tokens = extra_suppress_tokens;
+        self.phrase_bias_token_ids = phrase_bias_token_ids(
+            tokenizer,
+            &normalize_phrase_bias_terms(self.config.phrase_bias_terms.iter().map(String::as_str)),
+        );
+        if !self.config.phrase_bias_terms.is_empty() {
+            info!(
+                "  phrase bias: {} terms, {} tokens",
+                self.config.phrase_bias_terms.len(),
+                self.phrase_bias_token_ids.len()
+            );
+        }
 
         // Dummy encoder forward pass to populate CPU caches.
-        // Array3<f32> has Ix3: Dimension + 'static → OwnedTensorArrayData satisfied.
-        let dummy = Array3::<f32>::zeros((1, self.n_mels, N_FRAMES));
-        let dummy_val = Value::from_array(dummy)
-            .map_err(|e: ort::Error| DictumError::OnnxSession(e.to_string()))?;
-        let enc = self.encoder.as_mut().unwrap();
-        enc.run(ort::inputs!["input_features" => dummy_val])
-            .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+        self.run_dummy_encoder()?;
+
+        let may_use_gpu = self.execution_providers.iter().any(|p| *p != "cpu");
+        self.cpu_fallback = (self.config.cpu_fallback_on_degenerate && may_use_gpu)
+            .then(DegenerateOutputGuard::default);
 
         info!("=== OnnxModel warm-up complete ===");
         Ok(())
     }
 
+    fn is_warm(&self) -> bool {
+        self.encoder.is_some() && self.decoder.is_some() && self.tokenizer.is_some()
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        let languages = self
+            .tokenizer
+            .as_ref()
+            .map(|tokenizer| {
+                ["en", "zh", "ru"]
+                    .into_iter()
+                    .filter(|code| tokenizer.token_to_id(&format!("<|{code}|>")).is_some())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        ModelCapabilities {
+            backend: "onnx-whisper".into(),
+            vocab_size: self.tokenizer.as_ref().map(|t| t.get_vocab_size(true)),
+            n_mels: Some(self.n_mels),
+            decoder_with_past: self.decoder_with_past.is_some(),
+            execution_provider: registered_providers_label(&self.execution_providers),
+            languages,
+            segment_timestamps: self.config.emit_timestamps,
+        }
+    }
+
+    fn keep_warm(&mut self) -> Result<()> {
+        // Encoder on a zero mel: enough GPU work to hold clocks up, and it
+        // leaves decoder state alone.
+        if self.encoder.is_none() {
+            return Ok(());
+        }
+        self.run_dummy_encoder()
+    }
+
     fn transcribe(&mut self, chunk: &AudioChunk, partial: bool) -> Result<Vec<TranscriptSegment>> {
+        self.transcribe_until_stopped(chunk, partial, None)
+    }
+
+    fn transcribe_cancellable(
+        &mut self,
+        chunk: &AudioChunk,
+        partial: bool,
+        running: Arc<AtomicBool>,
+    ) -> Result<Vec<TranscriptSegment>> {
+        self.transcribe_until_stopped(chunk, partial, Some(running))
+    }
+
+    fn reset(&mut self) {
+        self.prompt_context = None;
+    }
+
+    fn last_transcript_meta(&self) -> TranscriptMeta {
+        self.last_meta.clone()
+    }
+
+    fn begin_session(&mut self) {
+        self.truncation_retries = RetryBudget::default();
+    }
+
+    fn set_short_utterance_decode(&mut self, enabled: bool) {
+        self.short_utterance_decode = enabled;
+    }
+
+    fn set_cancel_flag(&mut self, flag: Option<Arc<AtomicBool>>) {
+        self.cancel = flag;
+    }
+
+    fn set_prompt_context(&mut self, context: Option<&str>) {
+        self.prompt_context = context
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+    }
+
+    fn set_language_hint(&mut self, language: Option<&str>) -> Result<()> {
+        let hint = match language {
+            Some(raw) => DecodeLanguageHint::parse(raw).ok_or_else(|| {
+                DictumError::InvalidConfig(format!("unsupported language hint: {raw:?}"))
+            })?,
+            None => DecodeLanguageHint::Auto,
+        };
+        if hint != self.language_hint {
+            info!(previous = ?self.language_hint.code(), language = ?hint.code(), "language hint changed");
+        }
+        self.language_hint = hint;
+        Ok(())
+    }
+
+    fn language_hint(&self) -> Option<String> {
+        self.language_hint.code().map(str::to_string)
+    }
+}
+
+/// `<|startofprev|>` and the last `MAX_PROMPT_TOKENS` of `prompt_ids`, to go
+/// in front of a decode prefix. Empty when there is nothing to carry.
+fn prompt_prefix(startofprev: i64, prompt_ids: &[u32]) -> Vec<i64> {
+    if prompt_ids.is_empty() {
+        return Vec::new();
+    }
+    let kept = &prompt_ids[prompt_ids.len().saturating_sub(MAX_PROMPT_TOKENS)..];
+    std::iter::once(startofprev)
+        .chain(kept.iter().map(|&id| i64::from(id)))
+        .collect()
+}
+
+/// Prompt tokens for carried `context`, or none without context or when
+/// the tokenizer has no `<|startofprev|>` to introduce it.
+fn context_prompt(tokenizer: &Tokenizer, context: Option<&str>) -> Vec<i64> {
+    let (Some(context), Some(startofprev)) = (context, tokenizer.token_to_id("<|startofprev|>"))
+    else {
+        return Vec::new();
+    };
+    match tokenizer.encode(format!(" {context}"), false) {
+        Ok(encoding) => prompt_prefix(i64::from(startofprev), encoding.get_ids()),
+        Err(e) => {
+            warn!(error = %e, "failed to tokenize carried context; decoding without it");
+            Vec::new()
+        }
+    }
+}
+
+impl OnnxModel {
+    /// `transcribe`, cut short once `running` (when given) is cleared.
+    fn transcribe_until_stopped(
+        &mut self,
+        chunk: &AudioChunk,
+        partial: bool,
+        running: Option<Arc<AtomicBool>>,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let segments = self.decode_chunk(chunk, partial, running.clone())?;
+        let rebuilt = self.cpu_fallback.as_mut().is_some_and(|guard| {
+            guard.run_if_due(|| {
+                rebuild_sessions_on_cpu(
+                    &self.config,
+                    &mut self.encoder,
+                    &mut self.decoder,
+                    &mut self.decoder_with_past,
+                )
+            })
+        });
+        if rebuilt {
+            // Redo this chunk on the CPU sessions.
+            self.execution_providers = vec!["cpu"];
+            return self.decode_chunk(chunk, partial, running);
+        }
+        Ok(segments)
+    }
+
+    fn decode_chunk(
+        &mut self,
+        chunk: &AudioChunk,
+        partial: bool,
+        running: Option<Arc<AtomicBool>>,
+    ) -> Result<Vec<TranscriptSegment>> {
+        self.last_meta = TranscriptMeta::default();
         // Verify models are loaded before taking mutable borrows.
-        if self.encoder.is_none() || self.decoder.is_none() || self.tokenizer.is_none() {
-            return Err(DictumError::OnnxSession(
-                "model not loaded — call warm_up()".into(),
-            ));
+        if !self.is_warm() {
+            return Err(DictumError::NotWarmedUp);
         }
 
         // 1. Pad / trim to 30 s.
@@ -909,7 +2344,11 @@ impl SpeechModel for OnnxModel {
         samples.resize(MEL_SAMPLES, 0.0);
 
         // 2. Log-mel spectrogram (before taking mutable session borrows).
-        let mel = self.log_mel_spectrogram(&samples, active_samples);
+        let mel = self.log_mel_spectrogram(
+            &samples,
+            active_samples,
+            self.encoder_input_frames(active_samples),
+        )?;
         let mel_val = Value::from_array(mel)
             .map_err(|e: ort::Error| DictumError::OnnxSession(e.to_string()))?;
 
@@ -926,50 +2365,83 @@ impl SpeechModel for OnnxModel {
         let (enc_shape_raw, enc_data) = enc_out["last_hidden_state"]
             .try_extract_tensor::<f32>()
             .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+        ensure_finite_sampled(enc_data, DEFAULT_FINITE_PROBES, "encoder output")?;
+
+        // enc_shape is [1, n_enc_frames, d_model].
+        let (enc_n_frames, enc_d_model) =
+            self.encoder_dims.resolve(enc_shape_raw, enc_data.len())?;
 
-        // Derive encoder time and feature dims from shape.
-        // enc_shape is [1, n_enc_frames, d_model]; use data length as fallback.
-        let (enc_n_frames, enc_d_model) = {
-            let total = enc_data.len(); // 1 * n * d
-                                        // Try to read from ort Shape (treats elements as i64 via Deref)
-            if enc_shape_raw.len() >= 3 {
-                let n = enc_shape_raw[1] as usize;
-                let d = enc_shape_raw[2] as usize;
-                (n, d)
-            } else {
-                // Fallback: assume standard Whisper small (d_model=384)
-                let d = 384usize;
-                (total / d, d)
-            }
-        };
         // 4. Greedy decode. Try a few Whisper-compatible prefixes so tokenizer
         // variants still decode correctly.
         let eot_id = tokenizer
             .token_to_id("<|endoftext|>")
             .map(|id| id as i64)
             .unwrap_or(EOT);
-        let decode_prefixes = Self::decode_prefix_candidates(tokenizer, decode_language_hint());
+        let language_hint = self.language_hint;
+        let language = language_hint.code();
+        // Post-processing follows the output text, not the spoken language.
+        let text_lang = self.config.task.text_language(language);
+        let prompt = context_prompt(tokenizer, self.prompt_context.as_deref());
+        let candidates = Self::decode_prefix_candidates(
+            tokenizer,
+            language_hint,
+            self.config.task,
+            &self.config.prefix_search.fallback_languages,
+        );
+        // Without a hint, let the decoder pick the language after SOT, as
+        // Whisper does, so the auto prefixes carry it and results report it.
+        // Partials skip that extra decoder run and reuse the last final's.
+        let candidates = if language.is_none() && !self.language_tokens.is_empty() {
+            let detected = if partial {
+                self.detected_language
+            } else {
+                let sot = Self::token_id_or(tokenizer, "<|startoftranscript|>", SOT_FALLBACK);
+                let logits =
+                    Self::next_token_logits(decoder, enc_data, enc_n_frames, enc_d_model, &[sot])?;
+                let detected = detect_language(&logits, &self.language_tokens).map(|(id, code)| {
+                    debug!(language = %code, "detected language");
+                    *id
+                });
+                self.detected_language = detected;
+                detected
+            };
+            match detected {
+                Some(id) => with_detected_language(tokenizer, candidates, id),
+                None => candidates,
+            }
+        } else {
+            candidates
+        };
+        let (fast_prefix_limit, candidates) =
+            self.config.prefix_search.select(language_hint, candidates);
         let timestamp_begin = tokenizer.token_to_id("<|0.00|>").map(|id| id as i64);
+        let emit_timestamps = self.config.emit_timestamps && !partial && timestamp_begin.is_some();
+        let notimestamps = Self::token_id_or(tokenizer, "<|notimestamps|>", NOTIMESTAMPS_FALLBACK);
+        let mut decode_prefixes: Vec<Vec<i64>> = Vec::new();
+        for mut prefix in candidates {
+            if emit_timestamps {
+                prefix.retain(|&t| t != notimestamps);
+            }
+            let prefix = [prompt.as_slice(), &prefix].concat();
+            if !decode_prefixes.contains(&prefix) {
+                decode_prefixes.push(prefix);
+            }
+        }
         let mut begin_suppress_tokens = vec![220i64, eot_id];
         begin_suppress_tokens.sort_unstable();
         begin_suppress_tokens.dedup();
         let mut always_suppress_tokens = vec![];
-        for tok in [
-            "<|startoftranscript|>",
-            "<|translate|>",
-            "<|transcribe|>",
-            "<|notimestamps|>",
-            "<|nospeech|>",
-        ] {
+        for tok in suppressed_control_tokens(self.config.task) {
             if let Some(id) = tokenizer.token_to_id(tok) {
                 always_suppress_tokens.push(id as i64);
             }
         }
+        always_suppress_tokens.extend_from_slice(&self.extra_suppress_tokens);
         always_suppress_tokens.sort_unstable();
         always_suppress_tokens.dedup();
 
         let decode_to_text =
-            |tokens: &[i64], prefix_len: usize| -> Result<(String, Option<&'static str>)> {
+            |tokens: &[i64], prefix_len: usize| -> Result<(String, Option<EmptyReason>)> {
                 let text_tokens: Vec<u32> = tokens[prefix_len..]
                     .iter()
                     .take_while(|&&t| t != eot_id)
@@ -977,85 +2449,149 @@ impl SpeechModel for OnnxModel {
                     .map(|&t| t as u32)
                     .collect();
                 if text_tokens.is_empty() {
-                    return Ok((String::new(), Some("no_tokens_before_eot")));
+                    return Ok((String::new(), Some(EmptyReason::NoSpeech)));
                 }
-                let decoded = tokenizer
-                    .decode(&text_tokens, true)
-                    .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
-                let text = decoded.trim().to_string();
+                let text = decode_text(
+                    tokenizer,
+                    &text_tokens,
+                    self.config.tokenizer_decode,
+                    self.byte_level_text,
+                )?;
                 if text.is_empty() {
-                    return Ok((String::new(), Some("decoded_empty_text")));
+                    return Ok((String::new(), Some(EmptyReason::NoSpeech)));
                 }
                 Ok((text, None))
             };
-        let phrase_bias_terms = phrase_bias_terms_from_env();
-        let phrase_bias_token_ids = phrase_bias_token_ids(tokenizer, &phrase_bias_terms);
+        // Configured terms were resolved at warm-up; the environment is
+        // only a fallback, re-read so runtime changes apply.
+        let env_phrase_bias_token_ids;
+        let phrase_bias_token_ids = if self.config.phrase_bias_terms.is_empty() {
+            env_phrase_bias_token_ids =
+                phrase_bias_token_ids(tokenizer, &phrase_bias_terms_from_env());
+            &env_phrase_bias_token_ids
+        } else {
+            &self.phrase_bias_token_ids
+        };
 
         let debug_mode = is_debug_transcribe();
         let mut tokens = Vec::new();
         let mut text = String::new();
-        let mut empty_reason = Some("decode_not_attempted");
+        let mut empty_reason = Some(EmptyReason::NotDecoded);
         let audio_seconds = active_samples as f32 / chunk.sample_rate.max(1) as f32;
-        let adaptive_final_steps = {
-            // Heuristic: estimated tokens/s + prompt allowance. Clamp to avoid runaway
-            // decoding when EOT is not emitted promptly.
-            let estimated = ((active_samples as f32 / chunk.sample_rate as f32)
-                * TOKENS_PER_SECOND_ESTIMATE)
-                .ceil() as usize
-                + DECODE_TOKEN_OVERHEAD;
-            estimated.clamp(MIN_FINAL_TOKENS, MAX_TOKENS)
-        };
-        let max_decode_steps = if partial {
-            PARTIAL_MAX_TOKENS.min(adaptive_final_steps)
-        } else {
-            adaptive_final_steps
-        };
-        let fast_decode_steps = if partial {
-            max_decode_steps
-        } else {
-            let short_cap = if audio_seconds <= 4.0 {
-                96
-            } else if audio_seconds <= 8.0 {
-                128
-            } else {
-                160
+        let short_utterances = self.short_utterance_decode;
+        let (max_decode_steps, fast_decode_steps) =
+            decode_step_budget(audio_seconds, partial, short_utterances, emit_timestamps);
+        if !partial && short_utterances && audio_seconds <= SHORT_UTTERANCE_SECONDS {
+            debug!(
+                audio_seconds = format_args!("{audio_seconds:.2}"),
+                decode_steps = max_decode_steps,
+                "short utterance decode path"
+            );
+        }
+        let min_eot_steps =
+            min_decode_steps_before_eot(audio_seconds, partial, eot_min_steps_per_second());
+        // Details of every accepted candidate, by its text, so the ones
+        // behind the text finally chosen can be looked up.
+        let mut candidate_details: HashMap<String, CandidateDetails> = HashMap::new();
+        let word_timings_of = |tokens: &[i64], prefix_len: usize| -> Result<Vec<WordTiming>> {
+            let Some(timestamp_begin) = timestamp_begin else {
+                return Ok(Vec::new());
             };
-            max_decode_steps.clamp(MIN_FINAL_TOKENS, short_cap)
+            let audio_ms = (audio_seconds * 1000.0) as u32;
+            let mut timings = Vec::new();
+            for span in timed_spans(&tokens[prefix_len..], timestamp_begin, eot_id, audio_ms) {
+                let ids: Vec<u32> = span.tokens.iter().map(|&t| t as u32).collect();
+                let span_text = decode_text(
+                    tokenizer,
+                    &ids,
+                    self.config.tokenizer_decode,
+                    self.byte_level_text,
+                )?;
+                timings.extend(spread_words(&span_text, span.start_ms, span.end_ms));
+            }
+            Ok(timings)
         };
+        // Whether the first decode attempt looped; feeds the CPU fallback.
+        let first_candidate_degenerate: Cell<Option<bool>> = Cell::new(None);
+
+        let rules = TokenRules {
+            eot_id,
+            // Timestamp tokens stay suppressed unless requested.
+            timestamp_begin: timestamp_begin.filter(|_| !emit_timestamps),
+            timestamp_rules: timestamp_begin.filter(|_| emit_timestamps),
+            begin_suppress_tokens: &begin_suppress_tokens,
+            always_suppress_tokens: &always_suppress_tokens,
+            phrase_bias_token_ids,
+            partial,
+            min_decode_steps_before_eot: min_eot_steps,
+        };
+        // Finals only, and only where hypotheses need no KV cache of their own.
+        let beam_width = self
+            .config
+            .decode_strategy
+            .beam_width()
+            .filter(|_| !partial && decoder_runs_without_cache(decoder));
 
+        let deadline = DecodeDeadline::after(self.config.max_decode_ms)
+            .with_cancel(self.cancel.clone())
+            .with_running(running);
         let mut try_prefix =
             |prefix: &[i64], decode_steps: usize| -> Result<(Option<String>, bool)> {
+                if deadline.cancelled() {
+                    return Err(DictumError::Cancelled);
+                }
+                if deadline.expired() || deadline.interrupted() {
+                    return Ok((None, false));
+                }
                 if debug_mode {
                     info!(
                         prefix = ?prefix, decode_steps,
                         "DICTUM_DEBUG_TRANSCRIBE: trying decode prefix"
                     );
                 }
-                let candidate_tokens = Self::greedy_decode(
-                    decoder,
-                    decoder_with_past.as_mut().map(|s| &mut **s),
-                    tokenizer,
-                    enc_data,
-                    enc_n_frames,
-                    enc_d_model,
-                    decode_steps,
-                    prefix,
-                    eot_id,
-                    timestamp_begin,
-                    &begin_suppress_tokens,
-                    &always_suppress_tokens,
-                    &phrase_bias_token_ids,
-                    partial,
-                )?;
+                let candidate_tokens = match beam_width {
+                    Some(width) => {
+                        Self::beam_decode(decode_steps, prefix, width, &rules, &deadline, |t| {
+                            Self::next_token_logits(decoder, enc_data, enc_n_frames, enc_d_model, t)
+                        })?
+                    }
+                    None => Self::greedy_decode(
+                        decoder,
+                        decoder_with_past.as_mut().map(|s| &mut **s),
+                        tokenizer,
+                        enc_data,
+                        enc_n_frames,
+                        enc_d_model,
+                        decode_steps,
+                        prefix,
+                        &rules,
+                        &deadline,
+                    )?,
+                };
                 let generated_len = candidate_tokens.len().saturating_sub(prefix.len());
                 let ended_with_eot = candidate_tokens.last().copied() == Some(eot_id);
                 let reached_ceiling_no_eot = generated_len >= decode_steps && !ended_with_eot;
                 let (candidate_text_raw, candidate_reason) =
                     decode_to_text(&candidate_tokens, prefix.len())?;
+                let details = CandidateDetails {
+                    language: decoded_language(tokenizer, &candidate_tokens[prompt.len()..]),
+                    word_timings: if emit_timestamps {
+                        word_timings_of(&candidate_tokens, prefix.len())?
+                    } else {
+                        Vec::new()
+                    },
+                };
                 tokens = candidate_tokens;
                 empty_reason = candidate_reason;
-                let candidate_text = postprocess_transcript_text(&candidate_text_raw);
+                let candidate_text = postprocess_transcript_text(&candidate_text_raw, text_lang);
+                if first_candidate_degenerate.get().is_none() && !candidate_text.is_empty() {
+                    first_candidate_degenerate
+                        .set(Some(is_degenerate_transcript_text(&candidate_text)));
+                }
                 if candidate_text.is_empty() {
+                    // Post-processing can strip everything, e.g. a lone
+                    // `[BLANK_AUDIO]` marker.
+                    empty_reason = empty_reason.or(Some(EmptyReason::NoSpeech));
                     return Ok((None, reached_ceiling_no_eot));
                 }
                 if !partial && is_low_quality_transcript_text(&candidate_text, audio_seconds) {
@@ -1064,21 +2600,22 @@ impl SpeechModel for OnnxModel {
                         audio_seconds = format_args!("{audio_seconds:.2}"),
                         "dropping low-quality transcript candidate"
                     );
-                    empty_reason = Some("low_quality_candidate_filtered");
+                    empty_reason = Some(EmptyReason::LowQualityFiltered);
                     return Ok((None, reached_ceiling_no_eot));
                 }
+                candidate_details.insert(candidate_text.clone(), details);
                 Ok((Some(candidate_text), reached_ceiling_no_eot))
             };
 
+        let truncation_retry = self.config.truncation_retry;
         let mut ceiling_retry_needed = false;
-        let fast_prefix_limit = 1usize;
         for prefix in decode_prefixes.iter().take(fast_prefix_limit) {
             let (candidate, reached_ceiling_no_eot) = try_prefix(prefix, fast_decode_steps)?;
             if let Some(candidate_text) = candidate {
                 text = candidate_text;
                 if !partial
                     && reached_ceiling_no_eot
-                    && likely_truncated_transcript(&text, audio_seconds)
+                    && truncation_retry.suspects_truncation(&text, audio_seconds)
                 {
                     ceiling_retry_needed = true;
                 }
@@ -1092,7 +2629,7 @@ impl SpeechModel for OnnxModel {
                     text = candidate_text;
                     if !partial
                         && reached_ceiling_no_eot
-                        && likely_truncated_transcript(&text, audio_seconds)
+                        && truncation_retry.suspects_truncation(&text, audio_seconds)
                     {
                         ceiling_retry_needed = true;
                     }
@@ -1101,10 +2638,13 @@ impl SpeechModel for OnnxModel {
             }
         }
 
-        if !partial
-            && max_decode_steps > fast_decode_steps
-            && (text.is_empty() || ceiling_retry_needed)
-        {
+        if self.truncation_retries.full_budget_retry(
+            truncation_retry.session_budget,
+            partial,
+            text.is_empty(),
+            ceiling_retry_needed,
+            (max_decode_steps, fast_decode_steps),
+        ) {
             let mut retry_text = String::new();
             for prefix in decode_prefixes.iter().take(fast_prefix_limit) {
                 let (candidate, _) = try_prefix(prefix, max_decode_steps)?;
@@ -1158,6 +2698,15 @@ impl SpeechModel for OnnxModel {
             text = best_refine_text;
         }
 
+        if !partial {
+            if let (Some(guard), Some(degenerate)) =
+                (self.cpu_fallback.as_mut(), first_candidate_degenerate.get())
+            {
+                guard.record(degenerate);
+            }
+        }
+
+        let mut source = TranscriptSource::Local;
         let mut local_confidence = if partial {
             None
         } else {
@@ -1213,16 +2762,19 @@ impl SpeechModel for OnnxModel {
                 };
 
                 if should_try_cloud {
-                    if let Some(cloud_text_raw) =
-                        openai_cloud_fallback_text(&chunk.samples, chunk.sample_rate)
-                    {
-                        let cloud_text = postprocess_transcript_text(&cloud_text_raw);
+                    if let Some(cloud_text_raw) = openai_cloud_fallback_text(
+                        &chunk.samples,
+                        chunk.sample_rate,
+                        self.config.cloud_wav_format,
+                    ) {
+                        let cloud_text = postprocess_transcript_text(&cloud_text_raw, text_lang);
                         if cloud_text.is_empty() {
                             if text.is_empty() {
-                                empty_reason = Some("cloud_fallback_empty_after_postprocess");
+                                empty_reason = Some(EmptyReason::FallbackEmpty);
                             }
                         } else if text.is_empty() {
                             text = cloud_text;
+                            source = TranscriptSource::Cloud;
                             empty_reason = None;
                             info!("onnx empty decode recovered by OpenAI cloud fallback");
                         } else {
@@ -1232,6 +2784,7 @@ impl SpeechModel for OnnxModel {
                                 && cloud_score + 0.25 >= local_score;
                             if cloud_score > local_score + 0.30 || prefer_cloud {
                                 text = cloud_text;
+                                source = TranscriptSource::Cloud;
                                 info!(
                                     local_score = format_args!("{local_score:.2}"),
                                     cloud_score = format_args!("{cloud_score:.2}"),
@@ -1241,7 +2794,7 @@ impl SpeechModel for OnnxModel {
                         }
                         local_confidence = estimate_segment_confidence(&text, audio_seconds, false);
                     } else if text.is_empty() {
-                        empty_reason = Some("cloud_fallback_unavailable");
+                        empty_reason = Some(EmptyReason::CloudUnavailable);
                     }
                 }
             }
@@ -1251,11 +2804,12 @@ impl SpeechModel for OnnxModel {
             if let Some(fallback_text) =
                 windows_dictation_fallback_text(&chunk.samples, chunk.sample_rate)
             {
-                let fallback_text = postprocess_transcript_text(&fallback_text);
+                let fallback_text = postprocess_transcript_text(&fallback_text, text_lang);
                 if fallback_text.is_empty() {
-                    empty_reason = Some("fallback_empty_after_postprocess");
+                    empty_reason = Some(EmptyReason::FallbackEmpty);
                 } else {
                     text = fallback_text;
+                    source = TranscriptSource::OsDictation;
                     empty_reason = None;
                     info!("onnx empty decode recovered by Windows dictation fallback");
                 }
@@ -1270,33 +2824,364 @@ impl SpeechModel for OnnxModel {
                     "DICTUM_DEBUG_TRANSCRIBE: all decode paths produced empty text"
                 );
             }
+            self.last_meta.empty_reason = empty_reason;
             return Ok(vec![]);
         }
 
-        self.utterance_count += 1;
         let kind = if partial {
             SegmentKind::Partial
         } else {
             SegmentKind::Final
         };
 
+        let confidence = if partial {
+            None
+        } else {
+            local_confidence.or_else(|| estimate_segment_confidence(&text, audio_seconds, false))
+        };
+        // Cloud and OS fallbacks replace the text the details belong to.
+        let CandidateDetails {
+            language: decoded_language,
+            word_timings,
+        } = match source {
+            TranscriptSource::Local => candidate_details.remove(&text).unwrap_or_default(),
+            _ => CandidateDetails::default(),
+        };
+        // Translated text is reported as the English it is.
+        let decoded_language = self
+            .config
+            .task
+            .text_language(decoded_language.as_deref())
+            .map(str::to_string);
+        self.last_meta = TranscriptMeta {
+            confidence,
+            language: decoded_language
+                .clone()
+                .or_else(|| text_lang.map(str::to_string)),
+            source: Some(source),
+            ..TranscriptMeta::default()
+        };
         Ok(vec![TranscriptSegment {
-            id: self.utterance_count.to_string(),
-            text: text.clone(),
+            id: String::new(),
+            text,
             kind,
-            confidence: if partial {
-                None
-            } else {
-                local_confidence
-                    .or_else(|| estimate_segment_confidence(&text, audio_seconds, false))
-            },
+            confidence,
+            start_ms: word_timings.first().map(|w| w.start_ms),
+            end_ms: word_timings.last().map(|w| w.end_ms),
+            word_timings,
+            language: decoded_language,
         }])
     }
+}
+
+/// Finals considered when judging whether GPU output has gone bad.
+const DEGENERATE_WINDOW: usize = 8;
+/// Degenerate finals within the window that trigger the CPU rebuild.
+const DEGENERATE_TRIGGER: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CpuFallbackState {
+    Armed,
+    Due,
+    Spent,
+}
+
+/// Tracks how often final decodes loop and fires a one-time CPU rebuild
+/// once the rate is too high to be the speaker's fault.
+#[derive(Debug)]
+struct DegenerateOutputGuard {
+    recent: VecDeque<bool>,
+    state: CpuFallbackState,
+}
+
+impl Default for DegenerateOutputGuard {
+    fn default() -> Self {
+        Self {
+            recent: VecDeque::with_capacity(DEGENERATE_WINDOW),
+            state: CpuFallbackState::Armed,
+        }
+    }
+}
+
+impl DegenerateOutputGuard {
+    fn record(&mut self, degenerate: bool) {
+        if self.state != CpuFallbackState::Armed {
+            return;
+        }
+        if self.recent.len() == DEGENERATE_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(degenerate);
+        if self.recent.iter().filter(|&&d| d).count() >= DEGENERATE_TRIGGER {
+            self.state = CpuFallbackState::Due;
+        }
+    }
+
+    /// Run `rebuild` if the threshold was reached. Never runs twice, even
+    /// when the rebuild fails. Returns whether the rebuild succeeded.
+    fn run_if_due(&mut self, rebuild: impl FnOnce() -> Result<()>) -> bool {
+        if self.state != CpuFallbackState::Due {
+            return false;
+        }
+        self.state = CpuFallbackState::Spent;
+        let degenerate = self.recent.iter().filter(|&&d| d).count();
+        warn!(
+            degenerate,
+            window = self.recent.len(),
+            "repeated degenerate GPU decodes; rebuilding ONNX sessions on CPU"
+        );
+        match rebuild() {
+            Ok(()) => {
+                warn!("ONNX sessions now running on the CPU execution provider");
+                true
+            }
+            Err(e) => {
+                error!(error = %e, "CPU session rebuild failed; keeping current sessions");
+                false
+            }
+        }
+    }
+}
+
+/// Recreate every session on the CPU execution provider. The existing
+/// sessions are only replaced once all new ones load.
+fn rebuild_sessions_on_cpu(
+    config: &OnnxModelConfig,
+    encoder: &mut Option<Session>,
+    decoder: &mut Option<Session>,
+    decoder_with_past: &mut Option<Session>,
+) -> Result<()> {
+    let cpu = OrtExecutionPreference::Cpu;
+    let (new_encoder, _) = create_session_with(&config.encoder_path, cpu)?;
+    let (new_decoder, _) = create_session_with(&config.decoder_path, cpu)?;
+    let new_with_past = match decoder_with_past {
+        Some(_) => match &config.decoder_with_past_path {
+            Some(path) => Some(create_session_with(path, cpu)?.0),
+            None => None,
+        },
+        None => None,
+    };
+    *encoder = Some(new_encoder);
+    *decoder = Some(new_decoder);
+    *decoder_with_past = new_with_past;
+    Ok(())
+}
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+/// What an accepted decode candidate carries besides its text.
+#[derive(Debug, Default)]
+struct CandidateDetails {
+    language: Option<String>,
+    word_timings: Vec<WordTiming>,
+}
+
+/// Language code a Whisper language token spells (`<|en|>`, `<|yue|>`,
+/// ...); `None` for any other token.
+fn language_code(token: &str) -> Option<&str> {
+    let code = token.strip_prefix("<|")?.strip_suffix("|>")?;
+    ((2..=3).contains(&code.len()) && code.bytes().all(|b| b.is_ascii_lowercase())).then_some(code)
+}
+
+/// Code of the first Whisper language token in `tokens`: the one a forced
+/// or detected prefix carries, or one the decoder emitted.
+fn decoded_language(tokenizer: &Tokenizer, tokens: &[i64]) -> Option<String> {
+    tokens.iter().find_map(|&id| {
+        let token = tokenizer.id_to_token(u32::try_from(id).ok()?)?;
+        language_code(&token).map(str::to_string)
+    })
+}
+
+/// Every language token in the vocabulary, as `(id, code)`, by id.
+fn language_tokens(tokenizer: &Tokenizer) -> Vec<(i64, String)> {
+    let mut tokens: Vec<(i64, String)> = tokenizer
+        .get_vocab(true)
+        .into_iter()
+        .filter_map(|(token, id)| Some((i64::from(id), language_code(&token)?.to_string())))
+        .collect();
+    tokens.sort_unstable();
+    tokens
+}
+
+/// The language token scoring highest in `logits`, the decoder's
+/// prediction for the token after SOT. `None` without language tokens.
+fn detect_language<'a>(
+    logits: &[f32],
+    language_tokens: &'a [(i64, String)],
+) -> Option<&'a (i64, String)> {
+    language_tokens
+        .iter()
+        .filter_map(|entry| Some((entry, *logits.get(usize::try_from(entry.0).ok()?)?)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entry, _)| entry)
+}
+
+/// `prefixes` with `language` inserted after SOT in those that carry no
+/// language token, the auto-detect ones. Duplicates this creates are
+/// dropped, keeping the first.
+fn with_detected_language(
+    tokenizer: &Tokenizer,
+    prefixes: Vec<Vec<i64>>,
+    language: i64,
+) -> Vec<Vec<i64>> {
+    let mut out: Vec<Vec<i64>> = Vec::with_capacity(prefixes.len());
+    for mut prefix in prefixes {
+        if !prefix.is_empty() && decoded_language(tokenizer, &prefix).is_none() {
+            prefix.insert(1, language);
+        }
+        if !out.contains(&prefix) {
+            out.push(prefix);
+        }
+    }
+    out
+}
+
+/// Whisper timestamp tokens advance in 20 ms steps from `<|0.00|>`.
+const TIMESTAMP_STEP_MS: u32 = 20;
+
+/// Offset (ms) timestamp token `token` stands for; `None` for text tokens.
+fn timestamp_token_ms(token: i64, timestamp_begin: i64) -> Option<u32> {
+    let steps = u32::try_from(token.checked_sub(timestamp_begin)?).ok()?;
+    Some(steps.saturating_mul(TIMESTAMP_STEP_MS))
+}
+
+/// Text tokens between two timestamp tokens.
+#[derive(Debug, PartialEq, Eq)]
+struct TimedSpan {
+    start_ms: u32,
+    end_ms: u32,
+    tokens: Vec<i64>,
+}
+
+/// Split generated tokens (prefix removed) into runs of text tokens with
+/// the times of the timestamp tokens around them. A run with no timestamp
+/// before it starts at 0 and one with none after it ends at `audio_ms`, so
+/// a decode with a single timestamp token still yields a range.
+fn timed_spans(tokens: &[i64], timestamp_begin: i64, eot_id: i64, audio_ms: u32) -> Vec<TimedSpan> {
+    let mut spans = Vec::new();
+    let mut start_ms = None;
+    let mut text = Vec::new();
+    for &token in tokens.iter().take_while(|&&t| t != eot_id) {
+        let Some(at_ms) = timestamp_token_ms(token, timestamp_begin) else {
+            text.push(token);
+            continue;
+        };
+        if !text.is_empty() {
+            let start = start_ms.unwrap_or(0);
+            spans.push(TimedSpan {
+                start_ms: start,
+                end_ms: at_ms.max(start),
+                tokens: std::mem::take(&mut text),
+            });
+        }
+        start_ms = Some(at_ms);
+    }
+    if !text.is_empty() {
+        let start = start_ms.unwrap_or(0);
+        spans.push(TimedSpan {
+            start_ms: start,
+            end_ms: audio_ms.max(start),
+            tokens: text,
+        });
+    }
+    spans
+}
+
+/// Share `start_ms..end_ms` among the words of `text` by character count.
+/// Whisper only times spans, so word edges inside one are estimates.
+fn spread_words(text: &str, start_ms: u32, end_ms: u32) -> Vec<WordTiming> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let total_chars: usize = words.iter().map(|w| w.chars().count()).sum();
+    let span_ms = u64::from(end_ms.saturating_sub(start_ms));
+    let mut chars_before = 0usize;
+    words
+        .into_iter()
+        .map(|word| {
+            let at = |chars: usize| {
+                start_ms + (span_ms * chars as u64 / total_chars.max(1) as u64) as u32
+            };
+            let word_start = at(chars_before);
+            chars_before += word.chars().count();
+            WordTiming {
+                word: word.to_string(),
+                start_ms: word_start,
+                end_ms: at(chars_before),
+            }
+        })
+        .collect()
+}
+
+/// Decode `ids` to trimmed text as `options` ask. `byte_level` says the
+/// tokenizer leaves byte-level markers (see [`leaves_byte_level_markers`]).
+fn decode_text(
+    tokenizer: &Tokenizer,
+    ids: &[u32],
+    options: TokenizerDecodeConfig,
+    byte_level: bool,
+) -> Result<String> {
+    let decoded = tokenizer
+        .decode(ids, options.skip_special_tokens)
+        .map_err(|e| DictumError::OnnxSession(e.to_string()))?;
+    if !options.clean_up_text {
+        return Ok(decoded.trim().to_string());
+    }
+    let decoded = if byte_level {
+        undo_byte_level(&decoded)
+    } else {
+        decoded
+    };
+    Ok(collapse_spaces(&decoded))
+}
+
+/// `text` trimmed, with each run of spaces and tabs collapsed to one space
+/// and spaces next to a line break dropped. Line breaks themselves, from a
+/// dictated "new line" or a multi-line snippet, are kept.
+fn collapse_spaces(text: &str) -> String {
+    text.trim()
+        .split('\n')
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    fn reset(&mut self) {}
+/// Whether `tokenizer` decodes a space-prefixed token with its `Ġ` marker
+/// intact, i.e. the export has no byte-level decoder. Tokenizers without
+/// such tokens are not byte-level BPE and are left alone.
+fn leaves_byte_level_markers(tokenizer: &Tokenizer) -> bool {
+    ["Ġthe", "Ġa", "Ġ"]
+        .iter()
+        .find_map(|token| tokenizer.token_to_id(token))
+        .and_then(|id| tokenizer.decode(&[id], false).ok())
+        .is_some_and(|text| text.contains('Ġ'))
 }
 
-// ── Helpers ──────────────────────────────────────────────────────────────────
+/// Reverse GPT-2's byte-to-unicode mapping: printable Latin-1 characters
+/// stand for their own byte, and the other 68 bytes (controls, space,
+/// ...) were shifted to U+0100 onwards in byte order, so `Ġ` is a space
+/// and `Ċ` a newline. Characters outside the mapping are kept as they are.
+fn undo_byte_level(text: &str) -> String {
+    fn printable(byte: u32) -> bool {
+        matches!(byte, 0x21..=0x7E | 0xA1..=0xAC | 0xAE..=0xFF)
+    }
+    let shifted: Vec<u8> = (0u8..=255).filter(|&b| !printable(b.into())).collect();
+
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        let code = c as u32;
+        if printable(code) {
+            bytes.push(code as u8);
+        } else if let Some(&byte) = code
+            .checked_sub(0x100)
+            .and_then(|i| shifted.get(i as usize))
+        {
+            bytes.push(byte);
+        } else {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
 
 fn decoder_with_past_input_names(session: &Session) -> Vec<String> {
     session
@@ -1385,77 +3270,6 @@ fn has_repeating_tail_pattern(generated: &[i64]) -> bool {
     false
 }
 
-fn postprocess_transcript_text(text: &str) -> String {
-    let trimmed = text.trim();
-    if trimmed.is_empty() {
-        return String::new();
-    }
-
-    // Normalize whitespace and remove spaces before punctuation.
-    let mut compact = String::with_capacity(trimmed.len() + 8);
-    let mut prev_was_space = false;
-    for ch in trimmed.chars() {
-        if ch.is_whitespace() {
-            if !prev_was_space {
-                compact.push(' ');
-            }
-            prev_was_space = true;
-            continue;
-        }
-        if matches!(ch, '.' | ',' | '!' | '?' | ';' | ':') && compact.ends_with(' ') {
-            compact.pop();
-        }
-        compact.push(ch);
-        prev_was_space = false;
-    }
-    let mut out = compact.trim().to_string();
-
-    // Remove leading punctuation artifacts from decoder restarts.
-    out = out
-        .trim_start_matches(|ch: char| matches!(ch, ',' | ';' | ':' | '.' | '!' | '?'))
-        .trim_start()
-        .to_string();
-
-    // Uppercase standalone "i" pronoun.
-    out = out
-        .split_whitespace()
-        .map(|w| if w == "i" { "I" } else { w })
-        .collect::<Vec<_>>()
-        .join(" ");
-
-    // Capitalize sentence starts.
-    out = capitalize_sentence_starts(&out);
-
-    // Add terminal punctuation for longer phrases lacking it.
-    let has_terminal_punct = out.ends_with('.') || out.ends_with('!') || out.ends_with('?');
-    let word_count = out.split_whitespace().count();
-    if !has_terminal_punct && word_count >= 8 {
-        out.push('.');
-    }
-
-    out
-}
-
-fn capitalize_sentence_starts(text: &str) -> String {
-    let mut out = String::with_capacity(text.len());
-    let mut cap_next = true;
-    for ch in text.chars() {
-        if cap_next && ch.is_ascii_alphabetic() {
-            out.push(ch.to_ascii_uppercase());
-            cap_next = false;
-        } else {
-            out.push(ch);
-            if ch.is_ascii_alphabetic() {
-                cap_next = false;
-            }
-        }
-        if matches!(ch, '.' | '!' | '?') {
-            cap_next = true;
-        }
-    }
-    out
-}
-
 fn is_degenerate_transcript_text(text: &str) -> bool {
     let words: Vec<String> = text
         .split_whitespace()
@@ -1584,14 +3398,7 @@ fn has_repeating_phrase_words(words: &[String], phrase_len: usize, repeats: usiz
 }
 
 fn likely_truncated_transcript(text: &str, audio_seconds: f32) -> bool {
-    let words = text.split_whitespace().count();
-    if audio_seconds >= 10.0 && words <= 8 {
-        return true;
-    }
-    if audio_seconds >= 6.0 && words <= 4 {
-        return true;
-    }
-    false
+    TruncationRetryConfig::default().suspects_truncation(text, audio_seconds)
 }
 
 fn phrase_bias_token_ids(tokenizer: &Tokenizer, terms: &[String]) -> HashSet<i64> {
@@ -1650,7 +3457,11 @@ fn estimate_segment_confidence(text: &str, audio_seconds: f32, partial: bool) ->
     Some(confidence.clamp(0.05, 0.98))
 }
 
-fn openai_cloud_fallback_text(samples: &[f32], sample_rate: u32) -> Option<String> {
+fn openai_cloud_fallback_text(
+    samples: &[f32],
+    sample_rate: u32,
+    wav_format: WavSampleFormat,
+) -> Option<String> {
     if !cloud_fallback_enabled() {
         return None;
     }
@@ -1664,29 +3475,7 @@ fn openai_cloud_fallback_text(samples: &[f32], sample_rate: u32) -> Option<Strin
         return None;
     }
 
-    let tmp_name = format!(
-        "dictum-openai-fallback-{}-{}.wav",
-        std::process::id(),
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .ok()?
-            .as_millis()
-    );
-    let wav_path = std::env::temp_dir().join(tmp_name);
-    if let Err(e) = write_pcm16_wav(&wav_path, &prepared, sample_rate) {
-        warn!(error = %e, "cloud fallback wav write failed");
-        return None;
-    }
-
-    let wav_bytes = match std::fs::read(&wav_path) {
-        Ok(v) => v,
-        Err(e) => {
-            let _ = std::fs::remove_file(&wav_path);
-            warn!(error = %e, "cloud fallback wav read failed");
-            return None;
-        }
-    };
-    let _ = std::fs::remove_file(&wav_path);
+    let wav_bytes = encode_wav(&prepared, 1, sample_rate, wav_format);
 
     let file_part = match multipart::Part::bytes(wav_bytes)
         .file_name("audio.wav")
@@ -1768,7 +3557,7 @@ fn prepare_cloud_samples(samples: &[f32], sample_rate: u32) -> Vec<f32> {
     let start = first.saturating_sub(pad);
     let end = (last + pad).min(samples.len().saturating_sub(1));
     let mut out = samples[start..=end].to_vec();
-    normalize_rms_in_place(&mut out, 0.12);
+    normalize_rms_in_place(&mut out, 0.12, &FALLBACK_GAIN_LIMITS);
     out
 }
 
@@ -1798,7 +3587,10 @@ fn windows_dictation_fallback_text(samples: &[f32], sample_rate: u32) -> Option<
     );
     let wav_path = std::env::temp_dir().join(tmp_name);
 
-    if let Err(e) = write_pcm16_wav(&wav_path, &prepared, sample_rate) {
+    if let Err(e) = std::fs::write(
+        &wav_path,
+        encode_wav(&prepared, 1, sample_rate, WavSampleFormat::Pcm16),
+    ) {
         debug!(error = %e, "failed to write fallback wav");
         return None;
     }
@@ -1898,7 +3690,7 @@ fn prepare_fallback_samples(samples: &[f32], sample_rate: u32) -> Vec<f32> {
     let mut out = samples[start..=end].to_vec();
 
     // System.Speech tends to be brittle on low-volume input.
-    normalize_rms_in_place(&mut out, 0.12);
+    normalize_rms_in_place(&mut out, 0.12, &FALLBACK_GAIN_LIMITS);
     out
 }
 
@@ -1929,36 +3721,6 @@ fn run_powershell_script(script: &str) -> std::io::Result<std::process::Output>
     Err(last_err.unwrap_or_else(|| std::io::Error::other("no PowerShell runtime found")))
 }
 
-fn write_pcm16_wav(path: &Path, samples: &[f32], sample_rate: u32) -> std::io::Result<()> {
-    let mut file = std::fs::File::create(path)?;
-
-    let data_len = (samples.len() * 2) as u32;
-    let riff_len = 36u32 + data_len;
-
-    use std::io::Write;
-    file.write_all(b"RIFF")?;
-    file.write_all(&riff_len.to_le_bytes())?;
-    file.write_all(b"WAVE")?;
-
-    file.write_all(b"fmt ")?;
-    file.write_all(&16u32.to_le_bytes())?; // PCM fmt chunk size
-    file.write_all(&1u16.to_le_bytes())?; // PCM
-    file.write_all(&1u16.to_le_bytes())?; // mono
-    file.write_all(&sample_rate.to_le_bytes())?;
-    let byte_rate = sample_rate * 2;
-    file.write_all(&byte_rate.to_le_bytes())?;
-    file.write_all(&2u16.to_le_bytes())?; // block align
-    file.write_all(&16u16.to_le_bytes())?; // bits per sample
-
-    file.write_all(b"data")?;
-    file.write_all(&data_len.to_le_bytes())?;
-    for &sample in samples {
-        let v = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
-        file.write_all(&v.to_le_bytes())?;
-    }
-    Ok(())
-}
-
 fn build_hann_window(n: usize) -> Vec<f32> {
     use std::f32::consts::PI;
     (0..n)
@@ -2009,7 +3771,7 @@ fn build_mel_filters(
     filters
 }
 
-fn normalize_rms_in_place(samples: &mut [f32], target_rms: f32) {
+fn normalize_rms_in_place(samples: &mut [f32], target_rms: f32, limits: &GainLimits) {
     if samples.is_empty() {
         return;
     }
@@ -2018,7 +3780,7 @@ fn normalize_rms_in_place(samples: &mut [f32], target_rms: f32) {
     if rms <= 1e-6 {
         return;
     }
-    let gain = (target_rms / rms).clamp(0.8, 15.0);
+    let gain = limits.limit(samples, rms, (target_rms / rms).max(0.8));
     if (gain - 1.0).abs() < 1e-3 {
         return;
     }
@@ -2027,6 +3789,12 @@ fn normalize_rms_in_place(samples: &mut [f32], target_rms: f32) {
     }
 }
 
+/// Mel frames that contain real audio for `active_samples` (at least one).
+fn active_mel_frames(active_samples: usize) -> usize {
+    let active_samples = active_samples.min(MEL_SAMPLES);
+    (active_samples + N_FFT).div_ceil(HOP).clamp(1, N_FRAMES)
+}
+
 fn reflect_pad(samples: &[f32], pad: usize) -> Vec<f32> {
     if pad == 0 {
         return samples.to_vec();
@@ -2082,3 +3850,1087 @@ fn mel_to_hz_slaney(mel: f32) -> f32 {
         mel * f_sp
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        beam_search, canonical_dir, context_prompt, decode_step_budget, decode_text,
+        decoded_language, detect_language, drive_decode_steps, language_tokens,
+        leaves_byte_level_markers, load_suppress_tokens, min_decode_steps_before_eot,
+        normalize_phrase_bias_terms, normalize_rms_in_place, phrase_bias_token_ids, prompt_prefix,
+        registered_providers_label, resolve_suppress_tokens, select_next_token, spread_words,
+        suppressed_control_tokens, timed_spans, timestamp_rule, timestamp_token_ms,
+        validate_explicit_models_dir, with_detected_language, DecodeDeadline, DecodeEnd,
+        DecodeLanguageHint, DecodeStrategy, DecodeTask, DegenerateOutputGuard, EncoderDims,
+        OnnxModel, OnnxModelConfig, PrefixSearchConfig, TokenRules, TokenizerDecodeConfig,
+        TruncationRetryConfig, DEFAULT_MEL_TARGET_RMS, DEGENERATE_TRIGGER, EOT_MIN_STEPS_CAP,
+        EOT_MIN_STEPS_PER_SECOND, MAX_BEAM_WIDTH, MAX_INITIAL_TIMESTAMP_STEPS, MAX_PROMPT_TOKENS,
+        MAX_TOKENS, MEL_SAMPLES, MIN_FINAL_TOKENS, N_FRAMES, N_MELS, N_TEXT_CTX,
+        PARTIAL_MAX_TOKENS, SHORT_UTTERANCE_DECODE_STEPS, SHORT_UTTERANCE_SECONDS,
+    };
+    use crate::ipc::events::WordTiming;
+
+    #[test]
+    fn timestamp_rules_pair_timestamps_and_keep_them_monotonic() {
+        // Text below 100, timestamps from 100 to the end of a 200-token
+        // vocabulary. A strong text token keeps the probability rule out of
+        // it.
+        let ts = 100_i64;
+        let mut logits = vec![0.0_f32; 200];
+        logits[1] = 10.0;
+        let rule = |generated: &[i64]| timestamp_rule(generated, ts, &logits);
+
+        // Decoding opens on a timestamp within the first second.
+        let first = rule(&[]);
+        assert!(!first.text);
+        assert_eq!(
+            first.timestamps,
+            Some((ts, ts + MAX_INITIAL_TIMESTAMP_STEPS))
+        );
+        // After the opening timestamp: text, no second timestamp.
+        assert!(rule(&[ts + 5]).text);
+        assert_eq!(rule(&[ts + 5]).timestamps, None);
+        // After text: anything, but no earlier than the last timestamp.
+        let after_text = rule(&[ts + 5, 1, 2]);
+        assert!(after_text.text);
+        assert_eq!(after_text.timestamps, Some((ts + 6, 199)));
+        // A timestamp after text must be closed (or decoding end): no text,
+        // and the closing timestamp may repeat it.
+        let closing = rule(&[ts + 5, 1, 2, ts + 30]);
+        assert!(!closing.text);
+        assert_eq!(closing.timestamps, Some((ts + 30, 199)));
+        // A completed pair is followed by text.
+        let paired = rule(&[ts + 5, 1, 2, ts + 30, ts + 30]);
+        assert!(paired.text);
+        assert_eq!(paired.timestamps, None);
+        // A timestamp at the end of the vocabulary leaves nothing later.
+        assert_eq!(rule(&[ts, 1, 199, 199, 2]).timestamps, None);
+    }
+
+    #[test]
+    fn timestamp_rules_take_a_timestamp_when_they_outweigh_text() {
+        let ts = 100_i64;
+        let mut logits = vec![0.0_f32; 200];
+        logits[7] = 4.0;
+        // 100 timestamps at 0.0: log-sum 4.6 beats the best text token.
+        assert!(!timestamp_rule(&[ts, 7], ts, &logits).text);
+        logits[7] = 6.0;
+        assert!(timestamp_rule(&[ts, 7], ts, &logits).text);
+    }
+
+    #[test]
+    fn timestamp_rules_steer_the_decode_loops_scorer() {
+        // Text 0..6, EOT 6, token 7 the prefix, timestamps 8..12.
+        let no_bias = std::collections::HashSet::new();
+        let rules = TokenRules {
+            eot_id: 6,
+            timestamp_begin: None,
+            timestamp_rules: Some(8),
+            begin_suppress_tokens: &[],
+            always_suppress_tokens: &[],
+            phrase_bias_token_ids: &no_bias,
+            partial: false,
+            min_decode_steps_before_eot: 0,
+        };
+        let prefix = [7_i64];
+        let pick = |generated: &[i64], row: &[f32]| {
+            let tokens = [prefix.as_slice(), generated].concat();
+            select_next_token(row, rules.scorer(prefix.len(), &tokens, row))
+                .expect("a token survives")
+                .0 as i64
+        };
+        let text_heavy = [9.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 0.0, 0.0];
+        // First step: a timestamp, despite the strong text token.
+        assert_eq!(pick(&[], &text_heavy), 9);
+        // After an opening timestamp: text.
+        assert_eq!(pick(&[9], &text_heavy), 0);
+        // After text then a timestamp: no text, and never an earlier time.
+        let closing = [9.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 5.0, 3.0, 0.5];
+        assert_eq!(pick(&[10, 0, 11], &closing), 11);
+        assert_eq!(pick(&[8, 0, 10], &closing), 10);
+    }
+
+    #[test]
+    fn timestamp_tokens_become_millisecond_ranges_around_text() {
+        let (ts, eot) = (100, 50);
+        assert_eq!(timestamp_token_ms(99, ts), None);
+        assert_eq!(timestamp_token_ms(ts, ts), Some(0));
+        assert_eq!(timestamp_token_ms(ts + 75, ts), Some(1_500));
+
+        // <|0.00|> a b <|0.50|><|0.50|> c <|1.20|> EOT, then junk.
+        let spans = timed_spans(
+            &[ts, 1, 2, ts + 25, ts + 25, 3, ts + 60, eot, 4],
+            ts,
+            eot,
+            9_000,
+        );
+        let ranges: Vec<_> = spans
+            .iter()
+            .map(|s| (s.start_ms, s.end_ms, s.tokens.clone()))
+            .collect();
+        assert_eq!(ranges, vec![(0, 500, vec![1, 2]), (500, 1_200, vec![3])]);
+
+        // A single timestamp token: the open side falls back to the audio
+        // start or end.
+        let closing_only = timed_spans(&[1, 2, ts + 60, eot], ts, eot, 3_000);
+        assert_eq!(
+            (closing_only[0].start_ms, closing_only[0].end_ms),
+            (0, 1_200)
+        );
+        let opening_only = timed_spans(&[ts + 10, 1, 2, eot], ts, eot, 3_000);
+        assert_eq!(
+            (opening_only[0].start_ms, opening_only[0].end_ms),
+            (200, 3_000)
+        );
+        assert!(timed_spans(&[ts, ts + 5, eot], ts, eot, 3_000).is_empty());
+    }
+
+    #[test]
+    fn span_time_is_shared_among_words_by_length() {
+        let word = |w: &str, start_ms, end_ms| WordTiming {
+            word: w.into(),
+            start_ms,
+            end_ms,
+        };
+        assert_eq!(
+            spread_words(" ab  abcd ", 500, 1_100),
+            vec![word("ab", 500, 700), word("abcd", 700, 1_100)]
+        );
+        assert!(spread_words("  ", 0, 1_000).is_empty());
+    }
+
+    #[test]
+    fn decode_deadline_bounds_a_slow_decoder() {
+        use std::time::{Duration, Instant};
+
+        // Never emits EOT: unbounded, this would take MAX_TOKENS * 15 ms.
+        let step_time = Duration::from_millis(15);
+        let mut steps = 0;
+        let started = Instant::now();
+        let end = drive_decode_steps(MAX_TOKENS, &DecodeDeadline::after(60), |_| {
+            std::thread::sleep(step_time);
+            steps += 1;
+            Ok(true)
+        })
+        .expect("decode");
+        let elapsed = started.elapsed();
+        assert_eq!(end, DecodeEnd::Deadline);
+        assert!((1..MAX_TOKENS / 4).contains(&steps), "{steps} steps");
+        assert!(elapsed < Duration::from_millis(500), "{elapsed:?}");
+
+        assert_eq!(
+            drive_decode_steps(5, &DecodeDeadline::after(0), |_| Ok(true)).unwrap(),
+            DecodeEnd::StepLimit
+        );
+        assert_eq!(
+            drive_decode_steps(5, &DecodeDeadline::after(60_000), |step| Ok(step < 2)).unwrap(),
+            DecodeEnd::Stopped
+        );
+        assert_eq!(OnnxModelConfig::default().max_decode_ms, 10_000);
+    }
+
+    #[test]
+    fn raising_the_cancel_flag_stops_the_decode_between_steps() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let deadline = DecodeDeadline::after(0).with_cancel(Some(Arc::clone(&cancel)));
+        let mut steps = 0;
+        let end = drive_decode_steps(MAX_TOKENS, &deadline, |step| {
+            steps += 1;
+            if step == 2 {
+                // A session reset arriving mid-decode.
+                cancel.store(true, Ordering::Release);
+            }
+            Ok(true)
+        })
+        .expect("decode");
+        assert_eq!(end, DecodeEnd::Cancelled);
+        assert_eq!(steps, 3, "the step in flight finishes, no further ones run");
+    }
+
+    #[test]
+    fn clearing_the_running_flag_ends_the_decode_early() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let deadline = DecodeDeadline::after(0).with_running(Some(Arc::clone(&running)));
+        let mut tokens = Vec::new();
+        let end = drive_decode_steps(MAX_TOKENS, &deadline, |step| {
+            tokens.push(step);
+            if step == 4 {
+                // `stop()` during a long final.
+                running.store(false, Ordering::Release);
+            }
+            Ok(true)
+        })
+        .expect("decode");
+        assert_eq!(end, DecodeEnd::Interrupted);
+        assert_eq!(tokens, vec![0, 1, 2, 3, 4], "tokens so far are kept");
+        assert!(deadline.interrupted() && !deadline.cancelled());
+    }
+
+    /// Logits for a four-token vocabulary (EOT, A, B, C) after `tokens`:
+    /// A is the better first token, but B leads to the likelier sentence.
+    fn scripted_logits(tokens: &[i64]) -> Vec<f32> {
+        let probs = match tokens {
+            [9] => [0.0001, 0.5, 0.4, 0.1],
+            [9, 1] => [0.34, 0.0001, 0.33, 0.33],
+            [9, 2] => [0.99, 0.005, 0.0001, 0.005],
+            _ => [0.97, 0.01, 0.01, 0.01],
+        };
+        probs.iter().map(|p: &f32| p.ln()).collect()
+    }
+
+    #[test]
+    fn beam_search_finds_the_likelier_sequence_greedy_misses() {
+        let bias = std::collections::HashSet::new();
+        let rules = TokenRules {
+            eot_id: 0,
+            timestamp_begin: None,
+            timestamp_rules: None,
+            begin_suppress_tokens: &[],
+            always_suppress_tokens: &[],
+            phrase_bias_token_ids: &bias,
+            partial: false,
+            min_decode_steps_before_eot: 0,
+        };
+        let deadline = DecodeDeadline::after(0);
+        let decode = |width, rules: &TokenRules<'_>| {
+            let mut runs = 0;
+            let (tokens, end) = beam_search(&[9], width, 10, rules, &deadline, |tokens| {
+                runs += 1;
+                Ok(scripted_logits(tokens))
+            })
+            .expect("decode");
+            assert_eq!(end, DecodeEnd::Stopped);
+            (tokens, runs)
+        };
+
+        // Width 1 is greedy: A (0.5) then EOT, for 0.17 overall.
+        assert_eq!(decode(1, &rules), (vec![9, 1, 0], 2));
+        // Width 2 keeps B (0.4) alive, and B then EOT (0.396) wins.
+        assert_eq!(decode(2, &rules), (vec![9, 2, 0], 3));
+
+        // Suppression applies to every expansion.
+        let no_b = TokenRules {
+            always_suppress_tokens: &[2],
+            ..rules
+        };
+        assert!(!decode(2, &no_b).0.contains(&2));
+    }
+
+    #[test]
+    fn beam_width_is_capped_and_narrow_beams_are_greedy() {
+        assert_eq!(DecodeStrategy::Greedy.beam_width(), None);
+        assert_eq!(DecodeStrategy::Beam { width: 1 }.beam_width(), None);
+        assert_eq!(DecodeStrategy::Beam { width: 4 }.beam_width(), Some(4));
+        assert_eq!(
+            DecodeStrategy::Beam { width: 32 }.beam_width(),
+            Some(MAX_BEAM_WIDTH)
+        );
+        assert_eq!(
+            OnnxModelConfig::default().decode_strategy,
+            DecodeStrategy::Greedy
+        );
+    }
+
+    #[test]
+    fn carried_prompt_keeps_its_tail_within_the_decoder_context() {
+        assert!(prompt_prefix(50361, &[]).is_empty());
+        assert_eq!(prompt_prefix(50361, &[7, 8]), vec![50361, 7, 8]);
+
+        let long: Vec<u32> = (0..1_000).collect();
+        let prefix = prompt_prefix(50361, &long);
+        assert_eq!(prefix.len(), MAX_PROMPT_TOKENS + 1);
+        assert_eq!(prefix[1], (1_000 - MAX_PROMPT_TOKENS) as i64);
+        assert_eq!(prefix.last(), Some(&999));
+        // startofprev + prompt + the longest SOT prefix + a full decode.
+        assert!(prefix.len() + 4 + MAX_TOKENS <= N_TEXT_CTX);
+    }
+
+    #[test]
+    fn carried_context_is_tokenized_behind_startofprev() {
+        let tokenizer = word_level_tokenizer(&["<unk>", "<|startofprev|>", " Dr Okafor"]);
+        assert_eq!(context_prompt(&tokenizer, Some("Dr Okafor")), vec![1, 2]);
+        assert!(context_prompt(&tokenizer, None).is_empty());
+
+        // Exports without the prompt token decode unprompted.
+        let no_prev = word_level_tokenizer(&["<unk>", " Dr Okafor"]);
+        assert!(context_prompt(&no_prev, Some("Dr Okafor")).is_empty());
+    }
+
+    /// Tokenizer whose ids are the positions of `vocab`; needs `"<unk>"`.
+    fn word_level_tokenizer(vocab: &[&str]) -> tokenizers::Tokenizer {
+        use std::str::FromStr;
+
+        let vocab_json: serde_json::Map<String, serde_json::Value> = vocab
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), id.into()))
+            .collect();
+        tokenizers::Tokenizer::from_str(
+            &serde_json::json!({
+                "version": "1.0",
+                "truncation": null,
+                "padding": null,
+                "added_tokens": [],
+                "normalizer": null,
+                "pre_tokenizer": null,
+                "post_processor": null,
+                "decoder": null,
+                "model": { "type": "WordLevel", "vocab": vocab_json, "unk_token": "<unk>" },
+            })
+            .to_string(),
+        )
+        .expect("build tokenizer")
+    }
+
+    #[test]
+    fn byte_level_markers_are_cleaned_from_decoded_text() {
+        // No decoder in the tokenizer, as in the broken exports: a naive
+        // decode keeps the space markers and the UTF-8 bytes as Latin-1.
+        let tokenizer = word_level_tokenizer(&["<unk>", "Ġthe", "Ġhello", "ĠcafÃ©", "."]);
+        let ids = [2, 1, 3, 4];
+        let naive = tokenizer.decode(&ids, true).expect("decode");
+        assert!(naive.starts_with("Ġhello"), "{naive}");
+        assert!(leaves_byte_level_markers(&tokenizer));
+
+        let options = TokenizerDecodeConfig {
+            skip_special_tokens: true,
+            clean_up_text: true,
+        };
+        assert_eq!(
+            decode_text(&tokenizer, &ids, options, true).expect("decode"),
+            "hello the café ."
+        );
+
+        let raw = TokenizerDecodeConfig {
+            clean_up_text: false,
+            ..options
+        };
+        assert_eq!(
+            decode_text(&tokenizer, &ids, raw, true).expect("decode"),
+            naive
+        );
+
+        // Line breaks survive the cleanup; the spaces around them do not.
+        let tokenizer =
+            word_level_tokenizer(&["<unk>", "Ġdear", "Ġsam", ",", "Ċ", "ĊĊ", "Ġthanks"]);
+        assert_eq!(
+            decode_text(&tokenizer, &[1, 2, 3, 4, 6, 5, 1], options, true).expect("decode"),
+            "dear sam ,\nthanks\n\ndear"
+        );
+
+        // Tokenizers without byte-level tokens are not probed as such.
+        assert!(!leaves_byte_level_markers(&word_level_tokenizer(&[
+            "<unk>", "the"
+        ])));
+    }
+
+    #[test]
+    fn capabilities_reflect_the_loaded_tokenizer_and_frontend() {
+        use crate::inference::SpeechModel;
+
+        let mut model = OnnxModel::new(OnnxModelConfig::default());
+        let cold = model.capabilities();
+        assert_eq!(cold.backend, "onnx-whisper");
+        assert_eq!(cold.vocab_size, None);
+        assert!(cold.languages.is_empty());
+        assert_eq!(cold.execution_provider, None);
+
+        // What warm-up leaves behind for a 128-bin English + Mandarin export.
+        model.tokenizer = Some(word_level_tokenizer(&[
+            "<|startoftranscript|>",
+            "<|en|>",
+            "<|zh|>",
+            "<|transcribe|>",
+            "<unk>",
+        ]));
+        model.n_mels = 128;
+        model.execution_providers = vec!["directml", "directml"];
+        let warm = model.capabilities();
+        assert_eq!(warm.vocab_size, Some(5));
+        assert_eq!(warm.n_mels, Some(128));
+        assert_eq!(warm.languages, vec!["en", "zh"]);
+        assert!(!warm.decoder_with_past);
+        assert_eq!(warm.execution_provider.as_deref(), Some("directml"));
+    }
+
+    #[test]
+    fn registered_providers_label_names_each_provider_once() {
+        assert_eq!(registered_providers_label(&[]), None);
+        assert_eq!(
+            registered_providers_label(&["cpu", "cpu", "cpu"]).as_deref(),
+            Some("cpu")
+        );
+        // Decoder fell back while the encoder kept DirectML.
+        assert_eq!(
+            registered_providers_label(&["directml", "cpu", "directml"]).as_deref(),
+            Some("directml+cpu")
+        );
+    }
+
+    #[test]
+    fn mandarin_hint_moves_zh_prefix_to_the_front() {
+        use crate::inference::SpeechModel;
+
+        let tokenizer = word_level_tokenizer(&[
+            "<|startoftranscript|>",
+            "<|en|>",
+            "<|zh|>",
+            "<|ru|>",
+            "<|transcribe|>",
+            "<|notimestamps|>",
+            "<unk>",
+        ]);
+        let (sot, zh, transcribe, notimestamps) = (0, 2, 4, 5);
+
+        let mut model = OnnxModel::new(OnnxModelConfig::default());
+        model.set_language_hint(None).expect("auto");
+        assert_eq!(model.language_hint(), None);
+        let auto = OnnxModel::decode_prefix_candidates(
+            &tokenizer,
+            model.language_hint,
+            DecodeTask::Transcribe,
+            &PrefixSearchConfig::default().fallback_languages,
+        );
+        assert_eq!(auto[0], vec![sot, transcribe, notimestamps]);
+
+        model.set_language_hint(Some("mandarin")).expect("mandarin");
+        assert_eq!(model.language_hint().as_deref(), Some("zh"));
+        let mandarin = OnnxModel::decode_prefix_candidates(
+            &tokenizer,
+            model.language_hint,
+            DecodeTask::Transcribe,
+            &PrefixSearchConfig::default().fallback_languages,
+        );
+        assert_eq!(mandarin[0], vec![sot, zh, transcribe, notimestamps]);
+
+        assert!(model.set_language_hint(Some("klingon")).is_err());
+        assert_eq!(model.language_hint().as_deref(), Some("zh"));
+    }
+
+    #[test]
+    fn forced_english_decode_reports_its_language() {
+        let tokenizer = word_level_tokenizer(&[
+            "<|startoftranscript|>",
+            "<|en|>",
+            "<|zh|>",
+            "<|ru|>",
+            "<|transcribe|>",
+            "<|notimestamps|>",
+            "hello",
+            "<|endoftext|>",
+            "<unk>",
+        ]);
+        let (hello, eot) = (6, 7);
+        let decoded = |hint| {
+            let prefix = OnnxModel::decode_prefix_candidates(
+                &tokenizer,
+                hint,
+                DecodeTask::Transcribe,
+                &PrefixSearchConfig::default().fallback_languages,
+            )
+            .remove(0);
+            let tokens = [prefix.as_slice(), &[hello, eot]].concat();
+            decoded_language(&tokenizer, &tokens)
+        };
+
+        assert_eq!(decoded(DecodeLanguageHint::English).as_deref(), Some("en"));
+        assert_eq!(decoded(DecodeLanguageHint::Russian).as_deref(), Some("ru"));
+        // The auto-detect prefix names no language, and none was emitted.
+        assert_eq!(decoded(DecodeLanguageHint::Auto), None);
+    }
+
+    #[test]
+    fn auto_decode_detects_and_reports_the_language() {
+        let tokenizer = word_level_tokenizer(&[
+            "<|startoftranscript|>",
+            "<|en|>",
+            "<|zh|>",
+            "<|ru|>",
+            "<|transcribe|>",
+            "<|notimestamps|>",
+            "hello",
+            "<|endoftext|>",
+            "<unk>",
+        ]);
+        let (sot, zh, transcribe, notimestamps, hello, eot) = (0, 2, 4, 5, 6, 7);
+        let languages = language_tokens(&tokenizer);
+        assert_eq!(
+            languages,
+            vec![(1, "en".to_string()), (2, "zh".into()), (3, "ru".into())]
+        );
+
+        // Only language tokens compete: "hello" scoring higher is ignored.
+        let logits = [0.0, 1.0, 3.0, 2.0, 0.0, 0.0, 9.0, 0.0, 0.0];
+        let &(detected, ref code) = detect_language(&logits, &languages).unwrap();
+        assert_eq!((detected, code.as_str()), (zh, "zh"));
+        assert_eq!(detect_language(&logits, &[]), None);
+
+        let auto = OnnxModel::decode_prefix_candidates(
+            &tokenizer,
+            DecodeLanguageHint::Auto,
+            DecodeTask::Transcribe,
+            &PrefixSearchConfig::default().fallback_languages,
+        );
+        let prefixes = with_detected_language(&tokenizer, auto, detected);
+        assert_eq!(prefixes[0], vec![sot, zh, transcribe, notimestamps]);
+        assert_eq!(prefixes[1], vec![sot, zh, transcribe]);
+        // The Mandarin fallback now duplicates the first prefix.
+        assert_eq!(
+            prefixes.iter().filter(|p| p[1] == zh).count(),
+            2,
+            "{prefixes:?}"
+        );
+
+        let tokens = [prefixes[0].as_slice(), &[hello, eot]].concat();
+        assert_eq!(decoded_language(&tokenizer, &tokens).as_deref(), Some("zh"));
+    }
+
+    #[test]
+    fn english_hint_tries_one_prefix_while_auto_works_through_the_fallbacks() {
+        let tokenizer = word_level_tokenizer(&[
+            "<|startoftranscript|>",
+            "<|en|>",
+            "<|zh|>",
+            "<|ru|>",
+            "<|transcribe|>",
+            "<|notimestamps|>",
+            "<unk>",
+        ]);
+        let (sot, en, zh, ru, transcribe, notimestamps) = (0, 1, 2, 3, 4, 5);
+        let search = PrefixSearchConfig::default();
+        let candidates = |search: &PrefixSearchConfig, hint| {
+            OnnxModel::decode_prefix_candidates(
+                &tokenizer,
+                hint,
+                DecodeTask::Transcribe,
+                &search.fallback_languages,
+            )
+        };
+        let tried =
+            |search: &PrefixSearchConfig, hint| search.select(hint, candidates(search, hint));
+
+        let (fast, english) = tried(&search, DecodeLanguageHint::English);
+        assert_eq!(fast, 1);
+        assert_eq!(english, vec![vec![sot, en, transcribe, notimestamps]]);
+
+        let (fast, auto) = tried(&search, DecodeLanguageHint::Auto);
+        assert_eq!(fast, 1);
+        assert!(auto.len() > 1, "auto tries more than one prefix: {auto:?}");
+        assert_eq!(auto[0], vec![sot, transcribe, notimestamps]);
+        assert!(auto.contains(&vec![sot, zh, transcribe, notimestamps]));
+        assert!(auto.contains(&vec![sot, ru, transcribe, notimestamps]));
+
+        // Overrides: fallback order follows the list, limits are clamped.
+        let search = PrefixSearchConfig {
+            fast_prefix_limit: Some(5),
+            max_prefixes: Some(3),
+            fallback_languages: vec!["RU".into(), "xx".into()],
+        };
+        let (fast, auto) = tried(&search, DecodeLanguageHint::Auto);
+        assert_eq!(fast, 3);
+        assert_eq!(
+            auto,
+            vec![
+                vec![sot, transcribe, notimestamps],
+                vec![sot, transcribe],
+                vec![sot, ru, transcribe, notimestamps],
+            ]
+        );
+        let english = candidates(&search, DecodeLanguageHint::English);
+        assert!(english.len() > 3, "{english:?}");
+        let (fast, tried_english) = tried(&search, DecodeLanguageHint::English);
+        assert_eq!((fast, tried_english.len()), (3, 3));
+        assert_eq!(tried_english, english[..3]);
+    }
+
+    #[test]
+    fn translate_task_uses_the_translate_token_and_stops_suppressing_it() {
+        let tokenizer = word_level_tokenizer(&[
+            "<|startoftranscript|>",
+            "<|en|>",
+            "<|zh|>",
+            "<|transcribe|>",
+            "<|translate|>",
+            "<|notimestamps|>",
+            "<unk>",
+        ]);
+        let (sot, zh, transcribe, translate, notimestamps) = (0, 2, 3, 4, 5);
+
+        let prefixes = OnnxModel::decode_prefix_candidates(
+            &tokenizer,
+            DecodeLanguageHint::Mandarin,
+            DecodeTask::Translate,
+            &PrefixSearchConfig::default().fallback_languages,
+        );
+        assert_eq!(prefixes[0], vec![sot, zh, translate, notimestamps]);
+        assert!(prefixes
+            .iter()
+            .all(|p| p.contains(&translate) && !p.contains(&transcribe)));
+        assert!(!suppressed_control_tokens(DecodeTask::Translate).contains(&"<|translate|>"));
+        // Translated Mandarin is post-processed as English, not as CJK.
+        assert_eq!(DecodeTask::Translate.text_language(Some("zh")), Some("en"));
+        assert_eq!(DecodeTask::Transcribe.text_language(Some("zh")), Some("zh"));
+
+        let prefixes = OnnxModel::decode_prefix_candidates(
+            &tokenizer,
+            DecodeLanguageHint::Auto,
+            DecodeTask::Transcribe,
+            &PrefixSearchConfig::default().fallback_languages,
+        );
+        assert!(prefixes.iter().all(|p| !p.contains(&translate)));
+        let suppressed = suppressed_control_tokens(DecodeTask::Transcribe);
+        assert!(suppressed.contains(&"<|translate|>"));
+        assert!(suppressed.contains(&"<|transcribe|>"));
+
+        // An English-only export has no translate token and keeps transcribing.
+        let english_only = word_level_tokenizer(&[
+            "<|startoftranscript|>",
+            "<|en|>",
+            "<|transcribe|>",
+            "<|notimestamps|>",
+            "<unk>",
+        ]);
+        let prefixes = OnnxModel::decode_prefix_candidates(
+            &english_only,
+            DecodeLanguageHint::Auto,
+            DecodeTask::Translate,
+            &PrefixSearchConfig::default().fallback_languages,
+        );
+        assert_eq!(prefixes[0], vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn truncation_retries_stop_once_the_session_budget_is_spent() {
+        use crate::inference::SpeechModel;
+
+        let retry = TruncationRetryConfig {
+            long_audio_seconds: 12.0,
+            long_audio_max_words: 6,
+            session_budget: Some(2),
+            ..TruncationRetryConfig::default()
+        };
+        assert!(retry.suspects_truncation("one two three four five six", 12.5));
+        assert!(!retry.suspects_truncation("one two three four five six", 11.0));
+        assert!(retry.suspects_truncation("one two three", 6.0));
+
+        let mut model = OnnxModel::new(OnnxModelConfig {
+            truncation_retry: retry,
+            ..OnnxModelConfig::default()
+        });
+        let take = |model: &mut OnnxModel| {
+            model
+                .truncation_retries
+                .try_spend(model.config.truncation_retry.session_budget)
+        };
+        assert!(take(&mut model));
+        assert!(take(&mut model));
+        assert!(!take(&mut model), "budget exceeded");
+        assert!(!take(&mut model));
+
+        // A new listening session starts with a fresh budget.
+        model.begin_session();
+        assert!(take(&mut model));
+
+        // Through the decode path's retry decision: finals that cannot be
+        // retried (short utterances, whose fast budget is the full one) and
+        // partials leave the budget alone.
+        model.begin_session();
+        let budget = model.config.truncation_retry.session_budget;
+        let short = decode_step_budget(SHORT_UTTERANCE_SECONDS, false, true, false);
+        let partial = decode_step_budget(12.5, true, true, false);
+        let long = decode_step_budget(30.0, false, true, false);
+        for _ in 0..3 {
+            let retries = &mut model.truncation_retries;
+            assert!(!retries.full_budget_retry(budget, false, false, true, short));
+            assert!(!retries.full_budget_retry(budget, true, false, true, partial));
+            // Not suspected of truncation: nothing to spend on.
+            assert!(!retries.full_budget_retry(budget, false, false, false, long));
+        }
+        assert_eq!(model.truncation_retries.used, 0);
+        let retries = &mut model.truncation_retries;
+        assert!(retries.full_budget_retry(budget, false, false, true, long));
+        assert!(retries.full_budget_retry(budget, false, false, true, long));
+        assert!(!retries.full_budget_retry(budget, false, false, true, long));
+        // An empty fast decode is always retried, budget or not.
+        assert!(retries.full_budget_retry(budget, false, true, false, long));
+        assert_eq!(retries.used, 2);
+
+        let mut disabled = OnnxModel::new(OnnxModelConfig {
+            truncation_retry: TruncationRetryConfig {
+                session_budget: Some(0),
+                ..TruncationRetryConfig::default()
+            },
+            ..OnnxModelConfig::default()
+        });
+        assert!(!take(&mut disabled));
+    }
+
+    #[test]
+    fn transcribe_before_warm_up_is_rejected() {
+        use crate::buffering::chunk::AudioChunk;
+        use crate::error::DictumError;
+        use crate::inference::SpeechModel;
+
+        let mut model = OnnxModel::new(OnnxModelConfig::default());
+        assert!(!model.is_warm());
+        let chunk = AudioChunk::new(vec![0.0; 16_000], 16_000);
+        assert!(matches!(
+            model.transcribe(&chunk, false),
+            Err(DictumError::NotWarmedUp)
+        ));
+    }
+
+    #[test]
+    fn repeated_degenerate_finals_trigger_one_cpu_rebuild() {
+        let mut guard = DegenerateOutputGuard::default();
+        let mut rebuilds = 0;
+
+        // Occasional loops are tolerated.
+        for degenerate in [true, false, false, true, false] {
+            guard.record(degenerate);
+            assert!(!guard.run_if_due(|| {
+                rebuilds += 1;
+                Ok(())
+            }));
+        }
+        assert_eq!(rebuilds, 0);
+
+        guard.record(true);
+        assert!(guard.run_if_due(|| {
+            rebuilds += 1;
+            Ok(())
+        }));
+        assert_eq!(rebuilds, 1);
+
+        // One-time: further degenerate output does not rebuild again.
+        for _ in 0..DEGENERATE_TRIGGER * 2 {
+            guard.record(true);
+            assert!(!guard.run_if_due(|| {
+                rebuilds += 1;
+                Ok(())
+            }));
+        }
+        assert_eq!(rebuilds, 1);
+    }
+
+    #[test]
+    fn short_audio_uses_cropped_encoder_input_when_enabled() {
+        let mut samples: Vec<f32> = (0..16_000).map(|i| (i as f32 * 0.05).sin() * 0.2).collect();
+        samples.resize(MEL_SAMPLES, 0.0);
+
+        let full = OnnxModel::new(OnnxModelConfig {
+            crop_encoder_input: false,
+            ..OnnxModelConfig::default()
+        });
+        let frames = full.encoder_input_frames(16_000);
+        assert_eq!(frames, N_FRAMES);
+        let full_mel = full
+            .log_mel_spectrogram(&samples, 16_000, frames)
+            .expect("full mel");
+        assert_eq!(full_mel.shape(), &[1, N_MELS, N_FRAMES]);
+
+        let cropped = OnnxModel::new(OnnxModelConfig {
+            crop_encoder_input: true,
+            ..OnnxModelConfig::default()
+        });
+        // 1 s of audio is 103 active frames, rounded up to the granularity.
+        let frames = cropped.encoder_input_frames(16_000);
+        assert_eq!(frames, 200);
+        let mel = cropped
+            .log_mel_spectrogram(&samples, 16_000, frames)
+            .expect("cropped mel");
+        assert_eq!(mel.shape(), &[1, N_MELS, 200]);
+        // Same features over the active region.
+        for m in 0..N_MELS {
+            for t in 0..103 {
+                assert_eq!(mel[[0, m, t]], full_mel[[0, m, t]]);
+            }
+        }
+        // Long audio never exceeds the 30 s window.
+        assert_eq!(cropped.encoder_input_frames(MEL_SAMPLES * 2), N_FRAMES);
+    }
+
+    #[test]
+    fn a_higher_mel_target_rms_raises_mel_magnitudes() {
+        // Quiet input, so both targets are reachable within the gain limit.
+        let samples: Vec<f32> = (0..16_000)
+            .map(|i| (i as f32 * 0.05).sin() * 0.02)
+            .collect();
+        let mean_mel = |target: f32| {
+            let model = OnnxModel::new(OnnxModelConfig {
+                mel_target_rms: target,
+                ..OnnxModelConfig::default()
+            });
+            let mel = model
+                .log_mel_spectrogram(&samples, samples.len(), 100)
+                .expect("mel");
+            mel.mean().expect("non-empty mel")
+        };
+
+        let default = mean_mel(DEFAULT_MEL_TARGET_RMS);
+        let louder = mean_mel(DEFAULT_MEL_TARGET_RMS * 2.0);
+        // Doubling the amplitude adds log10(4) / 4 to every normalised bin.
+        let expected = 4f32.log10() / 4.0;
+        assert!(
+            (louder - default - expected).abs() < 0.01,
+            "{default} -> {louder}"
+        );
+    }
+
+    #[test]
+    fn encoder_dims_honour_the_model_width() {
+        // A base export (d_model 512) whose output tensor lost its shape.
+        let declared = EncoderDims::from_shape(&[1, 1500, 512]);
+        assert_eq!(
+            declared.resolve(&[], 1500 * 512).expect("dims"),
+            (1500, 512)
+        );
+        // The runtime shape wins when present, e.g. medium (1024).
+        assert_eq!(
+            EncoderDims::default()
+                .resolve(&[1, 1500, 1024], 1500 * 1024)
+                .expect("dims"),
+            (1500, 1024)
+        );
+        // Dynamic frame axis: frames follow from the data length.
+        let dynamic = EncoderDims::from_shape(&[-1, -1, 768]);
+        assert_eq!(dynamic.resolve(&[], 200 * 768).expect("dims"), (200, 768));
+
+        // No width anywhere is an error, not a guess.
+        let err = EncoderDims::default().resolve(&[], 1500 * 384).unwrap_err();
+        assert!(err.to_string().contains("d_model"), "{err}");
+        assert!(declared.resolve(&[], 1500 * 384).is_err());
+    }
+
+    #[test]
+    fn one_word_finals_get_a_fixed_small_decode_budget() {
+        use crate::inference::SpeechModel;
+
+        let short = SHORT_UTTERANCE_DECODE_STEPS;
+        assert_eq!(decode_step_budget(0.3, false, true, false), (short, short));
+        assert_eq!(decode_step_budget(0.8, false, true, false), (short, short));
+        // Longer finals keep the length-based estimate.
+        let (max, fast) = decode_step_budget(2.0, false, true, false);
+        assert!(max >= MIN_FINAL_TOKENS && fast >= MIN_FINAL_TOKENS);
+        assert!(decode_step_budget(10.0, false, true, false).0 > max);
+        // Partials are unaffected by the short path.
+        assert_eq!(
+            decode_step_budget(0.3, true, true, false),
+            (PARTIAL_MAX_TOKENS, PARTIAL_MAX_TOKENS)
+        );
+        // Without short-utterance padding, one-word finals are estimated
+        // like any other.
+        assert_eq!(
+            decode_step_budget(0.3, false, false, false).0,
+            MIN_FINAL_TOKENS
+        );
+        // Decoding timestamps adds room for their tokens, one-word finals
+        // included; partials never decode them.
+        let timed = decode_step_budget(10.0, false, false, true);
+        let untimed = decode_step_budget(10.0, false, false, false);
+        assert!(timed.0 > untimed.0 && timed.1 > untimed.1);
+        assert!(decode_step_budget(0.3, false, true, true).0 > short);
+        assert!(decode_step_budget(30.0, false, false, true).0 <= MAX_TOKENS);
+        assert_eq!(
+            decode_step_budget(0.3, true, true, true),
+            decode_step_budget(0.3, true, true, false)
+        );
+        let mut model = OnnxModel::new(OnnxModelConfig::default());
+        assert!(!model.short_utterance_decode);
+        model.set_short_utterance_decode(true);
+        assert!(model.short_utterance_decode);
+    }
+
+    #[test]
+    fn eot_floor_grows_with_audio_length_and_stays_bounded() {
+        let rate = EOT_MIN_STEPS_PER_SECOND;
+        let short = min_decode_steps_before_eot(1.0, false, rate);
+        let long = min_decode_steps_before_eot(6.0, false, rate);
+        assert_eq!(short, 2);
+        assert!(long > short, "long {long} vs short {short}");
+        assert_eq!(
+            min_decode_steps_before_eot(30.0, false, rate),
+            EOT_MIN_STEPS_CAP
+        );
+        assert_eq!(min_decode_steps_before_eot(30.0, true, rate), 1);
+        assert_eq!(min_decode_steps_before_eot(30.0, false, 0.0), 2);
+    }
+
+    fn temp_models_root(name: &str) -> std::path::PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("dictum-models-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("models")).expect("create models root");
+        root
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_models_dir_resolves_to_its_target() {
+        let base = temp_models_root("symlink");
+        let storage = base.join("network-share").join("small");
+        std::fs::create_dir_all(&storage).expect("create target");
+        let link = base.join("models").join("small");
+        std::os::unix::fs::symlink(&storage, &link).expect("symlink");
+
+        let target = std::fs::canonicalize(&storage).expect("canonical target");
+        assert_eq!(canonical_dir(&link), Some(target.clone()));
+        // Outside the models root, but not via `..`: allowed.
+        assert_eq!(
+            validate_explicit_models_dir(&link, &base.join("models")),
+            Ok(target.clone())
+        );
+        // Cached: still resolves after the link is gone.
+        std::fs::remove_file(&link).expect("remove link");
+        assert_eq!(canonical_dir(&link), Some(target));
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn models_dir_climbing_out_with_dot_dot_is_rejected() {
+        let base = temp_models_root("traversal");
+        let root = base.join("models");
+        std::fs::create_dir_all(base.join("elsewhere")).expect("create outside dir");
+        std::fs::create_dir_all(root.join("base")).expect("create profile dir");
+
+        let err = validate_explicit_models_dir(&root.join("..").join("elsewhere"), &root)
+            .expect_err("traversal rejected");
+        assert!(err.contains("outside the models dir"), "{err}");
+        // `..` that stays inside the root is fine.
+        assert!(
+            validate_explicit_models_dir(&root.join("base").join("..").join("base"), &root).is_ok()
+        );
+        // A dir that does not exist yet is passed through for warm-up to report.
+        let missing = root.join("not-downloaded");
+        assert_eq!(validate_explicit_models_dir(&missing, &root), Ok(missing));
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn suppress_tokens_file_keeps_listed_tokens_out_of_decode() {
+        let path = std::env::temp_dir().join(format!(
+            "dictum-suppress-tokens-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, r#"{"suppress_tokens": [7, 3, 7]}"#).expect("write list");
+        let suppressed = load_suppress_tokens(&path, 10).expect("load list");
+        assert_eq!(suppressed, vec![3, 7]);
+
+        // Bare arrays work too; out-of-vocab ids and junk are rejected.
+        std::fs::write(&path, "[1, 2]").expect("write list");
+        assert_eq!(
+            load_suppress_tokens(&path, 10).expect("load list"),
+            vec![1, 2]
+        );
+        std::fs::write(&path, "[1, 12]").expect("write list");
+        assert!(load_suppress_tokens(&path, 10).is_err());
+        std::fs::write(&path, "[\"a\"]").expect("write list");
+        assert!(load_suppress_tokens(&path, 10).is_err());
+        let _ = std::fs::remove_file(&path);
+
+        // Decode steps where the suppressed ids carry the strongest logits,
+        // scored by the rules the decode loops use, with the loaded list as
+        // their always-suppressed tokens.
+        let no_bias = std::collections::HashSet::new();
+        let rules = TokenRules {
+            eot_id: 99,
+            timestamp_begin: None,
+            timestamp_rules: None,
+            begin_suppress_tokens: &[],
+            always_suppress_tokens: &suppressed,
+            phrase_bias_token_ids: &no_bias,
+            partial: false,
+            min_decode_steps_before_eot: 0,
+        };
+        let rows: [[f32; 10]; 3] = [
+            [0.0, 0.1, 0.2, 9.0, 0.3, 0.4, 0.5, 8.0, 0.6, 0.7],
+            [0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 9.0, 0.7, 0.8],
+            [0.9, 0.1, 0.2, 5.0, 0.4, 0.5, 0.6, 0.7, 0.8, 0.3],
+        ];
+        let prefix = [0_i64];
+        let picked: Vec<usize> = rows
+            .iter()
+            .map(|row| {
+                select_next_token(row, rules.scorer(prefix.len(), &prefix, row))
+                    .expect("a token survives")
+                    .0
+            })
+            .collect();
+        assert_eq!(picked, vec![9, 9, 0]);
+        assert!(picked.iter().all(|&id| !suppressed.contains(&(id as i64))));
+    }
+
+    #[test]
+    fn configured_bias_terms_and_suppress_tokens_resolve_to_ids() {
+        let tokenizer = word_level_tokenizer(&[
+            "<unk>",
+            "the",
+            "tachycardia",
+            " tachycardia",
+            "TACHYCARDIA",
+            "<|nocaptions|>",
+        ]);
+        let terms = normalize_phrase_bias_terms([" Tachycardia ", "", "stent"]);
+        assert_eq!(terms, vec!["tachycardia", "stent"]);
+        let boosted = phrase_bias_token_ids(&tokenizer, &terms);
+        assert_eq!(boosted, [2, 3, 4].into_iter().collect());
+
+        let suppress = |tokens: &[&str]| {
+            let tokens: Vec<String> = tokens.iter().map(|t| t.to_string()).collect();
+            resolve_suppress_tokens(&tokenizer, &tokens)
+        };
+        assert_eq!(
+            suppress(&["<|nocaptions|>", "the", "the"]).unwrap(),
+            vec![1, 5]
+        );
+        assert!(suppress(&["<|notimestamps|>"]).is_err());
+    }
+
+    #[test]
+    fn mel_normalisation_boosts_noisy_speech_as_if_unguarded() {
+        use crate::audio::gain::GainLimits;
+
+        // Two seconds of a quiet 140 Hz voice in syllables with pauses,
+        // over white noise about 10 dB below the voice.
+        let mut state = 0x2545_f491_u32;
+        let noisy_speech: Vec<f32> = (0..32_000)
+            .map(|i| {
+                let t = i as f32 / 16_000.0;
+                let envelope = (std::f32::consts::TAU * 2.0 * t).sin().max(0.0);
+                let voice: f32 = (1..=8)
+                    .map(|k| (std::f32::consts::TAU * 140.0 * k as f32 * t).sin() / k as f32)
+                    .sum();
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                let noise = (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * 0.001;
+                0.004 * envelope * voice + noise
+            })
+            .collect();
+
+        let config = OnnxModelConfig::default();
+        assert!(config.mel_gain_limits.hiss_correlation.is_some());
+        let unguarded = GainLimits {
+            hiss_correlation: None,
+            ..config.mel_gain_limits
+        };
+        let mut guarded_out = noisy_speech.clone();
+        normalize_rms_in_place(
+            &mut guarded_out,
+            config.mel_target_rms,
+            &config.mel_gain_limits,
+        );
+        let mut unguarded_out = noisy_speech.clone();
+        normalize_rms_in_place(&mut unguarded_out, config.mel_target_rms, &unguarded);
+        let rms = |s: &[f32]| (s.iter().map(|x| x * x).sum::<f32>() / s.len() as f32).sqrt();
+        assert!(rms(&guarded_out) > rms(&noisy_speech) * 5.0);
+        assert_eq!(guarded_out, unguarded_out);
+    }
+}
@@ -0,0 +1,265 @@
+//! Transcript text clean-up applied to raw decoder output.
+//!
+//! Whitespace and punctuation rules depend on the script: Latin-style
+//! normalisation (strip spaces before `.`/`,`, capitalise sentence starts)
+//! mangles Chinese/Japanese, which do not separate words with spaces and use
+//! full-width punctuation, and must leave Arabic/Hebrew word spacing alone.
+
+/// Writing system family that decides spacing and punctuation rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    /// Space-separated scripts with Latin-style punctuation (also the
+    /// fallback for Cyrillic, Greek, Hangul, ...).
+    Latin,
+    /// Chinese/Japanese: no inter-word spaces, full-width punctuation.
+    Cjk,
+    /// Right-to-left scripts (Arabic, Hebrew, ...): space-separated, no case.
+    Rtl,
+}
+
+/// Script implied by a language code, when the language is known.
+pub fn script_for_language(code: &str) -> Option<Script> {
+    let primary = code
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match primary.as_str() {
+        "zh" | "ja" | "yue" => Some(Script::Cjk),
+        "ar" | "he" | "fa" | "ur" | "yi" | "ps" => Some(Script::Rtl),
+        "" => None,
+        _ => Some(Script::Latin),
+    }
+}
+
+/// Most frequent script family among the letters of `text`.
+pub fn dominant_script(text: &str) -> Script {
+    let (mut latin, mut cjk, mut rtl) = (0usize, 0usize, 0usize);
+    for ch in text.chars() {
+        if is_cjk_char(ch) {
+            cjk += 1;
+        } else if is_rtl_char(ch) {
+            rtl += 1;
+        } else if ch.is_alphabetic() {
+            latin += 1;
+        }
+    }
+    // An ideograph carries roughly a word, a Latin letter a fraction of one.
+    if cjk > 0 && cjk * 4 >= latin {
+        Script::Cjk
+    } else if rtl > latin {
+        Script::Rtl
+    } else {
+        Script::Latin
+    }
+}
+
+/// Clean up decoder output. `language` (e.g. the forced decode language)
+/// takes precedence over script detection when given.
+pub fn postprocess_transcript_text(text: &str, language: Option<&str>) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return String::new();
+    }
+    let script = language
+        .and_then(script_for_language)
+        .unwrap_or_else(|| dominant_script(trimmed));
+
+    let mut out = normalize_spacing(trimmed, script);
+
+    // Remove leading punctuation artifacts from decoder restarts.
+    out = out
+        .trim_start_matches(is_clause_punctuation)
+        .trim_start()
+        .to_string();
+
+    if script == Script::Latin {
+        // Uppercase standalone "i" pronoun.
+        out = out
+            .split('\n')
+            .map(|line| {
+                line.split(' ')
+                    .map(|w| if w == "i" { "I" } else { w })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // Capitalize sentence starts.
+        out = capitalize_sentence_starts(&out);
+    }
+
+    // Add terminal punctuation for longer phrases lacking it.
+    let has_terminal_punct = out.ends_with(is_terminal_punctuation);
+    if !has_terminal_punct {
+        match script {
+            Script::Cjk if out.chars().filter(|&c| is_cjk_char(c)).count() >= 12 => out.push('。'),
+            Script::Latin | Script::Rtl if out.split_whitespace().count() >= 8 => out.push('.'),
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Collapse whitespace runs and drop spaces that should not be there for
+/// `script`: before punctuation, and between CJK characters. Line breaks
+/// are kept, without the spaces around them.
+fn normalize_spacing(text: &str, script: Script) -> String {
+    let mut compact = String::with_capacity(text.len() + 8);
+    let mut pending_space = false;
+    let mut pending_newlines = 0usize;
+    for ch in text.chars() {
+        if ch == '\n' && !compact.is_empty() {
+            pending_newlines += 1;
+            continue;
+        }
+        if ch.is_whitespace() {
+            pending_space = !compact.is_empty();
+            continue;
+        }
+        if pending_newlines > 0 {
+            compact.extend(std::iter::repeat_n('\n', pending_newlines));
+            pending_newlines = 0;
+            pending_space = false;
+        }
+        if pending_space {
+            let prev = compact.chars().next_back();
+            let drop = is_clause_punctuation(ch)
+                || (script == Script::Cjk
+                    && prev.is_some_and(|p| is_cjk_char(p) || is_fullwidth_punctuation(p))
+                    && (is_cjk_char(ch) || is_fullwidth_punctuation(ch)));
+            if !drop {
+                compact.push(' ');
+            }
+            pending_space = false;
+        }
+        compact.push(ch);
+    }
+    compact
+}
+
+fn capitalize_sentence_starts(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut cap_next = true;
+    for ch in text.chars() {
+        if cap_next && ch.is_ascii_alphabetic() {
+            out.push(ch.to_ascii_uppercase());
+            cap_next = false;
+        } else {
+            out.push(ch);
+            if ch.is_ascii_alphabetic() {
+                cap_next = false;
+            }
+        }
+        if matches!(ch, '.' | '!' | '?') {
+            cap_next = true;
+        }
+    }
+    out
+}
+
+/// Punctuation that attaches to the preceding word (no space before it).
+fn is_clause_punctuation(ch: char) -> bool {
+    matches!(ch, '.' | ',' | '!' | '?' | ';' | ':')
+        || matches!(ch, '،' | '؛' | '؟')
+        || is_fullwidth_punctuation(ch)
+}
+
+fn is_terminal_punctuation(ch: char) -> bool {
+    matches!(ch, '.' | '!' | '?' | '。' | '！' | '？' | '؟')
+}
+
+fn is_fullwidth_punctuation(ch: char) -> bool {
+    matches!(
+        ch,
+        '。' | '、' | '，' | '！' | '？' | '；' | '：' | '」' | '』' | '）' | '…'
+    )
+}
+
+fn is_cjk_char(ch: char) -> bool {
+    matches!(ch as u32,
+        0x3040..=0x30FF     // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0x20000..=0x2FA1F // Extensions B+ and compatibility supplement
+    )
+}
+
+fn is_rtl_char(ch: char) -> bool {
+    matches!(ch as u32,
+        0x0590..=0x05FF     // Hebrew
+        | 0x0600..=0x06FF   // Arabic
+        | 0x0750..=0x077F   // Arabic Supplement
+        | 0x08A0..=0x08FF   // Arabic Extended-A
+        | 0xFB1D..=0xFDFF   // Hebrew/Arabic presentation forms A
+        | 0xFE70..=0xFEFF   // Arabic presentation forms B
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dominant_script, postprocess_transcript_text, script_for_language, Script};
+
+    #[test]
+    fn latin_output_is_unchanged_by_script_handling() {
+        assert_eq!(
+            postprocess_transcript_text("  so i think , we should go .", None),
+            "So I think, we should go."
+        );
+    }
+
+    #[test]
+    fn dictated_line_breaks_are_kept() {
+        assert_eq!(
+            postprocess_transcript_text("dear sam , \n i agree .\n\n thanks", None),
+            "Dear sam,\nI agree.\n\nThanks"
+        );
+    }
+
+    #[test]
+    fn cjk_loses_spaces_injected_between_characters() {
+        assert_eq!(
+            postprocess_transcript_text("我 今天 去 了 商店 ， 买 了 苹果 。", None),
+            "我今天去了商店，买了苹果。"
+        );
+        // Spaces around embedded Latin words survive.
+        assert_eq!(
+            postprocess_transcript_text("我 的 iPhone 坏 了", Some("zh")),
+            "我的 iPhone 坏了"
+        );
+        // Long sentences get a full-width stop, not an ASCII one.
+        assert_eq!(
+            postprocess_transcript_text(
+                "今日は とても いい 天気 ですね 散歩 に 行きましょう",
+                None
+            ),
+            "今日はとてもいい天気ですね散歩に行きましょう。"
+        );
+    }
+
+    #[test]
+    fn arabic_keeps_word_spacing_and_attaches_punctuation() {
+        assert_eq!(
+            postprocess_transcript_text("  مرحبا   بكم ،  كيف حالك ؟ ", None),
+            "مرحبا بكم، كيف حالك؟"
+        );
+        assert_eq!(
+            postprocess_transcript_text("שלום  עולם", Some("he")),
+            "שלום עולם"
+        );
+    }
+
+    #[test]
+    fn detects_scripts_and_maps_language_codes() {
+        assert_eq!(dominant_script("hello world"), Script::Latin);
+        assert_eq!(dominant_script("你好 world"), Script::Cjk);
+        assert_eq!(dominant_script("مرحبا hi"), Script::Rtl);
+        assert_eq!(script_for_language("zh-CN"), Some(Script::Cjk));
+        assert_eq!(script_for_language("ar"), Some(Script::Rtl));
+        assert_eq!(script_for_language("ko"), Some(Script::Latin));
+        assert_eq!(script_for_language(""), None);
+    }
+}
@@ -7,7 +7,7 @@
 use crate::buffering::chunk::AudioChunk;
 use crate::error::Result;
 use crate::inference::SpeechModel;
-use crate::ipc::events::{SegmentKind, TranscriptSegment};
+use crate::ipc::events::{ModelCapabilities, SegmentKind, TranscriptSegment};
 use tracing::debug;
 
 /// Echo-style stub model.
@@ -15,13 +15,11 @@ use tracing::debug;
 /// For every chunk of non-trivial length it emits:
 /// 1. A partial segment: `"…"` (simulates streaming latency)
 /// 2. A final segment: `"[stub: <N> samples @ <SR> Hz]"`
-pub struct StubModel {
-    utterance_count: u32,
-}
+pub struct StubModel;
 
 impl StubModel {
     pub fn new() -> Self {
-        Self { utterance_count: 0 }
+        Self
     }
 }
 
@@ -37,24 +35,32 @@ impl SpeechModel for StubModel {
         Ok(())
     }
 
+    fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            backend: "stub".into(),
+            ..ModelCapabilities::default()
+        }
+    }
+
     fn transcribe(&mut self, chunk: &AudioChunk, partial: bool) -> Result<Vec<TranscriptSegment>> {
         if chunk.samples.len() < 160 {
             return Ok(vec![]);
         }
 
-        self.utterance_count += 1;
-        let id = format!("stub-{}", self.utterance_count);
-
         let segments = if partial {
             vec![TranscriptSegment {
-                id: id.clone(),
+                id: String::new(),
                 text: "\u{2026}".to_string(), // "…"
                 kind: SegmentKind::Partial,
                 confidence: None,
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
             }]
         } else {
             vec![TranscriptSegment {
-                id,
+                id: String::new(),
                 text: format!(
                     "[stub: {} samples @ {} Hz]",
                     chunk.samples.len(),
@@ -62,6 +68,10 @@ impl SpeechModel for StubModel {
                 ),
                 kind: SegmentKind::Final,
                 confidence: Some(1.0),
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
             }]
         };
 
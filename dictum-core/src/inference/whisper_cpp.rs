@@ -0,0 +1,353 @@
+//! whisper.cpp backend via the `whisper-rs` bindings.
+//!
+//! Runs ggml/GGUF Whisper models (`ggml-base.en.bin`, `ggml-small-q5_1.bin`,
+//! ...) on the CPU, for machines where the ONNX export or DirectML is not an
+//! option. Segments come back in the same shape as [`super::OnnxModel`]'s, so
+//! the pipeline cannot tell the backends apart.
+//!
+//! ## Decoding
+//!
+//! Greedy, one segment per pass. Partials are capped at
+//! [`PARTIAL_MAX_TOKENS`] and skip timestamps; finals decode in full and
+//! carry `start_ms`/`end_ms`. Audio at other rates is resampled to 16 kHz.
+
+use std::path::PathBuf;
+
+use tracing::{debug, info};
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+use crate::audio::resample::RateConverter;
+use crate::buffering::chunk::AudioChunk;
+use crate::error::{DictumError, Result};
+use crate::inference::SpeechModel;
+use crate::ipc::events::{
+    EmptyReason, ModelCapabilities, SegmentKind, TranscriptMeta, TranscriptSegment,
+    TranscriptSource,
+};
+
+/// Sample rate whisper.cpp expects.
+const SAMPLE_RATE: u32 = 16_000;
+/// Decode step cap for partials, as for the ONNX backend.
+const PARTIAL_MAX_TOKENS: i32 = 10;
+/// Chunks shorter than this (100 ms) are not worth a pass.
+const MIN_SAMPLES: usize = 1_600;
+/// Input block size for resampling non-16 kHz chunks.
+const RESAMPLE_CHUNK: usize = 1_024;
+
+/// Configuration for [`WhisperCppModel`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhisperCppConfig {
+    /// ggml/GGUF Whisper model file.
+    pub model_path: PathBuf,
+    /// CPU threads per decode. Default: available parallelism, at most 8.
+    pub n_threads: usize,
+    /// Language forced on decoding (`"en"`, `"de"`, ...); `None`
+    /// auto-detects. Changeable later with `set_language_hint`.
+    pub language: Option<String>,
+}
+
+impl WhisperCppConfig {
+    /// Config for the model at `model_path`, with default threads and
+    /// language auto-detection.
+    pub fn new(model_path: impl Into<PathBuf>) -> Self {
+        Self {
+            model_path: model_path.into(),
+            n_threads: std::thread::available_parallelism()
+                .map(|n| n.get().min(8))
+                .unwrap_or(4),
+            language: None,
+        }
+    }
+}
+
+/// Whisper speech model backed by whisper.cpp.
+pub struct WhisperCppModel {
+    config: WhisperCppConfig,
+    context: Option<WhisperContext>,
+    state: Option<WhisperState>,
+    /// Forced decode language; starts from `WhisperCppConfig::language`.
+    language: Option<String>,
+    /// Previous text to condition the next decode on.
+    prompt_context: Option<String>,
+    last_meta: TranscriptMeta,
+}
+
+impl WhisperCppModel {
+    pub fn new(config: WhisperCppConfig) -> Self {
+        let language = config.language.clone();
+        Self {
+            config,
+            context: None,
+            state: None,
+            language,
+            prompt_context: None,
+            last_meta: TranscriptMeta::default(),
+        }
+    }
+}
+
+/// Decode parameters for one pass. `language: None` auto-detects; `prompt`
+/// is text to condition the decode on.
+fn decode_params<'a>(
+    n_threads: usize,
+    language: Option<&'a str>,
+    prompt: Option<&str>,
+    partial: bool,
+) -> FullParams<'a, 'a> {
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_n_threads(n_threads.max(1) as i32);
+    params.set_language(Some(language.unwrap_or("auto")));
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    // Context is managed here (`set_prompt_context`), not by whisper.cpp.
+    params.set_no_context(true);
+    params.set_single_segment(partial);
+    params.set_no_timestamps(partial);
+    if partial {
+        params.set_max_tokens(PARTIAL_MAX_TOKENS);
+    }
+    if let Some(prompt) = prompt {
+        params.set_initial_prompt(prompt);
+    }
+    params
+}
+
+/// `samples` at 16 kHz. The resampler's last partial block is padded with
+/// silence so no audio is held back.
+fn resample_to_whisper_rate(samples: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
+    if sample_rate == SAMPLE_RATE {
+        return Ok(samples.to_vec());
+    }
+    let mut converter = RateConverter::new(sample_rate, SAMPLE_RATE, RESAMPLE_CHUNK)?;
+    let mut out = converter.process(samples);
+    let pending = converter.pending_input();
+    if pending > 0 {
+        out.extend(converter.process(&vec![0.0; RESAMPLE_CHUNK - pending]));
+    }
+    // Drop what the padding produced.
+    let expected = samples.len() as u64 * u64::from(SAMPLE_RATE) / u64::from(sample_rate);
+    out.truncate(expected as usize);
+    Ok(out)
+}
+
+fn inference_error(e: whisper_rs::WhisperError) -> DictumError {
+    DictumError::Inference(e.to_string())
+}
+
+impl SpeechModel for WhisperCppModel {
+    fn warm_up(&mut self) -> Result<()> {
+        let path = &self.config.model_path;
+        if !path.exists() {
+            return Err(DictumError::ModelNotFound { path: path.clone() });
+        }
+        info!("loading WhisperCppModel from {:?}", path);
+        let context = WhisperContext::new_with_params(
+            &path.to_string_lossy(),
+            WhisperContextParameters::default(),
+        )
+        .map_err(inference_error)?;
+        if let Some(language) = &self.language {
+            if whisper_rs::get_lang_id(language).is_none() {
+                return Err(DictumError::InvalidConfig(format!(
+                    "unsupported language hint: {language:?}"
+                )));
+            }
+        }
+        self.state = Some(context.create_state().map_err(inference_error)?);
+        self.context = Some(context);
+
+        // Dummy pass on a second of silence to populate CPU caches.
+        let silence = vec![0.0_f32; SAMPLE_RATE as usize];
+        let params = decode_params(self.config.n_threads, self.language.as_deref(), None, true);
+        if let Some(state) = self.state.as_mut() {
+            state.full(params, &silence).map_err(inference_error)?;
+        }
+        info!(
+            threads = self.config.n_threads,
+            "=== WhisperCppModel warm-up complete ==="
+        );
+        Ok(())
+    }
+
+    fn is_warm(&self) -> bool {
+        self.state.is_some()
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        let multilingual = self
+            .context
+            .as_ref()
+            .is_some_and(WhisperContext::is_multilingual);
+        ModelCapabilities {
+            backend: "whisper-cpp".into(),
+            vocab_size: self.context.as_ref().map(|c| c.n_vocab() as usize),
+            n_mels: self.context.as_ref().map(|c| c.model_n_mels() as usize),
+            languages: if multilingual {
+                (0..=whisper_rs::get_lang_max_id())
+                    .filter_map(whisper_rs::get_lang_str)
+                    .map(str::to_string)
+                    .collect()
+            } else {
+                Vec::new()
+            },
+            segment_timestamps: self.context.is_some(),
+            ..ModelCapabilities::default()
+        }
+    }
+
+    fn transcribe(&mut self, chunk: &AudioChunk, partial: bool) -> Result<Vec<TranscriptSegment>> {
+        self.last_meta = TranscriptMeta::default();
+        if !self.is_warm() {
+            return Err(DictumError::NotWarmedUp);
+        }
+        let samples = resample_to_whisper_rate(&chunk.samples, chunk.sample_rate)?;
+        if samples.len() < MIN_SAMPLES {
+            self.last_meta.empty_reason = Some(EmptyReason::NotDecoded);
+            return Ok(Vec::new());
+        }
+
+        let params = decode_params(
+            self.config.n_threads,
+            self.language.as_deref(),
+            self.prompt_context.as_deref(),
+            partial,
+        );
+        // SAFETY: checked is_warm() above.
+        let state = self.state.as_mut().unwrap();
+        state.full(params, &samples).map_err(inference_error)?;
+
+        let language = state
+            .full_lang_id_from_state()
+            .ok()
+            .and_then(whisper_rs::get_lang_str)
+            .map(str::to_string);
+        let kind = if partial {
+            SegmentKind::Partial
+        } else {
+            SegmentKind::Final
+        };
+        let mut segments = Vec::new();
+        for i in 0..state.full_n_segments().map_err(inference_error)? {
+            let text = state
+                .full_get_segment_text_lossy(i)
+                .map_err(inference_error)?;
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            // whisper.cpp times segments in 10 ms units.
+            let time_ms = |t: std::result::Result<i64, _>| {
+                t.ok().map(|t: i64| (t.max(0) as u32).saturating_mul(10))
+            };
+            let (start_ms, end_ms) = if partial {
+                (None, None)
+            } else {
+                (
+                    time_ms(state.full_get_segment_t0(i)),
+                    time_ms(state.full_get_segment_t1(i)),
+                )
+            };
+            segments.push(TranscriptSegment {
+                id: String::new(),
+                text: text.to_string(),
+                kind,
+                confidence: None,
+                start_ms,
+                end_ms,
+                word_timings: Vec::new(),
+                language: language.clone(),
+            });
+        }
+
+        if segments.is_empty() {
+            debug!(partial, "whisper.cpp decode produced empty text");
+            self.last_meta.empty_reason = Some(EmptyReason::NoSpeech);
+            return Ok(segments);
+        }
+        self.last_meta = TranscriptMeta {
+            language,
+            source: Some(TranscriptSource::Local),
+            ..TranscriptMeta::default()
+        };
+        Ok(segments)
+    }
+
+    fn reset(&mut self) {
+        self.prompt_context = None;
+    }
+
+    fn last_transcript_meta(&self) -> TranscriptMeta {
+        self.last_meta.clone()
+    }
+
+    fn set_prompt_context(&mut self, context: Option<&str>) {
+        self.prompt_context = context
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+    }
+
+    fn set_language_hint(&mut self, language: Option<&str>) -> Result<()> {
+        let language = language.map(str::trim).filter(|l| !l.is_empty());
+        if let Some(code) = language {
+            if whisper_rs::get_lang_id(code).is_none() {
+                return Err(DictumError::InvalidConfig(format!(
+                    "unsupported language hint: {code:?}"
+                )));
+            }
+        }
+        self.language = language.map(str::to_ascii_lowercase);
+        Ok(())
+    }
+
+    fn language_hint(&self) -> Option<String> {
+        self.language.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WhisperCppConfig, WhisperCppModel};
+    use crate::buffering::chunk::AudioChunk;
+    use crate::inference::SpeechModel;
+    use crate::ipc::events::SegmentKind;
+
+    /// Needs a (tiny) ggml model: set `DICTUM_WHISPER_CPP_TEST_MODEL` to its
+    /// path. Skipped otherwise.
+    #[test]
+    fn smoke_transcribes_with_a_real_model() {
+        let Ok(path) = std::env::var("DICTUM_WHISPER_CPP_TEST_MODEL") else {
+            return;
+        };
+        let mut model = WhisperCppModel::new(WhisperCppConfig::new(path));
+        model.warm_up().expect("warm up");
+        assert!(model.is_warm());
+        assert_eq!(model.capabilities().backend, "whisper-cpp");
+
+        // A 440 Hz tone at 48 kHz: resampled, decoded, shaped like any
+        // other backend's output.
+        let samples: Vec<f32> = (0..48_000)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / 48_000.0).sin() * 0.1)
+            .collect();
+        let chunk = AudioChunk::new(samples, 48_000);
+        for partial in [true, false] {
+            let segments = model.transcribe(&chunk, partial).expect("transcribe");
+            for segment in segments {
+                assert!(segment.id.is_empty());
+                assert!(!segment.text.trim().is_empty());
+                let expected = if partial {
+                    SegmentKind::Partial
+                } else {
+                    SegmentKind::Final
+                };
+                assert_eq!(segment.kind, expected);
+                assert_eq!(segment.start_ms.is_some(), !partial);
+            }
+        }
+        model.reset();
+    }
+}
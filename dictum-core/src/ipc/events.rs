@@ -7,12 +7,29 @@
 //! | `TranscriptEvent` | `"dictum://transcript"` |
 //! | `EngineStatusEvent` | `"dictum://status"` |
 //! | `AudioActivityEvent` | `"dictum://activity"` |
+//! | `UtteranceBoundaryEvent` | `"dictum://utterance"` |
 //!
 //! TypeScript mirrors live in `shared/ipc_types.ts`.
 //! (ts-rs auto-generation is planned for P2-20.)
+//!
+//! ## Sequence numbers
+//!
+//! Transcript and activity `seq` counters advance by one per event and wrap
+//! from `u64::MAX` to `0`; they never stall. Order two of them with
+//! [`seq_follows`] rather than `>`.
 
 use serde::{Deserialize, Serialize};
 
+use crate::audio::fingerprint::AudioFingerprint;
+use crate::engine::activity::ACTIVITY_BINS;
+
+/// Whether `seq` comes after `previous` in wrapping order, i.e. is less than
+/// half the `u64` range ahead of it.
+pub fn seq_follows(seq: u64, previous: u64) -> bool {
+    let ahead = seq.wrapping_sub(previous);
+    ahead != 0 && ahead < 1 << 63
+}
+
 // ---------------------------------------------------------------------------
 // Transcript events
 // ---------------------------------------------------------------------------
@@ -21,13 +38,173 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TranscriptEvent {
-    /// Monotonically increasing event sequence number.
+    /// Event sequence number, shared by all transcript channels; wraps at
+    /// `u64::MAX` (see [`seq_follows`]).
     pub seq: u64,
     /// One or more transcript segments from this inference pass.
     pub segments: Vec<TranscriptSegment>,
+    /// Accuracy and provenance details for this inference pass.
+    #[serde(default)]
+    pub meta: TranscriptMeta,
+}
+
+/// Everything known about how a transcript was produced.
+///
+/// Every field is optional: producers fill what they know, consumers must
+/// tolerate any of them being absent (including fields added later).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMeta {
+    /// Aggregate model confidence in [0.0, 1.0].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Language of the transcript as a short code (e.g. `"en"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Which recogniser produced the text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<TranscriptSource>,
+    /// `true` when the text is a placeholder rather than a recognition result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_fallback: Option<bool>,
+    /// Wall-clock inference time for this pass (ms).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u32>,
+    /// Duration of the audio that was transcribed (ms).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_ms: Option<u32>,
+    /// Estimated signal-to-noise ratio of the transcribed audio (dB).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snr_db: Option<f32>,
+    /// First sample of the transcribed audio, counted at the engine's
+    /// `target_sample_rate` from the start of capture. Finals only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_start_sample: Option<u64>,
+    /// One past the last transcribed sample, same clock as
+    /// `audio_start_sample`. Finals only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_end_sample: Option<u64>,
+    /// Per-utterance update counter: 1 for the first event with a given
+    /// segment id, +1 for each later one. The final always carries the
+    /// highest revision, and no partial for an id is emitted after its
+    /// final. Partials and finals travel on separate channels, so a consumer
+    /// of both should drop anything at or below the last revision it applied
+    /// for that id (see [`UtteranceRevisions`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<u32>,
+    /// Why the pass produced no text. Set by models alongside an empty
+    /// result, and carried on the `[speech captured]` placeholder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub empty_reason: Option<EmptyReason>,
+    /// Spectral hash of a final's audio, with `EngineConfig::audio_fingerprint`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_fingerprint: Option<AudioFingerprint>,
+    /// Segment ids of the partials this final replaces, so a UI can morph
+    /// the interim text into the committed text instead of swapping it.
+    /// Finals only; empty when no partial was emitted for the utterance.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supersedes: Vec<String>,
+    /// Whether a partial was emitted for this utterance before its final.
+    /// Finals only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partial_emitted: Option<bool>,
+}
+
+/// Consumer-side filter for stale transcript updates.
+///
+/// Remembers the last applied revision of recent utterances and rejects
+/// events that are older, including any partial that arrives after its
+/// final. Events without a revision are always accepted.
+#[derive(Debug, Default)]
+pub struct UtteranceRevisions {
+    /// `(segment id, last applied revision, finalized)`, oldest first.
+    recent: std::collections::VecDeque<(String, u32, bool)>,
+}
+
+impl UtteranceRevisions {
+    /// Utterances remembered; older ids are forgotten.
+    const CAPACITY: usize = 64;
+
+    /// Whether `event` is newer than everything applied for its utterance.
+    /// Records it when it is.
+    pub fn accept(&mut self, event: &TranscriptEvent) -> bool {
+        let (Some(revision), Some(segment)) = (event.meta.revision, event.segments.first()) else {
+            return true;
+        };
+        let is_final = event
+            .segments
+            .iter()
+            .any(|segment| segment.kind == SegmentKind::Final);
+        match self.recent.iter_mut().find(|(id, _, _)| *id == segment.id) {
+            Some((_, last, finalized)) => {
+                if revision <= *last || (*finalized && !is_final) {
+                    return false;
+                }
+                *last = revision;
+                *finalized |= is_final;
+            }
+            None => {
+                if self.recent.len() == Self::CAPACITY {
+                    self.recent.pop_front();
+                }
+                self.recent
+                    .push_back((segment.id.clone(), revision, is_final));
+            }
+        }
+        true
+    }
+}
+
+/// Recogniser that produced a transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptSource {
+    /// On-device speech model.
+    Local,
+    /// Cloud transcription fallback.
+    Cloud,
+    /// Operating-system dictation fallback.
+    OsDictation,
+    /// Pipeline placeholder (`[speech captured]`).
+    Placeholder,
+}
+
+/// Why a transcription produced no text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyReason {
+    /// The model recognised no words, typically audio too quiet or
+    /// too short to decode.
+    NoSpeech,
+    /// The decoded text was dropped as repetitive or implausible for the
+    /// audio length.
+    LowQualityFiltered,
+    /// A cloud or OS dictation fallback ran but returned nothing usable.
+    FallbackEmpty,
+    /// The cloud fallback was wanted but is not configured or failed.
+    CloudUnavailable,
+    /// No decode completed, e.g. the decode time budget ran out first.
+    NotDecoded,
+}
+
+impl EmptyReason {
+    /// Short user-facing explanation.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::NoSpeech => "no speech recognised; audio may be too quiet",
+            Self::LowQualityFiltered => "output filtered as low-quality",
+            Self::FallbackEmpty => "fallback transcription returned nothing",
+            Self::CloudUnavailable => "cloud fallback unavailable",
+            Self::NotDecoded => "decoding did not finish in time",
+        }
+    }
 }
 
 /// A single recognised speech segment.
+///
+/// One event may carry several segments for the same utterance (e.g. a
+/// sentence-split or timestamped decode). They share `id`, are in spoken
+/// order, and together replace every earlier segment with that `id`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TranscriptSegment {
@@ -39,6 +216,33 @@ pub struct TranscriptSegment {
     pub kind: SegmentKind,
     /// Model confidence in [0.0, 1.0], if available.
     pub confidence: Option<f32>,
+    /// Start of this segment within the transcribed audio (ms), when the
+    /// model reports segment timing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_ms: Option<u32>,
+    /// End of this segment within the transcribed audio (ms).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_ms: Option<u32>,
+    /// Per-word timing within the transcribed audio, in spoken order, when
+    /// the model reports it. Empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub word_timings: Vec<WordTiming>,
+    /// Language code (`"en"`, `"zh"`, ...) of the language token the
+    /// decode ran under, when the model knows it; `"en"` for translated
+    /// text. `None` for models without language control and for
+    /// auto-detect decodes that never committed to a language token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// When one word of a [`TranscriptSegment`] was spoken.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordTiming {
+    pub word: String,
+    /// Offset into the transcribed audio (ms).
+    pub start_ms: u32,
+    pub end_ms: u32,
 }
 
 /// Distinguishes streaming partials from committed finals.
@@ -59,12 +263,77 @@ pub enum SegmentKind {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioActivityEvent {
-    /// Monotonically increasing event sequence number.
+    /// Per-session chunk sequence number; wraps at `u64::MAX` (see
+    /// [`seq_follows`]).
     pub seq: u64,
     /// Root-mean-square level of the chunk in [0.0, 1.0].
     pub rms: f32,
     /// VAD decision for the current chunk.
     pub is_speech: bool,
+    /// Smoothed meter level in [0, 1] from the engine's `activity_level`
+    /// mapping; absent when the engine sends raw `rms` only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<f32>,
+    /// Per-chunk waveform peaks or spectrum, as picked by the engine's
+    /// `activity_bins`; absent unless that is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bins: Option<[f32; ACTIVITY_BINS]>,
+}
+
+// ---------------------------------------------------------------------------
+// Utterance boundary events
+// ---------------------------------------------------------------------------
+
+/// Which edge of an utterance an [`UtteranceBoundaryEvent`] marks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoundaryKind {
+    /// The VAD opened the utterance; segments with its id follow.
+    Start,
+    /// The utterance closed. Sent as soon as it does, before its final is
+    /// decoded, so the time to that final measures inference latency.
+    End,
+}
+
+/// Emitted on channel `"dictum://utterance"` when the pipeline opens or
+/// closes an utterance. Every `Start` is followed by exactly one `End` with
+/// the same `utterance_id`, including when the session is reset or stopped
+/// mid-utterance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtteranceBoundaryEvent {
+    /// Matches the `id` of the utterance's transcript segments.
+    pub utterance_id: String,
+    pub kind: BoundaryKind,
+    /// Stream offset (samples at the target rate since capture start): the
+    /// first speech sample for `Start`, one past the last for `End`.
+    pub at_sample: u64,
+}
+
+// ---------------------------------------------------------------------------
+// Model capabilities
+// ---------------------------------------------------------------------------
+
+/// What the loaded speech model can do, reported once warm-up succeeds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCapabilities {
+    /// Backend name, e.g. `"onnx-whisper"` or `"stub"`.
+    pub backend: String,
+    /// Tokenizer vocabulary size, including added tokens.
+    pub vocab_size: Option<usize>,
+    /// Mel bins the encoder expects (80, or 128 for large-v3 exports).
+    pub n_mels: Option<usize>,
+    /// Whether decoding reuses the KV cache through `decoder_with_past`.
+    pub decoder_with_past: bool,
+    /// Execution provider(s) the loaded sessions registered; `None` before
+    /// warm-up.
+    pub execution_provider: Option<String>,
+    /// Codes of the languages decoding can be forced to; empty when the
+    /// model has no language control.
+    pub languages: Vec<String>,
+    /// Whether segments carry `start_ms`/`end_ms`.
+    pub segment_timestamps: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -100,6 +369,15 @@ pub enum EngineStatus {
 mod tests {
     use super::*;
 
+    #[test]
+    fn seq_follows_across_the_wrap() {
+        assert!(seq_follows(1, 0));
+        assert!(seq_follows(0, u64::MAX));
+        assert!(seq_follows(3, u64::MAX - 2));
+        assert!(!seq_follows(u64::MAX, 0));
+        assert!(!seq_follows(5, 5));
+    }
+
     #[test]
     fn transcript_event_serializes_with_camel_case_and_lowercase_kind() {
         let event = TranscriptEvent {
@@ -109,7 +387,12 @@ mod tests {
                 text: "hello".into(),
                 kind: SegmentKind::Partial,
                 confidence: Some(0.91),
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
             }],
+            meta: TranscriptMeta::default(),
         };
 
         let json = serde_json::to_value(&event).expect("serialize transcript event");
@@ -129,6 +412,55 @@ mod tests {
         assert_eq!(round_trip.segments[0].kind, SegmentKind::Partial);
     }
 
+    #[test]
+    fn transcript_meta_round_trips_and_omits_missing_fields() {
+        let meta = TranscriptMeta {
+            confidence: Some(0.8),
+            language: Some("en".into()),
+            source: Some(TranscriptSource::OsDictation),
+            is_fallback: Some(false),
+            latency_ms: Some(142),
+            audio_ms: Some(2_300),
+            snr_db: Some(18.5),
+            audio_start_sample: Some(48_000),
+            audio_end_sample: Some(84_800),
+            revision: Some(3),
+            empty_reason: Some(EmptyReason::LowQualityFiltered),
+            audio_fingerprint: Some(AudioFingerprint(0x1f)),
+            supersedes: vec!["utt-4".into()],
+            partial_emitted: Some(true),
+        };
+
+        let json = serde_json::to_value(&meta).expect("serialize meta");
+        assert_eq!(json["source"], "os_dictation");
+        assert_eq!(json["latencyMs"], 142);
+        assert_eq!(json["isFallback"], false);
+        assert_eq!(json["audioStartSample"], 48_000);
+        assert_eq!(json["emptyReason"], "low_quality_filtered");
+        assert_eq!(json["audioFingerprint"], "000000000000001f");
+        assert_eq!(json["supersedes"], serde_json::json!(["utt-4"]));
+        assert_eq!(json["partialEmitted"], true);
+        let round_trip: TranscriptMeta = serde_json::from_value(json).expect("deserialize meta");
+        assert_eq!(round_trip, meta);
+
+        let sparse = TranscriptMeta {
+            latency_ms: Some(5),
+            ..TranscriptMeta::default()
+        };
+        let json = serde_json::to_value(&sparse).expect("serialize sparse meta");
+        assert_eq!(json, serde_json::json!({ "latencyMs": 5 }));
+    }
+
+    #[test]
+    fn transcript_event_without_meta_still_deserializes() {
+        let json = serde_json::json!({
+            "seq": 1,
+            "segments": [{ "id": "utt-0", "text": "hi", "kind": "final", "confidence": null }],
+        });
+        let event: TranscriptEvent = serde_json::from_value(json).expect("deserialize legacy");
+        assert_eq!(event.meta, TranscriptMeta::default());
+    }
+
     #[test]
     fn engine_status_event_serializes_with_lowercase_status() {
         let event = EngineStatusEvent {
@@ -159,6 +491,8 @@ mod tests {
             seq: 3,
             rms: 0.18,
             is_speech: true,
+            level: None,
+            bins: None,
         };
 
         let json = serde_json::to_value(&event).expect("serialize activity event");
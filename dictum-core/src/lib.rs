@@ -23,16 +23,22 @@ pub mod audio;
 pub mod buffering;
 pub mod engine;
 pub mod error;
+pub mod eval;
 pub mod inference;
 pub mod ipc;
+pub mod numerals;
+pub mod redact;
+pub mod sink;
 pub mod vad;
 
 // Convenience re-exports for downstream crates
-pub use engine::{DictumEngine, EngineConfig};
+pub use audio::fingerprint::AudioFingerprint;
+pub use engine::{DictumEngine, EngineConfig, EngineEvent, EngineEventStream};
 pub use error::DictumError;
-pub use inference::{ModelHandle, SpeechModel};
+pub use inference::{transcribe_file, BatchTranscriber, ModelHandle, SpeechModel};
 pub use ipc::events::{
-    AudioActivityEvent, EngineStatus, EngineStatusEvent, TranscriptEvent, TranscriptSegment,
+    AudioActivityEvent, EmptyReason, EngineStatus, EngineStatusEvent, ModelCapabilities,
+    TranscriptEvent, TranscriptMeta, TranscriptSegment, TranscriptSource,
 };
 
 #[cfg(feature = "onnx")]
@@ -40,3 +46,6 @@ pub use inference::{OnnxModel, OnnxModelConfig};
 
 #[cfg(feature = "onnx")]
 pub use vad::SileroVad;
+
+#[cfg(feature = "whisper-cpp")]
+pub use inference::{WhisperCppConfig, WhisperCppModel};
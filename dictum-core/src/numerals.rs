@@ -0,0 +1,635 @@
+//! Spelled-out numbers and units to digits and symbols in finalized text.
+//!
+//! Whisper writes "twenty twenty three" and "five kilometers" where technical
+//! dictation wants "2023" and "5 km". The pass is opt-in and English-only:
+//! runs of number words are parsed as compounds ("three hundred and five"),
+//! adjacent compounds as digit sequences ("nineteen eighty four"), and
+//! "point" followed by digits as a decimal. A known unit right after a
+//! number is replaced by its symbol.
+//!
+//! Conservative mode leaves lone small numbers ("one of them", "two ideas")
+//! as words unless a unit or decimal makes them clearly numeric, and only
+//! joins adjacent compounds when they read as a year.
+
+/// Thousands and decimal separators for formatted numbers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NumberSeparators {
+    /// `12,500.5`
+    #[default]
+    CommaPeriod,
+    /// `12.500,5`
+    PeriodComma,
+    /// `12 500,5` with a narrow no-break space.
+    SpaceComma,
+}
+
+impl NumberSeparators {
+    /// Conventional separators for a BCP 47 locale such as `"de-DE"`;
+    /// unknown locales get `CommaPeriod`.
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" => Self::PeriodComma,
+            "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "nb" | "no" | "fi" | "uk" | "hu" => {
+                Self::SpaceComma
+            }
+            _ => Self::CommaPeriod,
+        }
+    }
+
+    fn thousands(self) -> &'static str {
+        match self {
+            Self::CommaPeriod => ",",
+            Self::PeriodComma => ".",
+            Self::SpaceComma => "\u{202F}",
+        }
+    }
+
+    fn decimal(self) -> char {
+        match self {
+            Self::CommaPeriod => '.',
+            Self::PeriodComma | Self::SpaceComma => ',',
+        }
+    }
+}
+
+/// How [`format_numbers`] rewrites text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Keep lone numbers below ten as words and only join year-like pairs.
+    pub conservative: bool,
+    pub separators: NumberSeparators,
+}
+
+/// Integers from this value up get thousands separators; below it they
+/// would mostly be years and codes.
+const GROUPING_MIN: u64 = 10_000;
+
+/// Unit words and their symbols. Multi-word names come first so
+/// "degrees celsius" wins over "degrees".
+const UNITS: &[(&[&str], &str, bool)] = &[
+    (&["degrees", "celsius"], "°C", false),
+    (&["degrees", "fahrenheit"], "°F", false),
+    (&["degree", "celsius"], "°C", false),
+    (&["degree", "fahrenheit"], "°F", false),
+    (&["percent"], "%", false),
+    (&["degrees"], "°", false),
+    (&["degree"], "°", false),
+    (&["kilometers"], "km", true),
+    (&["kilometres"], "km", true),
+    (&["kilometer"], "km", true),
+    (&["kilometre"], "km", true),
+    (&["centimeters"], "cm", true),
+    (&["centimetres"], "cm", true),
+    (&["centimeter"], "cm", true),
+    (&["centimetre"], "cm", true),
+    (&["millimeters"], "mm", true),
+    (&["millimetres"], "mm", true),
+    (&["millimeter"], "mm", true),
+    (&["millimetre"], "mm", true),
+    (&["meters"], "m", true),
+    (&["metres"], "m", true),
+    (&["meter"], "m", true),
+    (&["metre"], "m", true),
+    (&["miles"], "mi", true),
+    (&["mile"], "mi", true),
+    (&["kilograms"], "kg", true),
+    (&["kilogram"], "kg", true),
+    (&["grams"], "g", true),
+    (&["gram"], "g", true),
+    (&["pounds"], "lb", true),
+    (&["pound"], "lb", true),
+    (&["liters"], "L", true),
+    (&["litres"], "L", true),
+    (&["liter"], "L", true),
+    (&["litre"], "L", true),
+    (&["milliliters"], "mL", true),
+    (&["millilitres"], "mL", true),
+    (&["milliliter"], "mL", true),
+    (&["millilitre"], "mL", true),
+    (&["milliseconds"], "ms", true),
+    (&["millisecond"], "ms", true),
+    (&["kilobytes"], "KB", true),
+    (&["kilobyte"], "KB", true),
+    (&["megabytes"], "MB", true),
+    (&["megabyte"], "MB", true),
+    (&["gigabytes"], "GB", true),
+    (&["gigabyte"], "GB", true),
+    (&["terabytes"], "TB", true),
+    (&["terabyte"], "TB", true),
+    (&["hertz"], "Hz", true),
+    (&["kilohertz"], "kHz", true),
+    (&["megahertz"], "MHz", true),
+    (&["gigahertz"], "GHz", true),
+    (&["volts"], "V", true),
+    (&["volt"], "V", true),
+    (&["watts"], "W", true),
+    (&["watt"], "W", true),
+    (&["kilowatts"], "kW", true),
+    (&["kilowatt"], "kW", true),
+];
+
+/// Rewrite spelled-out numbers (and units following them) in `text`.
+pub fn format_numbers(text: &str, format: &NumberFormat) -> String {
+    let words = split_words(text);
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    let mut i = 0;
+    while i < words.len() {
+        let Some(run) = parse_run(&words, i) else {
+            i += 1;
+            continue;
+        };
+        let unit = if words[run.end - 1].punct_after {
+            None
+        } else {
+            match_unit(&words, run.end)
+        };
+        let next = unit.map_or(run.end, |(_, _, end)| end);
+        if let Some(rendered) = render_run(text, &words, &run, unit, format) {
+            out.push_str(&text[cursor..words[i].start]);
+            out.push_str(&rendered);
+            cursor = words[next - 1].core_end;
+        }
+        i = next;
+    }
+    out.push_str(&text[cursor..]);
+    out
+}
+
+/// A whitespace-delimited word; `core` excludes trailing punctuation.
+#[derive(Debug)]
+struct Word {
+    start: usize,
+    core_end: usize,
+    core: String,
+    punct_after: bool,
+}
+
+fn split_words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut offset = 0;
+    for token in text.split_inclusive(char::is_whitespace) {
+        let start = offset;
+        offset += token.len();
+        let raw = token.trim_end();
+        if raw.is_empty() {
+            continue;
+        }
+        let core = raw.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        words.push(Word {
+            start,
+            core_end: start + core.len(),
+            core: core.to_lowercase(),
+            punct_after: core.len() < raw.len(),
+        });
+    }
+    words
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumWord {
+    /// zero..nineteen
+    Small(u64),
+    /// twenty, thirty, ... ninety
+    Tens(u64),
+    Hundred,
+    /// thousand, million, billion
+    Scale(u64),
+}
+
+fn num_word(word: &str) -> Option<NumWord> {
+    const SMALL: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 8] = [
+        "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+    if let Some(n) = SMALL.iter().position(|w| *w == word) {
+        return Some(NumWord::Small(n as u64));
+    }
+    if let Some(n) = TENS.iter().position(|w| *w == word) {
+        return Some(NumWord::Tens((n as u64 + 2) * 10));
+    }
+    match word {
+        "hundred" => Some(NumWord::Hundred),
+        "thousand" => Some(NumWord::Scale(1_000)),
+        "million" => Some(NumWord::Scale(1_000_000)),
+        "billion" => Some(NumWord::Scale(1_000_000_000)),
+        _ => None,
+    }
+}
+
+/// `"twenty-three"` → `[Tens(20), Small(3)]`.
+fn num_parts(word: &str) -> Option<Vec<NumWord>> {
+    word.split('-').map(num_word).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Last {
+    None,
+    Zero,
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+/// One number spoken as a compound, e.g. "three hundred and five".
+#[derive(Debug, Clone, Copy)]
+struct Compound {
+    total: u64,
+    small: u64,
+    last: Last,
+    /// Next scale word must be below this.
+    scale_ceiling: u64,
+    first_word: usize,
+    last_word: usize,
+}
+
+impl Compound {
+    fn new(word: usize) -> Self {
+        Self {
+            total: 0,
+            small: 0,
+            last: Last::None,
+            scale_ceiling: u64::MAX,
+            first_word: word,
+            last_word: word,
+        }
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.small
+    }
+
+    fn uses_scale(&self) -> bool {
+        self.scale_ceiling != u64::MAX
+    }
+
+    /// Attach `part`, or `false` if it starts a new number.
+    fn push(&mut self, part: NumWord) -> bool {
+        let open = matches!(self.last, Last::None | Last::Hundred | Last::Scale);
+        match part {
+            NumWord::Small(0) if self.last == Last::None => self.last = Last::Zero,
+            NumWord::Small(0) => return false,
+            NumWord::Small(n) if n < 10 && (open || self.last == Last::Tens) => {
+                self.small += n;
+                self.last = Last::Unit;
+            }
+            NumWord::Small(n) if n >= 10 && open => {
+                self.small += n;
+                self.last = Last::Teen;
+            }
+            NumWord::Tens(n) if open => {
+                self.small += n;
+                self.last = Last::Tens;
+            }
+            NumWord::Hundred
+                if matches!(self.last, Last::Unit | Last::Teen | Last::Tens)
+                    && self.small < 100 =>
+            {
+                self.small *= 100;
+                self.last = Last::Hundred;
+            }
+            NumWord::Scale(scale)
+                if matches!(
+                    self.last,
+                    Last::Unit | Last::Teen | Last::Tens | Last::Hundred
+                ) && scale < self.scale_ceiling =>
+            {
+                self.total += self.small * scale;
+                self.small = 0;
+                self.scale_ceiling = scale;
+                self.last = Last::Scale;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Consecutive number words starting at one word index.
+#[derive(Debug)]
+struct Run {
+    compounds: Vec<Compound>,
+    /// Digits spoken after "point".
+    decimals: String,
+    /// One past the last consumed word.
+    end: usize,
+}
+
+fn parse_run(words: &[Word], start: usize) -> Option<Run> {
+    let mut compounds: Vec<Compound> = Vec::new();
+    let mut current = Compound::new(start);
+    let mut decimals = String::new();
+    let mut i = start;
+    while i < words.len() {
+        let word = &words[i];
+        let started = current.last != Last::None;
+        if started && (word.core == "and" || word.core == "point") && !word.punct_after {
+            if word.core == "point" {
+                let digits: String = words[i + 1..]
+                    .iter()
+                    .scan(false, |stop, w| {
+                        if *stop {
+                            return None;
+                        }
+                        *stop = w.punct_after;
+                        match num_word(&w.core) {
+                            Some(NumWord::Small(d)) if d < 10 => char::from_digit(d as u32, 10),
+                            _ => None,
+                        }
+                    })
+                    .collect();
+                if !digits.is_empty() {
+                    i += 1 + digits.len();
+                    current.last_word = i - 1;
+                    decimals = digits;
+                }
+                break;
+            }
+            // "and" only joins a hundred/thousand to what follows it.
+            let mut joined = current;
+            let follows = matches!(current.last, Last::Hundred | Last::Scale)
+                && words
+                    .get(i + 1)
+                    .and_then(|w| num_parts(&w.core))
+                    .is_some_and(|parts| {
+                        parts
+                            .iter()
+                            .all(|p| !matches!(p, NumWord::Hundred | NumWord::Scale(_)))
+                            && parts.into_iter().all(|p| joined.push(p))
+                    });
+            if !follows {
+                break;
+            }
+            current = joined;
+            current.last_word = i + 1;
+            let stop = words[i + 1].punct_after;
+            i += 2;
+            if stop {
+                break;
+            }
+            continue;
+        }
+        let Some(parts) = num_parts(&word.core) else {
+            break;
+        };
+        let mut attached = current;
+        if !parts.iter().all(|p| attached.push(*p)) {
+            if !started {
+                break;
+            }
+            let mut fresh = Compound::new(i);
+            if !parts.iter().all(|p| fresh.push(*p)) {
+                break;
+            }
+            compounds.push(current);
+            attached = fresh;
+        }
+        current = attached;
+        current.last_word = i;
+        i += 1;
+        if word.punct_after {
+            break;
+        }
+    }
+    if current.last == Last::None {
+        return None;
+    }
+    compounds.push(current);
+    Some(Run {
+        compounds,
+        decimals,
+        end: i,
+    })
+}
+
+/// Unit starting at word `at`: `(symbol, attaches_with_space, end)`.
+fn match_unit(words: &[Word], at: usize) -> Option<(&'static str, bool, usize)> {
+    UNITS.iter().find_map(|(names, symbol, spaced)| {
+        let candidate = words.get(at..at + names.len())?;
+        let matches = candidate
+            .iter()
+            .zip(names.iter())
+            .enumerate()
+            .all(|(k, (word, name))| {
+                word.core == *name && (k + 1 == names.len() || !word.punct_after)
+            });
+        matches.then_some((*symbol, *spaced, at + names.len()))
+    })
+}
+
+fn render_run(
+    text: &str,
+    words: &[Word],
+    run: &Run,
+    unit: Option<(&'static str, bool, usize)>,
+    format: &NumberFormat,
+) -> Option<String> {
+    let compounds = &run.compounds;
+    let numeric_tail = unit.is_some() || !run.decimals.is_empty();
+    let year_like =
+        compounds.len() == 2 && compounds.iter().all(|c| (10..100).contains(&c.value()));
+    let digit_sequence =
+        compounds.len() > 1 && compounds.iter().all(|c| !c.uses_scale() && c.value() < 100);
+    let joined = if format.conservative {
+        year_like
+    } else {
+        digit_sequence
+    };
+
+    let mut number = if joined {
+        let digits: String = compounds.iter().map(|c| c.value().to_string()).collect();
+        Some(digits)
+    } else {
+        None
+    };
+    let mut out = String::new();
+    if number.is_none() {
+        // Render each compound on its own, keeping the text between them.
+        let last = compounds.len() - 1;
+        let mut changed = false;
+        for (k, compound) in compounds.iter().enumerate() {
+            if k > 0 {
+                let gap_start = words[compounds[k - 1].last_word].core_end;
+                out.push_str(&text[gap_start..words[compound.first_word].start]);
+            }
+            let keep_words =
+                format.conservative && compound.value() < 10 && !(k == last && numeric_tail);
+            if keep_words {
+                let end = words[compound.last_word].core_end;
+                out.push_str(&text[words[compound.first_word].start..end]);
+            } else if k == last {
+                number = Some(group_thousands(compound.value(), format.separators));
+                changed = true;
+            } else {
+                out.push_str(&group_thousands(compound.value(), format.separators));
+                changed = true;
+            }
+        }
+        if !changed {
+            return None;
+        }
+    }
+
+    if let Some(number) = number {
+        out.push_str(&number);
+        if !run.decimals.is_empty() {
+            out.push(format.separators.decimal());
+            out.push_str(&run.decimals);
+        }
+        if let Some((symbol, spaced, _)) = unit {
+            if spaced {
+                out.push(' ');
+            }
+            out.push_str(symbol);
+        }
+    }
+    Some(out)
+}
+
+fn group_thousands(value: u64, separators: NumberSeparators) -> String {
+    let digits = value.to_string();
+    if value < GROUPING_MIN {
+        return digits;
+    }
+    let mut out = String::with_capacity(digits.len() * 2);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push_str(separators.thousands());
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_numbers, NumberFormat, NumberSeparators};
+
+    fn all() -> NumberFormat {
+        NumberFormat::default()
+    }
+
+    fn conservative() -> NumberFormat {
+        NumberFormat {
+            conservative: true,
+            ..NumberFormat::default()
+        }
+    }
+
+    #[test]
+    fn spoken_years_decimals_and_compounds_become_digits() {
+        for format in [all(), conservative()] {
+            assert_eq!(
+                format_numbers("Released in twenty twenty three.", &format),
+                "Released in 2023."
+            );
+            assert_eq!(
+                format_numbers("It scored three point five overall", &format),
+                "It scored 3.5 overall"
+            );
+            assert_eq!(
+                format_numbers("three hundred and five rows, nineteen eighty-four", &format),
+                "305 rows, 1984"
+            );
+            assert_eq!(
+                format_numbers("Fifty two thousand users", &format),
+                "52,000 users"
+            );
+        }
+        assert_eq!(
+            format_numbers("call five five five one two", &all()),
+            "call 55512"
+        );
+    }
+
+    #[test]
+    fn units_become_symbols() {
+        let format = conservative();
+        assert_eq!(
+            format_numbers("I ran five kilometers", &format),
+            "I ran 5 km"
+        );
+        assert_eq!(
+            format_numbers("about ninety percent done", &format),
+            "about 90% done"
+        );
+        assert_eq!(
+            format_numbers("it was twenty one degrees celsius.", &format),
+            "it was 21°C."
+        );
+        // A unit word without a number is prose.
+        assert_eq!(
+            format_numbers("kilometers of road", &format),
+            "kilometers of road"
+        );
+    }
+
+    #[test]
+    fn conservative_mode_keeps_small_numbers_in_prose() {
+        let text = "No one wanted two of them, but one said nine.";
+        assert_eq!(format_numbers(text, &conservative()), text);
+        assert_eq!(
+            format_numbers(text, &all()),
+            "No 1 wanted 2 of them, but 1 said 9."
+        );
+        // Unrelated small numbers are not glued into a digit string.
+        assert_eq!(
+            format_numbers("one two three", &conservative()),
+            "one two three"
+        );
+        assert_eq!(
+            format_numbers("one point five liters", &conservative()),
+            "1.5 L"
+        );
+    }
+
+    #[test]
+    fn separators_follow_the_locale() {
+        let german = NumberFormat {
+            conservative: false,
+            separators: NumberSeparators::for_locale("de-DE"),
+        };
+        assert_eq!(
+            format_numbers("twelve thousand five hundred point five", &german),
+            "12.500,5"
+        );
+        assert_eq!(
+            NumberSeparators::for_locale("fr_FR"),
+            NumberSeparators::SpaceComma
+        );
+        assert_eq!(
+            NumberSeparators::for_locale("en-US"),
+            NumberSeparators::CommaPeriod
+        );
+        // Years are never grouped.
+        assert_eq!(format_numbers("two thousand and five", &german), "2005");
+    }
+}
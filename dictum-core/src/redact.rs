@@ -0,0 +1,292 @@
+//! Masking of personal data in finalized transcripts.
+//!
+//! Runs on final text before it is injected or persisted. Detection is
+//! deliberately narrow to keep ordinary numbers intact: card numbers must
+//! pass the Luhn check, SSNs and phone numbers must be written in their usual
+//! grouped form.
+
+/// Which kinds of personal data to mask. Everything is off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RedactionPolicy {
+    pub credit_cards: bool,
+    pub emails: bool,
+    pub phone_numbers: bool,
+    pub ssns: bool,
+}
+
+impl RedactionPolicy {
+    /// Mask every supported kind.
+    pub fn all() -> Self {
+        Self {
+            credit_cards: true,
+            emails: true,
+            phone_numbers: true,
+            ssns: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.credit_cards || self.emails || self.phone_numbers || self.ssns
+    }
+
+    fn allows(&self, kind: PiiKind) -> bool {
+        match kind {
+            PiiKind::CreditCard => self.credit_cards,
+            PiiKind::Email => self.emails,
+            PiiKind::PhoneNumber => self.phone_numbers,
+            PiiKind::Ssn => self.ssns,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PiiKind {
+    CreditCard,
+    Email,
+    PhoneNumber,
+    Ssn,
+}
+
+impl PiiKind {
+    /// Placeholder written in place of the masked text.
+    pub fn mask(self) -> &'static str {
+        match self {
+            PiiKind::CreditCard => "[card number]",
+            PiiKind::Email => "[email]",
+            PiiKind::PhoneNumber => "[phone number]",
+            PiiKind::Ssn => "[ssn]",
+        }
+    }
+}
+
+/// One masked region, as byte offsets into the *original* text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedactionSpan {
+    pub kind: PiiKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Mask everything `policy` asks for. Returns the masked text and the spans
+/// that were replaced, in order.
+pub fn redact(text: &str, policy: &RedactionPolicy) -> (String, Vec<RedactionSpan>) {
+    if !policy.is_enabled() {
+        return (text.to_string(), Vec::new());
+    }
+    let mut spans: Vec<RedactionSpan> = find_emails(text)
+        .into_iter()
+        .chain(find_number_runs(text))
+        .filter(|span| policy.allows(span.kind))
+        .collect();
+    spans.sort_by_key(|span| span.start);
+    // Emails may contain digit runs; keep the first (outermost) match.
+    let mut kept: Vec<RedactionSpan> = Vec::with_capacity(spans.len());
+    for span in spans {
+        if kept.last().is_none_or(|last| span.start >= last.end) {
+            kept.push(span);
+        }
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for span in &kept {
+        out.push_str(&text[cursor..span.start]);
+        out.push_str(span.kind.mask());
+        cursor = span.end;
+    }
+    out.push_str(&text[cursor..]);
+    (out, kept)
+}
+
+fn find_emails(text: &str) -> Vec<RedactionSpan> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for token in text.split_inclusive(char::is_whitespace) {
+        let start = offset;
+        offset += token.len();
+        let word = token
+            .trim_end()
+            .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '"', '\''])
+            .trim_start_matches(['(', '"', '\'', '<']);
+        if is_email(word) {
+            let lead = token.find(word).unwrap_or(0);
+            spans.push(RedactionSpan {
+                kind: PiiKind::Email,
+                start: start + lead,
+                end: start + lead + word.len(),
+            });
+        }
+    }
+    spans
+}
+
+fn is_email(word: &str) -> bool {
+    let Some((local, domain)) = word.split_once('@') else {
+        return false;
+    };
+    let local_ok = !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-'));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+    local_ok && domain_ok
+}
+
+/// Scan for runs of digit groups joined by short separators (`4111 1111`,
+/// `555-123-4567`, `(555) 123.4567`) and classify each run.
+fn find_number_runs(text: &str) -> Vec<RedactionSpan> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let lead =
+            matches!(bytes[i], b'+' | b'(') && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+        if !(bytes[i].is_ascii_digit() || lead) || (i > 0 && is_word_byte(bytes[i - 1])) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        if lead {
+            i += 1;
+        }
+        let mut groups = Vec::new();
+        let mut digits = String::new();
+        let mut end;
+        loop {
+            let group_start = i;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            groups.push(i - group_start);
+            digits.push_str(&text[group_start..i]);
+            end = i;
+            let sep_len = bytes[i..]
+                .iter()
+                .take(2)
+                .take_while(|b| matches!(b, b' ' | b'-' | b'.' | b'(' | b')'))
+                .count();
+            if sep_len > 0 && bytes.get(i + sep_len).is_some_and(u8::is_ascii_digit) {
+                i += sep_len;
+            } else {
+                break;
+            }
+        }
+        // A trailing letter means this was part of a word like `4th`.
+        if bytes.get(end).copied().is_some_and(is_word_byte) {
+            i = end + 1;
+            continue;
+        }
+        if let Some(kind) = classify_number(&digits, &groups) {
+            spans.push(RedactionSpan { kind, start, end });
+        }
+        i = end;
+    }
+    spans
+}
+
+fn classify_number(digits: &str, groups: &[usize]) -> Option<PiiKind> {
+    if groups == [3, 2, 4] {
+        return Some(PiiKind::Ssn);
+    }
+    if (13..=19).contains(&digits.len()) && luhn_valid(digits) {
+        return Some(PiiKind::CreditCard);
+    }
+    let national = match digits.len() {
+        10 => true,
+        11 => digits.starts_with('1'),
+        _ => false,
+    };
+    if national && groups.len() >= 3 && groups.ends_with(&[3, 4]) {
+        return Some(PiiKind::PhoneNumber);
+    }
+    None
+}
+
+fn luhn_valid(digits: &str) -> bool {
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, b)| {
+            let d = u32::from(b - b'0');
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact, PiiKind, RedactionPolicy};
+
+    #[test]
+    fn masks_luhn_valid_card_and_leaves_other_numbers() {
+        let policy = RedactionPolicy::all();
+        let (out, spans) = redact("My card is 4111 1111 1111 1111, thanks.", &policy);
+        assert_eq!(out, "My card is [card number], thanks.");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].kind, PiiKind::CreditCard);
+
+        // Same shape, fails Luhn: an order number, not a card.
+        let text = "Order 1234 5678 9012 3456 shipped.";
+        let (out, spans) = redact(text, &policy);
+        assert_eq!(out, text);
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn masks_email_phone_and_ssn() {
+        let (out, spans) = redact(
+            "Mail jane.doe@example.com or call (555) 123-4567, SSN 123-45-6789.",
+            &RedactionPolicy::all(),
+        );
+        assert_eq!(out, "Mail [email] or call [phone number], SSN [ssn].");
+        let kinds: Vec<PiiKind> = spans.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![PiiKind::Email, PiiKind::PhoneNumber, PiiKind::Ssn]
+        );
+    }
+
+    #[test]
+    fn policy_controls_what_is_masked() {
+        let text = "Card 4111-1111-1111-1111 to bob@example.org";
+        let (out, _) = redact(text, &RedactionPolicy::default());
+        assert_eq!(out, text);
+
+        let emails_only = RedactionPolicy {
+            emails: true,
+            ..RedactionPolicy::default()
+        };
+        let (out, _) = redact(text, &emails_only);
+        assert_eq!(out, "Card 4111-1111-1111-1111 to [email]");
+    }
+
+    #[test]
+    fn ordinary_numbers_survive() {
+        let text = "Meet at 10:30 on 2026-03-14, room 4th floor, 1500 people, 555 1234.";
+        let (out, spans) = redact(text, &RedactionPolicy::all());
+        assert_eq!(out, text);
+        assert!(spans.is_empty());
+    }
+}
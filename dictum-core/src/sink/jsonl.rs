@@ -0,0 +1,280 @@
+//! Append-only JSON-lines log of final transcripts.
+//!
+//! **The log is plaintext.** Unlike the app's encrypted history database,
+//! every final transcript and its `TranscriptMeta` is written as-is, one JSON
+//! object per line, for pipelines that ingest it (auditing, consented
+//! training-data collection). It is off unless a caller attaches it.
+//!
+//! Files are named `<stem>-<YYYY-MM-DD>.jsonl` (local date) when rotating
+//! daily, `<stem>.jsonl` otherwise. Once a file reaches `max_file_bytes` the
+//! log moves on to `<name>.1.jsonl`, `<name>.2.jsonl`, ...
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate};
+
+use crate::{
+    error::Result,
+    ipc::events::{SegmentKind, TranscriptEvent},
+};
+
+use super::OutputSink;
+
+/// Where and how the JSON-lines log is written.
+#[derive(Debug, Clone)]
+pub struct JsonlLogConfig {
+    /// Directory holding the log files. Created on first write.
+    pub dir: PathBuf,
+    /// File name prefix. Default: `"transcripts"`.
+    pub file_stem: String,
+    /// Size at which a new file is started. A single line larger than this
+    /// still gets a file of its own; 0 is taken as 1, a file per line.
+    /// Default: 16 MiB.
+    pub max_file_bytes: u64,
+    /// Start a new file each local calendar day. Default: true.
+    pub rotate_daily: bool,
+}
+
+impl Default for JsonlLogConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::new(),
+            file_stem: "transcripts".into(),
+            max_file_bytes: 16 * 1024 * 1024,
+            rotate_daily: true,
+        }
+    }
+}
+
+/// [`OutputSink`] that appends every final `TranscriptEvent` to a rotating
+/// JSON-lines file. Partial events are ignored.
+#[derive(Debug)]
+pub struct JsonlTranscriptLog {
+    config: JsonlLogConfig,
+    current: Option<OpenLog>,
+}
+
+#[derive(Debug)]
+struct OpenLog {
+    file: File,
+    path: PathBuf,
+    date: Option<NaiveDate>,
+    index: u32,
+    bytes: u64,
+}
+
+impl JsonlTranscriptLog {
+    pub fn new(config: JsonlLogConfig) -> Self {
+        Self {
+            config,
+            current: None,
+        }
+    }
+
+    /// File currently being appended to, if any event was written yet.
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current.as_ref().map(|log| log.path.as_path())
+    }
+
+    fn write_event_on(&mut self, event: &TranscriptEvent, today: NaiveDate) -> Result<()> {
+        if !event
+            .segments
+            .iter()
+            .any(|segment| segment.kind == SegmentKind::Final)
+        {
+            return Ok(());
+        }
+        let mut line = serde_json::to_vec(event).map_err(anyhow::Error::from)?;
+        line.push(b'\n');
+
+        let date = self.config.rotate_daily.then_some(today);
+        let line_len = line.len() as u64;
+        let max_bytes = self.max_file_bytes();
+        let log = match self.current.take() {
+            Some(log)
+                if log.date == date && (log.bytes == 0 || log.bytes + line_len <= max_bytes) =>
+            {
+                log
+            }
+            Some(log) if log.date == date => self.open(date, log.index + 1)?,
+            _ => self.open(date, 0)?,
+        };
+        let log = self.current.insert(log);
+        log.file.write_all(&line)?;
+        log.bytes += line_len;
+        Ok(())
+    }
+
+    /// Open the first file for `date` at or after `index` that still has
+    /// room, so a restart keeps appending to today's last file.
+    fn open(&self, date: Option<NaiveDate>, mut index: u32) -> Result<OpenLog> {
+        std::fs::create_dir_all(&self.config.dir)?;
+        let max_bytes = self.max_file_bytes();
+        loop {
+            let path = self.file_path(date, index);
+            let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if bytes < max_bytes {
+                let file = OpenOptions::new().create(true).append(true).open(&path)?;
+                return Ok(OpenLog {
+                    file,
+                    path,
+                    date,
+                    index,
+                    bytes,
+                });
+            }
+            index += 1;
+        }
+    }
+
+    /// `max_file_bytes`, at least 1 so `open` always finds a file with room.
+    fn max_file_bytes(&self) -> u64 {
+        self.config.max_file_bytes.max(1)
+    }
+
+    fn file_path(&self, date: Option<NaiveDate>, index: u32) -> PathBuf {
+        let mut name = self.config.file_stem.clone();
+        if let Some(date) = date {
+            name.push_str(&format!("-{}", date.format("%Y-%m-%d")));
+        }
+        if index > 0 {
+            name.push_str(&format!(".{index}"));
+        }
+        name.push_str(".jsonl");
+        self.config.dir.join(name)
+    }
+}
+
+impl OutputSink for JsonlTranscriptLog {
+    fn write_event(&mut self, event: &TranscriptEvent) -> Result<()> {
+        self.write_event_on(event, Local::now().date_naive())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonlLogConfig, JsonlTranscriptLog};
+    use crate::ipc::events::{
+        SegmentKind, TranscriptEvent, TranscriptMeta, TranscriptSegment, TranscriptSource,
+    };
+    use chrono::NaiveDate;
+
+    fn event(seq: u64, kind: SegmentKind, text: &str) -> TranscriptEvent {
+        TranscriptEvent {
+            seq,
+            segments: vec![TranscriptSegment {
+                id: format!("utt-{seq}"),
+                text: text.into(),
+                kind,
+                confidence: Some(0.9),
+                start_ms: None,
+                end_ms: None,
+                word_timings: Vec::new(),
+                language: None,
+            }],
+            meta: TranscriptMeta {
+                source: Some(TranscriptSource::Local),
+                latency_ms: Some(42),
+                ..TranscriptMeta::default()
+            },
+        }
+    }
+
+    fn log_dir(tag: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dictum-jsonl-test-{}-{tag}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn finals_are_written_as_json_lines() {
+        let dir = log_dir("lines");
+        let mut log = JsonlTranscriptLog::new(JsonlLogConfig {
+            dir: dir.clone(),
+            ..JsonlLogConfig::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+        log.write_event_on(&event(1, SegmentKind::Partial, "hel"), day)
+            .unwrap();
+        log.write_event_on(&event(2, SegmentKind::Final, "hello"), day)
+            .unwrap();
+        log.write_event_on(&event(3, SegmentKind::Final, "world"), day)
+            .unwrap();
+
+        let path = dir.join("transcripts-2026-03-14.jsonl");
+        assert_eq!(log.current_path(), Some(path.as_path()));
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let events: Vec<TranscriptEvent> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("valid JSON line"))
+            .collect();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].seq, 2);
+        assert_eq!(events[0].segments[0].text, "hello");
+        assert_eq!(events[1].segments[0].kind, SegmentKind::Final);
+        assert_eq!(events[1].meta, event(3, SegmentKind::Final, "world").meta);
+    }
+
+    #[test]
+    fn rotates_past_size_limit_and_on_new_day() {
+        let dir = log_dir("rotate");
+        let mut log = JsonlTranscriptLog::new(JsonlLogConfig {
+            dir: dir.clone(),
+            file_stem: "audit".into(),
+            max_file_bytes: 400,
+            rotate_daily: true,
+        });
+        let day = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+        for seq in 0..6 {
+            log.write_event_on(&event(seq, SegmentKind::Final, "some dictated text"), day)
+                .unwrap();
+        }
+        let first = std::fs::metadata(dir.join("audit-2026-03-14.jsonl")).unwrap();
+        assert!(first.len() <= 400, "first file is {} bytes", first.len());
+        assert!(dir.join("audit-2026-03-14.1.jsonl").exists());
+
+        log.write_event_on(
+            &event(7, SegmentKind::Final, "tomorrow"),
+            day.succ_opt().unwrap(),
+        )
+        .unwrap();
+        let next_day = dir.join("audit-2026-03-15.jsonl");
+        assert_eq!(log.current_path(), Some(next_day.as_path()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn zero_size_limit_writes_a_file_per_line() {
+        let dir = log_dir("zero-limit");
+        let config = JsonlLogConfig {
+            dir: dir.clone(),
+            file_stem: "audit".into(),
+            max_file_bytes: 0,
+            rotate_daily: false,
+        };
+        let day = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+        let mut log = JsonlTranscriptLog::new(config.clone());
+        for seq in 0..3 {
+            log.write_event_on(&event(seq, SegmentKind::Final, "text"), day)
+                .unwrap();
+        }
+        // A restart skips the full files rather than spinning over them.
+        let mut restarted = JsonlTranscriptLog::new(config);
+        restarted
+            .write_event_on(&event(3, SegmentKind::Final, "text"), day)
+            .unwrap();
+        let files = std::fs::read_dir(&dir).unwrap().count();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(files, 4);
+        assert_eq!(
+            restarted.current_path(),
+            Some(dir.join("audit.3.jsonl").as_path())
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Consumers that persist or forward transcript events outside the app.
+//!
+//! A sink runs on its own thread, fed from one of the engine's broadcast
+//! channels, so slow I/O never stalls the pipeline. See
+//! `DictumEngine::attach_output_sink`.
+
+pub mod jsonl;
+
+use std::thread::JoinHandle;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{error, warn};
+
+use crate::{error::Result, ipc::events::TranscriptEvent};
+
+pub use jsonl::{JsonlLogConfig, JsonlTranscriptLog};
+
+/// Destination for transcript events.
+pub trait OutputSink: Send + 'static {
+    /// Handle one event. Sinks decide themselves which events they keep.
+    ///
+    /// # Errors
+    /// Returns an error if the event could not be written; the event is
+    /// dropped and the sink keeps receiving.
+    fn write_event(&mut self, event: &TranscriptEvent) -> Result<()>;
+}
+
+/// Drive `sink` from `rx` on a dedicated thread until the sender is dropped.
+pub fn spawn_output_sink<S: OutputSink>(
+    mut rx: broadcast::Receiver<TranscriptEvent>,
+    mut sink: S,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        match rx.blocking_recv() {
+            Ok(event) => {
+                if let Err(e) = sink.write_event(&event) {
+                    error!(seq = event.seq, error = %e, "output sink failed to write event");
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(skipped, "output sink lagged; events were dropped");
+            }
+            Err(RecvError::Closed) => break,
+        }
+    })
+}
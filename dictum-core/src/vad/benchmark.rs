@@ -0,0 +1,237 @@
+//! Scoring a VAD against labeled audio.
+//!
+//! Threshold tuning is only as good as the feedback behind it. This module
+//! runs a recording through a [`VoiceActivityDetector`] in pipeline-sized
+//! frames and compares each decision with hand-labeled speech regions,
+//! reporting frame precision/recall and how long each labeled onset took to
+//! be detected.
+//!
+//! Labels use Audacity's label-track export: one region per line,
+//! `start<TAB>end[<TAB>text]` in seconds. Every listed region is speech;
+//! everything else is silence.
+
+use serde::Serialize;
+
+use super::{VadDecision, VoiceActivityDetector};
+use crate::buffering::chunk::AudioChunk;
+use crate::error::{DictumError, Result};
+
+/// Frame length fed to the detector, matching the live pipeline's chunks.
+pub const BENCHMARK_FRAME_MS: u32 = 20;
+
+/// A labeled stretch of speech, in seconds from the start of the audio.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechRegion {
+    pub start_secs: f64,
+    pub end_secs: f64,
+}
+
+impl SpeechRegion {
+    fn contains(&self, secs: f64) -> bool {
+        (self.start_secs..self.end_secs).contains(&secs)
+    }
+}
+
+/// How well a detector's decisions matched the labels.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VadBenchmarkReport {
+    pub frame_ms: u32,
+    pub frames: usize,
+    /// Frames whose midpoint lies inside a labeled region.
+    pub labeled_speech_frames: usize,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    /// Share of speech decisions that were labeled speech; `None` when the
+    /// detector never reported speech.
+    pub precision: Option<f32>,
+    /// Share of labeled speech frames detected; `None` without labeled speech.
+    pub recall: Option<f32>,
+    pub onsets: usize,
+    /// Labeled regions with at least one frame detected as speech.
+    pub detected_onsets: usize,
+    /// Mean time from a labeled onset to the end of the first frame inside
+    /// the region reported as speech, over detected onsets.
+    pub mean_onset_latency_ms: Option<f64>,
+    pub max_onset_latency_ms: Option<f64>,
+}
+
+/// Parse Audacity-style label lines into speech regions.
+///
+/// Blank lines and lines starting with `#` are skipped.
+///
+/// # Errors
+/// `DictumError::Other` naming the line for a missing or non-numeric bound,
+/// or a region that ends before it starts.
+pub fn parse_labels(text: &str) -> Result<Vec<SpeechRegion>> {
+    let mut regions = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let bad = |why: &str| DictumError::Other(anyhow::anyhow!("label line {}: {why}", i + 1));
+        let mut fields = line.split_whitespace();
+        let mut bound = |name: &str| -> Result<f64> {
+            fields
+                .next()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .ok_or_else(|| bad(&format!("missing or invalid {name} time")))
+        };
+        let start_secs = bound("start")?;
+        let end_secs = bound("end")?;
+        if end_secs < start_secs {
+            return Err(bad("region ends before it starts"));
+        }
+        regions.push(SpeechRegion {
+            start_secs,
+            end_secs,
+        });
+    }
+    Ok(regions)
+}
+
+/// Run `audio` through `vad` in [`BENCHMARK_FRAME_MS`] frames and score the
+/// decisions against `speech`.
+///
+/// The detector is reset and warmed first. `Uncertain` decisions keep the
+/// previous state, as in the pipeline. A trailing partial frame is dropped.
+pub fn benchmark_vad(
+    vad: &mut dyn VoiceActivityDetector,
+    audio: &AudioChunk,
+    speech: &[SpeechRegion],
+) -> VadBenchmarkReport {
+    vad.reset();
+    vad.warm();
+
+    let rate = audio.sample_rate.max(1) as f64;
+    let frame_len = ((audio.sample_rate as usize * BENCHMARK_FRAME_MS as usize) / 1000).max(1);
+    let mut report = VadBenchmarkReport {
+        frame_ms: BENCHMARK_FRAME_MS,
+        frames: 0,
+        labeled_speech_frames: 0,
+        true_positives: 0,
+        false_positives: 0,
+        false_negatives: 0,
+        precision: None,
+        recall: None,
+        onsets: speech.len(),
+        detected_onsets: 0,
+        mean_onset_latency_ms: None,
+        max_onset_latency_ms: None,
+    };
+    // First detection time (frame end, seconds) per labeled region.
+    let mut detected_at: Vec<Option<f64>> = vec![None; speech.len()];
+    let mut in_speech = false;
+
+    for (i, frame) in audio.samples.chunks_exact(frame_len).enumerate() {
+        let decision = vad
+            .classify(&AudioChunk::new(frame.to_vec(), audio.sample_rate))
+            .resolve(in_speech);
+        in_speech = decision == VadDecision::Speech;
+
+        let start = (i * frame_len) as f64 / rate;
+        let end = ((i + 1) * frame_len) as f64 / rate;
+        let region = speech.iter().position(|r| r.contains((start + end) / 2.0));
+        report.frames += 1;
+        match (region.is_some(), in_speech) {
+            (true, true) => report.true_positives += 1,
+            (false, true) => report.false_positives += 1,
+            (true, false) => report.false_negatives += 1,
+            (false, false) => {}
+        }
+        if let Some(r) = region {
+            report.labeled_speech_frames += 1;
+            if in_speech && detected_at[r].is_none() {
+                detected_at[r] = Some(end);
+            }
+        }
+    }
+
+    let detected = report.true_positives + report.false_positives;
+    report.precision = (detected > 0).then(|| report.true_positives as f32 / detected as f32);
+    report.recall = (report.labeled_speech_frames > 0)
+        .then(|| report.true_positives as f32 / report.labeled_speech_frames as f32);
+
+    let latencies: Vec<f64> = speech
+        .iter()
+        .zip(&detected_at)
+        .filter_map(|(region, at)| at.map(|t| ((t - region.start_secs) * 1000.0).max(0.0)))
+        .collect();
+    report.detected_onsets = latencies.len();
+    if !latencies.is_empty() {
+        report.mean_onset_latency_ms = Some(latencies.iter().sum::<f64>() / latencies.len() as f64);
+        report.max_onset_latency_ms = latencies.iter().copied().reduce(f64::max);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vad::energy::EnergyVad;
+
+    /// Four cycles of 0.5 s of 300 Hz tone then 0.5 s of silence at 16 kHz,
+    /// with matching labels.
+    fn tone_fixture() -> (AudioChunk, Vec<SpeechRegion>) {
+        let mut samples = Vec::new();
+        let mut labels = String::new();
+        for cycle in 0..4 {
+            samples.extend(
+                (0..8_000).map(|i| {
+                    0.2 * (i as f32 * 2.0 * std::f32::consts::PI * 300.0 / 16_000.0).sin()
+                }),
+            );
+            samples.extend([0.0; 8_000]);
+            labels.push_str(&format!("{}\t{}\ttone\n", cycle as f64, cycle as f64 + 0.5));
+        }
+        let regions = parse_labels(&labels).expect("labels parse");
+        (AudioChunk::new(samples, 16_000), regions)
+    }
+
+    #[test]
+    fn energy_vad_scores_high_recall_on_alternating_tone() {
+        let (audio, regions) = tone_fixture();
+        let mut vad = EnergyVad::new(0.02, 2);
+        let report = benchmark_vad(&mut vad, &audio, &regions);
+
+        assert_eq!(report.frames, 200);
+        assert_eq!(report.labeled_speech_frames, 100);
+        assert_eq!(report.recall, Some(1.0));
+        // Only the hangover frames after each tone count against precision.
+        assert!(report.precision.expect("speech detected") > 0.9);
+        assert_eq!(report.detected_onsets, 4);
+        // Each onset is caught by its first frame.
+        let latency = report.mean_onset_latency_ms.expect("onsets detected");
+        assert!((latency - 20.0).abs() < 1e-6, "{latency}");
+    }
+
+    #[test]
+    fn a_threshold_above_the_tone_detects_nothing() {
+        let (audio, regions) = tone_fixture();
+        let mut vad = EnergyVad::new(0.5, 0);
+        let report = benchmark_vad(&mut vad, &audio, &regions);
+
+        assert_eq!(report.recall, Some(0.0));
+        assert_eq!(report.precision, None);
+        assert_eq!(report.detected_onsets, 0);
+        assert_eq!(report.mean_onset_latency_ms, None);
+    }
+
+    #[test]
+    fn malformed_labels_are_rejected_with_the_line() {
+        assert_eq!(
+            parse_labels("# comment\n\n0.5 1.25 hello\n").expect("valid"),
+            vec![SpeechRegion {
+                start_secs: 0.5,
+                end_secs: 1.25
+            }]
+        );
+        let err = parse_labels("0.0\t1.0\n2.0\tsoon\n").unwrap_err();
+        assert!(err.to_string().contains("label line 2"), "{err}");
+        assert!(parse_labels("3.0\t1.0\n").is_err());
+    }
+}
@@ -6,22 +6,86 @@
 //! 2. If RMS ≥ `threshold` → emit `Speech`, reset hangover counter.
 //! 3. If RMS < `threshold` and hangover counter > 0 → emit `Speech`,
 //!    decrement counter (prevents clipping syllable endings).
-//! 4. Otherwise → emit `Silence`.
+//! 4. If an uncertainty band is set and RMS falls inside it → emit
+//!    `Uncertain`.
+//! 5. Otherwise → emit `Silence`.
+//!
+//! With [`EnergyGate::NoiseFloor`] the level compared in steps 2–4 is the
+//! larger of `threshold` and the tracked noise floor plus a margin, so a
+//! fan or air conditioner slowly getting louder does not open an utterance.
+//! The floor follows non-speech frames, and creeps up at a bounded rate
+//! under speech frames too, so noise that steps up past the margin (a fan
+//! switching on) is not speech forever.
+
+use serde::{Deserialize, Serialize};
 
-use super::{VadDecision, VoiceActivityDetector};
+use super::{uncertain_floor, LiveThreshold, VadDecision, VoiceActivityDetector};
 use crate::buffering::chunk::AudioChunk;
 
+/// Tunables for [`EnergyGate::NoiseFloor`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseFloorConfig {
+    /// Weight of each new non-speech frame in the floor's moving average,
+    /// in (0, 1]. Default: 0.05 (≈ 20 frames of memory).
+    pub alpha: f32,
+    /// How far above the floor a frame must be to count as speech (dB).
+    /// Default: 6.0.
+    pub margin_db: f32,
+    /// Fastest the floor rises toward the level of speech frames, in dB per
+    /// second of audio. Speech has pauses that pull the floor back down;
+    /// steady noise does not. Default: 2.0.
+    pub speech_rise_db_per_sec: f32,
+}
+
+impl Default for NoiseFloorConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.05,
+            margin_db: 6.0,
+            speech_rise_db_per_sec: 2.0,
+        }
+    }
+}
+
+impl NoiseFloorConfig {
+    /// `margin_db` as an amplitude ratio.
+    fn margin(&self) -> f32 {
+        10f32.powf(self.margin_db / 20.0)
+    }
+}
+
+/// What level [`EnergyVad`] compares each frame's RMS against.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnergyGate {
+    /// The fixed `threshold` alone.
+    #[default]
+    Static,
+    /// `threshold` or the tracked noise floor plus a margin, whichever is
+    /// higher.
+    NoiseFloor(NoiseFloorConfig),
+}
+
 /// A simple energy-based voice activity detector.
 #[derive(Debug, Clone)]
 pub struct EnergyVad {
     /// RMS amplitude threshold. Frames above this are considered speech.
-    /// Typical range: 0.01–0.05 for a quiet microphone.
-    threshold: f32,
+    /// Typical range: 0.01–0.05 for a quiet microphone. Read on every frame.
+    threshold: LiveThreshold,
     /// How many consecutive below-threshold frames to still emit `Speech`
     /// after real speech ends (prevents clipping word endings).
     hangover_frames: u32,
     /// Current hangover countdown.
     hangover_counter: u32,
+    /// Fraction of the threshold below it within which a frame is
+    /// `Uncertain`.
+    uncertain_band: Option<f32>,
+    gate: EnergyGate,
+    /// Moving average of the RMS of recent non-speech frames, raised
+    /// slowly under speech; `None` until the first frame is seen or when
+    /// the gate is static.
+    noise_floor: Option<f32>,
 }
 
 impl EnergyVad {
@@ -34,12 +98,76 @@ impl EnergyVad {
     ///   Default: `8` (≈ 160 ms at a 20 ms frame stride).
     pub fn new(threshold: f32, hangover_frames: u32) -> Self {
         Self {
-            threshold,
+            threshold: LiveThreshold::new(threshold),
             hangover_frames,
             hangover_counter: 0,
+            uncertain_band: None,
+            gate: EnergyGate::Static,
+            noise_floor: None,
+        }
+    }
+
+    /// Report frames whose RMS is within `band` (a fraction of the
+    /// threshold) below the threshold as `Uncertain` instead of `Silence`.
+    pub fn with_uncertain_band(mut self, band: f32) -> Self {
+        self.uncertain_band = Some(band);
+        self
+    }
+
+    /// Read the threshold from `threshold` instead, so whoever holds a
+    /// clone can change it while this detector runs.
+    pub fn with_live_threshold(mut self, threshold: LiveThreshold) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Compare frames against `gate` instead of the static threshold.
+    pub fn with_gate(mut self, gate: EnergyGate) -> Self {
+        self.gate = gate;
+        self
+    }
+
+    /// Current noise floor estimate (RMS), when the gate tracks one and
+    /// at least one frame has been seen.
+    pub fn noise_floor(&self) -> Option<f32> {
+        self.noise_floor
+    }
+
+    /// Level a frame has to reach to count as speech.
+    fn speech_level(&self) -> f32 {
+        let threshold = self.threshold.get();
+        match (self.gate, self.noise_floor) {
+            (EnergyGate::NoiseFloor(config), Some(floor)) => threshold.max(floor * config.margin()),
+            _ => threshold,
+        }
+    }
+
+    /// Fold a non-speech frame's RMS into the noise floor.
+    fn track_noise(&mut self, rms: f32) {
+        if let EnergyGate::NoiseFloor(config) = self.gate {
+            self.noise_floor = Some(match self.noise_floor {
+                Some(floor) => floor + config.alpha * (rms - floor),
+                None => rms,
+            });
         }
     }
 
+    /// Let the floor rise toward a speech frame's RMS, by at most
+    /// `speech_rise_db_per_sec` over the frame's duration. Never lowers it.
+    /// Without a floor yet, as in a room louder than `threshold` from the
+    /// start, it rises from the one that leaves the threshold in charge.
+    fn track_noise_under_speech(&mut self, rms: f32, chunk: &AudioChunk) {
+        let EnergyGate::NoiseFloor(config) = self.gate else {
+            return;
+        };
+        let floor = self
+            .noise_floor
+            .unwrap_or_else(|| self.threshold.get() / config.margin());
+        let seconds = chunk.samples.len() as f32 / chunk.sample_rate.max(1) as f32;
+        let max_rise = 10f32.powf(config.speech_rise_db_per_sec * seconds / 20.0);
+        self.noise_floor = Some(floor.max(rms.min(floor * max_rise)));
+    }
+
     /// Compute the root-mean-square of a sample slice.
     fn rms(samples: &[f32]) -> f32 {
         if samples.is_empty() {
@@ -59,23 +187,39 @@ impl Default for EnergyVad {
 impl VoiceActivityDetector for EnergyVad {
     fn classify(&mut self, chunk: &AudioChunk) -> VadDecision {
         let rms = Self::rms(&chunk.samples);
+        let threshold = self.speech_level();
 
-        if rms >= self.threshold {
+        if rms >= threshold {
             // Active speech detected — reset hangover
             self.hangover_counter = self.hangover_frames;
-            VadDecision::Speech
-        } else if self.hangover_counter > 0 {
-            // Within hangover window — still report speech
+            self.track_noise_under_speech(rms, chunk);
+            return VadDecision::Speech;
+        }
+        // Hangover frames are the tail of speech, not background noise.
+        if self.hangover_counter > 0 {
             self.hangover_counter -= 1;
-            VadDecision::Speech
+            return VadDecision::Speech;
+        }
+        self.track_noise(rms);
+        if self
+            .uncertain_band
+            .is_some_and(|band| rms >= uncertain_floor(threshold, band))
+        {
+            VadDecision::Uncertain
         } else {
             VadDecision::Silence
         }
     }
 
+    /// Clears the hangover but keeps the noise floor: the room does not
+    /// change between utterances.
     fn reset(&mut self) {
         self.hangover_counter = 0;
     }
+
+    fn noise_floor(&self) -> Option<f32> {
+        self.noise_floor
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +274,105 @@ mod tests {
         assert_eq!(vad.classify(&silent_chunk(160)), VadDecision::Silence);
     }
 
+    #[test]
+    fn frames_just_below_threshold_are_uncertain_within_the_band() {
+        let mut vad = EnergyVad::new(0.02, 0).with_uncertain_band(0.25);
+        assert_eq!(vad.classify(&loud_chunk(0.03, 160)), VadDecision::Speech);
+        assert_eq!(
+            vad.classify(&loud_chunk(0.017, 160)),
+            VadDecision::Uncertain
+        );
+        assert_eq!(vad.classify(&loud_chunk(0.012, 160)), VadDecision::Silence);
+        assert_eq!(vad.classify(&silent_chunk(160)), VadDecision::Silence);
+    }
+
+    #[test]
+    fn live_threshold_changes_apply_to_the_next_frame() {
+        let threshold = LiveThreshold::new(0.02);
+        let mut vad = EnergyVad::new(0.5, 0).with_live_threshold(threshold.clone());
+        assert_eq!(vad.classify(&loud_chunk(0.05, 160)), VadDecision::Speech);
+
+        threshold.set(0.1);
+        assert_eq!(vad.classify(&loud_chunk(0.05, 160)), VadDecision::Silence);
+        threshold.set(0.04);
+        assert_eq!(vad.classify(&loud_chunk(0.05, 160)), VadDecision::Speech);
+    }
+
+    #[test]
+    fn slowly_rising_noise_is_tracked_instead_of_opening_speech() {
+        // Background noise creeping from 0.01 to 0.05 RMS over ~8 s of
+        // 20 ms frames, well past the static 0.02 threshold.
+        let noise = |i: usize| loud_chunk(0.01 + 0.04 * i as f32 / 400.0, 320);
+        let gate = EnergyGate::NoiseFloor(NoiseFloorConfig::default());
+
+        let mut adaptive = EnergyVad::new(0.02, 0).with_gate(gate);
+        for i in 0..=400 {
+            assert_ne!(
+                adaptive.classify(&noise(i)),
+                VadDecision::Speech,
+                "frame {i} flagged as speech"
+            );
+        }
+        let floor = adaptive.noise_floor().expect("floor tracked");
+        assert!((0.04..=0.05).contains(&floor), "floor={floor}");
+        // Speech well above the raised floor still gets through.
+        assert_eq!(
+            adaptive.classify(&loud_chunk(0.2, 320)),
+            VadDecision::Speech
+        );
+
+        let mut fixed = EnergyVad::new(0.02, 0);
+        assert!((0..=400).any(|i| fixed.classify(&noise(i)) == VadDecision::Speech));
+        assert_eq!(fixed.noise_floor(), None);
+    }
+
+    #[test]
+    fn noise_stepping_up_is_speech_only_until_the_floor_catches_up() {
+        // A fan switching on: 0.01 RMS, then 0.1 (+20 dB, past the margin)
+        // for good, in 20 ms frames.
+        let gate = EnergyGate::NoiseFloor(NoiseFloorConfig::default());
+        let mut vad = EnergyVad::new(0.02, 0).with_gate(gate);
+        for _ in 0..50 {
+            vad.classify(&loud_chunk(0.01, 320));
+        }
+        assert_eq!(vad.classify(&loud_chunk(0.1, 320)), VadDecision::Speech);
+
+        // 2 dB/s covers the 14 dB to bring 0.1 within the 6 dB margin in
+        // about 7 s.
+        let speech_frames = (0..1_000)
+            .take_while(|_| vad.classify(&loud_chunk(0.1, 320)) == VadDecision::Speech)
+            .count();
+        assert!((300..=400).contains(&speech_frames), "{speech_frames}");
+        for _ in 0..100 {
+            assert_ne!(vad.classify(&loud_chunk(0.1, 320)), VadDecision::Speech);
+        }
+        let floor = vad.noise_floor().unwrap();
+        assert!((0.08..=0.1).contains(&floor), "floor={floor}");
+
+        // A voice over the fan still counts, and one second of it barely
+        // moves the floor.
+        for _ in 0..50 {
+            assert_eq!(vad.classify(&loud_chunk(0.5, 320)), VadDecision::Speech);
+        }
+        let raised = vad.noise_floor().unwrap();
+        assert!(raised <= floor * 1.3, "floor={floor} raised={raised}");
+    }
+
+    #[test]
+    fn noise_above_the_threshold_from_the_start_becomes_silence() {
+        // A loud room from the first frame: 0.1 RMS, 14 dB over 0.02 plus
+        // the 6 dB margin, so about 7 s at 2 dB/s.
+        let gate = EnergyGate::NoiseFloor(NoiseFloorConfig::default());
+        let mut vad = EnergyVad::new(0.02, 0).with_gate(gate);
+        let speech_frames = (0..1_000)
+            .take_while(|_| vad.classify(&loud_chunk(0.1, 320)) == VadDecision::Speech)
+            .count();
+        assert!((300..=400).contains(&speech_frames), "{speech_frames}");
+        for _ in 0..100 {
+            assert_eq!(vad.classify(&loud_chunk(0.1, 320)), VadDecision::Silence);
+        }
+    }
+
     #[test]
     fn empty_chunk_is_silence() {
         let mut vad = EnergyVad::default();
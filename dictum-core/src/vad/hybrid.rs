@@ -0,0 +1,156 @@
+//! Two-stage VAD: a cheap gate in front of an accurate confirmer.
+//!
+//! The intended pairing is `EnergyVad` as the gate and `SileroVad` as the
+//! confirmer. The confirmer only runs on frames whose outcome it can still
+//! change, so during clear silence (AND) the neural model is skipped
+//! entirely.
+
+use serde::{Deserialize, Serialize};
+
+use super::{VadDecision, VoiceActivityDetector};
+use crate::buffering::chunk::AudioChunk;
+
+/// How the two inner decisions are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HybridPolicy {
+    /// Speech only when both detectors say so. The confirmer runs only on
+    /// frames the gate passes.
+    #[default]
+    And,
+    /// Speech when either detector says so. The confirmer runs only on
+    /// frames the gate rejects.
+    Or,
+}
+
+/// [`VoiceActivityDetector`] composed of a gate and a confirmer.
+pub struct HybridVad {
+    gate: Box<dyn VoiceActivityDetector>,
+    confirm: Box<dyn VoiceActivityDetector>,
+    policy: HybridPolicy,
+}
+
+impl HybridVad {
+    pub fn new(
+        gate: Box<dyn VoiceActivityDetector>,
+        confirm: Box<dyn VoiceActivityDetector>,
+        policy: HybridPolicy,
+    ) -> Self {
+        Self {
+            gate,
+            confirm,
+            policy,
+        }
+    }
+
+    pub fn policy(&self) -> HybridPolicy {
+        self.policy
+    }
+}
+
+impl VoiceActivityDetector for HybridVad {
+    fn classify(&mut self, chunk: &AudioChunk) -> VadDecision {
+        match (self.policy, self.gate.classify(chunk)) {
+            (HybridPolicy::And, VadDecision::Silence) => VadDecision::Silence,
+            (HybridPolicy::Or, VadDecision::Speech) => VadDecision::Speech,
+            // An unsure gate leaves the combination unsure unless the
+            // confirmer settles it the way the policy lets it.
+            (HybridPolicy::And, VadDecision::Uncertain) => match self.confirm.classify(chunk) {
+                VadDecision::Silence => VadDecision::Silence,
+                _ => VadDecision::Uncertain,
+            },
+            (HybridPolicy::Or, VadDecision::Uncertain) => match self.confirm.classify(chunk) {
+                VadDecision::Speech => VadDecision::Speech,
+                _ => VadDecision::Uncertain,
+            },
+            _ => self.confirm.classify(chunk),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.gate.reset();
+        self.confirm.reset();
+    }
+
+    fn warm(&mut self) {
+        self.gate.warm();
+        self.confirm.warm();
+    }
+
+    fn noise_floor(&self) -> Option<f32> {
+        self.gate
+            .noise_floor()
+            .or_else(|| self.confirm.noise_floor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HybridPolicy, HybridVad};
+    use crate::buffering::chunk::AudioChunk;
+    use crate::vad::{VadDecision, VoiceActivityDetector};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    const S: VadDecision = VadDecision::Speech;
+    const N: VadDecision = VadDecision::Silence;
+
+    /// Answers from a fixed script indexed by the shared frame number, so
+    /// skipped calls do not shift later answers.
+    struct Scripted {
+        script: [VadDecision; 4],
+        frame: Arc<AtomicUsize>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl VoiceActivityDetector for Scripted {
+        fn classify(&mut self, _chunk: &AudioChunk) -> VadDecision {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.script[self.frame.load(Ordering::Relaxed)]
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    /// Four frames covering every (gate, confirm) pair. Returns the combined
+    /// decisions and how often the confirmer ran.
+    fn run(policy: HybridPolicy) -> (Vec<VadDecision>, usize) {
+        let frame = Arc::new(AtomicUsize::new(0));
+        let confirm_calls = Arc::new(AtomicUsize::new(0));
+        let gate = Scripted {
+            script: [N, N, S, S],
+            frame: Arc::clone(&frame),
+            calls: Arc::new(AtomicUsize::new(0)),
+        };
+        let confirm = Scripted {
+            script: [N, S, N, S],
+            frame: Arc::clone(&frame),
+            calls: Arc::clone(&confirm_calls),
+        };
+        let mut vad = HybridVad::new(Box::new(gate), Box::new(confirm), policy);
+        let chunk = AudioChunk::new(vec![0.0; 320], 16_000);
+        let decisions = (0..4)
+            .map(|i| {
+                frame.store(i, Ordering::Relaxed);
+                vad.classify(&chunk)
+            })
+            .collect();
+        (decisions, confirm_calls.load(Ordering::Relaxed))
+    }
+
+    #[test]
+    fn and_policy_needs_both_and_skips_confirm_on_gate_silence() {
+        let (decisions, confirm_calls) = run(HybridPolicy::And);
+        assert_eq!(decisions, vec![N, N, N, S]);
+        assert_eq!(confirm_calls, 2);
+    }
+
+    #[test]
+    fn or_policy_needs_either_and_skips_confirm_on_gate_speech() {
+        let (decisions, confirm_calls) = run(HybridPolicy::Or);
+        assert_eq!(decisions, vec![N, S, S, S]);
+        assert_eq!(confirm_calls, 2);
+    }
+}
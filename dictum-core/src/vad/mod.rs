@@ -2,9 +2,11 @@
 //!
 //! The `VoiceActivityDetector` trait is the primary extensibility point:
 //! swap in `EnergyVad` (default), SileroVad (P1-08), or any future neural VAD
-//! without touching the pipeline.
+//! without touching the pipeline. `HybridVad` composes two detectors.
 
+pub mod benchmark;
 pub mod energy;
+pub mod hybrid;
 
 #[cfg(feature = "onnx")]
 pub mod silero;
@@ -12,6 +14,11 @@ pub mod silero;
 #[cfg(feature = "onnx")]
 pub use silero::SileroVad;
 
+pub use hybrid::{HybridPolicy, HybridVad};
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
 use crate::buffering::chunk::AudioChunk;
 
 /// Whether a given audio frame contains speech or silence.
@@ -21,12 +28,52 @@ pub enum VadDecision {
     Speech,
     /// The frame is silent (or below threshold, including hangover period).
     Silence,
+    /// The frame scored just below threshold, inside the detector's
+    /// uncertainty band. The pipeline keeps whatever state it was in.
+    Uncertain,
 }
 
 impl VadDecision {
     pub fn is_speech(self) -> bool {
         self == VadDecision::Speech
     }
+
+    /// Resolve `Uncertain` to the current state (`Speech` while an
+    /// utterance is open, `Silence` otherwise); definite decisions pass
+    /// through unchanged.
+    pub fn resolve(self, in_speech: bool) -> VadDecision {
+        match self {
+            VadDecision::Uncertain if in_speech => VadDecision::Speech,
+            VadDecision::Uncertain => VadDecision::Silence,
+            decided => decided,
+        }
+    }
+}
+
+/// Lower edge of an uncertainty band `band` (a fraction of `threshold`)
+/// below `threshold`; scores in `[floor, threshold)` are `Uncertain`.
+pub(crate) fn uncertain_floor(threshold: f32, band: f32) -> f32 {
+    threshold * (1.0 - band.clamp(0.0, 1.0))
+}
+
+/// A detector threshold that can change while the detector runs, e.g. from
+/// a settings slider. Clones share one value, stored as `f32` bits, and
+/// detectors read it on every `classify`.
+#[derive(Debug, Clone)]
+pub struct LiveThreshold(Arc<AtomicU32>);
+
+impl LiveThreshold {
+    pub fn new(value: f32) -> Self {
+        Self(Arc::new(AtomicU32::new(value.to_bits())))
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, value: f32) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
 }
 
 /// Trait for all VAD implementations.
@@ -41,4 +88,16 @@ pub trait VoiceActivityDetector: Send + 'static {
 
     /// Reset any internal state (e.g. hangover counters, hidden states).
     fn reset(&mut self);
+
+    /// Bring the detector to a steady "listening to silence" state so the
+    /// first real speech frame after a start or reset classifies correctly.
+    ///
+    /// Stateless detectors can rely on the default no-op.
+    fn warm(&mut self) {}
+
+    /// Estimated background noise level (RMS), for detectors that track
+    /// one. Default: `None`.
+    fn noise_floor(&self) -> Option<f32> {
+        None
+    }
 }
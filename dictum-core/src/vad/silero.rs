@@ -34,9 +34,9 @@ use ndarray::{Array1, Array2, Array3};
 use ort::session::builder::SessionBuilder;
 use ort::session::SessionInputValue;
 use ort::value::Value;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
-use super::{VadDecision, VoiceActivityDetector};
+use super::{uncertain_floor, LiveThreshold, VadDecision, VoiceActivityDetector};
 use crate::inference::onnx::default_models_dir;
 use crate::{
     buffering::chunk::AudioChunk,
@@ -49,6 +49,10 @@ const WINDOW: usize = 512;
 const LSTM_SIZE: usize = 128;
 /// v5 GRU state size: 2 layers × 1 batch × 128 units = 256 floats.
 const GRU_STATE_SIZE: usize = 256;
+/// Default number of silence windows used to prime the recurrent state (8 × 32 ms = 256 ms).
+const DEFAULT_PRIME_WINDOWS: usize = 8;
+/// Peak amplitude of the dither fed during priming; digital zero is not a realistic room.
+const PRIME_DITHER_AMPLITUDE: f32 = 1.0e-4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SileroIoMode {
@@ -79,8 +83,13 @@ pub struct SileroVad {
     h: Vec<f32>,     // [2, 1, 64] row-major (LSTM h)
     c: Vec<f32>,     // [2, 1, 64] row-major (LSTM c)
     state: Vec<f32>, // [2, 1, 64] row-major (GRU state)
-    threshold: f32,
+    /// Speech probability threshold, read on every `classify`.
+    threshold: LiveThreshold,
+    /// Fraction of the threshold below it within which a window is
+    /// uncertain.
+    uncertain_band: Option<f32>,
     input_buf: Vec<f32>,
+    prime_windows: usize,
 }
 
 impl SileroVad {
@@ -169,11 +178,34 @@ impl SileroVad {
             h: vec![0.0; LSTM_SIZE],
             c: vec![0.0; LSTM_SIZE],
             state: vec![0.0; GRU_STATE_SIZE],
-            threshold,
+            threshold: LiveThreshold::new(threshold),
+            uncertain_band: None,
             input_buf: Vec::new(),
+            prime_windows: DEFAULT_PRIME_WINDOWS,
         })
     }
 
+    /// Set how many 32 ms silence windows [`VoiceActivityDetector::warm`] feeds
+    /// through the model. `0` disables priming and leaves the state cold.
+    pub fn with_prime_windows(mut self, windows: usize) -> Self {
+        self.prime_windows = windows;
+        self
+    }
+
+    /// Report chunks whose best window probability is within `band` (a
+    /// fraction of the threshold) below the threshold as `Uncertain`.
+    pub fn with_uncertain_band(mut self, band: f32) -> Self {
+        self.uncertain_band = Some(band);
+        self
+    }
+
+    /// Read the threshold from `threshold` instead, so whoever holds a
+    /// clone can change it while this detector runs.
+    pub fn with_live_threshold(mut self, threshold: LiveThreshold) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
     /// Default path for the Silero VAD model file.
     pub fn default_model_path() -> PathBuf {
         default_models_dir().join("silero_vad.onnx")
@@ -288,6 +320,19 @@ impl SileroVad {
     }
 }
 
+/// Low-level deterministic dither standing in for room silence.
+fn priming_window(index: usize) -> Vec<f32> {
+    let mut seed = 0x9E37_79B9u32 ^ (index as u32).wrapping_mul(0x85EB_CA6B);
+    (0..WINDOW)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            ((seed as f32 / u32::MAX as f32) * 2.0 - 1.0) * PRIME_DITHER_AMPLITUDE
+        })
+        .collect()
+}
+
 fn resolve_name(candidates: &[String], preferred: &[&str]) -> Option<String> {
     preferred.iter().find_map(|needle| {
         candidates
@@ -301,16 +346,24 @@ impl VoiceActivityDetector for SileroVad {
     fn classify(&mut self, chunk: &AudioChunk) -> VadDecision {
         self.input_buf.extend_from_slice(&chunk.samples);
 
+        let threshold = self.threshold.get();
+        let floor = self
+            .uncertain_band
+            .map(|band| uncertain_floor(threshold, band));
         let mut any_speech = false;
+        let mut any_uncertain = false;
 
         while self.input_buf.len() >= WINDOW {
             let window: Vec<f32> = self.input_buf[..WINDOW].to_vec();
             self.input_buf.drain(..WINDOW);
 
             match self.run_window(&window) {
-                Ok(prob) if prob >= self.threshold => {
+                Ok(prob) if prob >= threshold => {
                     any_speech = true;
                 }
+                Ok(prob) if floor.is_some_and(|floor| prob >= floor) => {
+                    any_uncertain = true;
+                }
                 Ok(_) => {}
                 Err(e) => {
                     error!("SileroVad inference error: {e}");
@@ -320,6 +373,8 @@ impl VoiceActivityDetector for SileroVad {
 
         if any_speech {
             VadDecision::Speech
+        } else if any_uncertain {
+            VadDecision::Uncertain
         } else {
             VadDecision::Silence
         }
@@ -331,4 +386,57 @@ impl VoiceActivityDetector for SileroVad {
         self.state.iter_mut().for_each(|v| *v = 0.0);
         self.input_buf.clear();
     }
+
+    fn warm(&mut self) {
+        if self.io_mode == SileroIoMode::Stateless || self.prime_windows == 0 {
+            return;
+        }
+        self.reset();
+        for index in 0..self.prime_windows {
+            if let Err(e) = self.run_window(&priming_window(index)) {
+                warn!("SileroVad priming failed after {index} windows: {e}");
+                self.reset();
+                return;
+            }
+        }
+        debug!(windows = self.prime_windows, "SileroVad state primed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priming_window_is_quiet_and_deterministic() {
+        let a = priming_window(3);
+        assert_eq!(a.len(), WINDOW);
+        assert!(a.iter().all(|s| s.abs() <= PRIME_DITHER_AMPLITUDE));
+        assert!(a.iter().any(|s| *s != 0.0));
+        assert_eq!(a, priming_window(3));
+        assert_ne!(a, priming_window(4));
+    }
+
+    #[test]
+    #[ignore = "requires silero_vad.onnx in the models directory"]
+    fn warm_primes_recurrent_state_and_leaves_buffer_empty() {
+        let mut vad = SileroVad::new(SileroVad::default_model_path(), 0.5).expect("load silero");
+        vad.input_buf.extend_from_slice(&[0.0; 100]);
+        vad.warm();
+
+        assert!(vad.input_buf.is_empty());
+        let state_is_warm = match vad.io_mode {
+            SileroIoMode::StatefulLstm => vad.h.iter().chain(&vad.c).any(|v| *v != 0.0),
+            SileroIoMode::StatefulGru => vad.state.iter().any(|v| *v != 0.0),
+            SileroIoMode::Stateless => true,
+        };
+        assert!(
+            state_is_warm,
+            "priming should move the recurrent state off zero"
+        );
+
+        // A primed detector must still report silence on silence.
+        let silence = AudioChunk::new(priming_window(99), 16_000);
+        assert_eq!(vad.classify(&silence), VadDecision::Silence);
+    }
 }
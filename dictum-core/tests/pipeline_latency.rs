@@ -8,7 +8,7 @@ use std::time::{Duration, Instant};
 use dictum_core::buffering::{chunk::AudioChunk, create_audio_ring, Producer};
 use dictum_core::engine::{pipeline, EngineConfig};
 use dictum_core::ipc::events::{EngineStatus, SegmentKind, TranscriptEvent, TranscriptSegment};
-use dictum_core::vad::{VadDecision, VoiceActivityDetector};
+use dictum_core::vad::{LiveThreshold, VadDecision, VoiceActivityDetector};
 use dictum_core::{DictumError, ModelHandle, SpeechModel};
 use parking_lot::Mutex;
 use tokio::sync::broadcast;
@@ -55,6 +55,10 @@ impl SpeechModel for DelayModel {
                 SegmentKind::Final
             },
             confidence: None,
+            start_ms: None,
+            end_ms: None,
+            word_timings: Vec::new(),
+            language: None,
         }])
     }
 
@@ -98,18 +102,24 @@ fn first_transcript_latency_under_500ms() {
     config.max_speech_samples = 16_000;
 
     let ctx = pipeline::PipelineContext {
+        vad_threshold: LiveThreshold::new(config.vad_threshold),
         config,
         model: ModelHandle::new(DelayModel::new(Duration::from_millis(20))),
         vad: Box::new(AlwaysSpeechVad),
         consumer,
         running: Arc::clone(&running),
         transcript_tx,
+        partial_tx: broadcast::channel(16).0,
+        final_tx: broadcast::channel(16).0,
+        final_fanout: Default::default(),
         status_tx,
         activity_tx,
+        boundary_tx: broadcast::channel(8).0,
         status: Arc::new(Mutex::new(EngineStatus::Idle)),
         seq,
         capture_sample_rate: 16_000,
         diagnostics: Arc::new(pipeline::PipelineDiagnostics::default()),
+        reset_requested: Arc::new(AtomicBool::new(false)),
     };
 
     let start = Instant::now();
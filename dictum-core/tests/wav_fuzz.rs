@@ -0,0 +1,65 @@
+//! Property tests for the WAV parser behind `transcribe_file`: arbitrary and
+//! mutated input must come back as `Ok` or `InvalidWav`, never a panic.
+
+use dictum_core::{audio::wav::parse_wav_mono, error::DictumError};
+use proptest::prelude::*;
+
+/// A valid mono 16-bit header plus `data`, as the seed for mutations.
+fn seed_wav(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    for field in [16u32, 0x0001_0001, 16_000, 32_000, 0x0010_0002] {
+        out.extend_from_slice(&field.to_le_bytes());
+    }
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    out
+}
+
+fn assert_graceful(bytes: &[u8]) {
+    match parse_wav_mono(bytes) {
+        Ok(chunk) => {
+            assert!(chunk.sample_rate > 0);
+            assert!(chunk.samples.iter().all(|s| s.is_finite()));
+        }
+        Err(DictumError::InvalidWav(_)) => {}
+        Err(other) => panic!("unexpected error kind: {other:?}"),
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2_000))]
+
+    #[test]
+    fn random_bytes_never_panic(bytes in proptest::collection::vec(any::<u8>(), 0..256)) {
+        assert_graceful(&bytes);
+    }
+
+    #[test]
+    fn random_chunks_after_a_riff_header_never_panic(
+        body in proptest::collection::vec(any::<u8>(), 0..256),
+    ) {
+        let mut bytes = b"RIFF\xff\xff\xff\xffWAVE".to_vec();
+        bytes.extend_from_slice(&body);
+        assert_graceful(&bytes);
+    }
+
+    /// Overwrite header bytes (sizes, tags, channel counts, alignment) of an
+    /// otherwise valid file and cut it at a random length.
+    #[test]
+    fn mutated_headers_never_panic(
+        data in proptest::collection::vec(any::<u8>(), 0..64),
+        edits in proptest::collection::vec((0usize..44, any::<u8>()), 1..8),
+        cut in any::<prop::sample::Index>(),
+    ) {
+        let mut bytes = seed_wav(&data);
+        for (at, value) in edits {
+            bytes[at] = value;
+        }
+        let len = cut.index(bytes.len() + 1);
+        assert_graceful(&bytes[..len]);
+    }
+}
@@ -7,6 +7,7 @@ import { useEngine } from "@/hooks/useEngine";
 import { useTranscript } from "@/hooks/useTranscript";
 import type {
   AppUpdateInfo,
+  DeviceInfo,
   DictionaryEntry,
   HistoryItem,
   LearnedCorrection,
@@ -185,6 +186,10 @@ const jaccardSimilarity = (a: string, b: string): number => {
   return union <= 0 ? 0 : intersection / union;
 };
 
+/** Match a saved selection by stable id, or by name for pre-id settings. */
+const findSelectedDevice = (devices: DeviceInfo[], selection: string): DeviceInfo | null =>
+  devices.find((d) => d.id === selection) ?? devices.find((d) => d.name === selection) ?? null;
+
 export default function Home() {
   const { isListening, status, startEngine, stopEngine, error } = useEngine();
   const [activitySensitivity, setActivitySensitivity] = useState(4.2);
@@ -320,7 +325,7 @@ export default function Home() {
         const weighted = (1 - segConf) * (1 + Math.min(rule.hits, 12) / 12) * (sim + contains * 0.4);
         if (weighted < 0.18) continue;
         suggestions.push({
-          id: `${seg.id}:${rule.heard}:${rule.corrected}`,
+          id: `${seg.key}:${rule.heard}:${rule.corrected}`,
           heard: seg.text,
           corrected: rule.corrected,
           confidence: segConf,
@@ -576,14 +581,22 @@ export default function Home() {
     if (devices.length === 0) return;
 
     const selectedDevice =
-      selectedDeviceName ? devices.find((d) => d.name === selectedDeviceName) ?? null : null;
+      selectedDeviceName ? findSelectedDevice(devices, selectedDeviceName) : null;
     const preferredByHeuristic =
       recommendedDevice ?? defaultDevice ?? devices.find((d) => !d.isLoopbackLike) ?? devices[0] ?? null;
 
     if (!preferredByHeuristic) return;
-    if (!selectedDeviceName || !selectedDevice) {
-      setSelectedDeviceName(preferredByHeuristic.name);
-      void setPreferredInputDevice(preferredByHeuristic.name).catch(console.error);
+    // No pin: the engine follows the OS default at every start.
+    if (!selectedDeviceName) return;
+    if (!selectedDevice) {
+      setSelectedDeviceName(preferredByHeuristic.id);
+      void setPreferredInputDevice(preferredByHeuristic.id).catch(console.error);
+      return;
+    }
+    if (selectedDevice.id !== selectedDeviceName) {
+      // Saved by name before device ids existed; pin it to the stable id.
+      setSelectedDeviceName(selectedDevice.id);
+      void setPreferredInputDevice(selectedDevice.id).catch(console.error);
       return;
     }
 
@@ -591,10 +604,10 @@ export default function Home() {
       !manualDeviceSelectionRef.current &&
       selectedDevice.isLoopbackLike &&
       !preferredByHeuristic.isLoopbackLike &&
-      preferredByHeuristic.name !== selectedDevice.name
+      preferredByHeuristic.id !== selectedDevice.id
     ) {
-      setSelectedDeviceName(preferredByHeuristic.name);
-      void setPreferredInputDevice(preferredByHeuristic.name).catch(console.error);
+      setSelectedDeviceName(preferredByHeuristic.id);
+      void setPreferredInputDevice(preferredByHeuristic.id).catch(console.error);
       setRuntimeMsg(
         `Switched input from '${selectedDevice.name}' to '${preferredByHeuristic.name}' to avoid system-audio capture.`,
       );
@@ -1597,7 +1610,7 @@ export default function Home() {
             {hasSegments ? (
               <div className="transcript-feed">
                 {deferredSegments.map((seg) => (
-                  <div key={seg.id} className={`seg-row has-context-menu ${seg.kind === "partial" ? "is-partial" : "is-final"}`}>
+                  <div key={seg.key} className={`seg-row has-context-menu ${seg.kind === "partial" ? "is-partial" : "is-final"}`}>
                     <p className={seg.kind === "partial" ? "seg-partial" : "seg-final"}>
                       {seg.text}
                       {seg.kind === "final" && typeof seg.confidence === "number" && (
@@ -1618,13 +1631,13 @@ export default function Home() {
                         <button
                           type="button"
                           className="context-action"
-                          onClick={() => startInlineFix(seg.id, seg.text)}
+                          onClick={() => startInlineFix(seg.key, seg.text)}
                         >
                           Fix
                         </button>
                       </div>
                     )}
-                    {activeFixSegmentId === seg.id && (
+                    {activeFixSegmentId === seg.key && (
                       <div className="seg-fix-form">
                         <input
                           className="runtime-select panel-input"
@@ -1700,11 +1713,14 @@ export default function Home() {
                 disabled={isListening || loading}
                 aria-label="Microphone input device"
               >
-                {selectedDeviceName && !devices.some((d) => d.name === selectedDeviceName) && (
+                <option value="">
+                  System default{defaultDevice ? ` (${defaultDevice.name})` : ""}
+                </option>
+                {selectedDeviceName && !findSelectedDevice(devices, selectedDeviceName) && (
                   <option value={selectedDeviceName}>{selectedDeviceName} (unavailable)</option>
                 )}
                 {devices.map((d) => (
-                  <option key={d.name} value={d.name}>
+                  <option key={d.id} value={d.id}>
                     {d.name}
                     {d.isRecommended ? " (recommended)" : d.isDefault ? " *" : ""}
                     {d.isLoopbackLike ? " (system output)" : ""}
@@ -2080,7 +2096,11 @@ export default function Home() {
               <div className="settings-health-row">
                 <article className="settings-health-chip">
                   <span>Active Mic</span>
-                  <strong>{selectedDeviceName ?? (loading ? "Detecting..." : "System Default")}</strong>
+                  <strong>
+                    {(selectedDeviceName && findSelectedDevice(devices, selectedDeviceName)?.name) ??
+                      selectedDeviceName ??
+                      (loading ? "Detecting..." : "System Default")}
+                  </strong>
                 </article>
                 <article className="settings-health-chip">
                   <span>Voice Activity</span>
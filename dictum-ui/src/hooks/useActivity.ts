@@ -57,10 +57,12 @@ export function useActivity(options?: UseActivityOptions): UseActivityResult {
     let unlisten: (() => void) | undefined;
     listenActivity((event) => {
       const { sensitivity, noiseGate, clipThreshold } = settingsRef.current;
+      // Prefer the engine's meter; the local mapping is for older engines.
       const gated = Math.max(0, event.rms - noiseGate);
       const nextLevel = clamp01(gated * sensitivity);
       latestStateRef.current = {
-        level: clamp01(Math.max(nextLevel, latestStateRef.current.level * 0.78)),
+        level:
+          event.level ?? clamp01(Math.max(nextLevel, latestStateRef.current.level * 0.78)),
         rawRms: event.rms,
         isSpeech: event.isSpeech,
         isNoisy: !event.isSpeech && event.rms > noiseGate * 1.2,
@@ -4,7 +4,10 @@ import { useCallback, useEffect, useRef, useState } from "react";
 import { listenTranscript } from "@/lib/tauri";
 import type { TranscriptSegment } from "@shared/ipc_types";
 
-type SegmentEnvelope = TranscriptSegment & { receivedAt: number };
+/** A segment plus a key unique across the list (ids repeat within an utterance). */
+export type LiveSegment = TranscriptSegment & { key: string };
+
+type SegmentEnvelope = LiveSegment & { receivedAt: number };
 
 const UNSTABLE_TAIL_MS = 6500;
 const MAX_TAIL_SEGMENTS = 2;
@@ -59,9 +62,10 @@ const shouldRewriteTail = (existingTail: string, incomingFinal: string): boolean
  * a deduplicated, ordered list of segments.
  *
  * ## Deduplication
- * Segments with the same `id` replace each other — this handles the
- * partial → final transition where the server emits multiple events for
- * the same utterance.
+ * Each event's segments replace every earlier segment with the same `id` —
+ * this handles the partial → final transition where the server emits
+ * multiple events for the same utterance, each possibly split into several
+ * segments.
  *
  * ## Usage
  * ```tsx
@@ -71,7 +75,7 @@ const shouldRewriteTail = (existingTail: string, incomingFinal: string): boolean
 export function useTranscript() {
   const segmentMapRef = useRef<Map<string, SegmentEnvelope>>(new Map());
   const flushTimerRef = useRef<number | null>(null);
-  const [segments, setSegments] = useState<TranscriptSegment[]>([]);
+  const [segments, setSegments] = useState<LiveSegment[]>([]);
 
   const flushSegments = useCallback(() => {
     flushTimerRef.current = null;
@@ -86,7 +90,7 @@ export function useTranscript() {
         if (
           !a ||
           !b ||
-          a.id !== b.id ||
+          a.key !== b.key ||
           a.text !== b.text ||
           a.kind !== b.kind ||
           a.confidence !== b.confidence
@@ -107,34 +111,64 @@ export function useTranscript() {
     let unlisten: (() => void) | undefined;
 
     listenTranscript((event) => {
-      const next = new Map(segmentMapRef.current);
+      let next = new Map(segmentMapRef.current);
       const now = Date.now();
       let didChange = false;
-      for (const seg of event.segments) {
-        if (seg.kind === "final") {
-          const finalTail = Array.from(next.entries())
-            .filter(([, existing]) => existing.kind === "final" && now - existing.receivedAt <= UNSTABLE_TAIL_MS)
-            .slice(-MAX_TAIL_SEGMENTS);
-          if (finalTail.length > 0) {
-            const tailText = finalTail.map(([, existing]) => existing.text).join(" ").trim();
-            if (shouldRewriteTail(tailText, seg.text)) {
-              for (const [tailId] of finalTail) {
-                didChange = next.delete(tailId) || didChange;
-              }
+      const finalText = event.segments
+        .filter((seg) => seg.kind === "final")
+        .map((seg) => seg.text)
+        .join(" ")
+        .trim();
+      const ids = new Set(event.segments.map((seg) => seg.id));
+      if (finalText) {
+        const finalTail = Array.from(next.entries())
+          .filter(
+            ([, existing]) =>
+              existing.kind === "final" &&
+              !ids.has(existing.id) &&
+              now - existing.receivedAt <= UNSTABLE_TAIL_MS,
+          )
+          .slice(-MAX_TAIL_SEGMENTS);
+        if (finalTail.length > 0) {
+          const tailText = finalTail.map(([, existing]) => existing.text).join(" ").trim();
+          if (shouldRewriteTail(tailText, finalText)) {
+            for (const [tailKey] of finalTail) {
+              didChange = next.delete(tailKey) || didChange;
             }
           }
         }
-        const previous = next.get(seg.id);
-        if (
-          !previous ||
-          previous.text !== seg.text ||
-          previous.kind !== seg.kind ||
-          previous.confidence !== seg.confidence
-        ) {
-          didChange = true;
+      }
+      const previous = Array.from(next.values()).filter((existing) => ids.has(existing.id));
+      if (
+        previous.length !== event.segments.length ||
+        previous.some((existing, i) => {
+          const seg = event.segments[i];
+          return (
+            existing.id !== seg.id ||
+            existing.text !== seg.text ||
+            existing.kind !== seg.kind ||
+            existing.confidence !== seg.confidence
+          );
+        })
+      ) {
+        didChange = true;
+      }
+      const firstKey = previous[0]?.key;
+      const rebuilt = new Map<string, SegmentEnvelope>();
+      const incoming = event.segments.map((seg, i): [string, SegmentEnvelope] => {
+        const key = i === 0 ? seg.id : `${seg.id}#${i}`;
+        return [key, { ...seg, key, receivedAt: now }];
+      });
+      // Keep the utterance where it was in the list; new ones go last.
+      for (const [key, value] of next) {
+        if (ids.has(value.id)) {
+          if (key === firstKey) incoming.forEach(([k, v]) => rebuilt.set(k, v));
+          continue;
         }
-        next.set(seg.id, { ...seg, receivedAt: now });
+        rebuilt.set(key, value);
       }
+      if (firstKey === undefined) incoming.forEach(([k, v]) => rebuilt.set(k, v));
+      next = rebuilt;
       segmentMapRef.current = next;
       if (!didChange) return;
       scheduleFlush();
@@ -10,6 +10,7 @@ import { listen as tauriListen, type UnlistenFn } from "@tauri-apps/api/event";
 import { getVersion as tauriGetVersion } from "@tauri-apps/api/app";
 import type {
   AudioActivityEvent,
+  DictationSession,
   DictionaryEntry,
   EngineStatus,
   TranscriptEvent,
@@ -21,19 +22,61 @@ import type {
   SnippetEntry,
   StatsPayload,
   PerfSnapshot,
+  PermissionStatus,
   DiagnosticsBundle,
+  EffectiveConfig,
+  ModelCapabilities,
   ModelProfileMetadata,
   ModelProfileRecommendation,
   AutoTuneResult,
   BenchmarkAutoTuneResult,
+  ModelBenchmarkReport,
+  VadBenchmarkReport,
+  FormState,
+  FormTemplate,
   AppUpdateInfo,
   LearnedCorrection,
+  StorageError,
+  UtteranceBoundaryEvent,
 } from "@shared/ipc_types";
 
 // ---------------------------------------------------------------------------
 // Commands
 // ---------------------------------------------------------------------------
 
+export const isStorageError = (error: unknown): error is StorageError =>
+  typeof error === "object" &&
+  error !== null &&
+  typeof (error as StorageError).code === "string" &&
+  typeof (error as StorageError).retryable === "boolean";
+
+const STORAGE_RETRY_DELAYS_MS = [100, 300, 800];
+
+/**
+ * Invoke a local-store command, retrying while the database is locked by
+ * another writer (e.g. background maintenance). Other errors reject at once.
+ *
+ * Only for idempotent commands (reads, deletes by id): a write may have
+ * committed before a later step hit the lock, and repeating an upsert with
+ * an empty id would insert a second row.
+ */
+const invokeStore = async <T>(
+  command: string,
+  args?: Record<string, unknown>,
+): Promise<T> => {
+  for (let attempt = 0; ; attempt++) {
+    try {
+      return await tauriInvoke<T>(command, args);
+    } catch (error) {
+      const delay = STORAGE_RETRY_DELAYS_MS[attempt];
+      if (!isStorageError(error) || !error.retryable || delay === undefined) {
+        throw error;
+      }
+      await new Promise((resolve) => setTimeout(resolve, delay));
+    }
+  }
+};
+
 export const startEngine = (deviceName?: string | null): Promise<void> =>
   tauriInvoke("start_engine", {
     deviceName: deviceName ?? null,
@@ -42,9 +85,16 @@ export const startEngine = (deviceName?: string | null): Promise<void> =>
 export const stopEngine = (): Promise<void> =>
   tauriInvoke("stop_engine");
 
+/** Discard the utterance in progress; capture keeps running. */
+export const resetSession = (): Promise<void> =>
+  tauriInvoke("reset_session");
+
 export const getStatus = (): Promise<EngineStatus> =>
   tauriInvoke("get_status");
 
+export const getMicrophonePermission = (): Promise<PermissionStatus> =>
+  tauriInvoke("get_microphone_permission");
+
 export const getAppVersion = (): Promise<string> =>
   tauriGetVersion();
 
@@ -88,6 +138,31 @@ export const runBenchmarkAutoTune = (
     fallbackRatePct,
   });
 
+export const runModelBenchmark = (
+  corpusDir?: string | null,
+): Promise<ModelBenchmarkReport> =>
+  tauriInvoke("run_model_benchmark", {
+    corpusDir: corpusDir ?? null,
+  });
+
+export const benchmarkVad = (
+  wavPath: string,
+  labelsPath?: string | null,
+): Promise<VadBenchmarkReport> =>
+  tauriInvoke("benchmark_vad", {
+    wavPath,
+    labelsPath: labelsPath ?? null,
+  });
+
+export const startFormMode = (template: FormTemplate): Promise<FormState> =>
+  tauriInvoke("start_form_mode", { templateJson: JSON.stringify(template) });
+
+export const stopFormMode = (): Promise<FormState | null> =>
+  tauriInvoke("stop_form_mode");
+
+export const getFormState = (): Promise<FormState | null> =>
+  tauriInvoke("get_form_state");
+
 export const checkForAppUpdate = (
   repoSlug?: string | null,
 ): Promise<AppUpdateInfo> =>
@@ -133,6 +208,16 @@ export const setRuntimeSettings = (
   onboardingCompleted?: boolean | null,
   historyEnabled?: boolean | null,
   retentionDays?: number | null,
+  injectionMode?: string | null,
+  injectionMinIntervalMs?: number | null,
+  redactPii?: boolean | null,
+  maintenanceIntervalMinutes?: number | null,
+  numberFormatting?: string | null,
+  numberLocale?: string | null,
+  utteranceSeparator?: string | null,
+  utteranceSeparatorCustom?: string | null,
+  injectionStreamChunkChars?: number | null,
+  injectionStreamIntervalMs?: number | null,
 ): Promise<RuntimeSettings> =>
   tauriInvoke("set_runtime_settings", {
     modelProfile: modelProfile ?? null,
@@ -157,6 +242,16 @@ export const setRuntimeSettings = (
     onboardingCompleted: onboardingCompleted ?? null,
     historyEnabled: historyEnabled ?? null,
     retentionDays: retentionDays ?? null,
+    injectionMode: injectionMode ?? null,
+    injectionMinIntervalMs: injectionMinIntervalMs ?? null,
+    redactPii: redactPii ?? null,
+    maintenanceIntervalMinutes: maintenanceIntervalMinutes ?? null,
+    numberFormatting: numberFormatting ?? null,
+    numberLocale: numberLocale ?? null,
+    utteranceSeparator: utteranceSeparator ?? null,
+    utteranceSeparatorCustom: utteranceSeparatorCustom ?? null,
+    injectionStreamChunkChars: injectionStreamChunkChars ?? null,
+    injectionStreamIntervalMs: injectionStreamIntervalMs ?? null,
   });
 
 export const getPerfSnapshot = (): Promise<PerfSnapshot> =>
@@ -165,6 +260,12 @@ export const getPerfSnapshot = (): Promise<PerfSnapshot> =>
 export const getDiagnosticsBundle = (): Promise<DiagnosticsBundle> =>
   tauriInvoke("get_diagnostics_bundle");
 
+export const getModelCapabilities = (): Promise<ModelCapabilities | null> =>
+  tauriInvoke("get_model_capabilities");
+
+export const getEffectiveConfig = (): Promise<EffectiveConfig> =>
+  tauriInvoke("get_effective_config");
+
 export const getPrivacySettings = (): Promise<PrivacySettings> =>
   tauriInvoke("get_privacy_settings");
 
@@ -183,29 +284,34 @@ export const getHistory = (
   page?: number,
   pageSize?: number,
   query?: string | null,
+  sessionId?: string | null,
 ): Promise<HistoryPage> =>
-  tauriInvoke("get_history", {
+  invokeStore("get_history", {
     page: page ?? null,
     pageSize: pageSize ?? null,
     query: query ?? null,
+    sessionId: sessionId ?? null,
   });
 
+export const getSessions = (): Promise<DictationSession[]> =>
+  invokeStore("get_sessions");
+
 export const deleteHistory = (
   ids?: string[] | null,
   olderThanDays?: number | null,
 ): Promise<number> =>
-  tauriInvoke("delete_history", {
+  invokeStore("delete_history", {
     ids: ids ?? null,
     olderThanDays: olderThanDays ?? null,
   });
 
 export const getStats = (rangeDays?: number): Promise<StatsPayload> =>
-  tauriInvoke("get_stats", {
+  invokeStore("get_stats", {
     rangeDays: rangeDays ?? null,
   });
 
 export const getDictionary = (): Promise<DictionaryEntry[]> =>
-  tauriInvoke("get_dictionary");
+  invokeStore("get_dictionary");
 
 export const upsertDictionary = (
   entry: DictionaryEntry,
@@ -213,16 +319,16 @@ export const upsertDictionary = (
   tauriInvoke("upsert_dictionary", { entry });
 
 export const deleteDictionary = (id: string): Promise<void> =>
-  tauriInvoke("delete_dictionary", { id });
+  invokeStore("delete_dictionary", { id });
 
 export const getSnippets = (): Promise<SnippetEntry[]> =>
-  tauriInvoke("get_snippets");
+  invokeStore("get_snippets");
 
 export const upsertSnippet = (entry: SnippetEntry): Promise<SnippetEntry> =>
   tauriInvoke("upsert_snippet", { entry });
 
 export const deleteSnippet = (id: string): Promise<void> =>
-  tauriInvoke("delete_snippet", { id });
+  invokeStore("delete_snippet", { id });
 
 export const getLearnedCorrections = (): Promise<LearnedCorrection[]> =>
   tauriInvoke("get_learned_corrections");
@@ -252,6 +358,8 @@ export const deleteLearnedCorrection = (
 export const TRANSCRIPT_EVENT = "dictum://transcript" as const;
 export const STATUS_EVENT = "dictum://status" as const;
 export const ACTIVITY_EVENT = "dictum://activity" as const;
+export const UTTERANCE_EVENT = "dictum://utterance" as const;
+export const FORM_EVENT = "dictum://form" as const;
 
 export const listenTranscript = (
   handler: (event: TranscriptEvent) => void
@@ -267,3 +375,13 @@ export const listenActivity = (
   handler: (event: AudioActivityEvent) => void
 ): Promise<UnlistenFn> =>
   tauriListen<AudioActivityEvent>(ACTIVITY_EVENT, (e) => handler(e.payload));
+
+export const listenUtteranceBoundaries = (
+  handler: (event: UtteranceBoundaryEvent) => void
+): Promise<UnlistenFn> =>
+  tauriListen<UtteranceBoundaryEvent>(UTTERANCE_EVENT, (e) => handler(e.payload));
+
+export const listenForm = (
+  handler: (event: FormState) => void
+): Promise<UnlistenFn> =>
+  tauriListen<FormState>(FORM_EVENT, (e) => handler(e.payload));
@@ -22,15 +22,85 @@
  * Rust: `TranscriptEvent`
  */
 export interface TranscriptEvent {
-  /** Monotonically increasing event sequence number. */
+  /** Event sequence number; wraps to 0 after `u64::MAX` (Rust `seq_follows`). */
   seq: number;
   /** One or more transcript segments from this inference pass. */
   segments: TranscriptSegment[];
+  /** Accuracy and provenance details. Absent from older engines. */
+  meta?: TranscriptMeta;
 }
 
+/**
+ * How a transcript was produced. Every field is optional; absent fields are
+ * omitted from the JSON rather than sent as null.
+ *
+ * Rust: `TranscriptMeta`
+ */
+export interface TranscriptMeta {
+  /** Aggregate model confidence in [0, 1]. */
+  confidence?: number;
+  /** Language of the transcript as a short code (e.g. "en"). */
+  language?: string;
+  /** Which recogniser produced the text. */
+  source?: TranscriptSource;
+  /** True when the text is a placeholder rather than a recognition result. */
+  isFallback?: boolean;
+  /** Wall-clock inference time for this pass (ms). */
+  latencyMs?: number;
+  /** Duration of the transcribed audio (ms). */
+  audioMs?: number;
+  /** Estimated signal-to-noise ratio of the transcribed audio (dB). */
+  snrDb?: number;
+  /** First transcribed sample at the engine's target rate, counted from capture start. Finals only. */
+  audioStartSample?: number;
+  /** One past the last transcribed sample, same clock as audioStartSample. Finals only. */
+  audioEndSample?: number;
+  /**
+   * Per-utterance update counter, starting at 1. The final has the highest
+   * revision and no partial follows it; drop updates at or below the last
+   * revision applied for the same segment id.
+   */
+  revision?: number;
+  /** Why the pass produced no text; set on the `[speech captured]` placeholder. */
+  emptyReason?: EmptyReason;
+  /** Hex spectral hash of a final's audio, with `audio_fingerprint` on. */
+  audioFingerprint?: string;
+  /**
+   * Segment ids of the partials this final replaces; lets the UI animate
+   * from interim to committed text. Finals only; omitted when none.
+   */
+  supersedes?: string[];
+  /** Whether a partial was emitted for this utterance before its final. Finals only. */
+  partialEmitted?: boolean;
+}
+
+/**
+ * Why a transcription produced no text.
+ *
+ * Rust: `EmptyReason`
+ *
+ * Serialised as snake_case string.
+ */
+export type EmptyReason =
+  | "no_speech"
+  | "low_quality_filtered"
+  | "fallback_empty"
+  | "cloud_unavailable"
+  | "not_decoded";
+
+/**
+ * Rust: `TranscriptSource`
+ *
+ * Serialised as snake_case string.
+ */
+export type TranscriptSource = "local" | "cloud" | "os_dictation" | "placeholder";
+
 /**
  * A single recognised speech segment.
  *
+ * One event may carry several segments for the same utterance; they share
+ * `id`, are in spoken order, and replace every earlier segment with that id.
+ *
  * Rust: `TranscriptSegment`
  */
 export interface TranscriptSegment {
@@ -42,6 +112,29 @@ export interface TranscriptSegment {
   kind: SegmentKind;
   /** Model confidence in [0, 1], or null if not available. */
   confidence: number | null;
+  /** Start of the segment within the transcribed audio (ms), when known. */
+  startMs?: number;
+  /** End of the segment within the transcribed audio (ms), when known. */
+  endMs?: number;
+  /** Per-word timing in spoken order; absent unless the model reports it. */
+  wordTimings?: WordTiming[];
+  /**
+   * Language code the decode ran under (`"en"`, `"zh"`, ...), when known;
+   * `"en"` for translated text.
+   */
+  language?: string;
+}
+
+/**
+ * When one word of a segment was spoken, as offsets into the transcribed
+ * audio (ms).
+ *
+ * Rust: `WordTiming`
+ */
+export interface WordTiming {
+  word: string;
+  startMs: number;
+  endMs: number;
 }
 
 /**
@@ -90,12 +183,41 @@ export type EngineStatus =
  * Rust: `AudioActivityEvent`
  */
 export interface AudioActivityEvent {
-  /** Monotonically increasing event sequence number. */
+  /** Per-session sequence number; wraps to 0 after `u64::MAX`. */
   seq: number;
   /** Root-mean-square level of the chunk in [0, 1]. */
   rms: number;
   /** True if VAD classified current chunk as speech. */
   isSpeech: boolean;
+  /** Smoothed meter level in [0, 1] computed by the engine from the activity sliders; absent when not configured. */
+  level?: number;
+  /**
+   * 16 per-chunk values in [0, 1]: waveform peaks (oldest first) or spectrum
+   * amplitudes (lowest frequency first), per the engine's `activity_bins`.
+   * Absent when not configured.
+   */
+  bins?: number[];
+}
+
+// ---------------------------------------------------------------------------
+// Utterance boundary events (channel: "dictum://utterance")
+// ---------------------------------------------------------------------------
+
+export type BoundaryKind = "start" | "end";
+
+/**
+ * Emitted when the pipeline opens or closes an utterance. Every `start` is
+ * followed by exactly one `end` with the same `utteranceId`; `end` arrives
+ * before the utterance's final is decoded.
+ *
+ * Rust: `UtteranceBoundaryEvent`
+ */
+export interface UtteranceBoundaryEvent {
+  /** Matches the `id` of the utterance's transcript segments. */
+  utteranceId: string;
+  kind: BoundaryKind;
+  /** Stream offset in samples at the target rate: first speech sample for `start`, one past the last for `end`. */
+  atSample: number;
 }
 
 // ---------------------------------------------------------------------------
@@ -108,6 +230,8 @@ export interface AudioActivityEvent {
  * Rust: `DeviceInfo`
  */
 export interface DeviceInfo {
+  /** Stable identifier (name plus occurrence among same-named devices). */
+  id: string;
   /** Human-readable device name reported by the OS. */
   name: string;
   /** Whether this is the system default input device. */
@@ -173,8 +297,31 @@ export interface RuntimeSettings {
   retentionDays: number;
   /** Number of learned correction rules. */
   correctionCount: number;
+  /** Text injection: type each final, or revise the live utterance in place. */
+  injectionMode: "final_only" | "continuous_document";
+  /** Minimum gap between typed finals in ms (0 = none); finals arriving sooner are batched. */
+  injectionMinIntervalMs: number;
+  /** Mask card numbers, emails, phone numbers and SSNs in finals before typing/saving. */
+  redactPii: boolean;
+  /** Minutes between background history prunes (0 = only at startup and on settings changes). */
+  maintenanceIntervalMinutes: number;
+  /** Spelled-out numbers and units as digits/symbols; conservative keeps lone small numbers as words. */
+  numberFormatting: "off" | "conservative" | "all";
+  /** Locale for digit separators in formatted numbers, e.g. "en-US" or "de-DE". */
+  numberLocale: string;
+  /** Text typed between consecutive finals; the first final of a session gets none. */
+  utteranceSeparator: "space" | "newline" | "double_newline" | "custom";
+  /** Separator used when `utteranceSeparator` is "custom". */
+  utteranceSeparatorCustom: string;
+  /** Finals longer than this many characters are typed in word-aligned pieces (0 = all at once). */
+  injectionStreamChunkChars: number;
+  /** Pause between streamed pieces of one final, in ms. */
+  injectionStreamIntervalMs: number;
 }
 
+/** OS microphone permission, checked before capture starts. */
+export type PermissionStatus = "granted" | "denied" | "undetermined" | "unknown";
+
 // ---------------------------------------------------------------------------
 // Model profile metadata + recommendation
 // ---------------------------------------------------------------------------
@@ -216,6 +363,63 @@ export interface BenchmarkAutoTuneResult {
   measuredFallbackRatePct: number;
 }
 
+export interface ModelBenchmarkCase {
+  /** Corpus-relative WAV path. */
+  file: string;
+  /** Reference text from the sibling `.txt`, if present. */
+  reference: string | null;
+  hypothesis: string;
+  /** Word error rate against `reference` (may exceed 1.0). */
+  wordErrorRate: number | null;
+  latencyMs: number;
+}
+
+export interface ModelBenchmarkReport {
+  corpusDir: string;
+  modelProfile: string;
+  ortEp: string;
+  files: number;
+  meanWordErrorRate: number | null;
+  medianLatencyMs: number;
+  cases: ModelBenchmarkCase[];
+}
+
+/** Frame-level score of the configured VAD against labeled speech regions. */
+export interface VadBenchmarkReport {
+  frameMs: number;
+  frames: number;
+  /** Frames whose midpoint lies inside a labeled speech region. */
+  labeledSpeechFrames: number;
+  truePositives: number;
+  falsePositives: number;
+  falseNegatives: number;
+  /** Null when the VAD never reported speech. */
+  precision: number | null;
+  /** Null when the labels contain no speech. */
+  recall: number | null;
+  onsets: number;
+  detectedOnsets: number;
+  /** Labeled onset to the end of the first frame detected as speech. */
+  meanOnsetLatencyMs: number | null;
+  maxOnsetLatencyMs: number | null;
+}
+
+/**
+ * Form mode: finals fill these fields instead of being typed. An utterance
+ * starting with a field's name or alias sets that field; anything else
+ * fills the current field and moves to the next.
+ */
+export interface FormTemplate {
+  fields: Array<{ name: string; aliases?: string[] }>;
+}
+
+/** Emitted on `dictum://form` after each routed final. */
+export interface FormState {
+  fields: Array<{ name: string; value: string }>;
+  /** Field the next unnamed utterance goes to. */
+  current: string;
+}
+
 export interface AppUpdateInfo {
   currentVersion: string;
   latestVersion: string;
@@ -252,6 +456,21 @@ export interface HistoryItem {
   charCount: number;
   dictionaryApplied: boolean;
   snippetApplied: boolean;
+  /** Dictation session this utterance belongs to, if any. */
+  sessionId: string | null;
+  /** Hex audio fingerprint, when `DICTUM_AUDIO_FINGERPRINT` is on. */
+  fingerprint: string | null;
+}
+
+/** One start→stop run of dictation. Rust: `storage::DictationSession` */
+export interface DictationSession {
+  id: string;
+  name: string;
+  startedAt: string;
+  /** `null` while the session is still open. */
+  endedAt: string | null;
+  utterances: number;
+  words: number;
 }
 
 export interface HistoryPage {
@@ -304,6 +523,25 @@ export interface PrivacySettings {
   cloudOptIn: boolean;
 }
 
+/** Machine-readable kind of a `StorageError`. */
+export type StorageErrorCode =
+  | "locked"
+  | "disk_full"
+  | "corrupt"
+  | "encrypt_failed"
+  | "decrypt_failed"
+  | "io"
+  | "serialization"
+  | "database";
+
+/** Rejection value of the history/dictionary/snippet/stats commands. */
+export interface StorageError {
+  code: StorageErrorCode;
+  message: string;
+  /** True when the same call may succeed shortly (another writer held the lock). */
+  retryable: boolean;
+}
+
 export interface PerfStageSnapshot {
   count: number;
   meanMs: number;
@@ -323,6 +561,12 @@ export interface AppDiagnostics {
   shortcutToggleExecuted: number;
   shortcutToggleDropped: number;
   pipelineFramesIn: number;
+  /** Capture samples dropped because the ring buffer was full. */
+  pipelineFramesDropped: number;
+  /** Ring buffer occupancy (percent) before the pipeline's latest drain. */
+  pipelineRingFillPct: number;
+  /** Background noise RMS the VAD gates against; null unless it tracks one. */
+  pipelineVadNoiseFloor: number | null;
   pipelineFramesResampled: number;
   pipelineVadWindows: number;
   pipelineVadSpeech: number;
@@ -330,6 +574,7 @@ export interface AppDiagnostics {
   pipelineInferenceErrors: number;
   pipelineSegmentsEmitted: number;
   pipelineFallbackEmitted: number;
+  pipelineRepeatedFinals: number;
   pipelineDrainMs: PerfStageSnapshot;
   pipelineResampleMs: PerfStageSnapshot;
   pipelineVadMs: PerfStageSnapshot;
@@ -348,6 +593,39 @@ export interface PerfSnapshot {
   finalizeMs: PerfStageSnapshot;
 }
 
+/**
+ * What the loaded speech model supports, reported after warm-up.
+ * Rust: `ModelCapabilities`
+ */
+export interface ModelCapabilities {
+  /** Backend name, e.g. "onnx-whisper" or "stub". */
+  backend: string;
+  vocabSize: number | null;
+  nMels: number | null;
+  decoderWithPast: boolean;
+  executionProvider: string | null;
+  /** Codes of the languages decoding can be forced to. */
+  languages: string[];
+  /** Whether segments carry `startMs`/`endMs`. */
+  segmentTimestamps: boolean;
+}
+
+/** Resolved runtime configuration from `get_effective_config`. */
+export interface EffectiveConfig {
+  /** `EngineConfig` as serialized by the core (snake_case field names). */
+  engine: Record<string, unknown> & {
+    target_sample_rate: number;
+    vad_threshold: number;
+  };
+  performanceProfile: string;
+  modelProfile: string;
+  modelDir: string;
+  /** Execution provider the loaded model registered; null before warm-up. */
+  executionProvider: string | null;
+  /** Set `DICTUM_*` variables; secrets are masked. */
+  envOverrides: Record<string, string>;
+}
+
 export interface DiagnosticsBundle {
   generatedAt: string;
   appVersion: string;